@@ -2,6 +2,7 @@ use criterion::{black_box, criterion_group, criterion_main, BatchSize, Benchmark
 use solver::solving::algorithm::heuristic::heuristics::{
     InversionDistance, LinearConflict, ManhattanDistance,
 };
+use solver::solving::algorithm::heuristic::DuplicatePolicy;
 use solver::solving::algorithm::{solvers::*, Solver};
 use solver::solving::movegen::MoveGenerator;
 
@@ -103,11 +104,43 @@ pub fn solver_algorithms_benchmark(c: &mut Criterion) {
             BatchSize::SmallInput,
         )
     });
+    heuristic_group.finish();
+}
+
+pub fn duplicate_policy_benchmark(c: &mut Criterion) {
+    let mut boards = shared::create_sample_boards();
+
+    let mut duplicate_group = c.benchmark_group("Duplicate policy");
+    for policy in [
+        DuplicatePolicy::Keep,
+        DuplicatePolicy::Ignore,
+        DuplicatePolicy::DecreaseKey,
+        DuplicatePolicy::LazyReinsert,
+    ] {
+        duplicate_group.bench_function(BenchmarkId::new("A star", format!("{policy:?}")), |b| {
+            b.iter_batched(
+                || {
+                    Box::new(
+                        AStarSolver::new(
+                            black_box(boards.next().unwrap()),
+                            Box::<ManhattanDistance>::default(),
+                        )
+                        .with_duplicate_policy(policy),
+                    )
+                },
+                |solver| {
+                    let _ = black_box(solver.solve());
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    duplicate_group.finish();
 }
 
 criterion_group!(
     name = extensive_benchmarks;
     config = Criterion::default().sample_size(50);
-    targets = solver_algorithms_benchmark
+    targets = solver_algorithms_benchmark, duplicate_policy_benchmark
 );
 criterion_main!(extensive_benchmarks);
@@ -1,3 +1,5 @@
+#![allow(dead_code)]
+
 use itertools::Itertools;
 use solver::board::{BoardMove, OwnedBoard};
 use solver::solving::movegen::SearchOrder;
@@ -88,7 +90,7 @@ pub fn generate_all_search_orders() -> Vec<SearchOrder> {
     ]
     .into_iter()
     .permutations(4)
-    .map(|p| SearchOrder::Provided([p[0], p[1], p[2], p[3]]))
+    .map(SearchOrder::Provided)
     // .chain(std::iter::once(SearchOrder::Random))
     .collect();
     search_orders
@@ -0,0 +1,283 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+use super::{standard_goal_position, Board, BoardMove, OwnedBoard, SearchBoard};
+
+/// Error of a board with more cells than the [`ArrayBoard`] can hold
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct CapacityExceeded {
+    pub cells: usize,
+    pub capacity: usize,
+}
+
+impl Display for CapacityExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Board has {} cells, but the array can hold only {}",
+            self.cells, self.capacity
+        )
+    }
+}
+
+impl Error for CapacityExceeded {}
+
+/// Board stored inline in an array of `N` cells, for boards of at most `N` cells.
+///
+/// Unlike [`OwnedBoard`] it does not allocate when cloned,
+/// which speeds up the solvers cloning the board for every generated move.
+/// Unused cells at the end of the array are always zero
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ArrayBoard<const N: usize> {
+    rows: u8,
+    columns: u8,
+    cells: [u8; N],
+    /// Flat index of the empty cell, cached to avoid scanning the cells on every move
+    empty: u8,
+    goal: Option<Arc<[(u8, u8)]>>,
+}
+
+impl<const N: usize> ArrayBoard<N> {
+    /// Number of cells of the largest board which can be stored
+    pub const CAPACITY: usize = N;
+
+    /// Copies the owned board, returning `None` if it has more than `N` cells
+    #[must_use]
+    pub fn from_board(board: &OwnedBoard) -> Option<Self> {
+        let mut cells = [0; N];
+        cells
+            .get_mut(..board.cells.len())?
+            .copy_from_slice(&board.cells);
+        let (row, column) = board.empty_cell_pos();
+        Some(Self {
+            rows: board.rows,
+            columns: board.columns,
+            cells,
+            empty: row * board.columns + column,
+            goal: board.goal.clone(),
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.rows as usize * self.columns as usize
+    }
+
+    fn flatten_index(&self, row: u8, column: u8) -> u8 {
+        row * self.columns + column
+    }
+}
+
+impl<const N: usize> TryFrom<OwnedBoard> for ArrayBoard<N> {
+    type Error = CapacityExceeded;
+
+    fn try_from(board: OwnedBoard) -> Result<Self, Self::Error> {
+        Self::from_board(&board).ok_or(CapacityExceeded {
+            cells: board.cells.len(),
+            capacity: N,
+        })
+    }
+}
+
+impl<const N: usize> From<ArrayBoard<N>> for OwnedBoard {
+    fn from(board: ArrayBoard<N>) -> Self {
+        Self {
+            rows: board.rows,
+            columns: board.columns,
+            cells: board.cells[..board.len()].into(),
+            goal: board.goal,
+        }
+    }
+}
+
+impl<const N: usize> Board for ArrayBoard<N> {
+    fn dimensions(&self) -> (u8, u8) {
+        (self.rows, self.columns)
+    }
+
+    fn goal_position(&self, tile: u8) -> (u8, u8) {
+        self.goal.as_ref().map_or_else(
+            || standard_goal_position(tile, self.dimensions()),
+            |positions| positions[tile as usize],
+        )
+    }
+
+    fn at(&self, row: u8, column: u8) -> u8 {
+        self.cells[self.flatten_index(row, column) as usize]
+    }
+
+    fn empty_cell_pos(&self) -> (u8, u8) {
+        (self.empty / self.columns, self.empty % self.columns)
+    }
+
+    fn is_solved(&self) -> bool {
+        if let Some(positions) = &self.goal {
+            return self
+                .iter_cells()
+                .all(|(position, tile)| positions[tile as usize] == position);
+        }
+        let last = self.len() - 1;
+        self.empty as usize == last
+            && self.cells[..last]
+                .iter()
+                .zip(1..)
+                .all(|(&actual, expected)| actual == expected)
+    }
+
+    fn clone_box(&self) -> Box<dyn Board> {
+        Box::new(self.clone())
+    }
+
+    fn can_move(&self, board_move: BoardMove) -> bool {
+        let (row, column) = self.empty_cell_pos();
+        match board_move {
+            BoardMove::Up => row > 0,
+            BoardMove::Down => row < self.rows - 1,
+            BoardMove::Left => column > 0,
+            BoardMove::Right => column < self.columns - 1,
+        }
+    }
+
+    fn exec_move(&mut self, board_move: BoardMove) {
+        assert!(self.can_move(board_move), "Board cannot execute this move");
+
+        let target = match board_move {
+            BoardMove::Up => self.empty - self.columns,
+            BoardMove::Down => self.empty + self.columns,
+            BoardMove::Left => self.empty - 1,
+            BoardMove::Right => self.empty + 1,
+        };
+        self.cells.swap(self.empty as usize, target as usize);
+        self.empty = target;
+    }
+}
+
+impl<const N: usize> SearchBoard for ArrayBoard<N> {}
+
+/// Board in the smallest representation which can hold it, created from the [`OwnedBoard`]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum AnyBoard {
+    /// Board of at most 16 cells, e.g. the 15-puzzle
+    Small(ArrayBoard<16>),
+    /// Board of at most 25 cells, e.g. the 24-puzzle
+    Medium(ArrayBoard<25>),
+    Large(OwnedBoard),
+}
+
+impl AnyBoard {
+    fn inner(&self) -> &dyn Board {
+        match self {
+            AnyBoard::Small(board) => board,
+            AnyBoard::Medium(board) => board,
+            AnyBoard::Large(board) => board,
+        }
+    }
+}
+
+impl From<OwnedBoard> for AnyBoard {
+    fn from(board: OwnedBoard) -> Self {
+        match board.cells.len() {
+            ..=16 => ArrayBoard::from_board(&board).map_or(Self::Large(board), Self::Small),
+            17..=25 => ArrayBoard::from_board(&board).map_or(Self::Large(board), Self::Medium),
+            _ => Self::Large(board),
+        }
+    }
+}
+
+impl From<AnyBoard> for OwnedBoard {
+    fn from(board: AnyBoard) -> Self {
+        match board {
+            AnyBoard::Small(board) => board.into(),
+            AnyBoard::Medium(board) => board.into(),
+            AnyBoard::Large(board) => board,
+        }
+    }
+}
+
+impl Board for AnyBoard {
+    fn dimensions(&self) -> (u8, u8) {
+        self.inner().dimensions()
+    }
+
+    fn goal_position(&self, tile: u8) -> (u8, u8) {
+        self.inner().goal_position(tile)
+    }
+
+    fn at(&self, row: u8, column: u8) -> u8 {
+        self.inner().at(row, column)
+    }
+
+    fn empty_cell_pos(&self) -> (u8, u8) {
+        self.inner().empty_cell_pos()
+    }
+
+    fn is_solved(&self) -> bool {
+        self.inner().is_solved()
+    }
+
+    fn clone_box(&self) -> Box<dyn Board> {
+        Box::new(self.clone())
+    }
+
+    fn can_move(&self, board_move: BoardMove) -> bool {
+        self.inner().can_move(board_move)
+    }
+
+    fn exec_move(&mut self, board_move: BoardMove) {
+        match self {
+            AnyBoard::Small(board) => board.exec_move(board_move),
+            AnyBoard::Medium(board) => board.exec_move(board_move),
+            AnyBoard::Large(board) => board.exec_move(board_move),
+        }
+    }
+}
+
+impl SearchBoard for AnyBoard {}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::scramble::scramble_from_solution;
+    use crate::board::BoardMove::*;
+
+    use super::*;
+
+    #[test]
+    fn representation_is_selected_by_size() {
+        assert!(matches!(
+            AnyBoard::from(OwnedBoard::new_solved(3, 3)),
+            AnyBoard::Small(_)
+        ));
+        assert!(matches!(
+            AnyBoard::from(OwnedBoard::new_solved(5, 5)),
+            AnyBoard::Medium(_)
+        ));
+        assert!(matches!(
+            AnyBoard::from(OwnedBoard::new_solved(6, 6)),
+            AnyBoard::Large(_)
+        ));
+        assert_eq!(
+            Err(CapacityExceeded {
+                cells: 25,
+                capacity: 16
+            }),
+            ArrayBoard::<16>::try_from(OwnedBoard::new_solved(5, 5))
+        );
+    }
+
+    #[test]
+    fn moves_match_owned_board() {
+        let scramble = [Up, Left, Up, Right, Down, Down, Left];
+        let owned = OwnedBoard::from_scramble(3, 4, &scramble).unwrap();
+        let mut board = ArrayBoard::<16>::try_from(OwnedBoard::new_solved(3, 4)).unwrap();
+        board.apply_moves(&scramble).unwrap();
+
+        assert_eq!(owned, OwnedBoard::from(board.clone()));
+        assert_eq!(owned.empty_cell_pos(), board.empty_cell_pos());
+        assert!(!board.is_solved());
+
+        board
+            .apply_moves(&scramble_from_solution(&scramble))
+            .unwrap();
+        assert!(board.is_solved());
+    }
+}
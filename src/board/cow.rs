@@ -0,0 +1,208 @@
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use super::{standard_goal_position, Board, BoardMove, OwnedBoard, SearchBoard};
+
+/// Board which shares its cells with the board it was cloned from.
+///
+/// The last executed move is kept aside instead of being written to the cells,
+/// so a board created by cloning and moving once does not own a cell buffer.
+/// The cells are copied only when the next move is executed on a board whose cells are shared.
+/// This makes it suitable for search nodes which are queued, but mostly never expanded
+#[derive(Clone, Debug)]
+pub struct CowBoard {
+    rows: u8,
+    columns: u8,
+    cells: Rc<[u8]>,
+    /// Flat index of the empty cell in `cells`
+    base_empty: usize,
+    /// Flat index of the empty cell after the pending move, equal to `base_empty` if there is none
+    empty: usize,
+    goal: Option<Arc<[(u8, u8)]>>,
+}
+
+impl CowBoard {
+    fn flatten_index(&self, row: u8, column: u8) -> usize {
+        row as usize * self.columns as usize + column as usize
+    }
+
+    fn get(&self, index: usize) -> u8 {
+        if index == self.empty {
+            0
+        } else if index == self.base_empty {
+            // the tile moved by the pending move
+            self.cells[self.empty]
+        } else {
+            self.cells[index]
+        }
+    }
+
+    /// Writes the pending move to the cells, copying them if they are shared
+    pub fn materialize(&mut self) {
+        if self.base_empty == self.empty {
+            return;
+        }
+        let cells = Rc::make_mut(&mut self.cells);
+        cells.swap(self.base_empty, self.empty);
+        self.base_empty = self.empty;
+    }
+}
+
+impl From<OwnedBoard> for CowBoard {
+    fn from(board: OwnedBoard) -> Self {
+        let (row, column) = board.empty_cell_pos();
+        let empty = row as usize * board.columns as usize + column as usize;
+        Self {
+            rows: board.rows,
+            columns: board.columns,
+            cells: Rc::from(board.cells),
+            base_empty: empty,
+            empty,
+            goal: board.goal,
+        }
+    }
+}
+
+impl From<CowBoard> for OwnedBoard {
+    fn from(board: CowBoard) -> Self {
+        let cells = (0..board.cells.len())
+            .map(|index| board.get(index))
+            .collect();
+        Self {
+            rows: board.rows,
+            columns: board.columns,
+            cells,
+            goal: board.goal,
+        }
+    }
+}
+
+impl Board for CowBoard {
+    fn dimensions(&self) -> (u8, u8) {
+        (self.rows, self.columns)
+    }
+
+    fn goal_position(&self, tile: u8) -> (u8, u8) {
+        self.goal.as_ref().map_or_else(
+            || standard_goal_position(tile, self.dimensions()),
+            |positions| positions[tile as usize],
+        )
+    }
+
+    fn at(&self, row: u8, column: u8) -> u8 {
+        self.get(self.flatten_index(row, column))
+    }
+
+    fn empty_cell_pos(&self) -> (u8, u8) {
+        let columns = self.columns as usize;
+        ((self.empty / columns) as u8, (self.empty % columns) as u8)
+    }
+
+    fn is_solved(&self) -> bool {
+        self.iter_cells()
+            .all(|(position, tile)| self.goal_position(tile) == position)
+    }
+
+    fn can_move(&self, board_move: BoardMove) -> bool {
+        let (row, column) = self.empty_cell_pos();
+        match board_move {
+            BoardMove::Up => row > 0,
+            BoardMove::Down => row < self.rows - 1,
+            BoardMove::Left => column > 0,
+            BoardMove::Right => column < self.columns - 1,
+        }
+    }
+
+    fn exec_move(&mut self, board_move: BoardMove) {
+        assert!(self.can_move(board_move), "Board cannot execute this move");
+
+        self.materialize();
+        self.empty = match board_move {
+            BoardMove::Up => self.empty - self.columns as usize,
+            BoardMove::Down => self.empty + self.columns as usize,
+            BoardMove::Left => self.empty - 1,
+            BoardMove::Right => self.empty + 1,
+        };
+    }
+}
+
+impl SearchBoard for CowBoard {
+    fn prepare_expansion(&mut self) {
+        self.materialize();
+    }
+}
+
+impl PartialEq for CowBoard {
+    fn eq(&self, other: &Self) -> bool {
+        self.dimensions() == other.dimensions()
+            && self.empty == other.empty
+            && (0..self.cells.len()).all(|index| self.get(index) == other.get(index))
+    }
+}
+
+impl Eq for CowBoard {}
+
+impl Hash for CowBoard {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for index in 0..self.cells.len() {
+            state.write_u8(self.get(index));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moved_clone_shares_cells_with_original() {
+        let original = CowBoard::from(OwnedBoard::new_solved(3, 3));
+        let mut moved = original.clone();
+        moved.exec_move(BoardMove::Up);
+
+        assert!(Rc::ptr_eq(&original.cells, &moved.cells));
+        assert_eq!((1, 2), moved.empty_cell_pos());
+        assert_eq!(6, moved.at(2, 2));
+        assert_eq!(0, moved.at(1, 2));
+        assert!(original.is_solved());
+        assert!(!moved.is_solved());
+
+        moved.exec_move(BoardMove::Left);
+        assert!(!Rc::ptr_eq(&original.cells, &moved.cells));
+        let mut expected = OwnedBoard::new_solved(3, 3);
+        expected
+            .apply_moves(&[BoardMove::Up, BoardMove::Left])
+            .unwrap();
+        assert_eq!(expected, OwnedBoard::from(moved.clone()));
+        assert_eq!(CowBoard::from(expected), moved);
+    }
+
+    #[test]
+    fn indexes_all_cells_of_largest_board() {
+        // 256 cells, whose flat indices do not fit in a byte
+        let original = CowBoard::from(OwnedBoard::new_solved(16, 16));
+        let mut moved = original.clone();
+        moved.exec_move(BoardMove::Up);
+        moved.exec_move(BoardMove::Left);
+
+        assert_eq!((14, 14), moved.empty_cell_pos());
+        assert_eq!(240, moved.at(15, 15));
+        assert_eq!(239, moved.at(14, 15));
+        let mut expected = OwnedBoard::new_solved(16, 16);
+        expected
+            .apply_moves(&[BoardMove::Up, BoardMove::Left])
+            .unwrap();
+        assert_eq!(CowBoard::from(expected.clone()), moved);
+        assert_eq!(expected, OwnedBoard::from(moved));
+    }
+
+    #[test]
+    fn keeps_goal_of_the_board() {
+        let goal = OwnedBoard::with_cells(2, 2, vec![0, 1, 2, 3]).unwrap();
+        let board = CowBoard::from(goal.clone().with_goal(goal).unwrap());
+
+        assert!(board.is_solved());
+        assert_eq!((1, 1), board.goal_position(3));
+    }
+}
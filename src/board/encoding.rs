@@ -0,0 +1,131 @@
+use super::parsing::BoardCreationError;
+use super::OwnedBoard;
+
+/// Version of the binary encoding, stored in its first byte
+pub const ENCODING_VERSION: u8 = 1;
+
+/// Boards with at most this many cells store two cells per byte
+const MAX_NIBBLE_PACKED_CELLS: usize = 16;
+
+const HEADER_LENGTH: usize = 3;
+
+impl OwnedBoard {
+    /// Encodes the board in a compact binary form, to be used as a key in files or caches.
+    ///
+    /// The encoding starts with the version byte, the number of rows and the number of columns,
+    /// followed by the cells in row-major order.
+    /// Boards of up to 16 cells (e.g. the 15-puzzle) store two cells per byte, the first one in the low nibble,
+    /// larger boards store one cell per byte.
+    /// The goal configuration of the board is not encoded
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![ENCODING_VERSION, self.rows, self.columns];
+        if self.cells.len() <= MAX_NIBBLE_PACKED_CELLS {
+            bytes.extend(
+                self.cells
+                    .chunks(2)
+                    .map(|pair| pair[0] | pair.get(1).map_or(0, |high| high << 4)),
+            );
+        } else {
+            bytes.extend_from_slice(&self.cells);
+        }
+        bytes
+    }
+
+    /// Decodes the board encoded with [`OwnedBoard::to_bytes`]
+    ///
+    /// # Errors
+    /// Returns error if the encoding has an unknown version or a wrong length,
+    /// or if the decoded cells do not form a valid board
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BoardCreationError> {
+        let [version, rows, columns, packed @ ..] = bytes else {
+            return Err(BoardCreationError::InvalidHeader);
+        };
+        if *version != ENCODING_VERSION {
+            return Err(BoardCreationError::UnrecognizedFormat);
+        }
+        let cell_count = *rows as usize * *columns as usize;
+
+        let cells: Vec<u8> = if cell_count <= MAX_NIBBLE_PACKED_CELLS {
+            if packed.len() != cell_count.div_ceil(2) {
+                return Err(BoardCreationError::MissingCells);
+            }
+            packed
+                .iter()
+                .flat_map(|byte| [byte & 0xF, byte >> 4])
+                .take(cell_count)
+                .collect()
+        } else {
+            packed.to_vec()
+        };
+
+        Self::with_cells(*rows, *columns, cells)
+    }
+
+    /// Returns the length of the encoding of a board with given dimensions
+    #[must_use]
+    pub fn encoded_len(rows: u8, columns: u8) -> usize {
+        let cell_count = rows as usize * columns as usize;
+        if cell_count <= MAX_NIBBLE_PACKED_CELLS {
+            HEADER_LENGTH + cell_count.div_ceil(2)
+        } else {
+            HEADER_LENGTH + cell_count
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, BoardMove};
+
+    use super::*;
+
+    #[test]
+    fn fifteen_puzzle_is_nibble_packed() {
+        let mut board = OwnedBoard::new_solved(4, 4);
+        board.exec_move(BoardMove::Left);
+        let bytes = board.to_bytes();
+
+        assert_eq!(OwnedBoard::encoded_len(4, 4), bytes.len());
+        assert_eq!([ENCODING_VERSION, 4, 4, 0x21], bytes[..4]);
+        assert_eq!(0xF0, bytes[10]);
+        assert_eq!(board, OwnedBoard::from_bytes(&bytes).unwrap());
+    }
+
+    #[test]
+    fn round_trips_odd_and_large_boards() {
+        for (rows, columns) in [(3, 3), (2, 3), (5, 5), (6, 4)] {
+            let board = OwnedBoard::new_solved(rows, columns);
+            let bytes = board.to_bytes();
+
+            assert_eq!(OwnedBoard::encoded_len(rows, columns), bytes.len());
+            assert_eq!(board, OwnedBoard::from_bytes(&bytes).unwrap());
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_encodings() {
+        let bytes = OwnedBoard::new_solved(3, 3).to_bytes();
+
+        assert!(matches!(
+            OwnedBoard::from_bytes(&bytes[..2]),
+            Err(BoardCreationError::InvalidHeader)
+        ));
+        assert!(matches!(
+            OwnedBoard::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(BoardCreationError::MissingCells)
+        ));
+        let mut future_version = bytes.clone();
+        future_version[0] = ENCODING_VERSION + 1;
+        assert!(matches!(
+            OwnedBoard::from_bytes(&future_version),
+            Err(BoardCreationError::UnrecognizedFormat)
+        ));
+        let mut duplicate = bytes;
+        duplicate[3] = 0x11;
+        assert!(matches!(
+            OwnedBoard::from_bytes(&duplicate),
+            Err(BoardCreationError::DuplicateCells)
+        ));
+    }
+}
@@ -0,0 +1,145 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use super::Board;
+
+/// Version of the fingerprint function. It changes whenever the computed values change
+pub const FINGERPRINT_VERSION: u8 = 1;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Stable identity of a board position, to be stored in files and compared across program versions.
+///
+/// Unlike [`Hash`](std::hash::Hash), the value is guaranteed not to change between builds.
+/// Version 1 is the 64-bit FNV-1a hash of the bytes `[rows, columns, cells...]`,
+/// with the cells in row-major order and `0` for the empty cell.
+/// The goal configuration of the board is not part of the fingerprint.
+///
+/// The textual form is `v<version>:<hash as 16 hex digits>`
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Fingerprint {
+    pub version: u8,
+    pub hash: u64,
+}
+
+impl Fingerprint {
+    #[must_use]
+    pub fn of(board: &(impl Board + ?Sized)) -> Self {
+        let (rows, columns) = board.dimensions();
+        Self::of_cells(rows, columns, board.iter_cells().map(|(_, value)| value))
+    }
+
+    /// Computes the fingerprint of the board with given cells in row-major order
+    #[must_use]
+    pub fn of_cells(rows: u8, columns: u8, cells: impl IntoIterator<Item = u8>) -> Self {
+        let hash = [rows, columns]
+            .into_iter()
+            .chain(cells)
+            .fold(FNV_OFFSET_BASIS, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+            });
+        Self {
+            version: FINGERPRINT_VERSION,
+            hash,
+        }
+    }
+
+    /// Returns the version byte followed by the little-endian hash
+    #[must_use]
+    pub fn to_bytes(&self) -> [u8; 9] {
+        let mut bytes = [0; 9];
+        bytes[0] = self.version;
+        bytes[1..].copy_from_slice(&self.hash.to_le_bytes());
+        bytes
+    }
+
+    #[must_use]
+    pub fn from_bytes(bytes: [u8; 9]) -> Self {
+        let mut hash = [0; 8];
+        hash.copy_from_slice(&bytes[1..]);
+        Self {
+            version: bytes[0],
+            hash: u64::from_le_bytes(hash),
+        }
+    }
+}
+
+impl Display for Fingerprint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "v{}:{:016x}", self.version, self.hash)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ParseFingerprintError;
+
+impl Display for ParseFingerprintError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Fingerprint must have the form v<version>:<16 hex digits>"
+        )
+    }
+}
+
+impl Error for ParseFingerprintError {}
+
+impl FromStr for Fingerprint {
+    type Err = ParseFingerprintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (version, hash) = s
+            .strip_prefix('v')
+            .and_then(|rest| rest.split_once(':'))
+            .ok_or(ParseFingerprintError)?;
+        if hash.len() != 16 {
+            return Err(ParseFingerprintError);
+        }
+        Ok(Self {
+            version: version.parse().map_err(|_| ParseFingerprintError)?,
+            hash: u64::from_str_radix(hash, 16).map_err(|_| ParseFingerprintError)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::OwnedBoard;
+
+    use super::*;
+
+    #[test]
+    fn fingerprint_is_stable() {
+        // these values must never change for version 1, as they may be stored in files
+        assert_eq!(
+            "v1:d31688a5ba864c55",
+            Fingerprint::of(&OwnedBoard::new_solved(4, 4)).to_string()
+        );
+        assert_eq!(
+            "v1:4a1562c45408a209",
+            Fingerprint::of(&OwnedBoard::new_solved(3, 3)).to_string()
+        );
+    }
+
+    #[test]
+    fn fingerprint_depends_on_dimensions_and_cells() {
+        let wide = OwnedBoard::new_solved(2, 3);
+        let tall = OwnedBoard::with_cells(3, 2, vec![1, 2, 3, 4, 5, 0]).unwrap();
+        let mut moved = wide.clone();
+        moved.exec_move(crate::board::BoardMove::Left);
+
+        assert_ne!(Fingerprint::of(&wide), Fingerprint::of(&tall));
+        assert_ne!(Fingerprint::of(&wide), Fingerprint::of(&moved));
+    }
+
+    #[test]
+    fn round_trips_through_text_and_bytes() {
+        let fingerprint = Fingerprint::of(&OwnedBoard::new_solved(3, 4));
+
+        assert_eq!(Ok(fingerprint), fingerprint.to_string().parse());
+        assert_eq!(fingerprint, Fingerprint::from_bytes(fingerprint.to_bytes()));
+        assert_eq!(Err(ParseFingerprintError), "v1:12".parse::<Fingerprint>());
+    }
+}
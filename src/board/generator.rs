@@ -0,0 +1,65 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use super::{Board, BoardMove, OwnedBoard};
+
+/// Creates a board by applying `n_moves` random legal moves to the solved board.
+/// Moves never undo the directly preceding move, so the scramble does not waste moves on trivial back-and-forth.
+///
+/// Returns the scrambled board and the sequence of moves applied to the solved board
+pub fn scramble(
+    rows: u8,
+    columns: u8,
+    n_moves: usize,
+    rng: &mut impl Rng,
+) -> (OwnedBoard, Vec<BoardMove>) {
+    let mut board = OwnedBoard::new_solved(rows, columns);
+
+    let mut moves: Vec<BoardMove> = Vec::with_capacity(n_moves);
+    for _ in 0..n_moves {
+        let candidates: Vec<BoardMove> = BoardMove::ALL
+            .into_iter()
+            .filter(|&m| board.can_move(m))
+            .filter(|&m| moves.last() != Some(&m.opposite()))
+            .collect();
+
+        let Some(&next_move) = candidates.choose(rng) else {
+            // only possible on a board with a single row or column
+            break;
+        };
+        board.exec_move(next_move);
+        moves.push(next_move);
+    }
+
+    (board, moves)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::board::Board;
+
+    use super::*;
+
+    #[test]
+    fn reversed_scramble_solves_board() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let (mut board, moves) = scramble(4, 4, 40, &mut rng);
+
+        assert_eq!(40, moves.len());
+        for m in moves.iter().rev() {
+            board.exec_move(m.opposite());
+        }
+        assert!(board.is_solved());
+    }
+
+    #[test]
+    fn same_seed_gives_same_scramble() {
+        let first = scramble(3, 4, 25, &mut StdRng::seed_from_u64(42));
+        let second = scramble(3, 4, 25, &mut StdRng::seed_from_u64(42));
+
+        assert_eq!(first, second);
+    }
+}
@@ -1,12 +1,32 @@
+use std::error::Error;
 use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
 
+pub use array::{AnyBoard, ArrayBoard, CapacityExceeded};
+pub use cow::CowBoard;
 pub use owned::OwnedBoard;
+pub use parsing::{validate_cells, BoardCreationError};
+pub use recording::RecordingBoard;
+pub use transposed::TransposedBoard;
 
+mod array;
+mod cow;
+pub mod encoding;
+pub mod fingerprint;
+pub mod generator;
+pub mod korf;
 mod owned;
+pub mod packed;
 mod parsing;
+pub mod pieces;
+mod recording;
+pub mod scramble;
+pub mod sub_board;
+mod transposed;
 
 #[repr(u8)]
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BoardMove {
     Up,
     Down,
@@ -15,6 +35,14 @@ pub enum BoardMove {
 }
 
 impl BoardMove {
+    /// Every move, in the order of their discriminants
+    pub const ALL: [BoardMove; 4] = [
+        BoardMove::Up,
+        BoardMove::Down,
+        BoardMove::Left,
+        BoardMove::Right,
+    ];
+
     #[must_use]
     pub fn opposite(&self) -> Self {
         match self {
@@ -37,10 +65,228 @@ impl Display for BoardMove {
     }
 }
 
-pub trait Board {
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MoveError {
+    /// The empty cell cannot move in the given direction, because it is at the edge of the board
+    OutOfBounds(BoardMove),
+    /// The move at the given index of a move sequence cannot be performed
+    IllegalMove { index: usize, board_move: BoardMove },
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveError::OutOfBounds(board_move) => {
+                write!(f, "Move {board_move} would leave the board")
+            }
+            MoveError::IllegalMove { index, board_move } => {
+                write!(
+                    f,
+                    "Move {board_move} at index {index} would leave the board"
+                )
+            }
+        }
+    }
+}
+
+impl Error for MoveError {}
+
+/// Error returned when a character does not denote any move
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ParseMoveError(pub char);
+
+impl Display for ParseMoveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid move character {}", self.0)
+    }
+}
+
+impl Error for ParseMoveError {}
+
+impl TryFrom<char> for BoardMove {
+    type Error = ParseMoveError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value.to_ascii_uppercase() {
+            'U' => Ok(BoardMove::Up),
+            'D' => Ok(BoardMove::Down),
+            'L' => Ok(BoardMove::Left),
+            'R' => Ok(BoardMove::Right),
+            _ => Err(ParseMoveError(value)),
+        }
+    }
+}
+
+/// Parses a sequence of moves written as consecutive letters, e.g. `ULDR`
+///
+/// # Errors
+/// Returns error with the first character which does not denote a move
+pub fn parse_moves(s: &str) -> Result<Vec<BoardMove>, ParseMoveError> {
+    s.trim().chars().map(BoardMove::try_from).collect()
+}
+
+/// Conversion of any board into a trait object, used by the provided methods of [`Board`].
+/// It is implemented automatically for every sized board
+pub trait AsDynBoard {
+    fn as_dyn_board(&self) -> &dyn Board;
+}
+
+impl<B: Board> AsDynBoard for B {
+    fn as_dyn_board(&self) -> &dyn Board {
+        self
+    }
+}
+
+/// Iterator over the cells of a board in row-major order, created by [`Board::iter_cells`]
+pub struct Cells<'a> {
+    board: &'a dyn Board,
+    columns: u8,
+    index: usize,
+    len: usize,
+}
+
+impl Iterator for Cells<'_> {
+    type Item = ((u8, u8), u8);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            return None;
+        }
+        let row = (self.index / self.columns as usize) as u8;
+        let column = (self.index % self.columns as usize) as u8;
+        self.index += 1;
+        Some(((row, column), self.board.at(row, column)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Cells<'_> {}
+
+/// Position of the tile in the standard solved board
+pub(crate) fn standard_goal_position(tile: u8, (rows, columns): (u8, u8)) -> (u8, u8) {
+    if tile == 0 {
+        (rows - 1, columns - 1)
+    } else {
+        ((tile - 1) / columns, (tile - 1) % columns)
+    }
+}
+
+/// Returns the cells of the goal configuration of the board in row-major order
+pub(crate) fn goal_cells(board: &(impl Board + ?Sized)) -> Vec<u8> {
+    let (rows, columns) = board.dimensions();
+    let mut cells = vec![0; rows as usize * columns as usize];
+    for tile in 0..cells.len() as u8 {
+        let (row, column) = board.goal_position(tile);
+        cells[row as usize * columns as usize + column as usize] = tile;
+    }
+    cells
+}
+
+/// Boards of any representation are equal when they are in the same state, see [`Board::same_state`]
+impl PartialEq for dyn Board + '_ {
+    fn eq(&self, other: &Self) -> bool {
+        self.same_state(other)
+    }
+}
+
+impl Eq for dyn Board + '_ {}
+
+impl Hash for dyn Board + '_ {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.state_key().hash(state);
+    }
+}
+
+impl Clone for Box<dyn Board> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// Board representation which can be stored in the nodes of the search algorithms.
+/// The conversion fails for representations which cannot hold every board, e.g. [`ArrayBoard`]
+pub trait SearchBoard:
+    Board
+    + Clone
+    + Eq
+    + std::hash::Hash
+    + TryFrom<OwnedBoard, Error: Into<Box<dyn std::error::Error + Send + Sync>>>
+{
+    /// Called before the moves from the board are generated, when the node is expanded
+    fn prepare_expansion(&mut self) {}
+}
+
+impl SearchBoard for OwnedBoard {}
+
+/// Returns the move which transforms board `a` into board `b`,
+/// or `None` if the boards are not exactly one move apart
+#[must_use]
+pub fn diff(a: &dyn Board, b: &dyn Board) -> Option<BoardMove> {
+    if a.dimensions() != b.dimensions() {
+        return None;
+    }
+    let (row, column) = a.empty_cell_pos();
+    let target = b.empty_cell_pos();
+    let board_move = BoardMove::ALL.into_iter().find(|&board_move| {
+        a.can_move(board_move)
+            && target
+                == match board_move {
+                    BoardMove::Up => (row - 1, column),
+                    BoardMove::Down => (row + 1, column),
+                    BoardMove::Left => (row, column - 1),
+                    BoardMove::Right => (row, column + 1),
+                }
+    })?;
+
+    // the moved tile swaps places with the empty cell, every other tile stays in place
+    let moved_tile = a.at(target.0, target.1);
+    a.iter_cells()
+        .all(|(position, value)| {
+            let expected = if position == (row, column) {
+                moved_tile
+            } else if position == target {
+                0
+            } else {
+                value
+            };
+            b.at(position.0, position.1) == expected
+        })
+        .then_some(board_move)
+}
+
+pub trait Board: AsDynBoard {
     /// Returns number of rows and columns
     fn dimensions(&self) -> (u8, u8);
 
+    fn rows(&self) -> u8 {
+        self.dimensions().0
+    }
+
+    fn columns(&self) -> u8 {
+        self.dimensions().1
+    }
+
+    /// Returns the position of the tile in the goal configuration, with `0` denoting the empty cell.
+    /// By default the goal is the standard solved board, with the empty cell in the bottom-right corner
+    fn goal_position(&self, tile: u8) -> (u8, u8) {
+        standard_goal_position(tile, self.dimensions())
+    }
+
+    /// Returns the position and value of every cell, row by row
+    fn iter_cells(&self) -> Cells<'_> {
+        let (rows, columns) = self.dimensions();
+        Cells {
+            board: self.as_dyn_board(),
+            columns,
+            index: 0,
+            len: rows as usize * columns as usize,
+        }
+    }
+
     fn at(&self, row: u8, column: u8) -> u8;
 
     /// Returns the row and column index of the empty cell
@@ -55,4 +301,50 @@ pub trait Board {
     /// This function may panic if the move cannot be performed.
     /// To avoid it, check before if a move can be executed using [`can_move`](Board::can_move)
     fn exec_move(&mut self, board_move: BoardMove);
+
+    /// Executes the move if it can be performed, leaving the board unchanged otherwise
+    ///
+    /// # Errors
+    /// Returns error if the move cannot be performed
+    fn try_exec_move(&mut self, board_move: BoardMove) -> Result<(), MoveError> {
+        if !self.can_move(board_move) {
+            return Err(MoveError::OutOfBounds(board_move));
+        }
+        self.exec_move(board_move);
+        Ok(())
+    }
+
+    /// Returns an owned copy of the board, for code which stores boards available only as trait objects.
+    /// By default it is an [`OwnedBoard`] with the same cells and goal
+    fn clone_box(&self) -> Box<dyn Board> {
+        Box::new(OwnedBoard::from_board(self.as_dyn_board()))
+    }
+
+    /// Returns a key of the position of the tiles, equal for boards in the same state regardless of their representation.
+    /// It is the hash of the [`Fingerprint`](fingerprint::Fingerprint), so different states may share the key,
+    /// and [`same_state`](Board::same_state) tells them apart
+    fn state_key(&self) -> u64 {
+        fingerprint::Fingerprint::of(self.as_dyn_board()).hash
+    }
+
+    /// Checks if the boards have the same dimensions and the same tiles in every cell.
+    /// Like [`state_key`](Board::state_key), it ignores the goal and the representation of the boards
+    fn same_state(&self, other: &dyn Board) -> bool {
+        self.dimensions() == other.dimensions() && self.iter_cells().eq(other.iter_cells())
+    }
+
+    /// Executes the moves in order, stopping at the first one which cannot be performed.
+    /// The moves before it remain executed
+    ///
+    /// # Errors
+    /// Returns [`MoveError::IllegalMove`] with the index of the first move which cannot be performed
+    fn apply_moves(&mut self, moves: &[BoardMove]) -> Result<(), MoveError> {
+        for (index, &board_move) in moves.iter().enumerate() {
+            if !self.can_move(board_move) {
+                return Err(MoveError::IllegalMove { index, board_move });
+            }
+            self.exec_move(board_move);
+        }
+        Ok(())
+    }
 }
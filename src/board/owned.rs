@@ -1,13 +1,111 @@
-use super::{Board, BoardMove};
-
-#[derive(Clone, Eq, PartialEq, Debug)]
+use std::sync::Arc;
+
+use super::parsing::{validate_cells, BoardCreationError};
+use super::{standard_goal_position, Board, BoardMove};
+
+#[derive(Eq, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        try_from = "serialized::SerializedBoard",
+        into = "serialized::SerializedBoard"
+    )
+)]
 pub struct OwnedBoard {
     pub(super) rows: u8,
     pub(super) columns: u8,
     pub(super) cells: Box<[u8]>,
+    /// Goal position of every tile, indexed by the tile. `None` means the standard solved board
+    pub(super) goal: Option<Arc<[(u8, u8)]>>,
 }
 
 impl OwnedBoard {
+    /// Creates the solved board of the given dimensions
+    #[must_use]
+    pub fn new_solved(rows: u8, columns: u8) -> Self {
+        let cell_count = rows as usize * columns as usize;
+        Self {
+            rows,
+            columns,
+            cells: (1..cell_count)
+                .map(|cell| cell as u8)
+                .chain(std::iter::once(0))
+                .collect(),
+            goal: None,
+        }
+    }
+
+    /// Creates the board from the cell values in row-major order, with `0` marking the empty cell
+    ///
+    /// # Errors
+    /// Returns error if the cells do not contain every value from `0` to `rows * columns - 1` exactly once
+    pub fn with_cells(rows: u8, columns: u8, cells: Vec<u8>) -> Result<Self, BoardCreationError> {
+        validate_cells(rows, columns, &cells)?;
+        Ok(Self {
+            rows,
+            columns,
+            cells: cells.into_boxed_slice(),
+            goal: None,
+        })
+    }
+
+    /// Checks the invariants of the board with [`validate_cells`](super::validate_cells).
+    /// They always hold for boards created by this crate, the goal is validated when it is set
+    ///
+    /// # Errors
+    /// Returns error describing the first violated invariant
+    pub fn validate(&self) -> Result<(), BoardCreationError> {
+        validate_cells(self.rows, self.columns, &self.cells)
+    }
+
+    /// Replaces the goal configuration of the board, which is the standard solved board by default.
+    /// Solvability checks, solvers and heuristics then aim for the given position
+    ///
+    /// # Errors
+    /// Returns error if the goal has different dimensions than the board
+    pub fn with_goal(mut self, goal: OwnedBoard) -> Result<Self, BoardCreationError> {
+        if goal.dimensions() != self.dimensions() {
+            return Err(BoardCreationError::GoalDimensionsMismatch);
+        }
+        let mut positions = vec![(0, 0); self.cells.len()];
+        for (position, tile) in goal.iter_cells() {
+            positions[tile as usize] = position;
+        }
+        self.goal = Some(positions.into());
+        Ok(self)
+    }
+
+    /// Copies the cells and the goal of a board of any representation
+    #[must_use]
+    pub fn from_board(board: &(impl Board + ?Sized)) -> Self {
+        let (rows, columns) = board.dimensions();
+        let positions: Vec<_> = (0..rows * columns)
+            .map(|tile| board.goal_position(tile))
+            .collect();
+        let is_standard = positions
+            .iter()
+            .zip(0..)
+            .all(|(&position, tile)| position == standard_goal_position(tile, (rows, columns)));
+        Self {
+            rows,
+            columns,
+            cells: board.iter_cells().map(|(_, tile)| tile).collect(),
+            goal: (!is_standard).then(|| positions.into()),
+        }
+    }
+
+    /// Returns the goal configuration of the board
+    #[must_use]
+    pub fn goal(&self) -> OwnedBoard {
+        Self {
+            rows: self.rows,
+            columns: self.columns,
+            cells: super::goal_cells(self).into_boxed_slice(),
+            goal: None,
+        }
+    }
+
     /// Convert 2D representation of cell coordinate to a single index in the underlying vec
     fn flatten_index(&self, row: u8, column: u8) -> usize {
         row as usize * self.columns as usize + column as usize
@@ -35,7 +133,19 @@ impl Board for OwnedBoard {
         (row as u8, column as u8)
     }
 
+    fn goal_position(&self, tile: u8) -> (u8, u8) {
+        self.goal.as_ref().map_or_else(
+            || standard_goal_position(tile, self.dimensions()),
+            |positions| positions[tile as usize],
+        )
+    }
+
     fn is_solved(&self) -> bool {
+        if let Some(positions) = &self.goal {
+            return self
+                .iter_cells()
+                .all(|(position, tile)| positions[tile as usize] == position);
+        }
         // first check if the empty square is at the last position,
         // as in most cases that will not be the case,
         // thus eliminating the need for checking any other squares
@@ -49,6 +159,10 @@ impl Board for OwnedBoard {
                 .all(|(actual, expected)| actual == expected as u8)
     }
 
+    fn clone_box(&self) -> Box<dyn Board> {
+        Box::new(self.clone())
+    }
+
     fn can_move(&self, board_move: BoardMove) -> bool {
         match board_move {
             BoardMove::Up => self.empty_cell_pos().0 > 0,
@@ -80,6 +194,96 @@ impl Board for OwnedBoard {
     }
 }
 
+/// Serialized form of the board, which is validated when deserializing
+#[cfg(feature = "serde")]
+mod serialized {
+    use super::{BoardCreationError, OwnedBoard};
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    pub(super) struct SerializedBoard {
+        rows: u8,
+        columns: u8,
+        cells: Vec<u8>,
+        /// Cells of the goal configuration, omitted for the standard solved board
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        goal: Option<Vec<u8>>,
+    }
+
+    impl From<OwnedBoard> for SerializedBoard {
+        fn from(board: OwnedBoard) -> Self {
+            Self {
+                rows: board.rows,
+                columns: board.columns,
+                goal: board.goal.is_some().then(|| board.goal().cells.into_vec()),
+                cells: board.cells.into_vec(),
+            }
+        }
+    }
+
+    impl TryFrom<SerializedBoard> for OwnedBoard {
+        type Error = BoardCreationError;
+
+        fn try_from(value: SerializedBoard) -> Result<Self, Self::Error> {
+            let board = OwnedBoard::with_cells(value.rows, value.columns, value.cells)?;
+            match value.goal {
+                Some(goal) => {
+                    board.with_goal(OwnedBoard::with_cells(value.rows, value.columns, goal)?)
+                }
+                None => Ok(board),
+            }
+        }
+    }
+}
+
+impl Clone for OwnedBoard {
+    fn clone(&self) -> Self {
+        Self {
+            rows: self.rows,
+            columns: self.columns,
+            cells: self.cells.clone(),
+            goal: self.goal.clone(),
+        }
+    }
+
+    /// Reuses the cell buffer if both boards have the same number of cells
+    fn clone_from(&mut self, source: &Self) {
+        if self.cells.len() == source.cells.len() {
+            self.cells.copy_from_slice(&source.cells);
+        } else {
+            self.cells = source.cells.clone();
+        }
+        self.rows = source.rows;
+        self.columns = source.columns;
+        self.goal.clone_from(&source.goal);
+    }
+}
+
+/// Creates the board from its rows, validated as in [`OwnedBoard::with_cells`].
+/// All rows must have the same number of cells
+impl TryFrom<Vec<Vec<u8>>> for OwnedBoard {
+    type Error = BoardCreationError;
+
+    fn try_from(value: Vec<Vec<u8>>) -> Result<Self, Self::Error> {
+        let rows = u8::try_from(value.len()).map_err(|_| BoardCreationError::UnrecognizedFormat)?;
+        let columns = value.first().map_or(0, Vec::len);
+        let columns = u8::try_from(columns).map_err(|_| BoardCreationError::UnrecognizedFormat)?;
+        if value.iter().any(|row| row.len() != columns as usize) {
+            return Err(BoardCreationError::MissingCells);
+        }
+        Self::with_cells(rows, columns, value.concat())
+    }
+}
+
+/// Creates the board from the cells in row-major order, the number of rows and the number of columns,
+/// validated as in [`OwnedBoard::with_cells`]
+impl TryFrom<(&[u8], u8, u8)> for OwnedBoard {
+    type Error = BoardCreationError;
+
+    fn try_from((cells, rows, columns): (&[u8], u8, u8)) -> Result<Self, Self::Error> {
+        Self::with_cells(rows, columns, cells.to_vec())
+    }
+}
+
 impl std::hash::Hash for OwnedBoard {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.cells.hash(state);
@@ -91,6 +295,7 @@ mod tests {
     use std::iter::once;
 
     use crate::board::owned::OwnedBoard;
+    use crate::board::parsing::BoardCreationError;
     use crate::board::*;
 
     fn create_solved_board() -> OwnedBoard {
@@ -98,6 +303,7 @@ mod tests {
             rows: 4,
             columns: 4,
             cells: (1..=15).chain(once(0)).collect(),
+            goal: None,
         }
     }
 
@@ -108,6 +314,7 @@ mod tests {
             rows: 4,
             columns: 4,
             cells: (1..=16).collect(),
+            goal: None,
         }
     }
 
@@ -118,6 +325,123 @@ mod tests {
         assert!(solved_board.is_solved());
     }
 
+    #[test]
+    fn new_solved_creates_solved_board() {
+        assert_eq!(create_solved_board(), OwnedBoard::new_solved(4, 4));
+        assert!(OwnedBoard::new_solved(3, 5).is_solved());
+    }
+
+    #[test]
+    fn with_cells_validates_cells() {
+        let board = OwnedBoard::with_cells(2, 2, vec![1, 2, 3, 0]).unwrap();
+        assert!(board.validate().is_ok());
+        assert!(matches!(
+            OwnedBoard::with_cells(2, 2, vec![1, 2, 2, 0]),
+            Err(BoardCreationError::DuplicateCells)
+        ));
+        assert!(matches!(
+            OwnedBoard::with_cells(2, 2, vec![1, 2, 0]),
+            Err(BoardCreationError::MissingCells)
+        ));
+    }
+
+    #[test]
+    fn board_is_created_from_nested_rows_and_slices() {
+        let expected = OwnedBoard::with_cells(2, 3, vec![1, 2, 3, 4, 0, 5]).unwrap();
+
+        assert_eq!(
+            expected,
+            OwnedBoard::try_from(vec![vec![1, 2, 3], vec![4, 0, 5]]).unwrap()
+        );
+        assert_eq!(
+            expected,
+            OwnedBoard::try_from((&[1, 2, 3, 4, 0, 5][..], 2, 3)).unwrap()
+        );
+        assert!(matches!(
+            OwnedBoard::try_from(vec![vec![1, 2, 3], vec![4, 0]]),
+            Err(BoardCreationError::MissingCells)
+        ));
+        assert!(matches!(
+            OwnedBoard::try_from(vec![vec![1, 2], vec![2, 0]]),
+            Err(BoardCreationError::DuplicateCells)
+        ));
+        assert!(OwnedBoard::try_from(Vec::<Vec<u8>>::new()).is_err());
+        assert!(matches!(
+            OwnedBoard::try_from((&[1, 2, 3, 0][..], 2, 3)),
+            Err(BoardCreationError::MissingCells)
+        ));
+    }
+
+    #[test]
+    fn board_with_goal_is_solved_in_goal_position() {
+        let goal = OwnedBoard::with_cells(2, 2, vec![0, 1, 2, 3]).unwrap();
+        let board = goal.clone().with_goal(goal.clone()).unwrap();
+
+        assert!(board.is_solved());
+        assert_eq!((0, 0), board.goal_position(0));
+        assert_eq!((1, 1), board.goal_position(3));
+        assert_eq!(goal, board.goal());
+        assert!(!OwnedBoard::new_solved(2, 2)
+            .with_goal(goal.clone())
+            .unwrap()
+            .is_solved());
+        assert!(OwnedBoard::new_solved(3, 2).with_goal(goal).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_validates_cells() {
+        let board = create_solved_board()
+            .with_goal(OwnedBoard::with_cells(4, 4, (0..16).collect()).unwrap())
+            .unwrap();
+
+        let json = serde_json::to_string(&board).unwrap();
+        assert_eq!(board, serde_json::from_str::<OwnedBoard>(&json).unwrap());
+        assert_eq!(
+            r#"{"rows":2,"columns":2,"cells":[1,2,3,0]}"#,
+            serde_json::to_string(&OwnedBoard::new_solved(2, 2)).unwrap()
+        );
+        assert!(
+            serde_json::from_str::<OwnedBoard>(r#"{"rows":2,"columns":2,"cells":[1,1,3,0]}"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn iter_cells_visits_cells_row_by_row() {
+        let board = OwnedBoard::new_solved(2, 3);
+        let cells: Vec<_> = board.iter_cells().collect();
+
+        assert_eq!(6, board.iter_cells().len());
+        assert_eq!(((0, 0), 1), cells[0]);
+        assert_eq!(((1, 0), 4), cells[3]);
+        assert_eq!(((1, 2), 0), cells[5]);
+        assert_eq!((2, 3), (board.rows(), board.columns()));
+    }
+
+    #[test]
+    fn diff_finds_move_between_adjacent_boards() {
+        let board = OwnedBoard::new_solved(3, 3);
+        let mut moved = board.clone();
+        moved.exec_move(BoardMove::Up);
+        let mut other = moved.clone();
+        other.exec_move(BoardMove::Left);
+
+        assert_eq!(Some(BoardMove::Up), diff(&board, &moved));
+        assert_eq!(Some(BoardMove::Down), diff(&moved, &board));
+        assert_eq!(None, diff(&board, &board));
+        assert_eq!(None, diff(&board, &other));
+        assert_eq!(None, diff(&board, &OwnedBoard::new_solved(3, 4)));
+    }
+
+    #[test]
+    fn diff_rejects_boards_with_other_tiles_swapped() {
+        let board: OwnedBoard = "2 2\n1 2\n3 0".parse().unwrap();
+        let swapped: OwnedBoard = "2 2\n2 1\n0 3".parse().unwrap();
+
+        assert_eq!(None, diff(&board, &swapped));
+    }
+
     #[test]
     fn can_move_works_correctly() {
         let mut board = create_filled_board();
@@ -137,7 +461,7 @@ mod tests {
     }
 
     mod exec_move {
-        use crate::board::{Board, BoardMove};
+        use crate::board::{Board, BoardMove, MoveError, OwnedBoard};
 
         use super::create_filled_board;
 
@@ -188,5 +512,56 @@ mod tests {
             assert_eq!((0, 1), board.empty_cell_pos());
             assert_eq!(cell_right, board.at(0, 0));
         }
+
+        #[test]
+        fn try_exec_move_rejects_illegal_move() {
+            let mut board = create_filled_board();
+            board.cells[0] = 0;
+            let before = board.clone();
+
+            assert_eq!(
+                Err(MoveError::OutOfBounds(BoardMove::Up)),
+                board.try_exec_move(BoardMove::Up)
+            );
+            assert_eq!(before, board);
+            assert!(board.try_exec_move(BoardMove::Down).is_ok());
+        }
+
+        #[test]
+        fn boxed_copy_keeps_goal() {
+            let goal = OwnedBoard::with_cells(2, 2, vec![0, 1, 2, 3]).unwrap();
+            let board = OwnedBoard::with_cells(2, 2, vec![1, 0, 2, 3])
+                .unwrap()
+                .with_goal(goal)
+                .unwrap();
+            let recording = crate::board::RecordingBoard::new(board.clone());
+
+            let copy = recording.clone_box();
+            assert!(&board as &dyn Board == &*copy);
+            assert_eq!((0, 0), copy.goal_position(0));
+            assert_eq!(board, OwnedBoard::from_board(&*copy));
+            assert_eq!(
+                OwnedBoard::new_solved(3, 3),
+                OwnedBoard::from_board(&*OwnedBoard::new_solved(3, 3).clone_box())
+            );
+        }
+
+        #[test]
+        fn apply_moves_reports_first_illegal_move() {
+            let mut board = OwnedBoard::new_solved(3, 3);
+
+            assert_eq!(
+                Err(MoveError::IllegalMove {
+                    index: 2,
+                    board_move: BoardMove::Down
+                }),
+                board.apply_moves(&[BoardMove::Up, BoardMove::Down, BoardMove::Down])
+            );
+            assert!(board.is_solved());
+            assert!(board
+                .apply_moves(&[BoardMove::Left, BoardMove::Up, BoardMove::Right])
+                .is_ok());
+            assert_eq!((1, 2), board.empty_cell_pos());
+        }
     }
 }
@@ -0,0 +1,191 @@
+use super::{Board, BoardMove, OwnedBoard};
+
+const ROWS: u8 = 4;
+const COLUMNS: u8 = 4;
+const CELL_BITS: u32 = 4;
+const CELL_MASK: u64 = 0xF;
+
+/// Standard 4x4 board stored in a single `u64`, with 4 bits per cell.
+/// Cloning and hashing it is much cheaper than for [`OwnedBoard`]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct PackedBoard {
+    cells: u64,
+    /// Flat index of the empty cell, cached to avoid scanning the cells on every move
+    empty_index: u8,
+}
+
+/// Value of the packed cells when the board is solved
+const SOLVED_CELLS: u64 = {
+    let mut cells = 0;
+    let mut index = 0;
+    while index < 15 {
+        cells |= (index + 1) << (index * CELL_BITS as u64);
+        index += 1;
+    }
+    cells
+};
+
+impl PackedBoard {
+    /// Packs the board, returning `None` if it is not a 4x4 board
+    #[must_use]
+    pub fn from_board(board: &dyn Board) -> Option<Self> {
+        if board.dimensions() != (ROWS, COLUMNS) {
+            return None;
+        }
+
+        let mut cells = 0;
+        for ((row, column), value) in board.iter_cells() {
+            let index = Self::flatten_index(row, column);
+            cells |= u64::from(value) << (index as u32 * CELL_BITS);
+        }
+        let (empty_row, empty_column) = board.empty_cell_pos();
+
+        Some(Self {
+            cells,
+            empty_index: Self::flatten_index(empty_row, empty_column),
+        })
+    }
+
+    fn flatten_index(row: u8, column: u8) -> u8 {
+        row * COLUMNS + column
+    }
+
+    fn get(&self, index: u8) -> u8 {
+        ((self.cells >> (u32::from(index) * CELL_BITS)) & CELL_MASK) as u8
+    }
+}
+
+impl From<PackedBoard> for OwnedBoard {
+    fn from(value: PackedBoard) -> Self {
+        OwnedBoard {
+            rows: ROWS,
+            columns: COLUMNS,
+            cells: (0..ROWS * COLUMNS).map(|i| value.get(i)).collect(),
+            goal: None,
+        }
+    }
+}
+
+impl Board for PackedBoard {
+    fn dimensions(&self) -> (u8, u8) {
+        (ROWS, COLUMNS)
+    }
+
+    fn at(&self, row: u8, column: u8) -> u8 {
+        self.get(Self::flatten_index(row, column))
+    }
+
+    fn empty_cell_pos(&self) -> (u8, u8) {
+        (self.empty_index / COLUMNS, self.empty_index % COLUMNS)
+    }
+
+    fn is_solved(&self) -> bool {
+        self.cells == SOLVED_CELLS
+    }
+
+    fn clone_box(&self) -> Box<dyn Board> {
+        Box::new(*self)
+    }
+
+    fn can_move(&self, board_move: BoardMove) -> bool {
+        let (row, column) = self.empty_cell_pos();
+        match board_move {
+            BoardMove::Up => row > 0,
+            BoardMove::Down => row < ROWS - 1,
+            BoardMove::Left => column > 0,
+            BoardMove::Right => column < COLUMNS - 1,
+        }
+    }
+
+    fn exec_move(&mut self, board_move: BoardMove) {
+        assert!(self.can_move(board_move), "Board cannot execute this move");
+
+        let target_index = match board_move {
+            BoardMove::Up => self.empty_index - COLUMNS,
+            BoardMove::Down => self.empty_index + COLUMNS,
+            BoardMove::Left => self.empty_index - 1,
+            BoardMove::Right => self.empty_index + 1,
+        };
+
+        // empty cell has value 0, so moving the tile is just shifting its bits
+        let target_value = u64::from(self.get(target_index));
+        self.cells &= !(CELL_MASK << (u32::from(target_index) * CELL_BITS));
+        self.cells |= target_value << (u32::from(self.empty_index) * CELL_BITS);
+        self.empty_index = target_index;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, BoardMove, OwnedBoard};
+
+    use super::PackedBoard;
+
+    const INPUT: &str = r"4 4
+1  2  3  4
+5  6  0  8
+9  10 7  12
+13 14 11 15
+";
+
+    #[test]
+    fn conversion_round_trips() {
+        let board: OwnedBoard = INPUT.parse().unwrap();
+        let packed = PackedBoard::from_board(&board).unwrap();
+
+        assert_eq!(board.empty_cell_pos(), packed.empty_cell_pos());
+        assert_eq!(board, OwnedBoard::from(packed));
+    }
+
+    #[test]
+    fn rejects_other_dimensions() {
+        let board: OwnedBoard = "2 2\n1 2\n3 0".parse().unwrap();
+        assert!(PackedBoard::from_board(&board).is_none());
+    }
+
+    #[test]
+    fn moves_match_owned_board() {
+        use BoardMove::*;
+        let mut board: OwnedBoard = INPUT.parse().unwrap();
+        let mut packed = PackedBoard::from_board(&board).unwrap();
+
+        for m in [Down, Down, Right, Up, Left, Left, Left, Up, Up] {
+            assert_eq!(board.can_move(m), packed.can_move(m));
+            board.exec_move(m);
+            packed.exec_move(m);
+            assert_eq!(board, OwnedBoard::from(packed));
+        }
+    }
+
+    #[test]
+    fn solved_board_shows_as_solved() {
+        use BoardMove::*;
+        let mut packed = PackedBoard::from_board(&INPUT.parse::<OwnedBoard>().unwrap()).unwrap();
+        assert!(!packed.is_solved());
+
+        for m in [Down, Down, Right] {
+            packed.exec_move(m);
+        }
+        assert!(packed.is_solved());
+    }
+
+    #[test]
+    fn trait_objects_compare_by_state() {
+        use std::collections::HashSet;
+
+        let board: OwnedBoard = INPUT.parse().unwrap();
+        let packed = PackedBoard::from_board(&board).unwrap();
+        let mut moved = packed;
+        moved.exec_move(BoardMove::Down);
+
+        assert_eq!(board.state_key(), packed.state_key());
+        assert!(board.same_state(&packed));
+        assert!(!moved.same_state(&board));
+
+        let boards: HashSet<Box<dyn Board>> =
+            [board.clone_box(), packed.clone_box(), moved.clone_box()]
+                .into_iter()
+                .collect();
+        assert_eq!(2, boards.len());
+    }
+}
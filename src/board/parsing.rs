@@ -1,5 +1,4 @@
 use std::borrow::Borrow;
-use std::cmp::Ordering;
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
 use std::num::ParseIntError;
@@ -17,90 +16,548 @@ impl FromStr for OwnedBoard {
 }
 
 impl OwnedBoard {
+    /// Parses the board from the lines of the input, starting with the size header.
+    ///
+    /// Blank lines and comment lines starting with `#` are skipped, and lines may end with `\r`.
+    /// The iterator is advanced exactly to the last row of the board, so the following lines
+    /// can be parsed as another board.
+    ///
+    /// Errors found at a specific line of the input carry its number, counted from 1,
+    /// errors concerning the whole board are reported at the line of the header
     pub fn try_from_iter<I: Borrow<str>>(
-        mut lines: impl Iterator<Item = I>,
+        lines: impl Iterator<Item = I>,
     ) -> Result<Self, BoardCreationError> {
+        let mut lines = lines
+            .enumerate()
+            .map(|(index, line)| (index + 1, line))
+            .filter(|(_, line)| !is_skipped_line(line.borrow()));
+
+        let (header_line, first_line_raw) = lines
+            .next()
+            .ok_or(BoardCreationError::InvalidHeader.at_line(1))?;
         let (rows, columns) = {
-            let first_line_raw = lines.next().ok_or(BoardCreationError::InvalidHeader)?;
-            let first_line = first_line_raw
-                .borrow()
-                .split_whitespace()
-                .collect::<Vec<_>>();
-
-            if first_line.len() != 2 {
-                return Err(BoardCreationError::InvalidHeader);
+            let first_line = strip_line_ending(first_line_raw.borrow());
+            if first_line.contains(':') {
+                return Self::from_compact(first_line).map_err(|e| e.at_line(header_line));
+            }
+            if is_hex_line(first_line) {
+                return Self::from_hex(first_line).map_err(|e| e.at_line(header_line));
+            }
+            let header: Vec<_> = tokens(first_line, char::is_whitespace).collect();
+
+            if header.len() != 2 {
+                return Err(BoardCreationError::InvalidHeader.at_line(header_line));
             }
 
-            let parsed: Vec<u8> = first_line
+            let parsed: Vec<u8> = header
                 .into_iter()
-                .map(str::parse)
+                .map(|(offset, token)| parse_token(token, header_line, offset))
                 .collect::<Result<_, _>>()?;
 
             (parsed[0], parsed[1])
         };
+        if rows == 0 || columns == 0 {
+            return Err(BoardCreationError::InvalidHeader.at_line(header_line));
+        }
 
         let mut cells = vec![0u8; rows as usize * columns as usize];
 
         let mut row_count: usize = 0;
-        for (board_row, input_line) in cells
-            .chunks_mut(columns as usize)
-            .zip(lines.take(rows as usize).by_ref())
-        {
-            let values: Vec<u8> = input_line
-                .borrow()
-                .split_whitespace()
+        let mut last_line = header_line;
+        // the rows are zipped after the chunks, so no line is taken after the last row
+        for (board_row, (line, input_line)) in cells.chunks_mut(columns as usize).zip(&mut lines) {
+            let values: Vec<u8> = tokens(input_line.borrow(), char::is_whitespace)
                 .take(columns as usize)
-                .map(str::parse)
+                .map(|(offset, token)| parse_token(token, line, offset))
                 .collect::<Result<_, _>>()?;
 
             debug_assert!(board_row.len() == columns as usize);
             if values.len() != board_row.len() {
-                return Err(BoardCreationError::MissingCells);
+                return Err(BoardCreationError::MissingCells.at_line(line));
             }
             board_row.clone_from_slice(&values);
 
             row_count += 1;
+            last_line = line;
         }
 
         if row_count != rows as usize {
-            return Err(BoardCreationError::MissingCells);
+            return Err(BoardCreationError::MissingCells.at_line(last_line + 1));
         }
 
-        for i in 0..=(columns * rows - 1) {
-            match cells.iter().copied().filter(|&x| x == i).count().cmp(&1) {
-                Ordering::Less => return Err(BoardCreationError::MissingCells),
-                Ordering::Greater => return Err(BoardCreationError::DuplicateCells),
-                Ordering::Equal => {}
+        validate_cells(rows, columns, &cells).map_err(|e| e.at_line(header_line))?;
+
+        Ok(Self {
+            rows,
+            columns,
+            cells: cells.into_boxed_slice(),
+            goal: None,
+        })
+    }
+
+    /// Writes the board in the format accepted by [`OwnedBoard::try_from_iter`]: the size header
+    /// followed by the rows of cells, each line ending with a newline.
+    /// Cells are right-aligned to the width of the largest value and separated by a single space,
+    /// so equal boards are always written the same way. The goal configuration is not written
+    #[must_use]
+    pub fn to_input_string(&self) -> String {
+        let width = (self.cells.len() - 1).to_string().len();
+        let mut output = format!("{} {}\n", self.rows, self.columns);
+        for row in self.cells.chunks(self.columns as usize) {
+            let cells: Vec<_> = row.iter().map(|cell| format!("{cell:>width$}")).collect();
+            output.push_str(&cells.join(" "));
+            output.push('\n');
+        }
+        output
+    }
+
+    /// Parses consecutive boards from the lines, e.g. a file with many puzzles for batch experiments.
+    ///
+    /// Every board has its own header, and boards may be separated by blank lines and `#` comment lines.
+    /// Boards written in a single line, in the compact format or as in instance datasets, are also accepted.
+    /// The iterator ends when there are no more lines. After an error, parsing resumes at the following line,
+    /// so callers should usually stop at the first error
+    pub fn parse_many<I: Borrow<str>>(
+        lines: impl IntoIterator<Item = I>,
+    ) -> impl Iterator<Item = Result<Self, BoardCreationError>> {
+        Self::parse_blocks(lines, false)
+    }
+
+    /// Parses consecutive boards like [`OwnedBoard::parse_many`], additionally accepting boards without the size header,
+    /// as found in many puzzle datasets. Their dimensions are inferred from the grid of cells, see [`OwnedBoard::from_grid`].
+    ///
+    /// A line with two numbers is always read as the header, so boards with two columns need one.
+    /// Other lines with at least 9 cells which form a board by themselves are read as instance lines.
+    /// A board without the header takes every following row of the same width, so it has to be separated
+    /// from the next one by a blank line, a comment or a header, and a row of another width is an error
+    pub fn parse_many_lenient<I: Borrow<str>>(
+        lines: impl IntoIterator<Item = I>,
+    ) -> impl Iterator<Item = Result<Self, BoardCreationError>> {
+        Self::parse_blocks(lines, true)
+    }
+
+    /// Parses a single board, which may be written without the size header, see [`OwnedBoard::parse_many_lenient`]
+    pub fn parse_lenient(input: &str) -> Result<Self, BoardCreationError> {
+        Self::parse_many_lenient(input.lines())
+            .next()
+            .unwrap_or(Err(BoardCreationError::InvalidHeader))
+    }
+
+    fn parse_blocks<I: Borrow<str>>(
+        lines: impl IntoIterator<Item = I>,
+        lenient: bool,
+    ) -> impl Iterator<Item = Result<Self, BoardCreationError>> {
+        let mut lines = lines.into_iter().enumerate().peekable();
+        std::iter::from_fn(move || {
+            while lines
+                .next_if(|(_, line)| is_skipped_line(line.borrow()))
+                .is_some()
+            {}
+            let &(index, ref first_line) = lines.peek()?;
+            let cell_count = first_line.borrow().split_whitespace().count();
+            // boards from instance datasets are written in a single line, without the size header
+            let is_instance_line = |line: &str| {
+                let cell_count = line.split_whitespace().count();
+                cell_count > 2
+                    && (!lenient || cell_count >= 9 && Self::from_instance_line(line).is_ok())
+            };
+            let result = if is_instance_line(first_line.borrow()) {
+                let (_, line) = lines.next().expect("Line was peeked");
+                Self::from_instance_line(line.borrow()).map_err(|e| e.at_line(1))
+            } else if lenient && cell_count > 2 {
+                // every following row of the same width belongs to the grid
+                let mut grid = vec![];
+                while let Some((_, line)) = lines
+                    .next_if(|(_, line)| line.borrow().split_whitespace().count() == cell_count)
+                {
+                    grid.push(line);
+                }
+                let other_row = lines.next_if(|(_, line)| {
+                    let line = line.borrow();
+                    !is_skipped_line(line)
+                        && line.split_whitespace().count() > 2
+                        && !is_instance_line(line)
+                });
+                match other_row {
+                    Some((line_index, _)) => {
+                        Err(BoardCreationError::MissingCells.at_line(line_index - index + 1))
+                    }
+                    // a single row forming a board by itself is an instance line
+                    None if grid.len() == 1 && Self::from_grid([grid[0].borrow()]).is_ok() => {
+                        Self::from_instance_line(grid[0].borrow()).map_err(|e| e.at_line(1))
+                    }
+                    None => Self::from_grid(grid.iter().map(Borrow::borrow)),
+                }
+            } else {
+                Self::try_from_iter(lines.by_ref().map(|(_, line)| line))
+            };
+            // lines of the board are counted from its first line
+            Some(result.map_err(|e| e.with_line_offset(index)))
+        })
+    }
+
+    /// Parses a board written as a grid of cells without the size header, one row per line.
+    /// The number of rows is the number of lines, and the number of columns is the number of cells in the first line
+    pub fn from_grid<I: Borrow<str>>(
+        lines: impl IntoIterator<Item = I>,
+    ) -> Result<Self, BoardCreationError> {
+        let mut cells: Vec<u8> = vec![];
+        let mut columns = None;
+        let mut rows: u8 = 0;
+        for (index, line) in lines.into_iter().enumerate() {
+            let line_number = index + 1;
+            let row: Vec<u8> = tokens(line.borrow(), char::is_whitespace)
+                .map(|(offset, token)| parse_token(token, line_number, offset))
+                .collect::<Result<_, _>>()?;
+            let row_columns = *columns.get_or_insert(row.len());
+            if row.len() != row_columns {
+                return Err(BoardCreationError::MissingCells.at_line(line_number));
             }
+            cells.extend(row);
+            rows = rows
+                .checked_add(1)
+                .ok_or(BoardCreationError::UnrecognizedFormat.at_line(line_number))?;
+        }
+        let columns = u8::try_from(columns.unwrap_or_default())
+            .map_err(|_| BoardCreationError::UnrecognizedFormat.at_line(1))?;
+
+        validate_cells(rows, columns, &cells).map_err(|e| e.at_line(1))?;
+
+        Ok(Self {
+            rows,
+            columns,
+            cells: cells.into_boxed_slice(),
+            goal: None,
+        })
+    }
+
+    /// Parses a board written in a single line in the compact format, e.g. `3x3:1,2,3,4,5,6,7,8,0`.
+    ///
+    /// The line contains the number of rows and columns separated by `x`, followed by a colon
+    /// and the cells in row-major order separated by commas, with `0` marking the empty cell.
+    /// Boards in this format are also accepted wherever the multi-line format is parsed
+    ///
+    /// # Errors
+    /// Returns [`BoardCreationError::InvalidHeader`] if the dimensions are missing or zero,
+    /// [`BoardCreationError::ParsingError`] with the position of a token which is not a number,
+    /// or the error of [`validate_cells`] if the cells do not form a valid board
+    pub fn from_compact(line: &str) -> Result<Self, BoardCreationError> {
+        let (header, cells) = line
+            .split_once(':')
+            .ok_or(BoardCreationError::InvalidHeader)?;
+        let (rows, columns) = header
+            .split_once(['x', 'X'])
+            .ok_or(BoardCreationError::InvalidHeader)?;
+        let rows_offset = trimmed_offset(rows, 0);
+        let columns_offset = trimmed_offset(columns, rows.len() + 1);
+        let (rows, columns): (u8, u8) = (
+            parse_token(rows.trim(), 1, rows_offset)?,
+            parse_token(columns.trim(), 1, columns_offset)?,
+        );
+        if rows == 0 || columns == 0 {
+            return Err(BoardCreationError::InvalidHeader);
         }
 
+        let mut offset = header.len() + 1;
+        let mut values = Vec::new();
+        for cell in cells.split(',') {
+            values.push(parse_token(cell.trim(), 1, trimmed_offset(cell, offset))?);
+            offset += cell.len() + 1;
+        }
+        let cells = values;
+        validate_cells(rows, columns, &cells)?;
+
         Ok(Self {
             rows,
             columns,
             cells: cells.into_boxed_slice(),
+            goal: None,
+        })
+    }
+
+    /// Parses a 4x4 board written as a permutation of 16 hexadecimal digits in row-major order,
+    /// e.g. `123456789ABCDEF0`, as used by several public 15-puzzle datasets.
+    /// Boards in this format are also accepted wherever the multi-line format is parsed
+    pub fn from_hex(line: &str) -> Result<Self, BoardCreationError> {
+        let line = line.trim();
+        if line.chars().count() != HEX_LENGTH {
+            return Err(BoardCreationError::UnrecognizedFormat);
+        }
+        let cells: Vec<u8> = line
+            .char_indices()
+            .map(|(offset, digit)| {
+                let token = &line[offset..offset + digit.len_utf8()];
+                u8::from_str_radix(token, 16).map_err(|source| BoardCreationError::ParsingError {
+                    line: 1,
+                    column: offset + 1,
+                    token: token.to_string(),
+                    source,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        validate_cells(4, 4, &cells)?;
+
+        Ok(Self {
+            rows: 4,
+            columns: 4,
+            cells: cells.into_boxed_slice(),
+            goal: None,
         })
     }
+
+    /// Writes the 4x4 board as a permutation of 16 upper-case hexadecimal digits, accepted by [`OwnedBoard::from_hex`].
+    /// Returns `None` for boards of other dimensions
+    #[must_use]
+    pub fn to_hex(&self) -> Option<String> {
+        if (self.rows, self.columns) != (4, 4) {
+            return None;
+        }
+        self.cells
+            .iter()
+            .map(|&cell| char::from_digit(u32::from(cell), 16).map(|c| c.to_ascii_uppercase()))
+            .collect()
+    }
+
+    /// Parses a square board written in a single line, as used by published instance datasets
+    /// (e.g. Korf's 100 instances).
+    ///
+    /// The line contains the cells in row-major order, separated by whitespace or commas,
+    /// optionally preceded by the instance number.
+    /// The empty cell may be written either as `0` or as the number of cells (e.g. `16` on a 4x4 board).
+    /// Both variants are detected automatically.
+    pub fn from_instance_line(line: &str) -> Result<Self, BoardCreationError> {
+        let mut values: Vec<u16> = tokens(line, |c| c.is_whitespace() || c == ',')
+            .map(|(offset, token)| parse_token(token, 1, offset))
+            .collect::<Result<_, _>>()?;
+
+        // the instance number makes the count exceed a square number by one
+        let side = match (
+            square_root(values.len()),
+            values.len().checked_sub(1).and_then(square_root),
+        ) {
+            (Some(side), _) => side,
+            (None, Some(side)) => {
+                values.remove(0);
+                side
+            }
+            _ => return Err(BoardCreationError::UnrecognizedFormat),
+        };
+        let side = u8::try_from(side).map_err(|_| BoardCreationError::UnrecognizedFormat)?;
+        let cell_count = values.len() as u16;
+
+        if !values.contains(&0) {
+            // empty cell is written as the number of cells
+            for value in &mut values {
+                if *value == cell_count {
+                    *value = 0;
+                }
+            }
+        }
+
+        let cells: Vec<u8> = values
+            .into_iter()
+            .map(|value| u8::try_from(value).map_err(|_| BoardCreationError::MissingCells))
+            .collect::<Result<_, _>>()?;
+
+        validate_cells(side, side, &cells)?;
+
+        Ok(Self {
+            rows: side,
+            columns: side,
+            cells: cells.into_boxed_slice(),
+            goal: None,
+        })
+    }
+}
+
+#[cfg(feature = "json")]
+impl OwnedBoard {
+    /// Parses the board from a JSON object with `rows`, `columns` and `cells` in row-major order,
+    /// and optionally the `goal` cells
+    ///
+    /// # Errors
+    /// Returns [`BoardCreationError::InvalidJson`] if the JSON is malformed,
+    /// or its cells or goal do not form a valid board
+    pub fn from_json(json: &str) -> Result<Self, BoardCreationError> {
+        serde_json::from_str(json).map_err(|e| BoardCreationError::InvalidJson(e.to_string()))
+    }
+
+    /// Writes the board as a JSON object accepted by [`OwnedBoard::from_json`]
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Board is always serializable")
+    }
+}
+
+/// Splits the line into the tokens between the separators, together with their byte offsets in the line
+fn tokens(line: &str, is_separator: impl Fn(char) -> bool) -> impl Iterator<Item = (usize, &str)> {
+    let mut offset = 0;
+    std::iter::from_fn(move || {
+        let rest = &line[offset..];
+        let start = rest.find(|c| !is_separator(c))?;
+        let length = rest[start..]
+            .find(&is_separator)
+            .unwrap_or(rest.len() - start);
+        let token_offset = offset + start;
+        offset = token_offset + length;
+        Some((token_offset, &line[token_offset..offset]))
+    })
+}
+
+/// Checks if the line carries no part of a board: it is blank or it is a comment starting with `#`
+fn is_skipped_line(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty() || line.starts_with('#')
+}
+
+/// Removes the carriage return left at the end of the line by Windows line endings
+fn strip_line_ending(line: &str) -> &str {
+    line.strip_suffix('\r').unwrap_or(line)
+}
+
+/// Number of digits of a 4x4 board written in hexadecimal
+const HEX_LENGTH: usize = 16;
+
+/// Checks if the line is a board in the hexadecimal format, which is a single token of 16 characters
+fn is_hex_line(line: &str) -> bool {
+    let line = line.trim();
+    line.len() == HEX_LENGTH && line.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Returns the offset of the text after its leading whitespace, given the offset of the text in the line
+fn trimmed_offset(text: &str, offset: usize) -> usize {
+    offset + text.len() - text.trim_start().len()
+}
+
+/// Parses a number found at the given line and byte offset, reporting the location when it is invalid
+fn parse_token<T: FromStr<Err = ParseIntError>>(
+    token: &str,
+    line: usize,
+    offset: usize,
+) -> Result<T, BoardCreationError> {
+    token
+        .parse()
+        .map_err(|source| BoardCreationError::ParsingError {
+            line,
+            column: offset + 1,
+            token: token.to_string(),
+            source,
+        })
+}
+
+fn square_root(value: usize) -> Option<usize> {
+    let root = (1..=value).find(|root| root * root >= value)?;
+    (root * root == value).then_some(root)
+}
+
+/// Checks that the cells form a board of the given dimensions:
+/// every value from `0` to `rows * columns - 1` appears exactly once, with `0` marking the empty cell.
+/// Boards created from text or from bytes are validated with it as well
+///
+/// # Errors
+/// Returns error describing the first violated invariant
+pub fn validate_cells(rows: u8, columns: u8, cells: &[u8]) -> Result<(), BoardCreationError> {
+    let cell_count = rows as usize * columns as usize;
+    // a board without cells has no empty cell either
+    if cell_count == 0 || cells.len() != cell_count {
+        return Err(BoardCreationError::MissingCells);
+    }
+    // with the right number of cells, values that are in range and unique are all the required ones
+    let mut seen = vec![false; cell_count];
+    for &value in cells {
+        let Some(seen) = seen.get_mut(value as usize) else {
+            return Err(BoardCreationError::CellOutOfRange(value));
+        };
+        if *seen {
+            return Err(BoardCreationError::DuplicateCells);
+        }
+        *seen = true;
+    }
+    Ok(())
 }
 
 #[derive(Debug, Clone)]
 pub enum BoardCreationError {
-    ParsingError(ParseIntError),
+    /// The token at the given line and column, both counted from 1, is not a valid number
+    ParsingError {
+        line: usize,
+        column: usize,
+        token: String,
+        source: ParseIntError,
+    },
     InvalidHeader,
     MissingCells,
     DuplicateCells,
+    /// The cell value is not lower than the number of cells
+    CellOutOfRange(u8),
+    UnrecognizedFormat,
+    /// The goal configuration has different dimensions than the board
+    GoalDimensionsMismatch,
+    /// The JSON representation is malformed or describes an invalid board.
+    /// Only returned with the `json` feature, but present without it so matches do not depend on the features
+    InvalidJson(String),
+    /// The error was found at the given line of the input, counted from 1
+    Located {
+        line: usize,
+        error: Box<BoardCreationError>,
+    },
 }
 
-impl From<ParseIntError> for BoardCreationError {
-    fn from(value: ParseIntError) -> Self {
-        BoardCreationError::ParsingError(value)
+impl BoardCreationError {
+    /// Returns the error without the line it was found at
+    #[must_use]
+    pub fn kind(&self) -> &Self {
+        match self {
+            BoardCreationError::Located { error, .. } => error.kind(),
+            _ => self,
+        }
+    }
+
+    /// Returns the line of the input at which the error was found, counted from 1
+    #[must_use]
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            BoardCreationError::ParsingError { line, .. }
+            | BoardCreationError::Located { line, .. } => Some(*line),
+            _ => None,
+        }
+    }
+
+    /// Attaches the line to the error, unless it already has one
+    fn at_line(self, line: usize) -> Self {
+        if self.line().is_some() {
+            return self;
+        }
+        BoardCreationError::Located {
+            line,
+            error: Box::new(self),
+        }
+    }
+
+    /// Moves the line of the error by the number of lines preceding the parsed ones
+    fn with_line_offset(mut self, offset: usize) -> Self {
+        match &mut self {
+            BoardCreationError::ParsingError { line, .. }
+            | BoardCreationError::Located { line, .. } => *line += offset,
+            _ => {}
+        }
+        self
     }
 }
 
 impl Display for BoardCreationError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            BoardCreationError::ParsingError(err) => {
-                write!(f, "Error while parsing board: {err}")
+            BoardCreationError::ParsingError {
+                line,
+                column,
+                token,
+                source,
+            } => {
+                write!(
+                    f,
+                    "Error while parsing board: invalid number '{token}' at line {line}, column {column}: {source}"
+                )
             }
             BoardCreationError::MissingCells => write!(
                 f,
@@ -109,7 +566,18 @@ impl Display for BoardCreationError {
             BoardCreationError::DuplicateCells => {
                 write!(f, "The board contains multiple cells with the same number")
             }
+            BoardCreationError::CellOutOfRange(value) => {
+                write!(f, "The cell value {value} is too large for the board")
+            }
             BoardCreationError::InvalidHeader => write!(f, "The size header is invalid or missing"),
+            BoardCreationError::UnrecognizedFormat => {
+                write!(f, "The number of cells does not match any supported format")
+            }
+            BoardCreationError::GoalDimensionsMismatch => {
+                write!(f, "The goal has different dimensions than the board")
+            }
+            BoardCreationError::InvalidJson(message) => write!(f, "Invalid JSON board: {message}"),
+            BoardCreationError::Located { line, error } => write!(f, "Line {line}: {error}"),
         }
     }
 }
@@ -117,7 +585,8 @@ impl Display for BoardCreationError {
 impl Error for BoardCreationError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
-            BoardCreationError::ParsingError(err) => Some(err),
+            BoardCreationError::ParsingError { source, .. } => Some(source),
+            BoardCreationError::Located { error, .. } => Some(error),
             _ => None,
         }
     }
@@ -159,4 +628,277 @@ mod tests {
         assert_eq!(board.at(3, 2), 15);
         assert_eq!(board.at(3, 3), 0);
     }
+
+    #[test]
+    fn cells_are_validated() {
+        assert!(validate_cells(2, 3, &[1, 2, 3, 4, 5, 0]).is_ok());
+        assert!(matches!(
+            validate_cells(2, 2, &[1, 2, 3]),
+            Err(BoardCreationError::MissingCells)
+        ));
+        assert!(matches!(
+            validate_cells(2, 2, &[1, 2, 4, 0]),
+            Err(BoardCreationError::CellOutOfRange(4))
+        ));
+        assert!(matches!(
+            validate_cells(2, 2, &[1, 1, 2, 3]),
+            Err(BoardCreationError::DuplicateCells)
+        ));
+        assert!(matches!(
+            validate_cells(0, 0, &[]),
+            Err(BoardCreationError::MissingCells)
+        ));
+    }
+
+    #[test]
+    fn compact_format_is_parsed() {
+        let solved: OwnedBoard = SOLVED_INPUT.parse().unwrap();
+        let compact = "4x4:1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,0";
+
+        assert_eq!(solved, OwnedBoard::from_compact(compact).unwrap());
+        assert_eq!(solved, compact.parse().unwrap());
+        assert_eq!(
+            OwnedBoard::with_cells(2, 3, vec![1, 2, 3, 4, 0, 5]).unwrap(),
+            OwnedBoard::from_compact(" 2x3: 1, 2, 3, 4, 0, 5 ").unwrap()
+        );
+        assert!(matches!(
+            OwnedBoard::from_compact("2x2:1,2,3"),
+            Err(BoardCreationError::MissingCells)
+        ));
+        assert!(matches!(
+            OwnedBoard::from_compact("2:1,2,3,0"),
+            Err(BoardCreationError::InvalidHeader)
+        ));
+        assert!(matches!(
+            OwnedBoard::from_compact("2x2:1, 2,a,0"),
+            Err(BoardCreationError::ParsingError {
+                line: 1,
+                column: 10,
+                ..
+            })
+        ));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_round_trip() {
+        let board: OwnedBoard = SOLVED_INPUT.parse().unwrap();
+
+        assert_eq!(board, OwnedBoard::from_json(&board.to_json()).unwrap());
+        assert!(matches!(
+            OwnedBoard::from_json(r#"{"rows":2,"columns":2,"cells":[1,2,2,0]}"#),
+            Err(BoardCreationError::InvalidJson(_))
+        ));
+    }
+
+    #[test]
+    fn instance_line_formats_are_detected() {
+        let solved: OwnedBoard = SOLVED_INPUT.parse().unwrap();
+
+        for line in [
+            "1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 0",
+            "1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16",
+            "42 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 0",
+            "1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,0",
+        ] {
+            assert_eq!(solved, OwnedBoard::from_instance_line(line).unwrap());
+        }
+    }
+
+    #[test]
+    fn parses_many_boards() {
+        let input = "2 2\n1 2\n3 0\n\n\n3 3\n1 2 3\n4 5 6\n7 8 0\n2x2:1,2,0,3\n1 2 3 0\n";
+
+        let boards: Vec<_> = OwnedBoard::parse_many(input.lines())
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(4, boards.len());
+        assert_eq!((2, 2), boards[0].dimensions());
+        assert_eq!((3, 3), boards[1].dimensions());
+        assert!(boards[1].is_solved());
+        assert_eq!((1, 0), boards[2].empty_cell_pos());
+        assert!(boards[3].is_solved());
+        assert_eq!(0, OwnedBoard::parse_many("\n\n".lines()).count());
+    }
+
+    #[test]
+    fn comments_blank_lines_and_crlf_are_skipped() {
+        let input = "# first board\r\n2 2\r\n\r\n1 2\r\n  # cells of the last row\r\n3 0\r\n# second board\r\n2x2:1,2,0,3\r\n";
+
+        let boards: Vec<_> = OwnedBoard::parse_many(input.split('\n'))
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(2, boards.len());
+        assert!(boards[0].is_solved());
+        assert_eq!((1, 0), boards[1].empty_cell_pos());
+    }
+
+    #[test]
+    fn parsing_stops_after_last_row() {
+        let mut lines = "# comment\n2 2\n1 2\n3 0\nnot a board\n".lines();
+
+        let board = OwnedBoard::try_from_iter(lines.by_ref()).unwrap();
+
+        assert!(board.is_solved());
+        assert_eq!(Some("not a board"), lines.next());
+    }
+
+    #[test]
+    fn skipped_lines_are_counted_in_error_location() {
+        let error = "# comment\n3 3\n1 2 3\n\n4 x5 6\n7 8 0"
+            .parse::<OwnedBoard>()
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            BoardCreationError::ParsingError {
+                line: 5,
+                column: 3,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn parse_many_reports_invalid_board() {
+        let mut boards = OwnedBoard::parse_many("2 2\n1 2\n3 0\n2 2\n1 2\n".lines());
+
+        assert!(boards.next().unwrap().is_ok());
+        let error = boards.next().unwrap().unwrap_err();
+        assert!(matches!(error.kind(), BoardCreationError::MissingCells));
+        assert_eq!(Some(6), error.line());
+    }
+
+    #[test]
+    fn errors_carry_their_location() {
+        let error = "3 3\n1 2 3\n4 x5 6\n7 8 0"
+            .parse::<OwnedBoard>()
+            .unwrap_err();
+        assert!(matches!(
+            &error,
+            BoardCreationError::ParsingError { line: 3, column: 3, token, .. } if token == "x5"
+        ));
+        assert_eq!(
+            "Error while parsing board: invalid number 'x5' at line 3, column 3: invalid digit found in string",
+            error.to_string()
+        );
+
+        let error = "3 3\n1 2 3\n4 5\n7 8 0".parse::<OwnedBoard>().unwrap_err();
+        assert!(matches!(error.kind(), BoardCreationError::MissingCells));
+        assert_eq!(Some(3), error.line());
+
+        let error = "2 2\n1 1\n3 0".parse::<OwnedBoard>().unwrap_err();
+        assert!(matches!(error.kind(), BoardCreationError::DuplicateCells));
+        assert_eq!(Some(1), error.line());
+
+        let mut boards = OwnedBoard::parse_many("1 2 3 4 5 6 7 8 0\n\n2 2\n1 2\n0 y\n".lines());
+        assert!(boards.next().unwrap().is_ok());
+        assert!(matches!(
+            boards.next(),
+            Some(Err(BoardCreationError::ParsingError {
+                line: 5,
+                column: 3,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn input_string_is_parsed_back() {
+        let board: OwnedBoard = "4 4\n1 2 3 4\n5 6 7 8\n9 10 11 12\n13 14 0 15"
+            .parse()
+            .unwrap();
+
+        let input = board.to_input_string();
+
+        assert_eq!(
+            "4 4\n 1  2  3  4\n 5  6  7  8\n 9 10 11 12\n13 14  0 15\n",
+            input
+        );
+        assert_eq!(board, input.parse().unwrap());
+        let small = OwnedBoard::with_cells(2, 3, vec![1, 2, 3, 4, 0, 5]).unwrap();
+        assert_eq!("2 3\n1 2 3\n4 0 5\n", small.to_input_string());
+    }
+
+    #[test]
+    fn dimensions_are_inferred_from_grid() {
+        let board = OwnedBoard::from_grid(["1 2 3", "4 5 0"]).unwrap();
+        assert_eq!((2, 3), board.dimensions());
+        assert_eq!((1, 2), board.empty_cell_pos());
+        assert!(matches!(
+            OwnedBoard::from_grid(["1 2 3", "4 0"]).unwrap_err(),
+            BoardCreationError::Located { line: 2, .. }
+        ));
+
+        let solved: OwnedBoard = SOLVED_INPUT.parse().unwrap();
+        let headerless = SOLVED_INPUT.split_once('\n').unwrap().1;
+        assert!(headerless.parse::<OwnedBoard>().is_err());
+        assert_eq!(solved, OwnedBoard::parse_lenient(headerless).unwrap());
+        assert_eq!(solved, OwnedBoard::parse_lenient(SOLVED_INPUT).unwrap());
+    }
+
+    #[test]
+    fn lenient_parsing_reads_mixed_boards() {
+        let input =
+            "1 2 3\n4 5 6\n7 0 8\n3 3\n1 2 3\n4 5 6\n7 8 0\n1 2 3 4 5 6 7 8 0\n1 2 3\n4 0 5\n";
+
+        let boards: Vec<_> = OwnedBoard::parse_many_lenient(input.lines())
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(4, boards.len());
+        assert!(boards.iter().all(|board| board.dimensions() != (1, 9)));
+        assert_eq!((2, 1), boards[0].empty_cell_pos());
+        assert!(boards[1].is_solved() && boards[2].is_solved());
+        assert_eq!((2, 3), boards[3].dimensions());
+    }
+
+    #[test]
+    fn lenient_parsing_reads_whole_grid() {
+        let board =
+            OwnedBoard::parse_lenient("0 1 2 3\n4 5 6 7\n8 9 10 11\n12 13 14 15\n").unwrap();
+        assert_eq!((4, 4), board.dimensions());
+        assert_eq!((0, 0), board.empty_cell_pos());
+
+        assert!(matches!(
+            OwnedBoard::parse_lenient("1 2 3\n4 5 6 0\n7 8 9 10\n"),
+            Err(BoardCreationError::Located { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn hex_format_is_parsed_and_written() {
+        let solved: OwnedBoard = SOLVED_INPUT.parse().unwrap();
+
+        assert_eq!(solved, OwnedBoard::from_hex("123456789ABCDEF0").unwrap());
+        assert_eq!(solved, "123456789abcdef0".parse().unwrap());
+        assert_eq!(Some("123456789ABCDEF0".to_string()), solved.to_hex());
+        assert_eq!(None, OwnedBoard::new_solved(3, 3).to_hex());
+        assert!(matches!(
+            OwnedBoard::from_hex("123456789ABCDEF1"),
+            Err(BoardCreationError::DuplicateCells)
+        ));
+        assert!(matches!(
+            OwnedBoard::from_hex("123456789ABCDEG0"),
+            Err(BoardCreationError::ParsingError { column: 15, .. })
+        ));
+        assert!(matches!(
+            OwnedBoard::from_hex("123"),
+            Err(BoardCreationError::UnrecognizedFormat)
+        ));
+    }
+
+    #[test]
+    fn invalid_instance_lines_are_rejected() {
+        assert!(matches!(
+            OwnedBoard::from_instance_line("1 2 3 4 5 6"),
+            Err(BoardCreationError::UnrecognizedFormat)
+        ));
+        assert!(matches!(
+            OwnedBoard::from_instance_line("1 1 3 4 5 6 7 8 0"),
+            Err(BoardCreationError::DuplicateCells)
+        ));
+    }
 }
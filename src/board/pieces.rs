@@ -0,0 +1,312 @@
+//! Sliding puzzles with rectangular pieces occupying multiple cells (Klotski-style).
+//!
+//! The board is a grid of piece identifiers, where `0` marks an empty cell.
+//! Unlike in the 15-puzzle, there may be multiple empty cells,
+//! and moves describe the direction in which a piece slides, not the direction of the empty cell.
+
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use super::{Board, BoardMove};
+use crate::solving::visited::VisitedPositions;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct PieceMove {
+    pub piece: u8,
+    pub direction: BoardMove,
+}
+
+impl Display for PieceMove {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.piece, self.direction)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct PieceBoard {
+    rows: u8,
+    columns: u8,
+    cells: Box<[u8]>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum PieceBoardError {
+    /// Number of cells does not match the dimensions
+    InvalidDimensions,
+    /// Cells of the piece do not form a filled rectangle
+    NonRectangularPiece(u8),
+}
+
+impl Display for PieceBoardError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PieceBoardError::InvalidDimensions => {
+                write!(f, "Number of cells does not match the board dimensions")
+            }
+            PieceBoardError::NonRectangularPiece(piece) => {
+                write!(f, "Piece {piece} is not a filled rectangle")
+            }
+        }
+    }
+}
+
+impl Error for PieceBoardError {}
+
+/// Bounding box of a piece, as (top row, left column, height, width)
+type Bounds = (u8, u8, u8, u8);
+
+impl PieceBoard {
+    /// Creates the board from the piece identifiers of every cell, in row-major order
+    ///
+    /// # Errors
+    /// Returns error if the number of cells does not match the dimensions,
+    /// or any of the pieces is not a filled rectangle
+    pub fn new(rows: u8, columns: u8, cells: Vec<u8>) -> Result<Self, PieceBoardError> {
+        if cells.len() != rows as usize * columns as usize {
+            return Err(PieceBoardError::InvalidDimensions);
+        }
+        let board = Self {
+            rows,
+            columns,
+            cells: cells.into_boxed_slice(),
+        };
+
+        for piece in board.pieces() {
+            let (top, left, height, width) = board.bounds(piece).expect("Piece is on the board");
+            let area = board.cells.iter().filter(|&&c| c == piece).count();
+            let filled = (top..top + height)
+                .all(|row| (left..left + width).all(|column| board.at(row, column) == piece));
+            if !filled || area != height as usize * width as usize {
+                return Err(PieceBoardError::NonRectangularPiece(piece));
+            }
+        }
+
+        Ok(board)
+    }
+
+    /// Converts a 15-puzzle board, where every tile is a 1x1 piece
+    #[must_use]
+    pub fn from_board(board: &dyn Board) -> Self {
+        let (rows, columns) = board.dimensions();
+        Self {
+            rows,
+            columns,
+            cells: (0..rows)
+                .flat_map(|row| (0..columns).map(move |column| board.at(row, column)))
+                .collect(),
+        }
+    }
+
+    #[must_use]
+    pub fn dimensions(&self) -> (u8, u8) {
+        (self.rows, self.columns)
+    }
+
+    #[must_use]
+    pub fn at(&self, row: u8, column: u8) -> u8 {
+        self.cells[row as usize * self.columns as usize + column as usize]
+    }
+
+    /// Returns the row and column of the top-left cell of the piece
+    #[must_use]
+    pub fn piece_position(&self, piece: u8) -> Option<(u8, u8)> {
+        self.bounds(piece).map(|(row, column, _, _)| (row, column))
+    }
+
+    /// Identifiers of all pieces on the board, in ascending order
+    fn pieces(&self) -> Vec<u8> {
+        let mut pieces: Vec<u8> = self.cells.iter().copied().filter(|&c| c != 0).collect();
+        pieces.sort_unstable();
+        pieces.dedup();
+        pieces
+    }
+
+    fn bounds(&self, piece: u8) -> Option<Bounds> {
+        let mut bounds: Option<(u8, u8, u8, u8)> = None;
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                if self.at(row, column) != piece {
+                    continue;
+                }
+                bounds = Some(match bounds {
+                    None => (row, column, row, column),
+                    Some((top, left, bottom, right)) => {
+                        (top, left.min(column), bottom.max(row), right.max(column))
+                    }
+                });
+            }
+        }
+        bounds.map(|(top, left, bottom, right)| (top, left, bottom - top + 1, right - left + 1))
+    }
+
+    /// Cells that have to be empty for the piece to slide in the given direction
+    fn cells_entered(
+        &self,
+        (top, left, height, width): Bounds,
+        direction: BoardMove,
+    ) -> Vec<(i16, i16)> {
+        let (top, left, height, width) = (top as i16, left as i16, height as i16, width as i16);
+        match direction {
+            BoardMove::Up => (left..left + width).map(|c| (top - 1, c)).collect(),
+            BoardMove::Down => (left..left + width).map(|c| (top + height, c)).collect(),
+            BoardMove::Left => (top..top + height).map(|r| (r, left - 1)).collect(),
+            BoardMove::Right => (top..top + height).map(|r| (r, left + width)).collect(),
+        }
+    }
+
+    #[must_use]
+    pub fn can_move(&self, piece_move: PieceMove) -> bool {
+        if piece_move.piece == 0 {
+            return false;
+        }
+        let Some(bounds) = self.bounds(piece_move.piece) else {
+            return false;
+        };
+        self.cells_entered(bounds, piece_move.direction)
+            .into_iter()
+            .all(|(row, column)| {
+                row >= 0
+                    && column >= 0
+                    && row < self.rows as i16
+                    && column < self.columns as i16
+                    && self.at(row as u8, column as u8) == 0
+            })
+    }
+
+    /// # Panics
+    /// This function may panic if the move cannot be performed.
+    /// To avoid it, check before if a move can be executed using [`can_move`](PieceBoard::can_move)
+    pub fn exec_move(&mut self, piece_move: PieceMove) {
+        assert!(self.can_move(piece_move), "Board cannot execute this move");
+
+        let (top, left, height, width) = self
+            .bounds(piece_move.piece)
+            .expect("Piece is on the board");
+        let (row_offset, column_offset): (i16, i16) = match piece_move.direction {
+            BoardMove::Up => (-1, 0),
+            BoardMove::Down => (1, 0),
+            BoardMove::Left => (0, -1),
+            BoardMove::Right => (0, 1),
+        };
+
+        for row in top..top + height {
+            for column in left..left + width {
+                let index = row as usize * self.columns as usize + column as usize;
+                self.cells[index] = 0;
+            }
+        }
+        for row in top..top + height {
+            for column in left..left + width {
+                let new_row = (row as i16 + row_offset) as usize;
+                let new_column = (column as i16 + column_offset) as usize;
+                self.cells[new_row * self.columns as usize + new_column] = piece_move.piece;
+            }
+        }
+    }
+
+    /// Returns all moves that can be executed on the board
+    #[must_use]
+    pub fn legal_moves(&self) -> Vec<PieceMove> {
+        use BoardMove::*;
+        self.pieces()
+            .into_iter()
+            .flat_map(|piece| {
+                [Up, Down, Left, Right]
+                    .into_iter()
+                    .map(move |direction| PieceMove { piece, direction })
+            })
+            .filter(|&m| self.can_move(m))
+            .collect()
+    }
+}
+
+/// Finds the shortest sequence of moves leading to a board which satisfies the goal.
+/// Returns `None` if no such board can be reached
+pub fn solve_bfs(board: PieceBoard, goal: impl Fn(&PieceBoard) -> bool) -> Option<Vec<PieceMove>> {
+    let visited_positions = VisitedPositions::new();
+    let mut queue = VecDeque::from([(board, Vec::new())]);
+
+    while let Some((board, path)) = queue.pop_front() {
+        if goal(&board) {
+            return Some(path);
+        }
+        if visited_positions.is_visited(&board) {
+            continue;
+        }
+        visited_positions.mark_visited(board.clone());
+
+        for next_move in board.legal_moves() {
+            let mut new_board = board.clone();
+            new_board.exec_move(next_move);
+            let mut new_path = path.clone();
+            new_path.push(next_move);
+            queue.push_back((new_board, new_path));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{BoardMove, OwnedBoard};
+
+    use super::*;
+
+    #[test]
+    fn rejects_non_rectangular_pieces() {
+        let result = PieceBoard::new(2, 2, vec![1, 1, 0, 1]);
+        assert_eq!(Err(PieceBoardError::NonRectangularPiece(1)), result);
+    }
+
+    #[test]
+    fn large_piece_needs_all_entered_cells_empty() {
+        let board = PieceBoard::new(2, 3, vec![1, 1, 0, 0, 2, 0]).unwrap();
+
+        assert!(board.can_move(PieceMove {
+            piece: 1,
+            direction: BoardMove::Right
+        }));
+        assert!(!board.can_move(PieceMove {
+            piece: 1,
+            direction: BoardMove::Down
+        }));
+        assert!(!board.can_move(PieceMove {
+            piece: 1,
+            direction: BoardMove::Left
+        }));
+    }
+
+    #[test]
+    fn finds_shortest_solution() {
+        let board = PieceBoard::new(2, 3, vec![1, 1, 0, 0, 0, 2]).unwrap();
+
+        let solution = solve_bfs(board.clone(), |b| b.piece_position(2) == Some((0, 0))).unwrap();
+
+        assert_eq!(4, solution.len());
+        let mut board = board;
+        for m in solution {
+            board.exec_move(m);
+        }
+        assert_eq!(Some((0, 0)), board.piece_position(2));
+    }
+
+    #[test]
+    fn standard_board_is_special_case() {
+        let board: OwnedBoard = "2 2\n1 2\n0 3".parse().unwrap();
+        let solved: OwnedBoard = "2 2\n1 2\n3 0".parse().unwrap();
+        let target = PieceBoard::from_board(&solved);
+
+        let solution = solve_bfs(PieceBoard::from_board(&board), |b| *b == target).unwrap();
+
+        // empty cell moving right is the same as the tile moving left
+        assert_eq!(
+            vec![PieceMove {
+                piece: 3,
+                direction: BoardMove::Left
+            }],
+            solution
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use super::{Board, BoardMove};
+
+/// Wrapper which forwards every operation to the inner board and records the executed moves.
+///
+/// Moves undoing earlier ones are recorded as well, so the record is the exact sequence
+/// of moves performed on the board, e.g. by a solver backtracking in place
+pub struct RecordingBoard<B: Board> {
+    board: B,
+    moves: Vec<BoardMove>,
+}
+
+impl<B: Board> RecordingBoard<B> {
+    #[must_use]
+    pub fn new(board: B) -> Self {
+        Self {
+            board,
+            moves: vec![],
+        }
+    }
+
+    /// Returns the moves executed so far, in order
+    #[must_use]
+    pub fn moves(&self) -> &[BoardMove] {
+        &self.moves
+    }
+
+    /// Returns the moves executed so far, clearing the record
+    pub fn take_moves(&mut self) -> Vec<BoardMove> {
+        std::mem::take(&mut self.moves)
+    }
+
+    #[must_use]
+    pub fn inner(&self) -> &B {
+        &self.board
+    }
+
+    /// Returns the inner board and the recorded moves
+    #[must_use]
+    pub fn into_parts(self) -> (B, Vec<BoardMove>) {
+        (self.board, self.moves)
+    }
+}
+
+impl<B: Board> Board for RecordingBoard<B> {
+    fn dimensions(&self) -> (u8, u8) {
+        self.board.dimensions()
+    }
+
+    fn goal_position(&self, tile: u8) -> (u8, u8) {
+        self.board.goal_position(tile)
+    }
+
+    fn at(&self, row: u8, column: u8) -> u8 {
+        self.board.at(row, column)
+    }
+
+    fn empty_cell_pos(&self) -> (u8, u8) {
+        self.board.empty_cell_pos()
+    }
+
+    fn is_solved(&self) -> bool {
+        self.board.is_solved()
+    }
+
+    fn can_move(&self, board_move: BoardMove) -> bool {
+        self.board.can_move(board_move)
+    }
+
+    fn exec_move(&mut self, board_move: BoardMove) {
+        self.board.exec_move(board_move);
+        self.moves.push(board_move);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::sub_board::SubBoard;
+    use crate::board::{BoardMove::*, OwnedBoard};
+
+    use super::*;
+
+    #[test]
+    fn records_moves_including_undos() {
+        let mut board = RecordingBoard::new(OwnedBoard::from_scramble(3, 3, &[Up]).unwrap());
+        board.exec_move(Left);
+        board.exec_move(Right);
+        board.apply_moves(&[Down]).unwrap();
+
+        assert!(board.is_solved());
+        assert_eq!(board.moves(), [Left, Right, Down]);
+    }
+
+    #[test]
+    fn illegal_moves_are_not_recorded() {
+        let mut board = RecordingBoard::new(OwnedBoard::from_scramble(3, 3, &[]).unwrap());
+        assert!(board.try_exec_move(Down).is_err());
+        board.exec_move(Up);
+
+        assert_eq!(board.take_moves(), [Up]);
+        assert!(board.moves().is_empty());
+    }
+
+    #[test]
+    fn collects_moves_executed_on_sub_board() {
+        let mut parent = OwnedBoard::from_scramble(4, 4, &[Up, Left]).unwrap();
+        let mut board = RecordingBoard::new(SubBoard::suffix(&mut parent, 2, 0).unwrap());
+        board.apply_moves(&[Right, Down]).unwrap();
+
+        let (_, moves) = board.into_parts();
+        assert_eq!(moves, [Right, Down]);
+        assert!(parent.is_solved());
+    }
+}
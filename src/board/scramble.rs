@@ -0,0 +1,97 @@
+use super::{Board, BoardMove, MoveError, OwnedBoard};
+
+impl OwnedBoard {
+    /// Creates the board by applying the scramble sequence to the solved board of the given dimensions.
+    /// This is how puzzles are usually exchanged in speedsolving communities
+    ///
+    /// # Errors
+    /// Returns error with the index of the first move which cannot be performed
+    pub fn from_scramble(rows: u8, columns: u8, scramble: &[BoardMove]) -> Result<Self, MoveError> {
+        let mut board = Self::new_solved(rows, columns);
+        board.apply_moves(scramble)?;
+        Ok(board)
+    }
+}
+
+/// Converts the solution of a board into a scramble sequence, which creates the board from the solved one.
+/// The solution is undone from the end, with every move reversed
+#[must_use]
+pub fn scramble_from_solution(solution: &[BoardMove]) -> Vec<BoardMove> {
+    solution.iter().rev().map(BoardMove::opposite).collect()
+}
+
+/// Reconstructs the board solved by the solution, by undoing the solution from the goal configuration.
+/// The reconstructed board has the standard goal, even if `goal` has another one
+///
+/// # Errors
+/// Returns error with the index of the first move of the reversed solution which cannot be performed
+pub fn reconstruct_from_solution(
+    goal: &OwnedBoard,
+    solution: &[BoardMove],
+) -> Result<OwnedBoard, MoveError> {
+    let mut board = goal.clone();
+    board.apply_moves(&scramble_from_solution(solution))?;
+    Ok(board)
+}
+
+/// Checks that undoing the solution from the goal configuration of the board reconstructs exactly the board,
+/// comparing the encoded cells byte for byte.
+/// Solutions produced by translating moves (e.g. of sub-boards) may solve the board by accident,
+/// and this check catches moves which were translated incorrectly
+#[must_use]
+pub fn round_trips(board: &OwnedBoard, solution: &[BoardMove]) -> bool {
+    reconstruct_from_solution(&board.goal(), solution)
+        .is_ok_and(|reconstructed| reconstructed.to_bytes() == board.to_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::parse_moves;
+    use crate::solving::algorithm::bfs::BFSSolver;
+    use crate::solving::algorithm::Solver;
+    use crate::solving::movegen::MoveGenerator;
+
+    use super::*;
+
+    #[test]
+    fn scramble_is_applied_to_solved_board() {
+        let board = OwnedBoard::from_scramble(3, 3, &parse_moves("ULDR").unwrap()).unwrap();
+        let expected: OwnedBoard = "3 3\n1 2 3\n4 8 5\n7 6 0".parse().unwrap();
+
+        assert_eq!(expected, board);
+        assert_eq!(
+            Err(MoveError::IllegalMove {
+                index: 2,
+                board_move: BoardMove::Right
+            }),
+            OwnedBoard::from_scramble(3, 3, &parse_moves("LRR").unwrap())
+        );
+    }
+
+    #[test]
+    fn solution_converts_to_scramble_of_the_board() {
+        let board: OwnedBoard = "3 3\n4 1 3\n0 2 5\n7 8 6".parse().unwrap();
+        let solution = Box::new(BFSSolver::new(board.clone(), MoveGenerator::default()))
+            .solve()
+            .unwrap();
+
+        let scramble = scramble_from_solution(&solution);
+        assert_eq!(Ok(board), OwnedBoard::from_scramble(3, 3, &scramble));
+    }
+
+    #[test]
+    fn solution_reconstructs_the_board() {
+        let input = "3 3\n4 1 3\n0 2 5\n7 8 6\n";
+        let board: OwnedBoard = input.parse().unwrap();
+        let solution = Box::new(BFSSolver::new(board.clone(), MoveGenerator::default()))
+            .solve()
+            .unwrap();
+
+        let reconstructed = reconstruct_from_solution(&board.goal(), &solution).unwrap();
+
+        assert_eq!(input, reconstructed.to_input_string());
+        assert!(round_trips(&board, &solution));
+        assert!(!round_trips(&board, &parse_moves("LU").unwrap()));
+        assert!(!round_trips(&board, &parse_moves("RRR").unwrap()));
+    }
+}
@@ -0,0 +1,214 @@
+use std::ops::Range;
+
+use super::{Board, BoardMove};
+
+/// View of a rectangular region of another board, presented as a standalone puzzle.
+///
+/// Tiles which belong to the region in the solved parent board are renumbered,
+/// so that the region is solved exactly when all of them are in place.
+/// Tiles which belong elsewhere are reported as [`SubBoard::FOREIGN_TILE`].
+/// Moves are executed on the parent board, but the empty cell can never leave the region
+pub struct SubBoard<'a, B: Board + ?Sized> {
+    parent: &'a mut B,
+    rows: Range<u8>,
+    columns: Range<u8>,
+}
+
+impl<'a, B: Board + ?Sized> SubBoard<'a, B> {
+    /// Value of cells holding tiles whose solved position is outside the region
+    pub const FOREIGN_TILE: u8 = u8::MAX;
+
+    /// Creates view of the cells in the given row and column ranges of the parent.
+    ///
+    /// Returns `None` if the region is empty, exceeds the parent board, or does not contain the empty cell
+    pub fn new(parent: &'a mut B, rows: Range<u8>, columns: Range<u8>) -> Option<Self> {
+        let (parent_rows, parent_columns) = parent.dimensions();
+        if rows.is_empty()
+            || columns.is_empty()
+            || rows.end > parent_rows
+            || columns.end > parent_columns
+        {
+            return None;
+        }
+        let (empty_row, empty_column) = parent.empty_cell_pos();
+        if !rows.contains(&empty_row) || !columns.contains(&empty_column) {
+            return None;
+        }
+
+        Some(Self {
+            parent,
+            rows,
+            columns,
+        })
+    }
+
+    /// Creates view of the bottom-right part of the parent, starting at the given row and column
+    pub fn suffix(parent: &'a mut B, first_row: u8, first_column: u8) -> Option<Self> {
+        let (rows, columns) = parent.dimensions();
+        Self::new(parent, first_row..rows, first_column..columns)
+    }
+
+    /// Returns the row and column of the parent board corresponding to the top-left cell of the view
+    #[must_use]
+    pub fn origin(&self) -> (u8, u8) {
+        (self.rows.start, self.columns.start)
+    }
+
+    /// Translates a tile of the parent board to the numbering of the region
+    fn translate(&self, value: u8) -> u8 {
+        if value == 0 {
+            return 0;
+        }
+        let (_, parent_columns) = self.parent.dimensions();
+        let target_row = (value - 1) / parent_columns;
+        let target_column = (value - 1) % parent_columns;
+        if !self.rows.contains(&target_row) || !self.columns.contains(&target_column) {
+            return Self::FOREIGN_TILE;
+        }
+
+        let (_, columns) = self.dimensions();
+        (target_row - self.rows.start) * columns + (target_column - self.columns.start) + 1
+    }
+}
+
+impl<B: Board + ?Sized> Board for SubBoard<'_, B> {
+    fn dimensions(&self) -> (u8, u8) {
+        (
+            self.rows.end - self.rows.start,
+            self.columns.end - self.columns.start,
+        )
+    }
+
+    fn at(&self, row: u8, column: u8) -> u8 {
+        let value = self
+            .parent
+            .at(self.rows.start + row, self.columns.start + column);
+        self.translate(value)
+    }
+
+    fn empty_cell_pos(&self) -> (u8, u8) {
+        let (row, column) = self.parent.empty_cell_pos();
+        (row - self.rows.start, column - self.columns.start)
+    }
+
+    /// Checks that the region's own tiles are in their goal cells.
+    ///
+    /// The empty cell never leaves the region, so unless the region contains its goal,
+    /// it always takes the place of one of the own tiles. Such region, e.g. a top-row strip,
+    /// is solved when all of its other cells hold their own tiles
+    fn is_solved(&self) -> bool {
+        let (rows, columns) = self.dimensions();
+        let (parent_rows, parent_columns) = self.parent.dimensions();
+        // the solved position of the empty cell is the last cell of the parent board
+        let contains_empty_cell_target =
+            self.rows.contains(&(parent_rows - 1)) && self.columns.contains(&(parent_columns - 1));
+        let empty_cell = self.empty_cell_pos();
+        (0..rows).all(|row| {
+            (0..columns).all(|column| {
+                if (row, column) == empty_cell && !contains_empty_cell_target {
+                    return true;
+                }
+                let is_empty_cell_target = self.rows.start + row == parent_rows - 1
+                    && self.columns.start + column == parent_columns - 1;
+                let expected = if is_empty_cell_target {
+                    0
+                } else {
+                    row * columns + column + 1
+                };
+                self.at(row, column) == expected
+            })
+        })
+    }
+
+    fn can_move(&self, board_move: BoardMove) -> bool {
+        let (rows, columns) = self.dimensions();
+        let (row, column) = self.empty_cell_pos();
+        match board_move {
+            BoardMove::Up => row > 0,
+            BoardMove::Down => row + 1 < rows,
+            BoardMove::Left => column > 0,
+            BoardMove::Right => column + 1 < columns,
+        }
+    }
+
+    fn exec_move(&mut self, board_move: BoardMove) {
+        assert!(
+            self.can_move(board_move),
+            "Move {board_move} would leave the sub-board"
+        );
+        self.parent.exec_move(board_move);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::OwnedBoard;
+
+    use super::*;
+
+    #[test]
+    fn bottom_right_suffix_is_standard_puzzle() {
+        let mut board: OwnedBoard = "3 3\n1 2 3\n4 5 6\n7 0 8".parse().unwrap();
+        let mut sub_board = SubBoard::suffix(&mut board, 1, 1).unwrap();
+
+        assert_eq!((2, 2), sub_board.dimensions());
+        assert_eq!(
+            [1, 0, 3],
+            [sub_board.at(0, 0), sub_board.at(1, 0), sub_board.at(1, 1)]
+        );
+        assert!(!sub_board.is_solved());
+
+        sub_board.exec_move(BoardMove::Right);
+        assert!(sub_board.is_solved());
+        assert!(board.is_solved());
+    }
+
+    #[test]
+    fn top_row_strip() {
+        let mut board: OwnedBoard = "3 3\n2 0 3\n1 4 5\n7 8 6".parse().unwrap();
+        let mut sub_board = SubBoard::new(&mut board, 0..1, 0..3).unwrap();
+
+        assert_eq!(
+            [2, 0, 3],
+            [sub_board.at(0, 0), sub_board.at(0, 1), sub_board.at(0, 2)]
+        );
+        assert!(!sub_board.can_move(BoardMove::Down));
+        assert!(!sub_board.is_solved());
+        sub_board.exec_move(BoardMove::Left);
+        assert_eq!((0, 0), sub_board.empty_cell_pos());
+        // tile 1 is right below the empty cell, ready to be moved in
+        assert!(sub_board.is_solved());
+    }
+
+    #[test]
+    fn solved_top_row_strip() {
+        let mut board: OwnedBoard = "3 3\n1 2 0\n4 5 3\n7 8 6".parse().unwrap();
+        let mut sub_board = SubBoard::new(&mut board, 0..1, 0..3).unwrap();
+
+        assert!(sub_board.is_solved());
+        sub_board.exec_move(BoardMove::Left);
+        assert!(!sub_board.is_solved());
+    }
+
+    #[test]
+    fn rejects_region_without_empty_cell() {
+        let mut board = OwnedBoard::new_solved(4, 4);
+
+        assert!(SubBoard::new(&mut board, 0..2, 0..4).is_none());
+        assert!(SubBoard::new(&mut board, 2..5, 0..4).is_none());
+        assert!(SubBoard::new(&mut board, 2..4, 1..4).is_some());
+    }
+
+    #[test]
+    fn left_column_strip_with_tile_moved_out() {
+        let mut board: OwnedBoard = "3 3\n1 2 3\n4 5 6\n7 0 8".parse().unwrap();
+        board.exec_move(BoardMove::Left);
+        let mut sub_board = SubBoard::new(&mut board, 1..3, 0..1).unwrap();
+
+        // tile 7 was moved out of the region, the empty cell takes its place
+        assert!(sub_board.is_solved());
+        // tile 4 leaves its goal cell
+        sub_board.exec_move(BoardMove::Up);
+        assert!(!sub_board.is_solved());
+    }
+}
@@ -0,0 +1,134 @@
+use super::{Board, BoardMove};
+
+/// View of another board mirrored along its main diagonal, so that rows become columns.
+///
+/// Code written for rows can be applied to columns through it, e.g. by heuristics.
+/// Tiles keep their values and their goal positions are transposed as well,
+/// so the view is solved exactly when the underlying board is.
+/// Moves are mapped accordingly: `Up` on the view is `Left` on the underlying board, and `Down` is `Right`
+pub struct TransposedBoard<'a> {
+    board: Inner<'a>,
+}
+
+enum Inner<'a> {
+    Shared(&'a dyn Board),
+    Exclusive(&'a mut dyn Board),
+}
+
+impl<'a> TransposedBoard<'a> {
+    /// Creates read-only view of the board, which does not allow any moves
+    #[must_use]
+    pub fn new(board: &'a dyn Board) -> Self {
+        Self {
+            board: Inner::Shared(board),
+        }
+    }
+
+    /// Creates view of the board which executes the moves on it
+    #[must_use]
+    pub fn new_mut(board: &'a mut dyn Board) -> Self {
+        Self {
+            board: Inner::Exclusive(board),
+        }
+    }
+
+    fn inner(&self) -> &dyn Board {
+        match &self.board {
+            Inner::Shared(board) => *board,
+            Inner::Exclusive(board) => &**board,
+        }
+    }
+
+    /// Converts move on the view into the move on the underlying board, and the other way round
+    #[must_use]
+    pub fn transpose_move(board_move: BoardMove) -> BoardMove {
+        match board_move {
+            BoardMove::Up => BoardMove::Left,
+            BoardMove::Left => BoardMove::Up,
+            BoardMove::Down => BoardMove::Right,
+            BoardMove::Right => BoardMove::Down,
+        }
+    }
+}
+
+impl Board for TransposedBoard<'_> {
+    fn dimensions(&self) -> (u8, u8) {
+        let (rows, columns) = self.inner().dimensions();
+        (columns, rows)
+    }
+
+    fn goal_position(&self, tile: u8) -> (u8, u8) {
+        let (row, column) = self.inner().goal_position(tile);
+        (column, row)
+    }
+
+    fn at(&self, row: u8, column: u8) -> u8 {
+        self.inner().at(column, row)
+    }
+
+    fn empty_cell_pos(&self) -> (u8, u8) {
+        let (row, column) = self.inner().empty_cell_pos();
+        (column, row)
+    }
+
+    fn is_solved(&self) -> bool {
+        self.inner().is_solved()
+    }
+
+    fn can_move(&self, board_move: BoardMove) -> bool {
+        match &self.board {
+            Inner::Shared(_) => false,
+            Inner::Exclusive(board) => board.can_move(Self::transpose_move(board_move)),
+        }
+    }
+
+    /// # Panics
+    /// Panics if the view is read-only, or the move cannot be performed on the underlying board
+    fn exec_move(&mut self, board_move: BoardMove) {
+        assert!(self.can_move(board_move), "Board cannot execute this move");
+        if let Inner::Exclusive(board) = &mut self.board {
+            board.exec_move(Self::transpose_move(board_move));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{BoardMove::*, OwnedBoard};
+    use crate::solving::algorithm::heuristic::heuristics::{
+        Heuristic, LinearConflict, ManhattanDistance,
+    };
+
+    use super::*;
+
+    #[test]
+    fn cells_and_moves_are_transposed() {
+        let mut board: OwnedBoard = "2 3\n1 2 3\n4 0 5".parse().unwrap();
+        let mut view = TransposedBoard::new_mut(&mut board);
+
+        assert_eq!((3, 2), view.dimensions());
+        assert_eq!(2, view.at(1, 0));
+        assert_eq!((1, 1), view.empty_cell_pos());
+        assert_eq!((2, 0), view.goal_position(3));
+        assert!(!view.can_move(Right));
+
+        view.exec_move(Down);
+        assert!(view.is_solved());
+        assert!(board.is_solved());
+    }
+
+    #[test]
+    fn heuristics_are_symmetric() {
+        let board: OwnedBoard = "3 3\n4 1 3\n7 2 5\n8 0 6".parse().unwrap();
+        let view = TransposedBoard::new(&board);
+
+        assert!(!view.can_move(Up));
+        let heuristics: [Box<dyn Heuristic>; 2] = [
+            Box::new(ManhattanDistance),
+            Box::new(LinearConflict::default()),
+        ];
+        for heuristic in heuristics {
+            assert_eq!(heuristic.evaluate(&board), heuristic.evaluate(&view));
+        }
+    }
+}
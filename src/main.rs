@@ -1,70 +1,513 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use log::LevelFilter;
 
-use solver::board::{BoardMove, OwnedBoard};
-use solver::solving::algorithm::heuristic::heuristics::{
-    Heuristic, InversionDistance, LinearConflict, ManhattanDistance,
-};
-use solver::solving::algorithm::{Solver, SolvingError};
-use solver::solving::movegen::SearchOrder;
+use solver::board::scramble::scramble_from_solution;
+use solver::board::{parse_moves, Board, BoardCreationError, BoardMove, OwnedBoard};
+use solver::solving::algorithm::heuristic::cache::{HeuristicKey, HeuristicLru};
+use solver::solving::algorithm::heuristic::heuristics::{self, Heuristic};
+use solver::solving::algorithm::heuristic::pattern_database::Partition;
+use solver::solving::algorithm::heuristic::registry;
+use solver::solving::algorithm::heuristic::DuplicatePolicy;
+use solver::solving::algorithm::{SolveReport, SolveStep, Solver, SolverConfig, SolvingError};
+use solver::solving::checkpoint::RunCheckpoint;
+use solver::solving::convergence::ConvergenceLog;
+use solver::solving::history::{HistoryTable, SharedHistory};
+use solver::solving::movegen::{MoveGenerator, SearchOrder, SearchOrderError};
+use solver::solving::plateau::{PlateauMonitor, PlateauPolicy};
+use solver::solving::run_info::RunInfo;
+use solver::solving::verification::{check_optimality, OptimalityCheck};
 
-fn parse_search_order(s: &str) -> Result<SearchOrder, String> {
-    const ORDER_LEN: usize = 4;
-    let input = s.to_uppercase();
-    if input == "R" {
-        Ok(SearchOrder::Random)
-    } else if input.len() != ORDER_LEN {
-        Err(format!("Order must be {ORDER_LEN} characters"))
-    } else {
-        let order: Vec<BoardMove> = input
-            .chars()
-            .map(|c| match c {
-                'U' => Ok(BoardMove::Up),
-                'D' => Ok(BoardMove::Down),
-                'L' => Ok(BoardMove::Left),
-                'R' => Ok(BoardMove::Right),
-                _ => Err(format!("Invalid character {c}")),
-            })
-            .collect::<Result<_, _>>()?;
+fn validate_scramble(s: &str) -> Result<String, String> {
+    parse_moves(s).map_err(|e| e.to_string())?;
+    Ok(s.to_string())
+}
 
-        for i in 1..ORDER_LEN {
-            let current = &order[i - 1];
-            if order[i..].contains(current) {
-                return Err(format!("Duplicate move {current}"));
-            }
-        }
+fn parse_size(s: &str) -> Result<(u8, u8), String> {
+    let (rows, columns) = s
+        .split_once(['x', 'X'])
+        .ok_or("Size must have the form ROWSxCOLUMNS")?;
+    let parse = |value: &str| {
+        value
+            .parse::<u8>()
+            .ok()
+            .filter(|&value| value > 1)
+            .ok_or(format!("Invalid dimension {value}"))
+    };
+    Ok((parse(rows)?, parse(columns)?))
+}
 
-        Ok(SearchOrder::Provided([
-            order[0], order[1], order[2], order[3],
-        ]))
+fn parse_duplicate_policy(s: &str) -> Result<DuplicatePolicy, String> {
+    match s {
+        "keep" => Ok(DuplicatePolicy::Keep),
+        "ignore" => Ok(DuplicatePolicy::Ignore),
+        "decrease-key" => Ok(DuplicatePolicy::DecreaseKey),
+        "lazy-reinsert" => Ok(DuplicatePolicy::LazyReinsert),
+        _ => Err("Possible values are: keep, ignore, decrease-key, lazy-reinsert".to_string()),
     }
 }
 
+fn parse_probability(s: &str) -> Result<f64, String> {
+    s.parse()
+        .ok()
+        .filter(|probability| (0.0..=1.0).contains(probability))
+        .ok_or_else(|| format!("Probability must be a number between 0 and 1, got '{s}'"))
+}
+
+fn parse_epsilon(s: &str) -> Result<f64, String> {
+    s.parse()
+        .ok()
+        .filter(|epsilon: &f64| *epsilon >= 0.0)
+        .ok_or_else(|| format!("Epsilon must be a non-negative number, got '{s}'"))
+}
+
+fn parse_weight(s: &str) -> Result<f64, String> {
+    s.parse()
+        .ok()
+        .filter(|weight: &f64| *weight >= 1.0)
+        .ok_or_else(|| format!("Weight must be a number not less than 1, got '{s}'"))
+}
+
+fn parse_seconds(s: &str) -> Result<std::time::Duration, String> {
+    s.parse()
+        .ok()
+        .and_then(|seconds| std::time::Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| format!("Time must be a non-negative number of seconds, got '{s}'"))
+}
+
+fn parse_search_order(s: &str) -> Result<SearchOrder, String> {
+    s.parse().map_err(|e: SearchOrderError| e.to_string())
+}
+
 fn validate_heuristic(heuristic_id: &str) -> Result<String, String> {
-    parse_heuristic(heuristic_id)?;
-    Ok(heuristic_id.to_string())
+    heuristics::validate_id(heuristic_id)
+        .map(|()| heuristic_id.to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Reuses heuristics, together with their caches, between boards of a batch
+struct HeuristicCache {
+    warm: bool,
+    cached: HeuristicLru,
+    /// File the pattern database heuristics are loaded from, or saved to after generating them
+    pdb_file: Option<std::path::PathBuf>,
+    /// Directory the generated pattern databases are registered in
+    registry: Option<std::path::PathBuf>,
 }
 
-fn parse_heuristic(heuristic_id: &str) -> Result<Box<dyn Heuristic>, String> {
-    match heuristic_id {
-        "MD" | "manhattan_distance" => Ok(Box::<ManhattanDistance>::default()),
-        "LC" | "linear_conflict" => Ok(Box::<LinearConflict>::default()),
-        "ID" | "inversion_distance" => Ok(Box::<InversionDistance>::default()),
-        _ => Err("Unknown heuristic id. \
-        Possible values are: MD, manhattan_distance, LC, linear_conflict, ID, inversion_distance."
-            .to_string()),
+impl HeuristicCache {
+    fn new(cli: &CliArgs) -> Self {
+        Self {
+            warm: !cli.cold_cache,
+            cached: HeuristicLru::new(cli.heuristic_cache_size),
+            pdb_file: cli.pdb_file.clone(),
+            registry: cli.register_pdb.then(registry::default_directory).flatten(),
+        }
+    }
+
+    /// Returns the heuristic for the board, reusing one created for a previous board
+    /// with the same id, dimensions and goal
+    fn get(
+        &mut self,
+        heuristic_id: &str,
+        board: &OwnedBoard,
+        partition: Option<&Partition>,
+    ) -> Box<dyn Heuristic> {
+        Box::new(self.get_shared(heuristic_id, board, partition))
+    }
+
+    /// Returns the heuristic for the board like [`get`](HeuristicCache::get),
+    /// which can be shared between the threads of a parallel search
+    fn get_shared(
+        &mut self,
+        heuristic_id: &str,
+        board: &OwnedBoard,
+        partition: Option<&Partition>,
+    ) -> std::sync::Arc<dyn Heuristic + Send + Sync> {
+        // instance databases are built for a single board
+        if matches!(heuristic_id, "IPDB" | "instance_pattern_database") {
+            return std::sync::Arc::from(create_heuristic(heuristic_id, board, partition));
+        }
+        let key = HeuristicKey::for_board(heuristic_id, board, partition);
+        let pdb_file = self.pdb_file.as_deref().filter(|_| {
+            matches!(
+                heuristic_id,
+                "PDB" | "pattern_database" | "DPDB" | "disjoint_pattern_database"
+            )
+        });
+        let registry = self.registry.as_deref();
+        self.cached
+            .get_or_insert_with(key, || match (pdb_file, registry) {
+                (Some(path), _) => {
+                    heuristics::pattern_database_file(path, heuristic_id, board, partition)
+                }
+                (None, Some(directory)) => {
+                    heuristics::from_id_registering(heuristic_id, board, partition, directory)
+                }
+                (None, None) => heuristics::from_id(heuristic_id, board, partition),
+            })
+            .unwrap_or_else(|e| {
+                log::error!("{e}");
+                std::process::exit(1);
+            })
+    }
+
+    /// Prepares the cached heuristics for the next board, or discards them if the cache is cold
+    fn next_board(&mut self) {
+        if self.warm {
+            self.cached.reset_soft();
+        } else {
+            self.cached.clear();
+        }
+    }
+}
+
+fn create_heuristic(
+    heuristic_id: &str,
+    board: &OwnedBoard,
+    partition: Option<&Partition>,
+) -> Box<dyn Heuristic + Send + Sync> {
+    match heuristics::from_id(heuristic_id, board, partition) {
+        Ok(heuristic) => heuristic,
+        Err(e) => {
+            log::error!("{e}");
+            std::process::exit(1);
+        }
     }
 }
 
 #[derive(Parser, Debug, Clone)]
+#[command(subcommand_negates_reqs = true)]
 struct CliArgs {
     #[clap(flatten)]
     algorithm_info: AlgorithmArgs,
 
-    #[arg(short, long, action = clap::ArgAction::Count)]
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Write the progress of the lowest heuristic value to a CSV file (heuristic algorithms only)"
+    )]
+    convergence_csv: Option<std::path::PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "EXPANSIONS",
+        help = "Report a heuristic plateau after this many expansions without improvement (A* only)"
+    )]
+    plateau_length: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "THREADS",
+        help = "Number of threads of the parallel searches: BFS expands the queued positions on 1 by default, HDA* runs its workers on all available ones by default"
+    )]
+    threads: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Prune the positions already visited by the iteration at the same or a shallower depth (IDFS only)"
+    )]
+    transposition_table: bool,
+
+    #[arg(
+        long,
+        value_name = "MOVES",
+        requires = "plateau_length",
+        help = "Escape detected plateaus with a random walk of the given length"
+    )]
+    plateau_escape: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "POLICY",
+        default_value = "keep",
+        value_parser = crate::parse_duplicate_policy,
+        help = "Handling of positions reached again by A*: keep, ignore, decrease-key or lazy-reinsert"
+    )]
+    duplicate_policy: DuplicatePolicy,
+
+    #[arg(
+        long,
+        help = "Queue the nodes of A* and best-first search in buckets of their costs instead of a binary heap"
+    )]
+    bucket_queue: bool,
+
+    #[arg(
+        long,
+        value_name = "EPSILON",
+        default_value = "0.5",
+        value_parser = crate::parse_epsilon,
+        help = "Solutions of focal search are at most 1 + epsilon times longer than the shortest"
+    )]
+    epsilon: f64,
+
+    #[arg(
+        long,
+        value_name = "WEIGHT",
+        default_value = "1",
+        value_parser = crate::parse_weight,
+        help = "Multiply the heuristic values of IDA* by this weight, finding solutions at most this many times longer than the shortest"
+    )]
+    weight: f64,
+
+    #[arg(
+        long,
+        help = "Search the children of IDA* from the lowest f-cost, finding the solution earlier in the last iteration"
+    )]
+    child_ordering: bool,
+
+    #[arg(
+        long,
+        value_name = "DEPTH",
+        default_value = "4",
+        help = "Number of moves from the goal stored by the perimeter search"
+    )]
+    perimeter_depth: u64,
+
+    #[arg(
+        long,
+        help = "Remove the loops of the found solution, e.g. of DFS, where it revisits a position"
+    )]
+    optimize_solution: bool,
+
+    #[arg(
+        long,
+        value_name = "PROBABILITY",
+        value_parser = crate::parse_probability,
+        help = "Shuffle the search order of an expansion with this probability (BFS, DFS and IDFS only)"
+    )]
+    jitter: Option<f64>,
+
+    #[arg(
+        long,
+        value_name = "SEED",
+        requires = "jitter",
+        help = "Seed of the search order shuffles, random by default and logged for reproducing the run"
+    )]
+    jitter_seed: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        value_parser = crate::parse_seconds,
+        help = "Stop the search of a board after this time, logging the best partial solution. Algorithms which cannot suspend their search, e.g. --hda, reject it"
+    )]
+    time_limit: Option<std::time::Duration>,
+
+    #[arg(
+        long,
+        value_name = "COUNT",
+        help = "Stop the search of a board after expanding this many positions, logging the best partial solution"
+    )]
+    max_expansions: Option<u64>,
+
+    #[arg(
+        long,
+        help = "Order the moves of IDA* and DFS by how often they led to a solution or the next bound of IDA* in the previous iterations and boards"
+    )]
+    history: bool,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        requires = "history",
+        help = "File the move history is read from if it exists, and written to after solving"
+    )]
+    history_file: Option<std::path::PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["batch", "bench_mode", "time_limit", "max_expansions", "history"],
+        help = "Periodically save the state of the A* or IDA* search to this file, resuming the search from it if it exists"
+    )]
+    checkpoint: Option<std::path::PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        value_parser = crate::parse_seconds,
+        default_value = "60",
+        requires = "checkpoint",
+        help = "Time between the saves of the search state"
+    )]
+    checkpoint_interval: std::time::Duration,
+
+    #[arg(
+        long,
+        value_name = "GROUPS",
+        global = true,
+        help = "Tile groups of the PDB heuristic, e.g. 1-6|7-12|13-15"
+    )]
+    pdb_partition: Option<Partition>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "File of the database of the PDB and DPDB heuristics and of the optimal members of the portfolio, loaded if it exists, otherwise generated and saved to it"
+    )]
+    pdb_file: Option<std::path::PathBuf>,
+
+    #[arg(
+        long,
+        conflicts_with = "pdb_file",
+        help = "Register the generated pattern databases in the user cache, where later runs find them under their own heuristic ids"
+    )]
+    register_pdb: bool,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        global = true,
+        help = "File with the board to reach instead of the solved board"
+    )]
+    goal: Option<std::path::PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "MOVES",
+        global = true,
+        value_parser = crate::validate_scramble,
+        help = "Use the board created by applying the moves to the solved board instead of reading it"
+    )]
+    scramble: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "ROWSxCOLUMNS",
+        global = true,
+        default_value = "4x4",
+        value_parser = crate::parse_size,
+        help = "Dimensions of the board created from the scramble"
+    )]
+    size: (u8, u8),
+
+    #[arg(
+        long,
+        conflicts_with = "scramble",
+        help = "Solve every board from the standard input, reusing heuristic caches between boards"
+    )]
+    batch: bool,
+
+    #[arg(
+        long,
+        requires = "batch",
+        help = "Discard heuristic caches between boards of the batch"
+    )]
+    cold_cache: bool,
+
+    #[arg(
+        long,
+        value_name = "COUNT",
+        default_value = "4",
+        requires = "batch",
+        help = "Number of heuristics kept for boards of different sizes or goals, the least recently used is discarded"
+    )]
+    heuristic_cache_size: usize,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Accept boards without the size header, inferring the dimensions from the grid of cells"
+    )]
+    lenient: bool,
+
+    #[cfg(feature = "json")]
+    #[arg(
+        long,
+        conflicts_with = "batch",
+        help = "Read the board as JSON and write the solution with its statistics as JSON"
+    )]
+    json: bool,
+
+    #[arg(
+        long,
+        help = "Disable logging, warm up on the first board, search on a single thread and print only the timing of every board as JSON"
+    )]
+    bench_mode: bool,
+
+    #[arg(
+        long,
+        value_name = "HEURISTIC_ID",
+        num_args = 0..=1,
+        default_missing_value = "MD",
+        value_parser = crate::validate_heuristic,
+        conflicts_with = "bench_mode",
+        help = "Check with IDA* that no shorter solution exists, using the given admissible heuristic (MD by default)"
+    )]
+    verify_optimality: Option<String>,
+
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
     verbose: u8,
 }
 
+#[derive(Subcommand, Debug, Clone)]
+enum Command {
+    /// Verify a solution of the board and compare its length with the optimal solution
+    Grade {
+        #[arg(value_name = "SOLUTION", help = "Moves of the solution, e.g. ULDR")]
+        solution: String,
+
+        #[arg(long, value_name = "HEURISTIC_ID", default_value = "LC", value_parser = crate::validate_heuristic, help = "Heuristic used to find the optimal solution")]
+        heuristic: String,
+
+        #[arg(
+            long,
+            help = "Only use the heuristic lower bound instead of finding the optimal solution"
+        )]
+        bound_only: bool,
+    },
+    /// Print a scramble sequence which creates the board from the solved board
+    Scramble {
+        #[arg(long, value_name = "HEURISTIC_ID", default_value = "LC", value_parser = crate::validate_heuristic, help = "Heuristic used to solve the board")]
+        heuristic: String,
+    },
+    /// Print every legal move of the board with the change of the heuristic value it causes
+    Analyze {
+        #[arg(long, value_name = "HEURISTIC_ID", default_value = "LC", value_parser = crate::validate_heuristic, help = "Heuristic used to evaluate the moves")]
+        heuristic: String,
+
+        #[arg(
+            long,
+            value_name = "COUNT",
+            default_value = "0",
+            help = "Also print up to this many of the shortest distinct solutions"
+        )]
+        alternatives: usize,
+
+        #[arg(
+            long,
+            value_name = "MOVES",
+            default_value = "1",
+            help = "Minimal edit distance between the printed alternative solutions"
+        )]
+        min_distance: usize,
+    },
+    /// Print how different two solutions of the board are
+    Compare {
+        #[arg(value_name = "FIRST", help = "Moves of the first solution, e.g. ULDR")]
+        first: String,
+
+        #[arg(value_name = "SECOND", help = "Moves of the second solution")]
+        second: String,
+    },
+    /// Print the number of distinct positions at every distance from the board as CSV
+    Profile {
+        #[arg(
+            long,
+            value_name = "DEPTH",
+            default_value = "10",
+            help = "Maximum distance from the board"
+        )]
+        depth: usize,
+    },
+    /// Explore the board interactively with commands read from the standard input, e.g. `move ULDR`, `hint` or `undo`.
+    /// The session starts with the board of the scramble, or with the solved board
+    Repl,
+}
+
 #[derive(Parser, Clone, Debug)]
 #[group(required = true, multiple = false)]
 #[clap(disable_help_flag = true)]
@@ -79,6 +522,18 @@ struct AlgorithmArgs {
     #[arg(short, long, value_name = "ORDER", value_parser = crate::parse_search_order, help = "Iterative deepening DFS")]
     idfs: Option<SearchOrder>,
 
+    #[arg(
+        long,
+        help = "Breadth-first search storing only the layer being expanded and the next one"
+    )]
+    frontier_bfs: bool,
+
+    #[arg(
+        long,
+        help = "Optimal 3x3 solver looking the distances up in a table, stored in the cache directory on first use"
+    )]
+    optimal8: bool,
+
     #[arg(short = 'h', long = "bf", value_name = "HEURISTIC_ID", value_parser = crate::validate_heuristic, help = "Greedy Best-first search")]
     best_first: Option<String>,
 
@@ -87,89 +542,897 @@ struct AlgorithmArgs {
 
     #[arg(long, value_name = "HEURISTIC_ID", value_parser = crate::validate_heuristic, help = "A* search algorithm")]
     ida: Option<String>,
+
+    #[arg(long, value_name = "HEURISTIC_ID", value_parser = crate::validate_heuristic, help = "Focal search, finding solutions at most 1 + epsilon times longer than the shortest")]
+    focal: Option<String>,
+
+    #[arg(long, value_name = "HEURISTIC_ID", value_parser = crate::validate_heuristic, help = "IDA* towards the perimeter of positions close to the goal")]
+    perimeter: Option<String>,
+
+    #[arg(long, value_name = "HEURISTIC_ID", value_parser = crate::validate_heuristic, help = "Breadth-first heuristic search, storing only a few layers of positions")]
+    bfhs: Option<String>,
+
+    #[arg(long, value_name = "HEURISTIC_ID", value_parser = crate::validate_heuristic, help = "A* search distributed between threads by the hash of the positions, see --threads")]
+    hda: Option<String>,
+
+    #[cfg(feature = "experimental")]
+    #[arg(long, value_name = "HEURISTIC_ID", value_parser = crate::validate_heuristic, help = "Monte Carlo tree search with rollouts guided by the heuristic, finding long solutions of large boards")]
+    mcts: Option<String>,
+
+    #[arg(
+        long,
+        help = "IDA* with additive disjoint pattern databases, 6-6-3 for 4x4 unless --pdb-partition is given"
+    )]
+    disjoint_pdb: bool,
+
+    #[arg(
+        long,
+        help = "IDA* with linear conflict, A* with linear conflict and greedy best-first search with Manhattan distance on their own threads, taking the first solution. With --pdb-file, IDA* and A* use its pattern database instead"
+    )]
+    portfolio: bool,
 }
 
-fn create_solver(config: AlgorithmArgs, board: OwnedBoard) -> Box<dyn Solver> {
-    use solver::solving::algorithm::solvers::*;
-    use solver::solving::movegen::MoveGenerator;
-
-    if let Some(order) = config.bfs {
-        Box::new(BFSSolver::new(board, MoveGenerator::new(order)))
-    } else if let Some(order) = config.dfs {
-        Box::new(DFSSolver::new(board, MoveGenerator::new(order)))
-    } else if let Some(order) = config.idfs {
-        Box::new(IncrementalDFSSolver::new(board, MoveGenerator::new(order)))
-    } else if let Some(heuristic_id) = &config.best_first {
-        let _heuristic = parse_heuristic(heuristic_id)
-            .expect("Parser should fail if heuristic id was incorrect");
-        todo!("Best-first search is not implemented yet")
-    } else if let Some(heuristic_id) = &config.astar {
-        let heuristic = parse_heuristic(heuristic_id)
-            .expect("Parser should fail if heuristic id was incorrect");
-        Box::new(AStarSolver::new(board, heuristic))
-    } else if let Some(heuristic_id) = &config.ida {
-        let heuristic = parse_heuristic(heuristic_id)
-            .expect("Parser should fail if heuristic id was incorrect");
-        Box::new(IterativeAStarSolver::new(board, heuristic))
+impl AlgorithmArgs {
+    /// Returns the algorithm chosen by the arguments, which are a group of mutually exclusive options
+    fn selected(&self) -> SelectedAlgorithm<'_> {
+        use SelectedAlgorithm::*;
+
+        let uninformed = [
+            (Bfs as fn(_) -> _, &self.bfs),
+            (Dfs, &self.dfs),
+            (Idfs, &self.idfs),
+        ]
+        .into_iter()
+        .find_map(|(algorithm, order)| order.as_ref().map(algorithm));
+        let informed = [
+            (BestFirst as fn(_) -> _, &self.best_first),
+            (AStar, &self.astar),
+            (Ida, &self.ida),
+            (Focal, &self.focal),
+            (Perimeter, &self.perimeter),
+            (Bfhs, &self.bfhs),
+            (Hda, &self.hda),
+            #[cfg(feature = "experimental")]
+            (Mcts, &self.mcts),
+        ]
+        .into_iter()
+        .find_map(|(algorithm, heuristic_id)| heuristic_id.as_deref().map(algorithm));
+        uninformed
+            .or(informed)
+            .or(self.frontier_bfs.then_some(FrontierBfs))
+            .or(self.optimal8.then_some(Optimal8))
+            .or(self.disjoint_pdb.then_some(DisjointPdb))
+            .or(self.portfolio.then_some(Portfolio))
+            .unwrap_or_else(|| {
+                unreachable!("Parser should fail if none of the options are selected")
+            })
+    }
+}
+
+/// Algorithm chosen by the arguments, with its search order or heuristic id.
+/// Both the description of the run and the solver are derived from it, so that they cannot disagree
+#[derive(Clone, Copy, Debug)]
+enum SelectedAlgorithm<'a> {
+    Bfs(&'a SearchOrder),
+    Dfs(&'a SearchOrder),
+    Idfs(&'a SearchOrder),
+    FrontierBfs,
+    Optimal8,
+    BestFirst(&'a str),
+    AStar(&'a str),
+    Ida(&'a str),
+    Focal(&'a str),
+    Perimeter(&'a str),
+    Bfhs(&'a str),
+    Hda(&'a str),
+    #[cfg(feature = "experimental")]
+    Mcts(&'a str),
+    /// IDA* with the disjoint pattern databases
+    DisjointPdb,
+    Portfolio,
+}
+
+impl<'a> SelectedAlgorithm<'a> {
+    /// Name of the algorithm, together with its search order if it has one, e.g. `bfs LRUD`
+    fn name(self) -> String {
+        match self {
+            Self::Bfs(order) => format!("bfs {order}"),
+            Self::Dfs(order) => format!("dfs {order}"),
+            Self::Idfs(order) => format!("idfs {order}"),
+            Self::FrontierBfs => "frontier-bfs".to_string(),
+            Self::Optimal8 => "optimal8".to_string(),
+            Self::BestFirst(_) => "bf".to_string(),
+            Self::AStar(_) => "astar".to_string(),
+            Self::Ida(_) | Self::DisjointPdb => "ida".to_string(),
+            Self::Focal(_) => "focal".to_string(),
+            Self::Perimeter(_) => "perimeter".to_string(),
+            Self::Bfhs(_) => "bfhs".to_string(),
+            Self::Hda(_) => "hda".to_string(),
+            #[cfg(feature = "experimental")]
+            Self::Mcts(_) => "mcts".to_string(),
+            Self::Portfolio => "portfolio".to_string(),
+        }
+    }
+
+    /// Id of the heuristic guiding the search, `None` for the uninformed algorithms
+    fn heuristic_id(self) -> Option<&'a str> {
+        match self {
+            Self::BestFirst(heuristic_id)
+            | Self::AStar(heuristic_id)
+            | Self::Ida(heuristic_id)
+            | Self::Focal(heuristic_id)
+            | Self::Perimeter(heuristic_id)
+            | Self::Bfhs(heuristic_id)
+            | Self::Hda(heuristic_id) => Some(heuristic_id),
+            #[cfg(feature = "experimental")]
+            Self::Mcts(heuristic_id) => Some(heuristic_id),
+            Self::DisjointPdb => Some("DPDB"),
+            Self::Bfs(_)
+            | Self::Dfs(_)
+            | Self::Idfs(_)
+            | Self::FrontierBfs
+            | Self::Optimal8
+            | Self::Portfolio => None,
+        }
+    }
+
+    /// Checks if the algorithm always finds the shortest solution with the options,
+    /// assuming the heuristic is admissible
+    fn finds_shortest(self, options: &SearchOptions) -> bool {
+        match self {
+            Self::Bfs(_)
+            | Self::Idfs(_)
+            | Self::FrontierBfs
+            | Self::Optimal8
+            | Self::AStar(_)
+            | Self::Perimeter(_)
+            | Self::Bfhs(_)
+            | Self::Hda(_) => true,
+            Self::Ida(_) | Self::DisjointPdb => options.weight <= 1.0,
+            #[cfg(feature = "experimental")]
+            Self::Mcts(_) => false,
+            Self::Dfs(_) | Self::BestFirst(_) | Self::Focal(_) | Self::Portfolio => false,
+        }
+    }
+
+    /// Describes the run of the algorithm on the board, with the options which change its search
+    fn run_info(self, board: &OwnedBoard, options: &SearchOptions) -> RunInfo {
+        let run = RunInfo::new(self.name(), board, &options.solver_config);
+        let run = match self.heuristic_id() {
+            Some(heuristic_id) => run.with_heuristic(heuristic_id),
+            None => run,
+        };
+        let run = match self {
+            Self::Ida(_) | Self::DisjointPdb => run
+                .with_parameter("weight", options.weight)
+                .with_parameter("child_ordering", options.child_ordering)
+                .with_parameter("history", options.history.is_some()),
+            Self::Focal(_) => run.with_parameter("epsilon", options.epsilon),
+            Self::Perimeter(_) => run.with_parameter("perimeter_depth", options.perimeter_depth),
+            Self::Dfs(_) => run.with_parameter("history", options.history.is_some()),
+            Self::Idfs(_) => run
+                .with_parameter("transposition_table", options.transposition_table)
+                .with_parameter("history", options.history.is_some()),
+            _ => run,
+        };
+        run.with_seed(options.jitter.map(|(_, seed)| seed))
+    }
+}
+
+/// Options changing how the search algorithms explore the boards
+#[derive(Clone)]
+struct SearchOptions {
+    duplicate_policy: DuplicatePolicy,
+    bucket_queue: bool,
+    optimize_solution: bool,
+    /// Suboptimality of the focal search
+    epsilon: f64,
+    /// Weight of the heuristic values of IDA*
+    weight: f64,
+    /// Search the children of IDA* from the lowest f-cost
+    child_ordering: bool,
+    perimeter_depth: u64,
+    /// Solve 3x3 boards with the dedicated solver if the algorithm finds the shortest solution anyway
+    fast_path: bool,
+    /// Threads of the parallel searches, `None` for the default of the algorithm
+    threads: Option<usize>,
+    transposition_table: bool,
+    /// Probability and seed of shuffling the search order of an expansion
+    jitter: Option<(f64, u64)>,
+    solver_config: SolverConfig,
+    /// Move history shared by the boards of the batch
+    history: Option<SharedHistory>,
+    /// File the state of the search is saved to, and the time between the saves
+    checkpoint: Option<(std::path::PathBuf, std::time::Duration)>,
+}
+
+impl SearchOptions {
+    /// Takes the options from the arguments, drawing the jitter seed unless it is given
+    fn new(cli: &CliArgs) -> Self {
+        let jitter = cli.jitter.map(|probability| {
+            let seed = cli.jitter_seed.unwrap_or_else(rand::random);
+            log::info!("Jittering the search order with seed {seed}");
+            (probability, seed)
+        });
+        Self {
+            duplicate_policy: cli.duplicate_policy,
+            bucket_queue: cli.bucket_queue,
+            optimize_solution: cli.optimize_solution,
+            epsilon: cli.epsilon,
+            weight: cli.weight,
+            child_ordering: cli.child_ordering,
+            perimeter_depth: cli.perimeter_depth,
+            // the benchmark measures the selected algorithm, and the dedicated solver cannot stop at the time limit
+            fast_path: !cli.bench_mode && cli.time_limit.is_none(),
+            // timings of the benchmark do not depend on the cores of the machine
+            threads: if cli.bench_mode { Some(1) } else { cli.threads },
+            transposition_table: cli.transposition_table,
+            jitter,
+            solver_config: SolverConfig {
+                time_limit: cli.time_limit,
+                max_expansions: cli.max_expansions,
+            },
+            history: cli.history.then(|| {
+                let table = cli
+                    .history_file
+                    .as_deref()
+                    .map_or_else(HistoryTable::new, read_history);
+                SharedHistory::new(table)
+            }),
+            checkpoint: cli
+                .checkpoint
+                .clone()
+                .map(|path| (path, cli.checkpoint_interval)),
+        }
+    }
+
+    fn move_generator(&self, order: SearchOrder) -> MoveGenerator {
+        let move_generator = MoveGenerator::new(order);
+        match self.jitter {
+            Some((probability, seed)) => move_generator.with_jitter(probability, seed),
+            None => move_generator,
+        }
+    }
+}
+
+/// Creates the depth-first search, or the iterative deepening one if `incremental` is set
+fn create_dfs_solver(
+    board: OwnedBoard,
+    order: SearchOrder,
+    incremental: bool,
+    options: &SearchOptions,
+) -> Box<dyn Solver> {
+    use solver::solving::algorithm::solvers::{DFSSolver, IncrementalDFSSolver};
+
+    let move_generator = options.move_generator(order);
+    if !incremental {
+        let solver = DFSSolver::new(board, move_generator);
+        return match options.history.clone() {
+            Some(history) => Box::new(solver.with_history(history)),
+            None => Box::new(solver),
+        };
+    }
+    let mut solver = IncrementalDFSSolver::new(board, move_generator);
+    if let Some(history) = options.history.clone() {
+        solver = solver.with_history(history);
+    }
+    if options.transposition_table {
+        solver = solver.with_transposition_table();
+    }
+    Box::new(solver)
+}
+
+/// Creates the solver of the algorithm, or the dedicated solver of 3x3 boards
+/// if the algorithm finds the shortest solution anyway
+fn create_solver(
+    algorithm: SelectedAlgorithm,
+    board: OwnedBoard,
+    convergence_log: Option<ConvergenceLog>,
+    plateau_monitor: Option<PlateauMonitor>,
+    partition: Option<&Partition>,
+    heuristics: &mut HeuristicCache,
+    options: &SearchOptions,
+) -> Box<dyn Solver> {
+    use solver::solving::algorithm::eight::with_fast_path;
+
+    let create = |board| {
+        create_search_solver(
+            algorithm,
+            board,
+            convergence_log,
+            plateau_monitor,
+            partition,
+            heuristics,
+            options,
+        )
+    };
+    if options.fast_path && algorithm.finds_shortest(options) {
+        with_fast_path(board, create)
     } else {
-        unreachable!("Parser should fail if none of the options are selected")
+        create(board)
     }
 }
 
-fn main() {
-    let cli = CliArgs::parse();
+fn create_search_solver(
+    algorithm: SelectedAlgorithm,
+    board: OwnedBoard,
+    convergence_log: Option<ConvergenceLog>,
+    plateau_monitor: Option<PlateauMonitor>,
+    partition: Option<&Partition>,
+    heuristics: &mut HeuristicCache,
+    options: &SearchOptions,
+) -> Box<dyn Solver> {
+    use solver::solving::algorithm::heuristic::bestfs::BestFSSolver;
+    use solver::solving::algorithm::solvers::*;
+    use SelectedAlgorithm::*;
 
-    simple_logger::SimpleLogger::new()
-        .with_local_timestamps()
-        .with_timestamp_format(time::macros::format_description!(
-            "[hour]:[minute]:[second]"
-        ))
-        .init()
-        .unwrap();
+    match algorithm {
+        #[cfg(feature = "experimental")]
+        Mcts(heuristic_id) => {
+            let heuristic = heuristics.get(heuristic_id, &board, partition);
+            Box::new(MonteCarloSolver::new(board, heuristic))
+        }
+        Bfs(order) => Box::new(
+            BFSSolver::new(board, options.move_generator(order.clone()))
+                .with_threads(options.threads.unwrap_or(1)),
+        ),
+        Dfs(order) => create_dfs_solver(board, order.clone(), false, options),
+        Idfs(order) => create_dfs_solver(board, order.clone(), true, options),
+        FrontierBfs => Box::new(FrontierBFSSolver::new(board)),
+        Optimal8 => Box::new(Optimal8Solver::new(board)),
+        BestFirst(heuristic_id) => {
+            let heuristic = heuristics.get(heuristic_id, &board, partition);
+            let mut solver =
+                BestFSSolver::new(board, heuristic).with_duplicate_policy(options.duplicate_policy);
+            if options.bucket_queue {
+                solver = solver.with_bucket_queue();
+            }
+            if let Some(log) = convergence_log {
+                solver = solver.with_convergence_log(log);
+            }
+            if let Some(monitor) = plateau_monitor {
+                solver = solver.with_plateau_monitor(monitor);
+            }
+            Box::new(solver)
+        }
+        AStar(heuristic_id) => {
+            let heuristic = heuristics.get(heuristic_id, &board, partition);
+            let mut solver =
+                AStarSolver::new(board, heuristic).with_duplicate_policy(options.duplicate_policy);
+            if options.bucket_queue {
+                solver = solver.with_bucket_queue();
+            }
+            if let Some(log) = convergence_log {
+                solver = solver.with_convergence_log(log);
+            }
+            if let Some(monitor) = plateau_monitor {
+                solver = solver.with_plateau_monitor(monitor);
+            }
+            Box::new(solver)
+        }
+        Ida(_) | DisjointPdb => {
+            let heuristic_id = algorithm.heuristic_id().unwrap_or("DPDB");
+            let heuristic = heuristics.get(heuristic_id, &board, partition);
+            let mut solver = IterativeAStarSolver::weighted(board, heuristic, options.weight)
+                .with_child_ordering(options.child_ordering);
+            if let Some(history) = &options.history {
+                solver = solver.with_history(history.clone());
+            }
+            match convergence_log {
+                Some(log) => Box::new(solver.with_convergence_log(log)),
+                None => Box::new(solver),
+            }
+        }
+        Focal(heuristic_id) => {
+            let heuristic = heuristics.get(heuristic_id, &board, partition);
+            Box::new(FocalSolver::new(board, heuristic, options.epsilon))
+        }
+        Perimeter(heuristic_id) => {
+            let heuristic = heuristics.get(heuristic_id, &board, partition);
+            Box::new(PerimeterSearchSolver::new(
+                board,
+                heuristic,
+                options.perimeter_depth,
+            ))
+        }
+        Bfhs(heuristic_id) => {
+            let heuristic = heuristics.get(heuristic_id, &board, partition);
+            Box::new(BreadthFirstHeuristicSolver::new(board, heuristic))
+        }
+        Hda(heuristic_id) => {
+            let threads = options
+                .threads
+                .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, usize::from));
+            // the workers share a single heuristic, so its tables are built or loaded only once
+            let heuristic = heuristics.get_shared(heuristic_id, &board, partition);
+            Box::new(HashDistributedAStarSolver::new(
+                board,
+                move || Box::new(std::sync::Arc::clone(&heuristic)),
+                threads,
+            ))
+        }
+        Portfolio => Box::new(default_portfolio(board, heuristics, partition)),
+    }
+}
 
-    log::set_max_level(match cli.verbose {
-        0 => LevelFilter::Error,
-        1 => LevelFilter::Info,
-        2 => LevelFilter::Debug,
-        3.. => LevelFilter::Trace,
-    });
+/// Portfolio of complementary solvers: the optimal ones, and the greedy one for the boards too hard for them.
+/// The optimal ones use the pattern database of `--pdb-file` if it is given, and the linear conflict otherwise
+fn default_portfolio(
+    board: OwnedBoard,
+    heuristics: &mut HeuristicCache,
+    partition: Option<&Partition>,
+) -> solver::solving::algorithm::solvers::PortfolioSolver {
+    use solver::solving::algorithm::heuristic::bestfs::BestFSSolver;
+    use solver::solving::algorithm::solvers::*;
+
+    let (optimal_id, optimal_name) = if heuristics.pdb_file.is_some() {
+        ("DPDB", "pattern database")
+    } else {
+        ("LC", "linear conflict")
+    };
+    let optimal = heuristics.get_shared(optimal_id, &board, partition);
+    let ida_heuristic = std::sync::Arc::clone(&optimal);
+    let greedy = heuristics.get_shared("MD", &board, None);
+
+    PortfolioSolver::new(board)
+        .with_member(format!("IDA* with {optimal_name}"), move |board| {
+            Box::new(IterativeAStarSolver::new(board, Box::new(ida_heuristic)))
+        })
+        .with_member(format!("A* with {optimal_name}"), move |board| {
+            Box::new(AStarSolver::new(board, Box::new(optimal)))
+        })
+        .with_member(
+            "Greedy best-first search with Manhattan distance",
+            move |board| Box::new(BestFSSolver::new(board, Box::new(greedy))),
+        )
+}
+
+fn stdin_lines() -> impl Iterator<Item = String> {
+    std::io::stdin()
+        .lines()
+        .map(|l| l.expect("Stdin must be valid UTF-8"))
+}
+
+/// Parses the boards from the standard input, also accepting boards without the size header if `lenient`
+fn stdin_boards(lenient: bool) -> Box<dyn Iterator<Item = Result<OwnedBoard, BoardCreationError>>> {
+    if lenient {
+        Box::new(OwnedBoard::parse_many_lenient(stdin_lines()))
+    } else {
+        Box::new(OwnedBoard::parse_many(stdin_lines()))
+    }
+}
+
+fn read_board(lenient: bool) -> OwnedBoard {
+    let parse_result = stdin_boards(lenient)
+        .next()
+        .unwrap_or(Err(BoardCreationError::InvalidHeader));
+    match parse_result {
+        Ok(board) => board,
+        Err(e) => {
+            log::error!("Error while parsing board: {e}");
+            std::process::exit(1);
+        }
+    }
+}
 
-    let board = match OwnedBoard::try_from_iter(
-        std::io::stdin()
-            .lines()
-            .map(|l| l.expect("Stdin must be valid UTF-8")),
-    ) {
+#[cfg(feature = "json")]
+fn read_json_board() -> OwnedBoard {
+    let parse_result = std::io::read_to_string(std::io::stdin())
+        .map_err(|e| e.to_string())
+        .and_then(|input| OwnedBoard::from_json(&input).map_err(|e| e.to_string()));
+    match parse_result {
         Ok(board) => board,
         Err(e) => {
             log::error!("Error while parsing board: {e}");
             std::process::exit(1);
         }
+    }
+}
+
+/// Returns the board given by the scramble, or reads it from the standard input
+fn input_board(cli: &CliArgs) -> OwnedBoard {
+    let Some(scramble) = cli.scramble.as_deref() else {
+        #[cfg(feature = "json")]
+        if cli.json {
+            return read_json_board();
+        }
+        return read_board(cli.lenient);
+    };
+    let (rows, columns) = cli.size;
+    let scramble = parse_moves(scramble).expect("Parser should fail if scramble was incorrect");
+    match OwnedBoard::from_scramble(rows, columns, &scramble) {
+        Ok(board) => board,
+        Err(e) => {
+            log::error!("Invalid scramble: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Reads all boards from the standard input, written one after another
+fn read_boards(lenient: bool) -> Vec<OwnedBoard> {
+    let mut boards = vec![];
+    for parse_result in stdin_boards(lenient) {
+        match parse_result {
+            Ok(board) => boards.push(board),
+            Err(e) => {
+                log::error!("Error while parsing board {}: {e}", boards.len() + 1);
+                std::process::exit(1);
+            }
+        }
+    }
+    boards
+}
+
+fn read_goal(board: OwnedBoard, path: &std::path::Path) -> OwnedBoard {
+    let goal = std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|input| input.parse::<OwnedBoard>().map_err(|e| e.to_string()))
+        .and_then(|goal| board.with_goal(goal).map_err(|e| e.to_string()));
+    match goal {
+        Ok(board) => board,
+        Err(e) => {
+            log::error!("Error while reading goal: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_command(command: Command, board: &OwnedBoard, partition: Option<&Partition>) {
+    match command {
+        Command::Grade {
+            solution,
+            heuristic,
+            bound_only,
+        } => {
+            let solution = match parse_moves(&solution) {
+                Ok(solution) => solution,
+                Err(e) => {
+                    log::error!("Error while parsing solution: {e}");
+                    std::process::exit(1);
+                }
+            };
+            let heuristic = create_heuristic(&heuristic, board, partition);
+            match solver::solving::grading::grade(board, &solution, heuristic, !bound_only) {
+                Ok(report) => println!("{report}"),
+                Err(e) => {
+                    log::error!("{e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Scramble { heuristic } => {
+            use solver::solving::algorithm::eight::with_fast_path;
+            use solver::solving::algorithm::solvers::IterativeAStarSolver;
+
+            let solver = with_fast_path(board.clone(), |board| {
+                let heuristic = create_heuristic(&heuristic, &board, partition);
+                Box::new(IterativeAStarSolver::new(board, heuristic))
+            });
+            match solver.solve() {
+                Ok(solution) => {
+                    let scramble: Vec<_> = scramble_from_solution(&solution)
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect();
+                    println!("{}", scramble.join(""));
+                }
+                Err(e) => {
+                    log::error!("Unable to solve board: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Analyze {
+            heuristic: heuristic_id,
+            alternatives,
+            min_distance,
+        } => {
+            print_analysis(board, &*create_heuristic(&heuristic_id, board, partition));
+            if alternatives > 0 {
+                print_alternatives(
+                    board,
+                    create_heuristic(&heuristic_id, board, partition),
+                    alternatives,
+                    min_distance,
+                );
+            }
+        }
+        Command::Compare { first, second } => {
+            use solver::solving::similarity::{edit_distance, shared_prefix_length};
+
+            let parse = |moves: &str| {
+                parse_moves(moves).unwrap_or_else(|e| {
+                    log::error!("Error while parsing solution: {e}");
+                    std::process::exit(1);
+                })
+            };
+            let (first, second) = (parse(&first), parse(&second));
+            for (name, solution) in [("First", &first), ("Second", &second)] {
+                let mut solved = board.clone();
+                let solves = solved.apply_moves(solution).is_ok() && solved.is_solved();
+                println!(
+                    "{name} solution: {} moves{}",
+                    solution.len(),
+                    if solves {
+                        ""
+                    } else {
+                        ", does not solve the board"
+                    }
+                );
+            }
+            println!("Edit distance: {}", edit_distance(&first, &second));
+            println!("Shared prefix: {}", shared_prefix_length(&first, &second));
+        }
+        Command::Repl => unreachable!("Session reads commands instead of the board"),
+        Command::Profile { depth } => {
+            let profile = solver::solving::census::GrowthProfile::compute(board, depth);
+            if let Err(e) = profile.write_csv(std::io::stdout().lock()) {
+                log::error!("Unable to write the profile: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+/// Prints the heuristic value of the board, its change caused by every legal move,
+/// and the contributions of the tiles from the most out of place one
+fn print_analysis(board: &OwnedBoard, heuristic: &dyn Heuristic) {
+    println!("Heuristic: {}", heuristic.evaluate(board));
+    for (board_move, change) in solver::solving::analysis::analyze_moves(board, heuristic) {
+        println!("{board_move}: {change:+}");
+    }
+    if let Some(mut breakdown) = heuristic.breakdown(board) {
+        breakdown.sort_by_key(|contribution| std::cmp::Reverse(contribution.value()));
+        for contribution in breakdown.iter().filter(|c| c.value() > 0) {
+            println!(
+                "Tile {}: {} (distance {}, {} conflicts)",
+                contribution.tile,
+                contribution.value(),
+                contribution.distance,
+                contribution.conflicts
+            );
+        }
+    }
+}
+
+/// Prints the shortest solutions of the board which differ from each other by at least `min_distance` moves
+fn print_alternatives(
+    board: &OwnedBoard,
+    heuristic: Box<dyn Heuristic>,
+    count: usize,
+    min_distance: usize,
+) {
+    use solver::solving::algorithm::solvers::AStarSolver;
+    use solver::solving::similarity::{deduplicate, edit_distance};
+
+    let solutions = match AStarSolver::new(board.clone(), heuristic).solve_k_shortest(count) {
+        Ok(solutions) => deduplicate(solutions, min_distance),
+        Err(e) => {
+            log::error!("Unable to solve board: {e}");
+            std::process::exit(1);
+        }
     };
+    for solution in &solutions {
+        let moves: String = solution.iter().map(ToString::to_string).collect();
+        println!(
+            "Alternative ({} moves, {} edits from the shortest): {moves}",
+            solution.len(),
+            edit_distance(&solutions[0], solution)
+        );
+    }
+}
 
-    let solver = create_solver(cli.algorithm_info, board);
+/// Executes the commands of the interactive session, one per line, until the input ends or `quit` is given
+fn run_repl(board: OwnedBoard) {
+    use std::io::{IsTerminal, Write};
+
+    use solver::solving::session::{Session, SessionCommand};
+
+    let interactive = std::io::stdin().is_terminal();
+    let prompt = || {
+        if interactive {
+            print!("> ");
+            let _ = std::io::stdout().flush();
+        }
+    };
+    let mut session = Session::new(board);
+    println!(
+        "{}",
+        session.execute(SessionCommand::Show).unwrap_or_default()
+    );
+    prompt();
+    for line in stdin_lines() {
+        let line = line.trim();
+        if matches!(line, "quit" | "exit") {
+            break;
+        }
+        if !line.is_empty() && !line.starts_with('#') {
+            match line.parse().and_then(|command| session.execute(command)) {
+                Ok(output) => println!("{output}"),
+                Err(e) => eprintln!("{e}"),
+            }
+        }
+        prompt();
+    }
+}
+
+/// Runs the solver, returning `None` if the board is unsolvable
+fn solve_board(
+    solver: Box<dyn Solver>,
+    options: &SearchOptions,
+    run: &RunInfo,
+) -> Option<Vec<BoardMove>> {
     log::info!("Starting solver");
 
-    let start = std::time::Instant::now();
-    let solve_result = solver.solve();
-    let finish = start.elapsed();
-    let solution = match solve_result {
-        Ok(solution) => {
+    let result = match &options.checkpoint {
+        Some((path, interval)) => solve_with_checkpoints(solver, path, *interval, run),
+        None => solver.solve_with_stats(&options.solver_config),
+    };
+    match result {
+        Ok(report) => {
             log::info!(
                 "Found solution in {:#}",
-                duration_human::DurationHuman::from(finish)
+                duration_human::DurationHuman::from(report.duration)
             );
-            solution
+            if let (Some(expanded), Some(generated), Some(frontier), Some(memory)) = (
+                report.nodes_expanded,
+                report.nodes_generated,
+                report.max_frontier,
+                report.peak_memory_estimate,
+            ) {
+                log::info!(
+                    "Expanded {expanded} positions, generated {generated}, largest frontier {frontier}, peak memory about {} KiB",
+                    memory / 1024
+                );
+            }
+            if !options.optimize_solution {
+                return Some(report.solution);
+            }
+            let optimized = solver::solving::solution::optimize(&report.solution);
+            log::info!(
+                "Removed {} moves of loops from the solution",
+                report.solution.len() - optimized.len()
+            );
+            Some(optimized)
         }
-        Err(SolvingError::UnsolvableBoard) => {
-            log::warn!("Board is unsolvable");
-            Vec::default()
+        Err(SolvingError::UnsolvableBoard(diagnosis)) => {
+            match diagnosis {
+                // shown at the default verbosity, as it usually points at a mistyped cell
+                Some(diagnosis) => log::error!("{diagnosis}"),
+                None => log::warn!("Board is unsolvable"),
+            }
+            None
         }
         Err(SolvingError::AlgorithmError(inner_error)) => {
             log::error!("Unable to solve board: {}", inner_error);
             std::process::exit(1);
         }
-    };
+        Err(e @ SolvingError::NoSolverFinished) => {
+            log::error!("Unable to solve board: {e}");
+            std::process::exit(1);
+        }
+        Err(e @ (SolvingError::TimedOut { .. } | SolvingError::ExpansionLimitReached { .. })) => {
+            let moves: String = e
+                .best_partial()
+                .unwrap_or_default()
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            log::error!("{e}: {moves}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Runs the search in slices of the interval, writing its state to the file after every slice.
+/// If the file exists, the search is resumed from it, as long as it was written by the same run.
+/// The file is removed once the search finishes
+fn solve_with_checkpoints(
+    mut solver: Box<dyn Solver>,
+    path: &std::path::Path,
+    interval: std::time::Duration,
+    run: &RunInfo,
+) -> Result<SolveReport, SolvingError> {
+    if path.exists() {
+        let checkpoint = std::fs::File::open(path)
+            .and_then(|file| RunCheckpoint::read(std::io::BufReader::new(file)))
+            .unwrap_or_else(|e| {
+                log::error!("Unable to read checkpoint {}: {e}", path.display());
+                std::process::exit(1);
+            });
+        let resumed = checkpoint
+            .into_checkpoint(run)
+            .and_then(|checkpoint| solver.resume(checkpoint));
+        if let Err(e) = resumed {
+            log::error!("Unable to resume the search: {e}");
+            std::process::exit(1);
+        }
+        log::info!("Resumed the search from {}", path.display());
+    }
 
+    let start = std::time::Instant::now();
+    loop {
+        match solver.solve_for(interval) {
+            SolveStep::Done(result) => {
+                if let Err(e) = std::fs::remove_file(path) {
+                    if e.kind() != std::io::ErrorKind::NotFound {
+                        log::warn!("Unable to remove checkpoint {}: {e}", path.display());
+                    }
+                }
+                // counters add up over the slices of this process, but not of the resumed ones
+                return result.map(|report| SolveReport {
+                    duration: start.elapsed(),
+                    ..report
+                });
+            }
+            SolveStep::Pending(suspended) => {
+                match suspended.checkpoint() {
+                    Some(checkpoint) => {
+                        write_checkpoint(path, &RunCheckpoint::new(run, checkpoint));
+                    }
+                    None => log::warn!("Solver cannot save the state of its search"),
+                }
+                solver = suspended;
+            }
+        }
+    }
+}
+
+/// Writes the file next to its path first, so that an interrupted write does not destroy the previous version
+fn write_atomically(
+    path: &std::path::Path,
+    write: impl FnOnce(&mut std::io::BufWriter<std::fs::File>) -> std::io::Result<()>,
+) -> std::io::Result<()> {
+    let temporary = path.with_extension("tmp");
+    std::fs::File::create(&temporary)
+        .and_then(|file| {
+            let mut writer = std::io::BufWriter::new(file);
+            write(&mut writer)?;
+            std::io::Write::flush(&mut writer)
+        })
+        .and_then(|()| std::fs::rename(&temporary, path))
+}
+
+fn write_checkpoint(path: &std::path::Path, checkpoint: &RunCheckpoint) {
+    match write_atomically(path, |writer| checkpoint.write(writer)) {
+        Ok(()) => log::debug!("Saved checkpoint to {}", path.display()),
+        Err(e) => log::warn!("Unable to save checkpoint {}: {e}", path.display()),
+    }
+}
+
+/// Checks that no solution is shorter than the found one, exiting with an error if a shorter one exists
+fn verify_optimality(
+    board: OwnedBoard,
+    solution: &[BoardMove],
+    heuristic_id: &str,
+    partition: Option<&Partition>,
+) -> OptimalityCheck {
+    let heuristic = create_heuristic(heuristic_id, &board, partition);
+    match check_optimality(board, solution.len(), heuristic) {
+        Ok(OptimalityCheck::Confirmed) => {
+            log::info!(
+                "Confirmed that no solution is shorter than {} moves",
+                solution.len()
+            );
+            OptimalityCheck::Confirmed
+        }
+        Ok(OptimalityCheck::Refuted { shortest }) => {
+            let moves: String = shortest.iter().map(ToString::to_string).collect();
+            log::error!(
+                "Solution is not optimal, a solution of {} moves exists: {moves}",
+                shortest.len()
+            );
+            OptimalityCheck::Refuted { shortest }
+        }
+        Err(e) => {
+            log::error!("Unable to check optimality: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn print_solution(solution: &[BoardMove]) {
     println!("{}", solution.len());
     let solution_str: Vec<_> = solution
         .iter()
@@ -177,3 +1440,279 @@ fn main() {
         .collect();
     println!("{}", solution_str.join(""));
 }
+
+/// Solves the board without timing it, which builds the heuristic tables and warms up the allocator
+/// before the timed searches of the benchmark
+fn warm_up(
+    cli: &CliArgs,
+    board: OwnedBoard,
+    heuristics: &mut HeuristicCache,
+    options: &SearchOptions,
+) {
+    let solver = create_solver(
+        cli.algorithm_info.selected(),
+        board,
+        None,
+        None,
+        cli.pdb_partition.as_ref(),
+        heuristics,
+        options,
+    );
+    let _ = solver.solve_with(&options.solver_config);
+    heuristics.next_board();
+}
+
+/// Solves the board without logging, returning its timing as a JSON object in a single line.
+/// The search itself is single-threaded, so the timings of different runs are comparable
+fn bench_board(
+    board_number: usize,
+    solver: Box<dyn Solver>,
+    setup: std::time::Duration,
+    config: &SolverConfig,
+    run: &RunInfo,
+) -> String {
+    let start = std::time::Instant::now();
+    let result = solver.solve_with_stats(config);
+    let elapsed = start.elapsed();
+    let report = match result {
+        Ok(report) => Some(report),
+        Err(SolvingError::UnsolvableBoard(_)) => None,
+        Err(e @ (SolvingError::TimedOut { .. } | SolvingError::ExpansionLimitReached { .. })) => {
+            eprintln!("Unable to solve board {board_number}: {e}");
+            None
+        }
+        Err(SolvingError::AlgorithmError(inner_error)) => {
+            eprintln!("Unable to solve board {board_number}: {inner_error}");
+            std::process::exit(1);
+        }
+        Err(e @ SolvingError::NoSolverFinished) => {
+            eprintln!("Unable to solve board {board_number}: {e}");
+            std::process::exit(1);
+        }
+    };
+    let counter = |value: fn(&SolveReport) -> Option<String>| {
+        report
+            .as_ref()
+            .and_then(value)
+            .unwrap_or_else(|| "null".to_string())
+    };
+    format!(
+        r#"{{"board":{board_number},"length":{},"setup_ms":{:.3},"solve_ms":{:.3},"expanded":{},"generated":{},"max_frontier":{},"peak_memory_bytes":{},"run":{}}}"#,
+        counter(|report| Some(report.solution.len().to_string())),
+        setup.as_secs_f64() * 1000.0,
+        elapsed.as_secs_f64() * 1000.0,
+        counter(|report| report.nodes_expanded.map(|count| count.to_string())),
+        counter(|report| report.nodes_generated.map(|count| count.to_string())),
+        counter(|report| report.max_frontier.map(|count| count.to_string())),
+        counter(|report| report.peak_memory_estimate.map(|bytes| bytes.to_string())),
+        run.to_json(),
+    )
+}
+
+/// Reads the move history of the previous runs, starting with an empty one if the file does not exist
+fn read_history(path: &std::path::Path) -> HistoryTable {
+    if !path.exists() {
+        return HistoryTable::new();
+    }
+    let read = std::fs::File::open(path)
+        .and_then(|file| HistoryTable::read(std::io::BufReader::new(file)));
+    read.unwrap_or_else(|e| {
+        log::warn!("Unable to read move history, starting with an empty one: {e}");
+        HistoryTable::new()
+    })
+}
+
+fn write_history(path: &std::path::Path, history: &SharedHistory) {
+    let history = history.snapshot();
+    if let Err(e) = write_atomically(path, |writer| history.write(writer)) {
+        log::error!("Unable to write move history: {e}");
+    }
+}
+
+/// Writes the convergence log as CSV, preceded by a comment line describing every run recorded in it
+fn write_convergence_log(
+    path: &std::path::Path,
+    convergence_log: &ConvergenceLog,
+    runs: &[RunInfo],
+) {
+    use std::io::Write;
+
+    let written = std::fs::File::create(path).and_then(|mut file| {
+        for run in runs {
+            writeln!(file, "# {run}")?;
+        }
+        convergence_log.write_csv(&mut file)
+    });
+    if let Err(e) = written {
+        log::error!("Unable to write convergence log: {e}");
+    }
+}
+
+/// Prepares the state shared by the solvers of a batch for the next board
+fn next_board(
+    heuristics: &mut HeuristicCache,
+    convergence_log: Option<&ConvergenceLog>,
+    plateau_monitor: Option<&PlateauMonitor>,
+) {
+    heuristics.next_board();
+    if let Some(convergence_log) = convergence_log {
+        convergence_log.next_board();
+    }
+    if let Some(plateau_monitor) = plateau_monitor {
+        plateau_monitor.next_board();
+    }
+}
+
+fn log_plateau_statistics(plateau_monitor: &PlateauMonitor) {
+    let statistics = plateau_monitor.statistics();
+    log::info!(
+        "Heuristic plateaus: {} (longest {} expansions, {} escapes)",
+        statistics.plateaus,
+        statistics.longest_plateau,
+        statistics.escapes
+    );
+}
+
+fn init_logging(cli: &CliArgs) {
+    simple_logger::SimpleLogger::new()
+        .with_local_timestamps()
+        .with_timestamp_format(time::macros::format_description!(
+            "[hour]:[minute]:[second]"
+        ))
+        .init()
+        .unwrap();
+
+    log::set_max_level(match cli.verbose {
+        _ if cli.bench_mode => LevelFilter::Off,
+        0 => LevelFilter::Error,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        3.. => LevelFilter::Trace,
+    });
+}
+
+fn run_subcommand(cli: &CliArgs, command: Command) {
+    if matches!(command, Command::Repl) {
+        let board = if cli.scramble.is_some() {
+            input_board(cli)
+        } else {
+            OwnedBoard::new_solved(cli.size.0, cli.size.1)
+        };
+        run_repl(board);
+        return;
+    }
+    let mut board = input_board(cli);
+    if let Some(path) = &cli.goal {
+        board = read_goal(board, path);
+    }
+    run_command(command, &board, cli.pdb_partition.as_ref());
+}
+
+fn main() {
+    let cli = CliArgs::parse();
+    init_logging(&cli);
+
+    if let Some(command) = cli.command.clone() {
+        run_subcommand(&cli, command);
+        return;
+    }
+
+    let boards = if cli.batch {
+        read_boards(cli.lenient)
+    } else {
+        vec![input_board(&cli)]
+    };
+
+    let convergence_log = cli.convergence_csv.as_ref().map(|_| ConvergenceLog::new());
+    let plateau_monitor = cli.plateau_length.map(|length| {
+        let policy = cli
+            .plateau_escape
+            .map_or(PlateauPolicy::Report, PlateauPolicy::RandomWalk);
+        PlateauMonitor::new(length, policy)
+    });
+    let mut heuristics = HeuristicCache::new(&cli);
+    let options = SearchOptions::new(&cli);
+    let mut refuted = false;
+    // descriptions of the runs, written with the convergence log
+    let mut runs = vec![];
+
+    for (index, mut board) in boards.into_iter().enumerate() {
+        if let Some(path) = &cli.goal {
+            board = read_goal(board, path);
+        }
+        #[cfg(feature = "json")]
+        let input = cli.json.then(|| (board.clone(), std::time::Instant::now()));
+        let original = cli.verify_optimality.is_some().then(|| board.clone());
+        let run = cli.algorithm_info.selected().run_info(&board, &options);
+        runs.push(run.clone());
+        if cli.bench_mode && index == 0 {
+            warm_up(&cli, board.clone(), &mut heuristics, &options);
+        }
+        // the heuristic tables are built or loaded when the solver is created
+        let setup_start = std::time::Instant::now();
+        let solver = create_solver(
+            cli.algorithm_info.selected(),
+            board,
+            convergence_log.clone(),
+            plateau_monitor.clone(),
+            cli.pdb_partition.as_ref(),
+            &mut heuristics,
+            &options,
+        );
+        if cli.bench_mode {
+            let setup = setup_start.elapsed();
+            println!(
+                "{}",
+                bench_board(index + 1, solver, setup, &options.solver_config, &run)
+            );
+            next_board(
+                &mut heuristics,
+                convergence_log.as_ref(),
+                plateau_monitor.as_ref(),
+            );
+            continue;
+        }
+        let solution = solve_board(solver, &options, &run);
+        next_board(
+            &mut heuristics,
+            convergence_log.as_ref(),
+            plateau_monitor.as_ref(),
+        );
+        let optimality = original
+            .zip(cli.verify_optimality.as_deref())
+            .zip(solution.as_deref())
+            .map(|((board, heuristic_id), solution)| {
+                verify_optimality(board, solution, heuristic_id, cli.pdb_partition.as_ref())
+            });
+        refuted |= matches!(optimality, Some(OptimalityCheck::Refuted { .. }));
+
+        #[cfg(feature = "json")]
+        if let Some((input, start)) = input {
+            let output = solver::solving::output::SolutionOutput::new(
+                input,
+                solution.as_deref(),
+                start.elapsed(),
+            )
+            .with_optimality(optimality)
+            .with_jitter_seed(options.jitter.map(|(_, seed)| seed))
+            .with_run_info(run);
+            println!("{}", output.to_json());
+            continue;
+        }
+        print_solution(&solution.unwrap_or_default());
+    }
+
+    if let Some(plateau_monitor) = &plateau_monitor {
+        log_plateau_statistics(plateau_monitor);
+    }
+
+    if let (Some(path), Some(history)) = (&cli.history_file, &options.history) {
+        write_history(path, history);
+    }
+    if let (Some(path), Some(convergence_log)) = (&cli.convergence_csv, &convergence_log) {
+        write_convergence_log(path, convergence_log, &runs);
+    }
+    if refuted {
+        std::process::exit(1);
+    }
+}
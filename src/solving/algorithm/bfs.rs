@@ -1,64 +1,437 @@
 use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
-use crate::board::{Board, BoardMove, OwnedBoard};
-use crate::solving::algorithm::{util, Solver, SolvingError};
-use crate::solving::is_solvable;
-use crate::solving::movegen::MoveGenerator;
+use crate::board::{BoardMove, OwnedBoard, SearchBoard};
+use crate::solving::algorithm::stepper::{FrontierNode, SearchStepper, StepEvent};
+use crate::solving::algorithm::util::SearchProgress;
+use crate::solving::algorithm::{
+    util, PruningUnsupported, SolveReport, SolveStep, Solver, SolverConfig, SolvingError,
+};
+use crate::solving::diagnosis::UnsolvableDiagnosis;
+use crate::solving::goal::{Goal, SolvedState};
+use crate::solving::movegen::{MoveGenerator, SearchOrderError};
+use crate::solving::pool::{AllocationCounter, NodePool};
+use crate::solving::pruning::{PruningRule, PruningRules};
 use crate::solving::visited::VisitedPositions;
 
-pub struct BFSSolver {
-    visited_positions: VisitedPositions<OwnedBoard>,
+/// Shards of the visited positions for every thread of the search, so the threads rarely wait for the same lock
+const SHARDS_PER_THREAD: usize = 16;
+
+/// Queued position, with the path to it
+type QueuedNode<B = OwnedBoard> = (B, Vec<BoardMove>);
+
+/// Expansion of the queued positions on multiple threads, available for the boards which can be sent between them
+type ParallelIteration<B> =
+    fn(&mut BFSSolver<B>, Option<Instant>) -> Option<Result<Vec<BoardMove>, SolvingError>>;
+
+/// Breadth-first search, which finds the shortest solution by expanding the positions in the order they were reached.
+///
+/// The boards of the queued positions are stored as `B`,
+/// which can be changed to `CowBoard` to avoid copying the cells of positions which are never expanded
+pub struct BFSSolver<B: SearchBoard = OwnedBoard> {
+    visited_positions: VisitedPositions<B>,
     move_generator: MoveGenerator,
-    queue: VecDeque<(OwnedBoard, Vec<BoardMove>)>,
+    queue: VecDeque<QueuedNode<B>>,
+    goal: Box<dyn Goal>,
+    node_pool: NodePool<B>,
+    /// Explanation of why the goal is unreachable, reported when the search ends without a solution
+    diagnosis: Option<UnsolvableDiagnosis>,
+    /// Search order which cannot reach every position of the board, reported when the search starts
+    invalid_order: Option<SearchOrderError>,
+    /// Error of storing the board in the representation `B`, reported when the search starts
+    invalid_board: Option<SolvingError>,
+    pruning_rules: PruningRules,
+    progress: SearchProgress,
+    /// Number of threads expanding the queued positions, 1 for the sequential search
+    threads: usize,
+    /// Expansion of the queued positions on the threads, `None` for the sequential search
+    parallel_iteration: Option<ParallelIteration<B>>,
 }
 
 impl BFSSolver {
     #[must_use]
     pub fn new(board: OwnedBoard, move_generator: MoveGenerator) -> Self {
+        Self::with_representation(board, move_generator)
+    }
+
+    /// Creates solver which finds the shortest path to any of the positions satisfying the goal
+    #[must_use]
+    pub fn with_goal(
+        board: OwnedBoard,
+        move_generator: MoveGenerator,
+        goal: Box<dyn Goal>,
+    ) -> Self {
+        Self::create(board, move_generator, goal)
+    }
+}
+
+impl<B: SearchBoard> BFSSolver<B> {
+    /// Creates solver which stores the boards of the queued positions as `B`,
+    /// e.g. `BFSSolver::<CowBoard>::with_representation(board, move_generator)`
+    #[must_use]
+    pub fn with_representation(board: OwnedBoard, move_generator: MoveGenerator) -> Self {
+        Self::create(board, move_generator, Box::new(SolvedState))
+    }
+
+    fn create(board: OwnedBoard, mut move_generator: MoveGenerator, goal: Box<dyn Goal>) -> Self {
+        let invalid_order = move_generator.validate_for(&board).err();
         let mut queue = VecDeque::new();
-        if is_solvable(&board) {
-            queue.push_back((board, Vec::new()));
+        let mut diagnosis = None;
+        let mut invalid_board = None;
+        if goal.is_reachable_from(&board) {
+            match B::try_from(board) {
+                Ok(board) => queue.push_back((board, Vec::new())),
+                Err(e) => invalid_board = Some(SolvingError::AlgorithmError(e.into())),
+            }
+        } else {
+            diagnosis = goal.diagnose_unreachable(&board);
         }
+        move_generator.set_parity_provider(goal.parity_provider());
         Self {
             visited_positions: VisitedPositions::new(),
             move_generator,
             queue,
+            goal,
+            node_pool: NodePool::default(),
+            diagnosis,
+            invalid_order,
+            invalid_board,
+            pruning_rules: PruningRules::default(),
+            progress: SearchProgress::default(),
+            threads: 1,
+            parallel_iteration: None,
         }
     }
 
+    /// Records how many boards and paths of the search nodes were allocated, and how many reused
+    #[must_use]
+    pub fn with_allocation_counter(mut self, counter: AllocationCounter) -> Self {
+        self.node_pool.set_counter(counter);
+        self
+    }
+
+    /// Leaves out of the search the positions cut off by the [`PruningRule`]
+    #[must_use]
+    pub fn with_pruning_rule(mut self, rule: impl PruningRule + 'static) -> Self {
+        self.pruning_rules.add(Box::new(rule));
+        self
+    }
+
     fn bfs_iteration(
         &mut self,
-        current_board: &OwnedBoard,
-        current_path: &[BoardMove],
-    ) -> Option<Vec<BoardMove>> {
-        if current_board.is_solved() {
-            return Some(current_path.to_vec());
+        mut current_board: B,
+        current_path: Vec<BoardMove>,
+    ) -> Option<Result<Vec<BoardMove>, SolvingError>> {
+        if self.goal.is_reached(&current_board) {
+            return Some(Ok(current_path));
         }
 
-        if self.visited_positions.is_visited(current_board) {
+        if self.visited_positions.is_visited(&current_board) {
+            // buffers of pruned nodes are reused for the new ones
+            self.node_pool.release_board(current_board);
+            self.node_pool.release_path(current_path);
             return None;
         }
 
-        self.visited_positions.mark_visited(current_board.clone());
-
-        for next_move in self.move_generator.generate_moves(current_board, None) {
-            let mut new_board = current_board.clone();
-            let mut new_path = current_path.to_vec();
+        if !self.progress.expand() {
+            return Some(Err(self.progress.error(current_path)));
+        }
+        self.pruning_rules.record_expansion();
+        current_board.prepare_expansion();
+        for next_move in self.move_generator.generate_moves(&current_board, None) {
+            let mut new_board = self.node_pool.board_from(&current_board);
+            let mut new_path = self.node_pool.path_from(&current_path);
             util::apply_move_sequence(&mut new_board, &mut new_path, next_move);
+            if self.pruning_rules.prunes(&new_board, &new_path) {
+                self.node_pool.release_board(new_board);
+                self.node_pool.release_path(new_path);
+                continue;
+            }
+            self.progress.generate();
             self.queue.push_back((new_board, new_path));
         }
 
+        // queued and visited nodes hold a board each, paths are counted at the length of the current one
+        let node_bytes = util::board_bytes(&current_board)
+            + std::mem::size_of::<Vec<BoardMove>>()
+            + current_path.len();
+        let stored = self.queue.len() + self.visited_positions.len();
+        self.progress.observe(self.queue.len(), stored, node_bytes);
+        self.node_pool.release_path(current_path);
+        self.visited_positions.mark_visited(current_board);
         None
     }
+
+    /// Runs the search until it finishes, or until the deadline passes, in which case `None` is returned
+    fn advance(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> Option<Result<Vec<BoardMove>, SolvingError>> {
+        if let Some(e) = self.invalid_order.take() {
+            self.queue.clear();
+            return Some(Err(e.into()));
+        }
+        if let Some(e) = self.invalid_board.take() {
+            return Some(Err(e));
+        }
+        while !self.queue.is_empty() {
+            let result = if let Some(parallel_iteration) = self.parallel_iteration {
+                parallel_iteration(self, deadline)
+            } else {
+                let (board, path) = self.queue.pop_front()?;
+                self.bfs_iteration(board, path)
+            };
+            if let Some(result) = result {
+                return Some(result);
+            }
+            if util::is_past(deadline) {
+                return None;
+            }
+        }
+        Some(Err(SolvingError::UnsolvableBoard(self.diagnosis)))
+    }
 }
 
-impl Solver for BFSSolver {
-    fn solve(mut self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
-        while let Some((board, path)) = self.queue.pop_front() {
-            if let Some(result) = self.bfs_iteration(&board, &path) {
-                return Ok(result);
+impl<B: SearchBoard + Send + Sync> BFSSolver<B> {
+    /// Expands the positions on the given number of threads, sharing the visited positions between them.
+    ///
+    /// All of the queued positions are expanded at once, split between the threads, while the goal is checked
+    /// and the pruning rules applied on the calling thread, so the solution is as short as with a single thread.
+    /// The threads check the limits and the deadline before every expansion, sharing the remaining expansions.
+    /// Stepping through the search is not affected, as it expands a single position at a time
+    #[must_use]
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self.visited_positions = VisitedPositions::with_shards(self.threads * SHARDS_PER_THREAD);
+        self.parallel_iteration = (self.threads > 1).then_some(Self::parallel_iteration);
+        self
+    }
+
+    /// Expands the queued positions as deep as the first one on the threads, and queues their children.
+    /// Positions left when the threads stop at a limit or the deadline are queued again before the children
+    fn parallel_iteration(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> Option<Result<Vec<BoardMove>, SolvingError>> {
+        // a deeper position expanded by one thread would hide the same position in the layer from the others
+        let depth = self.queue.front().map(|(_, path)| path.len());
+        let layer_size = self
+            .queue
+            .iter()
+            .take_while(|(_, path)| Some(path.len()) == depth)
+            .count();
+        let layer: Vec<QueuedNode<B>> = self.queue.drain(..layer_size).collect();
+        if let Some((_, path)) = layer.iter().find(|(board, _)| self.goal.is_reached(board)) {
+            return Some(Ok(path.clone()));
+        }
+
+        let chunk_size = layer.len().div_ceil(self.threads);
+        let limits = WorkerLimits {
+            remaining_expansions: self.progress.remaining_expansions().map(AtomicU64::new),
+            time_limit: self.progress.deadline(),
+            deadline,
+        };
+        let visited_positions = &self.visited_positions;
+        let move_generator = &self.move_generator;
+        let limits = &limits;
+        let (expansions, unexpanded): (Vec<_>, Vec<_>) = std::thread::scope(|scope| {
+            let workers: Vec<_> = layer
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        expand_chunk(chunk, visited_positions, move_generator, limits)
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .map(|worker| worker.join().expect("BFS worker thread panicked"))
+                .unzip()
+        });
+        let unexpanded: Vec<_> = unexpanded.into_iter().flatten().collect();
+        for (board, path) in unexpanded.iter().rev() {
+            self.queue.push_front((board.clone(), path.clone()));
+        }
+
+        let mut node_bytes = 0;
+        for (path, children) in expansions.into_iter().flatten() {
+            if !self.progress.expand() {
+                return Some(Err(self.progress.error(path.to_vec())));
+            }
+            self.pruning_rules.record_expansion();
+            for (board, path) in children {
+                if self.pruning_rules.prunes(&board, &path) {
+                    continue;
+                }
+                node_bytes =
+                    util::board_bytes(&board) + std::mem::size_of::<Vec<BoardMove>>() + path.len();
+                self.progress.generate();
+                self.queue.push_back((board, path));
             }
         }
-        Err(SolvingError::UnsolvableBoard)
+        let stored = self.queue.len() + self.visited_positions.len();
+        self.progress.observe(self.queue.len(), stored, node_bytes);
+        // unless the threads stopped at a limit of the configuration, they stopped at the deadline of `solve_for`
+        if let Some((_, path)) = unexpanded.first() {
+            let out_of_expansions = self.progress.remaining_expansions() == Some(0);
+            if (out_of_expansions || util::is_past(self.progress.deadline()))
+                && !self.progress.expand()
+            {
+                return Some(Err(self.progress.error(path.clone())));
+            }
+        }
+        None
+    }
+}
+
+/// Paths to the positions expanded by a thread with their children, and the positions left when it stopped
+type ChunkExpansion<'a, B> = (
+    Vec<(&'a [BoardMove], Vec<QueuedNode<B>>)>,
+    &'a [QueuedNode<B>],
+);
+
+/// Limits of the configuration and the deadline, checked by the threads of the parallel search
+struct WorkerLimits {
+    /// Expansions left to all of the threads, `None` without the expansion limit
+    remaining_expansions: Option<AtomicU64>,
+    /// Time at which the time limit of the configuration stops the search
+    time_limit: Option<Instant>,
+    /// Time at which [`Solver::solve_for`] suspends the search
+    deadline: Option<Instant>,
+}
+
+impl WorkerLimits {
+    /// Takes an expansion from the remaining ones, returning `false` if the thread has to stop before it.
+    /// Every thread makes its first expansion before the deadline is checked, so every time slice makes progress
+    fn expand(&self, first: bool) -> bool {
+        !util::is_past(self.time_limit)
+            && (first || !util::is_past(self.deadline))
+            && self.remaining_expansions.as_ref().is_none_or(|remaining| {
+                remaining
+                    .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |left| {
+                        left.checked_sub(1)
+                    })
+                    .is_ok()
+            })
+    }
+}
+
+/// Expands the positions of the chunk which were not visited yet,
+/// returning the path to every expanded position together with its children,
+/// and the positions left when a limit stopped the expansions
+fn expand_chunk<'a, B: SearchBoard>(
+    chunk: &'a [QueuedNode<B>],
+    visited_positions: &VisitedPositions<B>,
+    move_generator: &MoveGenerator,
+    limits: &WorkerLimits,
+) -> ChunkExpansion<'a, B> {
+    let mut expansions = vec![];
+    for (index, (board, path)) in chunk.iter().enumerate() {
+        if visited_positions.is_visited(board) {
+            continue;
+        }
+        if !limits.expand(expansions.is_empty()) {
+            return (expansions, &chunk[index..]);
+        }
+        if !visited_positions.insert(board.clone()) {
+            continue;
+        }
+        let children = move_generator
+            .generate_moves(board, None)
+            .into_iter()
+            .map(|next_move| {
+                let mut board = board.clone();
+                let mut path = path.clone();
+                util::apply_move_sequence(&mut board, &mut path, next_move);
+                (board, path)
+            })
+            .collect();
+        expansions.push((path.as_slice(), children));
+    }
+    (expansions, &[])
+}
+
+impl<B: SearchBoard> SearchStepper for BFSSolver<B> {
+    fn step(&mut self) -> StepEvent {
+        if let Some(e) = self.invalid_order.take() {
+            self.queue.clear();
+            return StepEvent::Failed(e.into());
+        }
+        if let Some(e) = self.invalid_board.take() {
+            return StepEvent::Failed(e);
+        }
+        let Some((board, path)) = self.queue.pop_front() else {
+            return StepEvent::Failed(SolvingError::UnsolvableBoard(self.diagnosis));
+        };
+        let node = FrontierNode::new(&board, &path);
+        let expanded = self.progress.expanded();
+        let queued = self.queue.len();
+        match self.bfs_iteration(board, path) {
+            Some(Ok(solution)) => StepEvent::Solved(solution),
+            Some(Err(e)) => StepEvent::Failed(e),
+            None if self.progress.expanded() > expanded => StepEvent::Expanded {
+                node,
+                children: self
+                    .queue
+                    .range(queued..)
+                    .map(|(board, path)| FrontierNode::new(board, path))
+                    .collect(),
+            },
+            None => StepEvent::Skipped(node),
+        }
+    }
+
+    fn frontier(&self) -> Vec<FrontierNode> {
+        self.queue
+            .iter()
+            .map(|(board, path)| FrontierNode::new(board, path))
+            .collect()
+    }
+}
+
+impl<B: SearchBoard + 'static> Solver for BFSSolver<B> {
+    fn solve(mut self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
+        self.advance(None)
+            .expect("Search without deadline runs until it finishes")
+    }
+
+    fn add_pruning_rule(&mut self, rule: Box<dyn PruningRule>) -> Result<(), PruningUnsupported> {
+        self.pruning_rules.add(rule);
+        Ok(())
+    }
+
+    fn solve_with(self: Box<Self>, config: &SolverConfig) -> Result<Vec<BoardMove>, SolvingError> {
+        self.solve_with_stats(config).map(|report| report.solution)
+    }
+
+    fn solve_with_stats(
+        mut self: Box<Self>,
+        config: &SolverConfig,
+    ) -> Result<SolveReport, SolvingError> {
+        self.progress = SearchProgress::new(config);
+        let solution = self
+            .advance(None)
+            .expect("Search without deadline runs until it finishes")?;
+        Ok(self.progress.report(solution))
+    }
+
+    fn is_suspendable(&self) -> bool {
+        true
+    }
+
+    fn solve_for(mut self: Box<Self>, duration: Duration) -> SolveStep {
+        match self.advance(Some(Instant::now() + duration)) {
+            Some(result) => SolveStep::Done(result.map(|solution| self.progress.report(solution))),
+            None => SolveStep::Pending(self),
+        }
+    }
+
+    /// Returns the path to the deepest queued position
+    fn best_partial(&self) -> Vec<BoardMove> {
+        self.queue
+            .back()
+            .map(|(_, path)| path.clone())
+            .unwrap_or_default()
     }
 }
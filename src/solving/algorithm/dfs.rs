@@ -1,37 +1,161 @@
 use crate::board::{Board, BoardMove, OwnedBoard};
 use std::fmt::{Display, Formatter};
 
-use crate::solving::algorithm::{util, Solver, SolvingError};
+use crate::solving::algorithm::util::SearchProgress;
+use crate::solving::algorithm::{
+    util, IntermediateResult, PruningUnsupported, SolveReport, Solver, SolverConfig, SolvingError,
+};
+use crate::solving::diagnosis::UnsolvableDiagnosis;
+use crate::solving::history::{SearchHistory, SharedHistory};
 use crate::solving::is_solvable;
 use crate::solving::movegen::MoveGenerator;
+use crate::solving::pruning::{PruningRule, PruningRules};
+use crate::solving::transposition::TranspositionTable;
 use crate::solving::visited::VisitedPositions;
 
 pub struct DFSSolver {
     visited_positions: Option<VisitedPositions<OwnedBoard>>,
+    /// Depths of the positions visited by the current iteration of the iterative deepening
+    transpositions: Option<TranspositionTable<OwnedBoard>>,
     move_generator: MoveGenerator,
     current_path: Vec<BoardMove>,
     board: OwnedBoard,
+    report: DFSReport,
+    progress: SearchProgress,
+    pruning_rules: PruningRules,
+    history: Option<SearchHistory>,
 }
 
-#[derive(Debug)]
-enum DFSError {
-    /// Solver visits the state it has already visited before
+/// Reason why the solver backtracked from a position
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Backtrack {
+    /// Solver visits the state it has already visited before, or at a shallower depth of the same iteration
     StateAlreadyVisited,
     /// Solver reached max depth of the search tree
     MaxDepthReached,
+    /// Solver ran out of the stack space needed to go deeper, the stack grows instead with `stack-expansion`
+    #[cfg(not(feature = "stack-expansion"))]
+    StackLimitReached,
     /// All of the moves possible from this position yielded an error
     StateExhausted,
+    /// A limit of the configuration was reached, the whole search stops at this position
+    LimitReached,
+}
+
+/// Summary of the search tree explored by the DFS solvers
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DFSReport {
+    /// Number of visited positions by the length of the path leading to them
+    pub depth_histogram: Vec<u64>,
+    /// Number of positions whose moves were explored
+    pub expanded: u64,
+    /// Number of positions pruned because they were already visited
+    pub revisits: u64,
+    /// Number of positions pruned because they were at the depth limit
+    pub depth_limit_cutoffs: u64,
+    /// Number of positions pruned because the stack space ran out
+    pub stack_limit_cutoffs: u64,
+}
+
+impl DFSReport {
+    fn record_visit(&mut self, depth: usize) {
+        if self.depth_histogram.len() <= depth {
+            self.depth_histogram.resize(depth + 1, 0);
+        }
+        self.depth_histogram[depth] += 1;
+    }
+
+    fn record_backtrack(&mut self, reason: Backtrack) {
+        match reason {
+            Backtrack::StateAlreadyVisited => self.revisits += 1,
+            Backtrack::MaxDepthReached => self.depth_limit_cutoffs += 1,
+            #[cfg(not(feature = "stack-expansion"))]
+            Backtrack::StackLimitReached => self.stack_limit_cutoffs += 1,
+            Backtrack::StateExhausted | Backtrack::LimitReached => {}
+        }
+    }
+
+    /// Total number of visited positions
+    #[must_use]
+    pub fn visited(&self) -> u64 {
+        self.depth_histogram.iter().sum()
+    }
+
+    /// Length of the longest explored path
+    #[must_use]
+    pub fn max_depth(&self) -> usize {
+        self.depth_histogram.len().saturating_sub(1)
+    }
+
+    /// Average number of positions generated by every expanded position
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn branching_factor(&self) -> f64 {
+        let roots = self.depth_histogram.first().copied().unwrap_or_default();
+        if self.expanded == 0 {
+            0.0
+        } else {
+            (self.visited() - roots) as f64 / self.expanded as f64
+        }
+    }
+}
+
+impl Display for DFSReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "visited {} positions up to depth {} with branching factor {:.2}, \
+             pruned {} revisits, {} at depth limit and {} at stack limit",
+            self.visited(),
+            self.max_depth(),
+            self.branching_factor(),
+            self.revisits,
+            self.depth_limit_cutoffs,
+            self.stack_limit_cutoffs,
+        )?;
+        for (depth, count) in self.depth_histogram.iter().enumerate() {
+            write!(f, "\n  depth {depth:>3}: {count}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Failure of the DFS solvers, with the report of the explored search tree
+#[derive(Debug)]
+pub enum DFSError {
+    /// None of the moves from the starting position results in a solution
+    StateExhausted(DFSReport),
+    /// Solution was not found because the depth or stack limit pruned the search tree
+    MaxDepthReached(DFSReport),
+}
+
+impl DFSError {
+    fn new(report: DFSReport) -> Self {
+        if report.depth_limit_cutoffs > 0 || report.stack_limit_cutoffs > 0 {
+            Self::MaxDepthReached(report)
+        } else {
+            Self::StateExhausted(report)
+        }
+    }
+
+    #[must_use]
+    pub fn report(&self) -> &DFSReport {
+        match self {
+            DFSError::StateExhausted(report) | DFSError::MaxDepthReached(report) => report,
+        }
+    }
 }
 
 impl Display for DFSError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            DFSError::StateAlreadyVisited => write!(f, "Solver has already visited this state"),
-            DFSError::MaxDepthReached => write!(f, "Solver reached max depth of the search tree"),
-            DFSError::StateExhausted => write!(
+            DFSError::StateExhausted(report) => write!(
                 f,
-                "None of the moves from this position results in a solution"
+                "None of the moves from this position results in a solution: {report}"
             ),
+            DFSError::MaxDepthReached(report) => {
+                write!(f, "Solver reached max depth of the search tree: {report}")
+            }
         }
     }
 }
@@ -50,8 +174,35 @@ impl DFSSolver {
         Self {
             board,
             visited_positions: Some(VisitedPositions::new()),
+            transpositions: None,
             move_generator,
             current_path: vec![],
+            report: DFSReport::default(),
+            progress: SearchProgress::default(),
+            pruning_rules: PruningRules::default(),
+            history: None,
+        }
+    }
+
+    /// Leaves out of the search the positions cut off by the [`PruningRule`]
+    #[must_use]
+    pub fn with_pruning_rule(mut self, rule: impl PruningRule + 'static) -> Self {
+        self.pruning_rules.add(Box::new(rule));
+        self
+    }
+
+    /// Orders the moves by the history table, crediting the moves of the solution when it is found
+    #[must_use]
+    pub fn with_history(mut self, history: SharedHistory) -> Self {
+        self.history = Some(SearchHistory::new(history));
+        self
+    }
+
+    /// Credits the moves of the found path, and shares the credits of the search with the next boards
+    fn record_solution(&mut self) {
+        if let Some(history) = &mut self.history {
+            history.record_path(&self.board, &self.current_path);
+            history.publish();
         }
     }
 
@@ -59,43 +210,85 @@ impl DFSSolver {
         &mut self,
         current_depth: usize,
         max_depth: Option<usize>,
-    ) -> Result<(), DFSError> {
+    ) -> Result<(), Backtrack> {
+        self.report.record_visit(self.current_path.len());
+        let result = self.visit_position(current_depth, max_depth);
+        if let Err(reason) = result {
+            self.report.record_backtrack(reason);
+        }
+        result
+    }
+
+    fn visit_position(
+        &mut self,
+        current_depth: usize,
+        max_depth: Option<usize>,
+    ) -> Result<(), Backtrack> {
         if self.board.is_solved() {
             return Ok(());
         }
 
         if let Some(visited_positions) = &self.visited_positions {
             if visited_positions.is_visited(&self.board) {
-                return Err(DFSError::StateAlreadyVisited);
+                return Err(Backtrack::StateAlreadyVisited);
             }
             visited_positions.mark_visited(self.board.clone());
         }
+        if let Some(transpositions) = &mut self.transpositions {
+            if !transpositions.visit(self.board.clone(), current_depth) {
+                return Err(Backtrack::StateAlreadyVisited);
+            }
+        }
 
         if let Some(max_depth) = max_depth {
             if current_depth >= max_depth {
-                return Err(DFSError::MaxDepthReached);
+                return Err(Backtrack::MaxDepthReached);
             }
         }
 
-        for next_move in self
+        if !self.progress.expand() {
+            return Err(Backtrack::LimitReached);
+        }
+        self.report.expanded += 1;
+        self.pruning_rules.record_expansion();
+        // the path is the frontier, the visited positions are the only boards stored besides the current one
+        let depth = self.current_path.len();
+        let stored = match (&self.visited_positions, &self.transpositions) {
+            (Some(visited_positions), _) => visited_positions.len(),
+            (None, Some(transpositions)) => transpositions.len(),
+            (None, None) => 1,
+        };
+        self.progress
+            .observe(depth, stored, util::board_bytes(&self.board) + depth);
+        let mut moves = self
             .move_generator
-            .generate_moves(&self.board, self.current_path.last().copied())
-        {
+            .generate_moves(&self.board, self.current_path.last().copied());
+        if let Some(history) = &self.history {
+            history.order(&self.board, &mut moves);
+        }
+        for next_move in moves {
             util::apply_move_sequence(&mut self.board, &mut self.current_path, next_move);
-            if self._call_recursive(current_depth + 1, max_depth).is_ok() {
-                return Ok(());
+            if self.pruning_rules.prunes(&self.board, &self.current_path) {
+                util::undo_move_sequence(&mut self.board, &mut self.current_path, next_move);
+                continue;
+            }
+            self.progress.generate();
+            let result = self._call_recursive(current_depth + 1, max_depth);
+            // when a limit is reached, the path to the position is kept as the partial solution
+            if matches!(result, Ok(()) | Err(Backtrack::LimitReached)) {
+                return result;
             }
             util::undo_move_sequence(&mut self.board, &mut self.current_path, next_move);
         }
 
-        Err(DFSError::StateExhausted)
+        Err(Backtrack::StateExhausted)
     }
 
     fn _call_recursive(
         &mut self,
         current_depth: usize,
         max_depth: Option<usize>,
-    ) -> Result<(), DFSError> {
+    ) -> Result<(), Backtrack> {
         const STACK_RED_ZONE: usize = 64 * 1024;
         #[cfg(feature = "stack-expansion")]
         {
@@ -110,7 +303,8 @@ impl DFSSolver {
                 // If we have less than `STACK_RED_ZONE` stack remaining, we must backtrack to avoid stack overflow
                 if remaining < STACK_RED_ZONE {
                     log::debug!("DFS reached stack limit at depth {current_depth}, backtracking");
-                    return Err(DFSError::MaxDepthReached);
+                    self.report.record_backtrack(Backtrack::StackLimitReached);
+                    return Err(Backtrack::StackLimitReached);
                 }
             }
             self.perform_iteration(current_depth + 1, max_depth)
@@ -119,19 +313,51 @@ impl DFSSolver {
 }
 
 impl Solver for DFSSolver {
-    fn solve(mut self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
+    fn solve(self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
+        self.solve_with(&SolverConfig::default())
+    }
+
+    fn add_pruning_rule(&mut self, rule: Box<dyn PruningRule>) -> Result<(), PruningUnsupported> {
+        self.pruning_rules.add(rule);
+        Ok(())
+    }
+
+    fn solve_with(self: Box<Self>, config: &SolverConfig) -> Result<Vec<BoardMove>, SolvingError> {
+        self.solve_with_stats(config).map(|report| report.solution)
+    }
+
+    fn solve_with_stats(
+        mut self: Box<Self>,
+        config: &SolverConfig,
+    ) -> Result<SolveReport, SolvingError> {
         if !is_solvable(&self.board) {
-            return Err(SolvingError::UnsolvableBoard);
+            return Err(SolvingError::UnsolvableBoard(
+                UnsolvableDiagnosis::diagnose(&self.board),
+            ));
         }
+        self.move_generator.validate_for(&self.board)?;
 
-        self.perform_iteration(0, None)?;
-
-        Ok(self.current_path)
+        self.progress = SearchProgress::new(config);
+        match self.perform_iteration(0, None) {
+            Ok(()) => {
+                self.record_solution();
+                Ok(self.progress.report(self.current_path))
+            }
+            Err(Backtrack::LimitReached) => Err(self.progress.error(self.current_path)),
+            Err(_) => Err(DFSError::new(self.report).into()),
+        }
     }
 }
 
+/// Iterative deepening DFS.
+/// Besides solving at once, it can be driven one depth at a time as an iterator
 pub struct IncrementalDFSSolver {
     dfs_solver: DFSSolver,
+    /// Depth limit of the last iteration, `None` before the first one
+    max_depth: Option<usize>,
+    /// Depth limit after which the solver stops deepening
+    depth_limit: Option<usize>,
+    finished: bool,
 }
 
 impl IncrementalDFSSolver {
@@ -143,28 +369,131 @@ impl IncrementalDFSSolver {
                 move_generator,
                 current_path: vec![],
                 visited_positions: None, // re-visit checking is not wanted because we may visit the same state but with a shallower depth
+                transpositions: None,
+                report: DFSReport::default(),
+                progress: SearchProgress::default(),
+                pruning_rules: PruningRules::default(),
+                history: None,
             },
+            max_depth: None,
+            depth_limit: None,
+            finished: false,
         }
     }
+
+    /// Fails with [`DFSError::MaxDepthReached`] instead of deepening past the given depth
+    #[must_use]
+    pub fn with_depth_limit(mut self, depth_limit: usize) -> Self {
+        self.depth_limit = Some(depth_limit);
+        self
+    }
+
+    /// Prunes the positions which the current iteration already visited at the same or a shallower depth.
+    /// It stores every position visited by the iteration, but searches every position at most once per depth
+    #[must_use]
+    pub fn with_transposition_table(mut self) -> Self {
+        self.dfs_solver.transpositions = Some(TranspositionTable::new());
+        self
+    }
+
+    /// Leaves out of the search the positions cut off by the [`PruningRule`]
+    #[must_use]
+    pub fn with_pruning_rule(mut self, rule: impl PruningRule + 'static) -> Self {
+        self.dfs_solver.pruning_rules.add(Box::new(rule));
+        self
+    }
+
+    /// Orders the moves by the history table, crediting the moves of the solution when it is found
+    #[must_use]
+    pub fn with_history(mut self, history: SharedHistory) -> Self {
+        self.dfs_solver = self.dfs_solver.with_history(history);
+        self
+    }
 }
 
-impl Solver for IncrementalDFSSolver {
-    fn solve(mut self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
-        if !is_solvable(&self.dfs_solver.board) {
-            return Err(SolvingError::UnsolvableBoard);
+impl Iterator for IncrementalDFSSolver {
+    type Item = IntermediateResult;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let max_depth = match self.max_depth {
+            Some(depth) => {
+                log::trace!("Increasing DFS depth to {}", depth + 1);
+                depth + 1
+            }
+            None if !is_solvable(&self.dfs_solver.board) => {
+                self.finished = true;
+                return Some(IntermediateResult::Failed(SolvingError::UnsolvableBoard(
+                    UnsolvableDiagnosis::diagnose(&self.dfs_solver.board),
+                )));
+            }
+            None => {
+                if let Err(e) = self
+                    .dfs_solver
+                    .move_generator
+                    .validate_for(&self.dfs_solver.board)
+                {
+                    self.finished = true;
+                    return Some(IntermediateResult::Failed(e.into()));
+                }
+                1
+            }
+        };
+        self.max_depth = Some(max_depth);
+        if let Some(transpositions) = &mut self.dfs_solver.transpositions {
+            transpositions.next_generation();
         }
 
-        let mut max_depth = 1;
-        while self
-            .dfs_solver
-            .perform_iteration(0, Some(max_depth))
-            .is_err()
-        {
-            max_depth += 1;
-            log::trace!("Increasing DFS depth to {max_depth}");
+        let result = self.dfs_solver.perform_iteration(0, Some(max_depth));
+        if result == Err(Backtrack::LimitReached) {
+            self.finished = true;
+            let best_partial = std::mem::take(&mut self.dfs_solver.current_path);
+            Some(IntermediateResult::Failed(
+                self.dfs_solver.progress.error(best_partial),
+            ))
+        } else if result.is_ok() {
+            self.finished = true;
+            self.dfs_solver.record_solution();
+            Some(IntermediateResult::Solved(std::mem::take(
+                &mut self.dfs_solver.current_path,
+            )))
+        } else if self.depth_limit.is_some_and(|limit| max_depth >= limit) {
+            self.finished = true;
+            let report = std::mem::take(&mut self.dfs_solver.report);
+            Some(IntermediateResult::Failed(DFSError::new(report).into()))
+        } else {
+            Some(IntermediateResult::BoundExhausted {
+                bound: max_depth as u64,
+                next_bound: max_depth as u64 + 1,
+            })
         }
+    }
+}
+
+impl Solver for IncrementalDFSSolver {
+    fn solve(self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
+        util::finish_iterations(*self)
+    }
+
+    fn add_pruning_rule(&mut self, rule: Box<dyn PruningRule>) -> Result<(), PruningUnsupported> {
+        self.dfs_solver.pruning_rules.add(rule);
+        Ok(())
+    }
+
+    fn solve_with(self: Box<Self>, config: &SolverConfig) -> Result<Vec<BoardMove>, SolvingError> {
+        self.solve_with_stats(config).map(|report| report.solution)
+    }
 
-        Ok(self.dfs_solver.current_path)
+    /// Counters add up over all of the iterations
+    fn solve_with_stats(
+        mut self: Box<Self>,
+        config: &SolverConfig,
+    ) -> Result<SolveReport, SolvingError> {
+        self.dfs_solver.progress = SearchProgress::new(config);
+        let solution = util::finish_iterations(&mut *self)?;
+        Ok(self.dfs_solver.progress.report(solution))
     }
 }
 
@@ -0,0 +1,27 @@
+use crate::board::{Board, OwnedBoard};
+use crate::solving::algorithm::retrograde::RetrogradeSolver;
+use crate::solving::algorithm::Solver;
+
+const SIDE: u8 = 3;
+
+/// Optimal solver of the 3x3 board (8-puzzle), the [`RetrogradeSolver`] looking the solution up
+/// in the distances of all of the 181,440 reachable states, which takes microseconds.
+/// Its distances are exact, which makes the solver the oracle of the lengths of the shortest solutions
+pub type EightPuzzleSolver = RetrogradeSolver;
+
+/// The oracle of the 3x3 board, the same solver as [`EightPuzzleSolver`]
+pub type Optimal8Solver = RetrogradeSolver;
+
+/// Returns the dedicated solver for 3x3 boards, and the solver created by `general` for other boards.
+/// Both solvers should return the shortest solution for the choice to be transparent
+#[must_use]
+pub fn with_fast_path(
+    board: OwnedBoard,
+    general: impl FnOnce(OwnedBoard) -> Box<dyn Solver>,
+) -> Box<dyn Solver> {
+    if board.dimensions() == (SIDE, SIDE) {
+        Box::new(EightPuzzleSolver::new(board))
+    } else {
+        general(board)
+    }
+}
@@ -0,0 +1,78 @@
+use crate::board::{Board, BoardMove, OwnedBoard};
+use crate::solving::algorithm::layered::{LayerResult, LayeredSearch};
+use crate::solving::algorithm::{SolveReport, Solver, SolverConfig, SolvingError};
+use crate::solving::goal::{Goal, Predicate, SolvedState};
+
+/// Breadth-first search which keeps only the layer being expanded and the next one.
+///
+/// Instead of the closed positions, every node stores the moves leading back to the previous layer,
+/// which are not generated again. Every move changes the color of the empty cell on the checkerboard,
+/// so a move never leads to a position in the same layer, and these moves are enough to detect all of the duplicates.
+/// It stores far fewer positions than [`BFSSolver`], which makes it suitable for wide searches,
+/// e.g. [`distance_counts`] over the whole state space.
+///
+/// As the paths are not stored either, the solution is reconstructed by searching again with a relay layer
+/// in the middle of its length, and recursively searching the path to the relay ancestor of the goal and from it,
+/// which takes about twice as long as a single search. The layers are searched the same way
+/// as by the [`BreadthFirstHeuristicSolver`], without a bound
+///
+/// [`BFSSolver`]: crate::solving::algorithm::bfs::BFSSolver
+/// [`BreadthFirstHeuristicSolver`]: crate::solving::algorithm::heuristic::bfhs::BreadthFirstHeuristicSolver
+pub struct FrontierBFSSolver {
+    board: OwnedBoard,
+    goal: Box<dyn Goal>,
+}
+
+impl FrontierBFSSolver {
+    #[must_use]
+    pub fn new(board: OwnedBoard) -> Self {
+        Self::with_goal(board, Box::new(SolvedState))
+    }
+
+    /// Creates solver which finds the shortest path to any of the positions satisfying the goal
+    #[must_use]
+    pub fn with_goal(board: OwnedBoard, goal: Box<dyn Goal>) -> Self {
+        Self { board, goal }
+    }
+}
+
+/// Counts the positions reachable from the board at every distance from it, by searching the whole state space.
+/// The length of the result is one more than the longest distance
+#[must_use]
+pub fn distance_counts(board: &OwnedBoard) -> Vec<usize> {
+    let mut counts = vec![];
+    let mut search = LayeredSearch::new(board, &SolverConfig::default());
+    let never = Predicate(|_: &dyn Board| false);
+    let result = search.search_layers(board, &never, None, None, |layer| counts.push(layer));
+    debug_assert!(matches!(result, LayerResult::Exceeded(None)));
+    counts
+}
+
+impl Solver for FrontierBFSSolver {
+    fn solve(self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
+        self.solve_with(&SolverConfig::default())
+    }
+
+    fn solve_with(self: Box<Self>, config: &SolverConfig) -> Result<Vec<BoardMove>, SolvingError> {
+        self.solve_with_stats(config).map(|report| report.solution)
+    }
+
+    /// Counters add up over the search for the distance of the goal and the searches reconstructing the solution
+    fn solve_with_stats(
+        self: Box<Self>,
+        config: &SolverConfig,
+    ) -> Result<SolveReport, SolvingError> {
+        if !self.goal.is_reachable_from(&self.board) {
+            let diagnosis = self.goal.diagnose_unreachable(&self.board);
+            return Err(SolvingError::UnsolvableBoard(diagnosis));
+        }
+        let mut search = LayeredSearch::new(&self.board, config);
+        let distance = match search.search_layers(&self.board, &*self.goal, None, None, |_| {}) {
+            LayerResult::Found { depth, .. } => depth,
+            LayerResult::Exceeded(_) => return Err(SolvingError::UnsolvableBoard(None)),
+            LayerResult::LimitReached => return Err(search.progress.error(vec![])),
+        };
+        let solution = search.path(&self.board, &*self.goal, distance)?;
+        Ok(search.progress.report(solution))
+    }
+}
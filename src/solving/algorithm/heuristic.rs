@@ -1,23 +1,144 @@
 use std::cmp::Reverse;
-use std::collections::BinaryHeap;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::rc::Rc;
+use std::time::Instant;
 
-use crate::board::{Board, BoardMove, OwnedBoard};
+use rand::seq::SliceRandom;
+
+use crate::board::{Board, BoardMove, OwnedBoard, SearchBoard};
 use crate::solving::algorithm::heuristic::heuristics::Heuristic;
-use crate::solving::algorithm::{util, Solver, SolvingError};
-use crate::solving::is_solvable;
+use crate::solving::algorithm::stepper::{FrontierNode, StepEvent};
+use crate::solving::algorithm::util::SearchProgress;
+use crate::solving::algorithm::{util, SolveReport, Solver, SolverConfig, SolvingError};
+use crate::solving::bucket_queue::BucketQueue;
+use crate::solving::checkpoint::{Checkpoint, CheckpointError};
+use crate::solving::convergence::ConvergenceLog;
+use crate::solving::cost::{self, CostModel, UnitCost};
+use crate::solving::diagnosis::UnsolvableDiagnosis;
+use crate::solving::goal::{Goal, SolvedState};
+use crate::solving::indexed_heap::IndexedHeap;
 use crate::solving::movegen::MoveGenerator;
+use crate::solving::plateau::{PlateauMonitor, PlateauPolicy};
+use crate::solving::pruning::PruningRules;
 
 pub mod astar;
 pub mod bestfs;
+pub mod bfhs;
+pub mod cache;
+pub mod focal;
+pub mod hda;
 pub mod heuristics;
+#[cfg(feature = "experimental")]
+pub mod mcts;
+pub mod pattern_database;
+pub mod perimeter;
+pub mod registry;
+pub mod sma;
 
 trait HeuristicSearchNode: Ord + Eq {
-    fn create(board: OwnedBoard, heuristic: Rc<dyn Heuristic>) -> Self;
-    fn with_path(board: OwnedBoard, path: Vec<BoardMove>, heuristic: Rc<dyn Heuristic>) -> Self;
+    /// Representation of the board stored in the node
+    type Board: SearchBoard;
+
+    fn create(board: Self::Board, heuristic: Rc<dyn Heuristic>) -> Self;
+    fn with_path(
+        board: Self::Board,
+        path: Vec<BoardMove>,
+        path_cost: u64,
+        heuristic: Rc<dyn Heuristic>,
+    ) -> Self;
 
     fn cost(&self) -> u64;
-    fn destructure(self) -> (OwnedBoard, Vec<BoardMove>);
+    fn h_cost(&self) -> u64;
+    fn board(&self) -> &Self::Board;
+    fn path(&self) -> &[BoardMove];
+    fn path_cost(&self) -> u64;
+    /// Returns the board, path leading to it, and the cost of the path
+    fn destructure(self) -> (Self::Board, Vec<BoardMove>, u64);
+}
+
+/// Action taken when a node is generated for a position which was already generated before
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DuplicatePolicy {
+    /// Queue every node, searching the tree of all paths instead of the graph of positions
+    #[default]
+    Keep,
+    /// Drop nodes of positions which were already generated.
+    /// It uses the least memory, but keeps the first path found to each position, which may not be the shortest
+    Ignore,
+    /// Keep a single queued node per position, lowering its cost in place when a cheaper path is found.
+    /// Expanded positions are never expanded again, so the solution is shortest only for consistent heuristics
+    DecreaseKey,
+    /// Queue the node again when a cheaper path to the position is found,
+    /// and skip the outdated nodes when they are taken from the queue
+    LazyReinsert,
+}
+
+/// Queue of nodes waiting for expansion, ordered from the lowest cost
+enum OpenList<Node: HeuristicSearchNode> {
+    Heap(BinaryHeap<Reverse<Node>>),
+    Indexed(IndexedHeap<Node::Board, Node>),
+    /// Buckets indexed by the cost, which is computed once when the node is queued
+    Buckets(BucketQueue<Node>),
+}
+
+impl<Node: HeuristicSearchNode> OpenList<Node> {
+    /// Queues the node, returning `false` if it did not replace a queued node of the same position
+    fn push(&mut self, node: Node) -> bool {
+        match self {
+            OpenList::Heap(heap) => {
+                heap.push(Reverse(node));
+                true
+            }
+            OpenList::Indexed(heap) => heap.push_or_decrease(node.board().clone(), node),
+            OpenList::Buckets(buckets) => {
+                buckets.push(node.cost(), node);
+                true
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        match self {
+            OpenList::Heap(heap) => heap.clear(),
+            OpenList::Indexed(heap) => *heap = IndexedHeap::new(),
+            OpenList::Buckets(buckets) => *buckets = BucketQueue::new(),
+        }
+    }
+
+    fn pop(&mut self) -> Option<Node> {
+        match self {
+            OpenList::Heap(heap) => heap.pop().map(|Reverse(node)| node),
+            OpenList::Indexed(heap) => heap.pop().map(|(_, node)| node),
+            OpenList::Buckets(buckets) => buckets.pop(),
+        }
+    }
+
+    fn peek(&self) -> Option<&Node> {
+        match self {
+            OpenList::Heap(heap) => heap.peek().map(|Reverse(node)| node),
+            OpenList::Indexed(heap) => heap.peek(),
+            OpenList::Buckets(buckets) => buckets.peek(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            OpenList::Heap(heap) => heap.len(),
+            OpenList::Indexed(heap) => heap.len(),
+            OpenList::Buckets(buckets) => buckets.len(),
+        }
+    }
+
+    /// Returns the queued nodes, the next one to be taken first
+    fn sorted(&self) -> Vec<&Node> {
+        let mut nodes: Vec<_> = match self {
+            OpenList::Heap(heap) => heap.iter().map(|Reverse(node)| node).collect(),
+            OpenList::Indexed(heap) => heap.values().collect(),
+            OpenList::Buckets(buckets) => buckets.values().collect(),
+        };
+        nodes.sort();
+        nodes
+    }
 }
 
 struct HeuristicSolver<Node>
@@ -25,8 +146,33 @@ where
     Node: HeuristicSearchNode,
 {
     heuristic: Rc<dyn Heuristic>,
-    queue: BinaryHeap<Reverse<Node>>,
+    queue: OpenList<Node>,
+    duplicate_policy: DuplicatePolicy,
+    /// Queue the nodes in buckets of their costs instead of a binary heap,
+    /// unless the duplicate policy needs the indexed heap
+    bucket_queue: bool,
+    /// Lowest path cost of every generated position, used by the graph search policies
+    best_costs: HashMap<Node::Board, u64>,
+    /// Positions which were already expanded, used by [`DuplicatePolicy::DecreaseKey`]
+    expanded: HashSet<Node::Board>,
     move_generator: MoveGenerator,
+    goal: Rc<dyn Goal>,
+    cost_model: Box<dyn CostModel>,
+    convergence_log: Option<ConvergenceLog>,
+    plateau_monitor: Option<PlateauMonitor>,
+    /// Number of times a single position may be expanded, used when searching for multiple solutions
+    expansion_limit: Option<usize>,
+    expansion_counts: HashMap<Node::Board, usize>,
+    /// Highest cost of the expanded nodes, logged when it increases
+    max_cost: u64,
+    /// Explanation of why the goal is unreachable, reported when the search ends without a solution
+    diagnosis: Option<UnsolvableDiagnosis>,
+    /// Error of storing the board in the representation of the nodes, reported when the search starts
+    invalid_board: Option<SolvingError>,
+    pruning_rules: PruningRules,
+    progress: SearchProgress,
+    /// Children queued by the expansion, collected only when the search is stepped
+    queued_children: Option<Vec<FrontierNode>>,
 }
 
 impl<Node> HeuristicSolver<Node>
@@ -35,41 +181,395 @@ where
 {
     #[must_use]
     pub fn new(board: OwnedBoard, heuristic: Box<dyn Heuristic>) -> Self {
+        Self::with_goal(board, Rc::from(heuristic), Rc::new(SolvedState))
+    }
+
+    #[must_use]
+    pub fn with_goal(board: OwnedBoard, heuristic: Rc<dyn Heuristic>, goal: Rc<dyn Goal>) -> Self {
         let mut queue = BinaryHeap::new();
-        let heuristic: Rc<dyn Heuristic> = Rc::from(heuristic);
-        if is_solvable(&board) {
-            queue.push(Reverse(Node::create(board, Rc::clone(&heuristic))));
+        let mut diagnosis = None;
+        let mut invalid_board = None;
+        if goal.is_reachable_from(&board) {
+            match Node::Board::try_from(board) {
+                Ok(board) => queue.push(Reverse(Node::create(board, Rc::clone(&heuristic)))),
+                Err(e) => invalid_board = Some(SolvingError::AlgorithmError(e.into())),
+            }
+        } else {
+            diagnosis = goal.diagnose_unreachable(&board);
         }
 
+        let mut move_generator = MoveGenerator::default();
+        move_generator.set_parity_provider(goal.parity_provider());
+
         Self {
             heuristic,
-            queue,
-            move_generator: MoveGenerator::default(),
+            queue: OpenList::Heap(queue),
+            duplicate_policy: DuplicatePolicy::Keep,
+            bucket_queue: false,
+            best_costs: HashMap::new(),
+            expanded: HashSet::new(),
+            move_generator,
+            goal,
+            cost_model: Box::new(UnitCost),
+            convergence_log: None,
+            plateau_monitor: None,
+            expansion_limit: None,
+            expansion_counts: HashMap::new(),
+            max_cost: 0,
+            diagnosis,
+            invalid_board,
+            pruning_rules: PruningRules::default(),
+            progress: SearchProgress::default(),
+            queued_children: None,
         }
     }
 
-    fn visit_node(&mut self, node: Node) -> Option<Vec<BoardMove>> {
-        let (board, path) = node.destructure();
+    fn set_duplicate_policy(&mut self, policy: DuplicatePolicy) {
+        self.duplicate_policy = policy;
+        self.requeue();
+    }
 
-        if board.is_solved() {
-            return Some(path);
+    fn set_bucket_queue(&mut self, bucket_queue: bool) {
+        self.bucket_queue = bucket_queue;
+        self.requeue();
+    }
+
+    /// Moves the queued nodes to the kind of queue required by the options
+    fn requeue(&mut self) {
+        let open_list = if self.duplicate_policy == DuplicatePolicy::DecreaseKey {
+            OpenList::Indexed(IndexedHeap::new())
+        } else if self.bucket_queue {
+            OpenList::Buckets(BucketQueue::new())
+        } else {
+            OpenList::Heap(BinaryHeap::new())
+        };
+        let mut queue = std::mem::replace(&mut self.queue, open_list);
+        // the costs are recorded again for the requeued nodes, which would be dropped as duplicates otherwise
+        self.best_costs.clear();
+        while let Some(node) = queue.pop() {
+            self.enqueue(node);
         }
+    }
 
+    /// Queues the node, unless the duplicate policy drops it, returning whether it was queued
+    fn enqueue(&mut self, node: Node) -> bool {
+        let path_cost = node.path_cost();
+        match self.duplicate_policy {
+            DuplicatePolicy::Keep => {}
+            DuplicatePolicy::Ignore => {
+                if self.best_costs.contains_key(node.board()) {
+                    return false;
+                }
+                self.best_costs.insert(node.board().clone(), path_cost);
+            }
+            DuplicatePolicy::LazyReinsert => {
+                if self
+                    .best_costs
+                    .get(node.board())
+                    .is_some_and(|&best| best <= path_cost)
+                {
+                    return false;
+                }
+                self.best_costs.insert(node.board().clone(), path_cost);
+            }
+            DuplicatePolicy::DecreaseKey => {
+                if self.expanded.contains(node.board()) {
+                    return false;
+                }
+            }
+        }
+        self.queue.push(node)
+    }
+
+    /// Takes the next node to expand from the queue, skipping outdated nodes
+    fn next_node(&mut self) -> Option<Node> {
+        loop {
+            let node = self.queue.pop()?;
+            match self.duplicate_policy {
+                DuplicatePolicy::Keep | DuplicatePolicy::Ignore => {}
+                DuplicatePolicy::LazyReinsert => {
+                    // a cheaper path to the position was found after the node was queued
+                    if self
+                        .best_costs
+                        .get(node.board())
+                        .is_some_and(|&best| best < node.path_cost())
+                    {
+                        continue;
+                    }
+                }
+                DuplicatePolicy::DecreaseKey => {
+                    self.expanded.insert(node.board().clone());
+                }
+            }
+            return Some(node);
+        }
+    }
+
+    fn visit_node(&mut self, node: Node) -> Option<Result<Vec<BoardMove>, SolvingError>> {
+        if let Some(convergence_log) = &self.convergence_log {
+            convergence_log.record(node.h_cost());
+        }
+        let cost = node.cost();
+        if cost > self.max_cost {
+            self.max_cost = cost;
+            log::trace!("Evaluating position with cost {}", cost);
+        }
+        let plateau_event = self
+            .plateau_monitor
+            .as_ref()
+            .and_then(|monitor| monitor.observe(node.h_cost()));
+        let (mut board, path, path_cost) = node.destructure();
+
+        // paths which return to an already visited position are not distinct solutions,
+        // and they would take the expansions of the position from the paths which are
+        if self.expansion_limit.is_some() && !util::is_simple_path(&board, &path) {
+            return None;
+        }
+        if self.goal.is_reached(&board) {
+            return Some(Ok(path));
+        }
+        if let Some(limit) = self.expansion_limit {
+            let count = self.expansion_counts.entry(board.clone()).or_default();
+            if *count >= limit {
+                return None;
+            }
+            *count += 1;
+        }
+        if !self.progress.expand() {
+            return Some(Err(self.progress.error(path)));
+        }
+        board.prepare_expansion();
+
+        if let Some(PlateauPolicy::RandomWalk(length)) = plateau_event {
+            let (walk_board, walk_path, walk_cost) =
+                self.random_walk(board.clone(), path.clone(), path_cost, length);
+            log::debug!(
+                "Heuristic plateau detected, escaping by {} random moves",
+                walk_path.len() - path.len()
+            );
+            // the goal is checked only when nodes are taken from the queue, so A* stays optimal
+            self.expand(&walk_board, &walk_path, walk_cost);
+        }
+
+        self.expand(&board, &path, path_cost);
+        None
+    }
+
+    fn expand(&mut self, board: &Node::Board, path: &[BoardMove], path_cost: u64) {
+        self.pruning_rules.record_expansion();
         for next_move in self
             .move_generator
-            .generate_moves(&board, path.last().copied())
+            .generate_moves(board, path.last().copied())
         {
+            let new_cost = path_cost + cost::sequence_cost(&*self.cost_model, board, next_move);
             let mut new_board = board.clone();
-            let mut new_path = path.clone();
+            let mut new_path = path.to_vec();
             util::apply_move_sequence(&mut new_board, &mut new_path, next_move);
-            self.queue.push(Reverse(Node::with_path(
+            if self.pruning_rules.prunes(&new_board, &new_path) {
+                continue;
+            }
+            self.progress.generate();
+            let child = self
+                .queued_children
+                .is_some()
+                .then(|| FrontierNode::new(&new_board, &new_path));
+            let queued = self.enqueue(Node::with_path(
                 new_board,
                 new_path,
+                new_cost,
                 Rc::clone(&self.heuristic),
-            )));
+            ));
+            if let (Some(children), Some(child), true) = (&mut self.queued_children, child, queued)
+            {
+                children.push(child);
+            }
         }
+        // positions kept by the duplicate policies are counted as boards only
+        let node_bytes = std::mem::size_of::<Node>() + util::board_bytes(board) + path.len();
+        let stored = self.queue.len() + self.best_costs.len() + self.expanded.len();
+        self.progress.observe(self.queue.len(), stored, node_bytes);
+    }
 
-        None
+    /// Applies up to `length` random move sequences allowed by the move generator
+    fn random_walk(
+        &self,
+        mut board: Node::Board,
+        mut path: Vec<BoardMove>,
+        mut path_cost: u64,
+        length: usize,
+    ) -> (Node::Board, Vec<BoardMove>, u64) {
+        let mut rng = rand::thread_rng();
+        for _ in 0..length {
+            let moves = self
+                .move_generator
+                .generate_moves(&board, path.last().copied());
+            let Some(&next_move) = moves.choose(&mut rng) else {
+                break;
+            };
+            path_cost += cost::sequence_cost(&*self.cost_model, &board, next_move);
+            util::apply_move_sequence(&mut board, &mut path, next_move);
+        }
+        (board, path, path_cost)
+    }
+
+    /// Runs the search until it finishes, or until the deadline passes, in which case `None` is returned
+    fn advance(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> Option<Result<Vec<BoardMove>, SolvingError>> {
+        if let Some(e) = self.invalid_board.take() {
+            return Some(Err(e));
+        }
+        while let Some(node) = self.next_node() {
+            if let Some(result) = self.visit_node(node) {
+                return Some(result);
+            }
+            if util::is_past(deadline) {
+                return None;
+            }
+        }
+        Some(Err(SolvingError::UnsolvableBoard(self.diagnosis)))
+    }
+
+    /// Takes the next node from the queue and expands it, collecting the queued children
+    fn step(&mut self) -> StepEvent {
+        if let Some(e) = self.invalid_board.take() {
+            return StepEvent::Failed(e);
+        }
+        let Some(node) = self.next_node() else {
+            return StepEvent::Failed(SolvingError::UnsolvableBoard(self.diagnosis));
+        };
+        let popped = FrontierNode::new(node.board(), node.path());
+        let expanded = self.progress.expanded();
+        self.queued_children = Some(vec![]);
+        let result = self.visit_node(node);
+        let children = self.queued_children.take().unwrap_or_default();
+        match result {
+            Some(Ok(solution)) => StepEvent::Solved(solution),
+            Some(Err(e)) => StepEvent::Failed(e),
+            None if self.progress.expanded() > expanded => StepEvent::Expanded {
+                node: popped,
+                children,
+            },
+            None => StepEvent::Skipped(popped),
+        }
+    }
+
+    fn frontier(&self) -> Vec<FrontierNode> {
+        self.queue
+            .sorted()
+            .into_iter()
+            .map(|node| FrontierNode::new(node.board(), node.path()))
+            .collect()
+    }
+
+    /// Returns the state of the search between two expansions, or `None` if the queue is empty
+    fn checkpoint(&self) -> Option<Checkpoint> {
+        let nodes = self.queue.sorted();
+        let first = nodes.first()?;
+        // every queued path starts at the initial board
+        let mut board = OwnedBoard::from_board(first.board());
+        for &board_move in first.path().iter().rev() {
+            board.exec_move(board_move.opposite());
+        }
+        Some(Checkpoint::AStar {
+            board,
+            open: nodes
+                .iter()
+                .map(|node| (node.path().to_vec(), node.path_cost()))
+                .collect(),
+            best_costs: self
+                .best_costs
+                .iter()
+                .map(|(board, &path_cost)| (OwnedBoard::from_board(board), path_cost))
+                .collect(),
+            expanded: self.expanded.iter().map(OwnedBoard::from_board).collect(),
+        })
+    }
+
+    /// Replaces the queue of the search which was not started yet with the nodes of the checkpoint,
+    /// replaying their paths from the initial board
+    fn resume(&mut self, checkpoint: Checkpoint) -> Result<(), CheckpointError> {
+        let Checkpoint::AStar {
+            board,
+            open,
+            best_costs,
+            expanded,
+        } = checkpoint
+        else {
+            return Err(CheckpointError::Unsupported);
+        };
+        let initial = self.queue.peek();
+        if !initial.is_some_and(|node| node.path().is_empty() && board.same_state(node.board())) {
+            return Err(CheckpointError::DifferentBoard);
+        }
+        let mut nodes = Vec::with_capacity(open.len());
+        for (path, path_cost) in open {
+            let mut node_board = board.clone();
+            node_board
+                .apply_moves(&path)
+                .map_err(|_| CheckpointError::DifferentBoard)?;
+            let node_board =
+                Node::Board::try_from(node_board).map_err(|_| CheckpointError::DifferentBoard)?;
+            nodes.push(Node::with_path(
+                node_board,
+                path,
+                path_cost,
+                Rc::clone(&self.heuristic),
+            ));
+        }
+        let best_costs = best_costs
+            .into_iter()
+            .map(|(board, path_cost)| {
+                let board =
+                    Node::Board::try_from(board).map_err(|_| CheckpointError::DifferentBoard)?;
+                Ok((board, path_cost))
+            })
+            .collect::<Result<_, CheckpointError>>()?;
+        let expanded = expanded
+            .into_iter()
+            .map(Node::Board::try_from)
+            .collect::<Result<_, _>>()
+            .map_err(|_| CheckpointError::DifferentBoard)?;
+        self.queue.clear();
+        for node in nodes {
+            self.queue.push(node);
+        }
+        self.best_costs = best_costs;
+        self.expanded = expanded;
+        Ok(())
+    }
+
+    /// Returns the path to the node which would be expanded next, the most promising one of the search
+    fn best_partial(&self) -> Vec<BoardMove> {
+        self.queue
+            .peek()
+            .map(|node| node.path().to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Continues the search after the first solution, collecting up to `k` solutions from the shortest.
+    ///
+    /// Every position is expanded at most `k` times, as the `k`-th path through a position
+    /// is the last one which can be a part of the `k` shortest solutions.
+    /// Solutions passing through the same position twice are skipped
+    fn solve_k_shortest(&mut self, k: usize) -> Result<Vec<Vec<BoardMove>>, SolvingError> {
+        // every path has to stay in the queue for the later solutions to be found
+        self.set_duplicate_policy(DuplicatePolicy::Keep);
+        self.expansion_limit = Some(k);
+
+        let mut solutions = Vec::with_capacity(k);
+        while solutions.len() < k {
+            match self
+                .advance(None)
+                .expect("Search without deadline runs until it finishes")
+            {
+                Ok(solution) => solutions.push(solution),
+                Err(e) if solutions.is_empty() => return Err(e),
+                Err(_) => break,
+            }
+        }
+        Ok(solutions)
     }
 }
 
@@ -78,17 +578,22 @@ where
     Node: HeuristicSearchNode,
 {
     fn solve(mut self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
-        let mut max_cost = 0;
-        while let Some(Reverse(node)) = self.queue.pop() {
-            let cost = node.cost();
-            if cost > max_cost {
-                max_cost = cost;
-                log::trace!("Evaluating position with cost {}", cost);
-            }
-            if let Some(result) = self.visit_node(node) {
-                return Ok(result);
-            }
-        }
-        Err(SolvingError::UnsolvableBoard)
+        self.advance(None)
+            .expect("Search without deadline runs until it finishes")
+    }
+
+    fn solve_with(self: Box<Self>, config: &SolverConfig) -> Result<Vec<BoardMove>, SolvingError> {
+        self.solve_with_stats(config).map(|report| report.solution)
+    }
+
+    fn solve_with_stats(
+        mut self: Box<Self>,
+        config: &SolverConfig,
+    ) -> Result<SolveReport, SolvingError> {
+        self.progress = SearchProgress::new(config);
+        let solution = self
+            .advance(None)
+            .expect("Search without deadline runs until it finishes")?;
+        Ok(self.progress.report(solution))
     }
 }
@@ -1,63 +1,82 @@
 use std::cmp::Ordering;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
-use crate::board::{Board, BoardMove, OwnedBoard};
-use crate::solving::algorithm::heuristic::{HeuristicSearchNode, HeuristicSolver};
-use crate::solving::algorithm::{util, Solver, SolvingError};
-use crate::solving::is_solvable;
+use crate::board::{Board, BoardMove, OwnedBoard, SearchBoard};
+use crate::solving::algorithm::heuristic::{DuplicatePolicy, HeuristicSearchNode, HeuristicSolver};
+use crate::solving::algorithm::stepper::{FrontierNode, SearchStepper, StepEvent};
+use crate::solving::algorithm::util::SearchProgress;
+use crate::solving::algorithm::{
+    util, IntermediateResult, PruningUnsupported, SolveReport, SolveStep, Solver, SolverConfig,
+    SolvingError,
+};
+use crate::solving::checkpoint::{Checkpoint, CheckpointError};
+use crate::solving::convergence::ConvergenceLog;
+use crate::solving::cost::{self, CostModel, UnitCost};
+use crate::solving::goal::{Goal, GoalEstimate, SolvedState};
+use crate::solving::history::{SearchHistory, SharedHistory};
 pub use crate::solving::movegen::MoveGenerator;
+use crate::solving::movegen::MoveSequence;
+use crate::solving::plateau::PlateauMonitor;
+use crate::solving::pruning::{PruningRule, PruningRules};
 
 use super::heuristics::Heuristic;
 
-struct SearchNode {
-    board: OwnedBoard,
+struct SearchNode<B> {
+    board: B,
     path: Vec<BoardMove>,
+    path_cost: u64,
     heuristic: Rc<dyn Heuristic>,
 }
 
-impl SearchNode {
-    fn h_cost(&self) -> u64 {
-        self.heuristic.evaluate(&self.board)
-    }
-
+impl<B: SearchBoard> SearchNode<B> {
     fn f_cost(&self) -> u64 {
-        self.h_cost() + self.path.len() as u64
+        self.h_cost() + self.path_cost
     }
 }
 
-impl PartialEq for SearchNode {
+impl<B: SearchBoard> PartialEq for SearchNode<B> {
     fn eq(&self, other: &Self) -> bool {
         self.board == other.board && self.path == other.path
     }
 }
 
-impl Eq for SearchNode {}
+impl<B: SearchBoard> Eq for SearchNode<B> {}
 
-impl PartialOrd for SearchNode {
+impl<B: SearchBoard> PartialOrd for SearchNode<B> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl Ord for SearchNode {
+impl<B: SearchBoard> Ord for SearchNode<B> {
     fn cmp(&self, other: &Self) -> Ordering {
         self.f_cost().cmp(&other.f_cost())
     }
 }
 
-impl HeuristicSearchNode for SearchNode {
-    fn create(board: OwnedBoard, heuristic: Rc<dyn Heuristic>) -> Self {
+impl<B: SearchBoard> HeuristicSearchNode for SearchNode<B> {
+    type Board = B;
+
+    fn create(board: B, heuristic: Rc<dyn Heuristic>) -> Self {
         Self {
             board,
             path: vec![],
+            path_cost: 0,
             heuristic,
         }
     }
 
-    fn with_path(board: OwnedBoard, path: Vec<BoardMove>, heuristic: Rc<dyn Heuristic>) -> Self {
+    fn with_path(
+        board: B,
+        path: Vec<BoardMove>,
+        path_cost: u64,
+        heuristic: Rc<dyn Heuristic>,
+    ) -> Self {
         Self {
             board,
             path,
+            path_cost,
             heuristic,
         }
     }
@@ -66,47 +85,246 @@ impl HeuristicSearchNode for SearchNode {
         self.f_cost()
     }
 
-    fn destructure(self) -> (OwnedBoard, Vec<BoardMove>) {
-        let Self { board, path, .. } = self;
-        (board, path)
+    fn h_cost(&self) -> u64 {
+        self.heuristic.evaluate(&self.board)
+    }
+
+    fn board(&self) -> &B {
+        &self.board
+    }
+
+    fn path(&self) -> &[BoardMove] {
+        &self.path
+    }
+
+    fn path_cost(&self) -> u64 {
+        self.path_cost
+    }
+
+    fn destructure(self) -> (B, Vec<BoardMove>, u64) {
+        let Self {
+            board,
+            path,
+            path_cost,
+            ..
+        } = self;
+        (board, path, path_cost)
     }
 }
 
 // OPTIMALITY
 //
 // This A* solver requires the heuristic to only be *admissible*,
-// as it does the search on a tree, not a graph.
-// As a consequence, it cannot implement search tree pruning in a simple way
-pub struct AStarSolver {
-    solver: HeuristicSolver<SearchNode>,
+// as by default it does the search on a tree, not a graph.
+// As a consequence, it cannot implement search tree pruning in a simple way.
+// Graph search can be enabled with a `DuplicatePolicy`,
+// in which case `DecreaseKey` additionally requires the heuristic to be *consistent*
+//
+// The boards of the queued nodes are stored as `B`,
+// which can be changed to `CowBoard` to avoid copying the cells of nodes which are never expanded
+pub struct AStarSolver<B: SearchBoard = OwnedBoard> {
+    solver: HeuristicSolver<SearchNode<B>>,
 }
 
 impl AStarSolver {
     #[must_use]
     pub fn new(board: OwnedBoard, heuristic: Box<dyn Heuristic>) -> Self {
+        Self::with_representation(board, heuristic)
+    }
+
+    /// Creates solver which finds the shortest path to any of the positions satisfying the goal.
+    /// The goal estimate is used as the heuristic
+    #[must_use]
+    pub fn with_goal(board: OwnedBoard, goal: Box<dyn Goal>) -> Self {
+        let goal: Rc<dyn Goal> = Rc::from(goal);
+        Self {
+            solver: HeuristicSolver::with_goal(
+                board,
+                Rc::new(GoalEstimate(Rc::clone(&goal))),
+                goal,
+            ),
+        }
+    }
+}
+
+impl<B: SearchBoard> AStarSolver<B> {
+    /// Creates solver which stores the boards of the queued nodes as `B`,
+    /// e.g. `AStarSolver::<CowBoard>::with_representation(board, heuristic)`
+    #[must_use]
+    pub fn with_representation(board: OwnedBoard, heuristic: Box<dyn Heuristic>) -> Self {
         Self {
             solver: HeuristicSolver::new(board, heuristic),
         }
     }
+
+    /// Makes the solver minimize the total cost of the moves instead of their number.
+    /// The heuristic has to be admissible under the cost model for the solution to be optimal
+    #[must_use]
+    pub fn with_cost_model(mut self, cost_model: Box<dyn CostModel>) -> Self {
+        self.solver.cost_model = cost_model;
+        self
+    }
+
+    /// Records the progress of the lowest heuristic value in the given log
+    #[must_use]
+    pub fn with_convergence_log(mut self, convergence_log: ConvergenceLog) -> Self {
+        self.solver.convergence_log = Some(convergence_log);
+        self
+    }
+
+    /// Detects heuristic plateaus during the search and applies the monitor's escape policy
+    #[must_use]
+    pub fn with_plateau_monitor(mut self, plateau_monitor: PlateauMonitor) -> Self {
+        self.solver.plateau_monitor = Some(plateau_monitor);
+        self
+    }
+
+    /// Leaves out of the search the positions cut off by the [`PruningRule`]
+    #[must_use]
+    pub fn with_pruning_rule(mut self, rule: impl PruningRule + 'static) -> Self {
+        self.solver.pruning_rules.add(Box::new(rule));
+        self
+    }
+
+    /// Sets how positions which are reached again by another path are handled.
+    /// By default the solver searches the tree of paths, queueing every generated node
+    #[must_use]
+    pub fn with_duplicate_policy(mut self, policy: DuplicatePolicy) -> Self {
+        self.solver.set_duplicate_policy(policy);
+        self
+    }
+
+    /// Queues the nodes in buckets indexed by their f-costs instead of a binary heap.
+    /// The f-cost is evaluated once per node instead of on every comparison,
+    /// and nodes of equal f-costs are expanded from the most recently generated one.
+    ///
+    /// Ignored with [`DuplicatePolicy::DecreaseKey`], which needs the indexed heap
+    #[must_use]
+    pub fn with_bucket_queue(mut self) -> Self {
+        self.solver.set_bucket_queue(true);
+        self
+    }
+
+    /// Returns up to `k` distinct solutions of the board, from the shortest.
+    /// Solutions never pass through the same position twice.
+    ///
+    /// Every position is expanded by at most `k` of the paths reaching it, so fewer than `k` solutions are returned
+    /// when there are no more of them, but also when the `k` shortest paths to some position cannot reach the goal
+    /// without passing through a position twice, while a longer path to it could.
+    ///
+    /// The heuristic has to be admissible for the solutions to be the shortest ones,
+    /// and the duplicate policy is reset, as the search has to keep every path
+    ///
+    /// # Errors
+    /// Returns error if the board has no solution
+    pub fn solve_k_shortest(mut self, k: usize) -> Result<Vec<Vec<BoardMove>>, SolvingError> {
+        self.solver.solve_k_shortest(k)
+    }
+
+    /// Prevents the solver from moving any of the given tiles
+    #[must_use]
+    pub(crate) fn with_frozen_tiles(mut self, tiles: impl IntoIterator<Item = u8>) -> Self {
+        self.solver.move_generator.freeze_tiles(tiles);
+        self
+    }
 }
 
-impl Solver for AStarSolver {
+impl<B: SearchBoard + 'static> SearchStepper for AStarSolver<B> {
+    fn step(&mut self) -> StepEvent {
+        self.solver.step()
+    }
+
+    fn frontier(&self) -> Vec<FrontierNode> {
+        self.solver.frontier()
+    }
+}
+
+impl<B: SearchBoard + 'static> Solver for AStarSolver<B> {
     fn solve(self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
         Box::new(self.solver).solve()
     }
+
+    fn add_pruning_rule(&mut self, rule: Box<dyn PruningRule>) -> Result<(), PruningUnsupported> {
+        self.solver.pruning_rules.add(rule);
+        Ok(())
+    }
+
+    fn solve_with(self: Box<Self>, config: &SolverConfig) -> Result<Vec<BoardMove>, SolvingError> {
+        Box::new(self.solver).solve_with(config)
+    }
+
+    fn solve_with_stats(
+        self: Box<Self>,
+        config: &SolverConfig,
+    ) -> Result<SolveReport, SolvingError> {
+        Box::new(self.solver).solve_with_stats(config)
+    }
+
+    fn is_suspendable(&self) -> bool {
+        true
+    }
+
+    fn solve_for(mut self: Box<Self>, duration: Duration) -> SolveStep {
+        match self.solver.advance(Some(Instant::now() + duration)) {
+            Some(result) => {
+                SolveStep::Done(result.map(|solution| self.solver.progress.report(solution)))
+            }
+            None => SolveStep::Pending(self),
+        }
+    }
+
+    fn best_partial(&self) -> Vec<BoardMove> {
+        self.solver.best_partial()
+    }
+
+    fn checkpoint(&self) -> Option<Checkpoint> {
+        self.solver.checkpoint()
+    }
+
+    fn resume(&mut self, checkpoint: Checkpoint) -> Result<(), CheckpointError> {
+        self.solver.resume(checkpoint)
+    }
 }
 
+/// IDA*, which can also be driven one f-cost bound at a time as an iterator
 pub struct IterativeAStarSolver {
     heuristic: Box<dyn Heuristic>,
     path: Vec<BoardMove>,
+    path_cost: u64,
     board: OwnedBoard,
     move_generator: MoveGenerator,
+    goal: Rc<dyn Goal>,
+    cost_model: Box<dyn CostModel>,
+    convergence_log: Option<ConvergenceLog>,
+    /// Bound of the current or the next iteration, `None` before the first one
+    bound: Option<u64>,
+    /// Lowest cost exceeding the bound found by the current iteration, the bound of the next one
+    next_bound: Option<u64>,
+    /// Moves leading to the position a suspended iteration continues from.
+    /// The branches generated before it were already searched
+    resume_path: Vec<BoardMove>,
+    /// Time after which [`Solver::solve_for`] suspends the search
+    deadline: Option<Instant>,
+    /// Whether a position was expanded since the deadline was set, so that every time slice makes progress
+    expanded_before_deadline: bool,
+    finished: bool,
+    progress: SearchProgress,
+    pruning_rules: PruningRules,
+    history: Option<SearchHistory>,
+    /// Search the children from the lowest f-cost instead of the order of the move generator
+    child_ordering: bool,
+    /// Factor of the heuristic value in the f-cost, 1 for plain IDA*
+    weight: f64,
 }
 
 enum IDAStarResult {
     Ok,
     NotFound,
     Exceeded(u64),
+    /// A limit of the configuration was reached, the path to the current position is kept
+    LimitReached,
+    /// The deadline of [`Solver::solve_for`] passed, the path to the current position is kept
+    Suspended,
 }
 
 impl IterativeAStarSolver {
@@ -116,57 +334,415 @@ impl IterativeAStarSolver {
             board,
             heuristic,
             path: vec![],
+            path_cost: 0,
             move_generator: MoveGenerator::default(),
+            goal: Rc::new(SolvedState),
+            cost_model: Box::new(UnitCost),
+            convergence_log: None,
+            bound: None,
+            next_bound: None,
+            resume_path: vec![],
+            deadline: None,
+            expanded_before_deadline: false,
+            finished: false,
+            progress: SearchProgress::default(),
+            pruning_rules: PruningRules::default(),
+            history: None,
+            child_ordering: false,
+            weight: 1.0,
         }
     }
 
-    fn search(&mut self, max_f_cost: u64) -> IDAStarResult {
-        let f_cost = self.path.len() as u64 + self.heuristic.evaluate(&self.board);
-        if f_cost > max_f_cost {
-            return IDAStarResult::Exceeded(f_cost);
+    /// Creates solver which finds the shortest path to any of the positions satisfying the goal.
+    /// The goal estimate is used as the heuristic
+    #[must_use]
+    pub fn with_goal(board: OwnedBoard, goal: Box<dyn Goal>) -> Self {
+        let goal: Rc<dyn Goal> = Rc::from(goal);
+        let mut move_generator = MoveGenerator::default();
+        move_generator.set_parity_provider(goal.parity_provider());
+        Self {
+            board,
+            heuristic: Box::new(GoalEstimate(Rc::clone(&goal))),
+            path: vec![],
+            path_cost: 0,
+            move_generator,
+            goal,
+            cost_model: Box::new(UnitCost),
+            convergence_log: None,
+            bound: None,
+            next_bound: None,
+            resume_path: vec![],
+            deadline: None,
+            expanded_before_deadline: false,
+            finished: false,
+            progress: SearchProgress::default(),
+            pruning_rules: PruningRules::default(),
+            history: None,
+            child_ordering: false,
+            weight: 1.0,
         }
-        if self.board.is_solved() {
-            return IDAStarResult::Ok;
+    }
+
+    /// Creates weighted IDA* (WIDA*), which compares `g + weight * h` with the bound instead of `g + h`.
+    ///
+    /// Positions with high heuristic values are cut off earlier, so far fewer iterations and nodes are searched,
+    /// while only the path is stored, as in IDA*. Every position on the shortest path has the weighted f-cost
+    /// of at most `weight` times the length of the shortest solution, so if the heuristic is admissible,
+    /// the solution is at most `weight` times longer than the shortest one. Weights below 1 are raised to 1
+    #[must_use]
+    pub fn weighted(board: OwnedBoard, heuristic: Box<dyn Heuristic>, weight: f64) -> Self {
+        Self {
+            weight: weight.max(1.0),
+            ..Self::new(board, heuristic)
+        }
+    }
+
+    /// Makes the solver minimize the total cost of the moves instead of their number.
+    /// The heuristic has to be admissible under the cost model for the solution to be optimal
+    #[must_use]
+    pub fn with_cost_model(mut self, cost_model: Box<dyn CostModel>) -> Self {
+        self.cost_model = cost_model;
+        self
+    }
+
+    /// Records the progress of the lowest heuristic value in the given log
+    #[must_use]
+    pub fn with_convergence_log(mut self, convergence_log: ConvergenceLog) -> Self {
+        self.convergence_log = Some(convergence_log);
+        self
+    }
+
+    /// Leaves out of the search the positions cut off by the [`PruningRule`]
+    #[must_use]
+    pub fn with_pruning_rule(mut self, rule: impl PruningRule + 'static) -> Self {
+        self.pruning_rules.add(Box::new(rule));
+        self
+    }
+
+    /// Returns the heuristic value multiplied by the weight, rounded down so that the bound on the length still holds
+    fn weighted_h_cost(&self, h_cost: u64) -> u64 {
+        (self.weight * h_cost as f64) as u64
+    }
+
+    /// Orders the moves by the history table, crediting the moves which lead to the next bound
+    /// of an iteration or to the solution. The credits are added to the shared table when the search ends
+    #[must_use]
+    pub fn with_history(mut self, history: SharedHistory) -> Self {
+        self.history = Some(SearchHistory::new(history));
+        self
+    }
+
+    /// Sets whether the children are searched from the lowest f-cost instead of the order of the move generator.
+    /// It finds the solution earlier in the last iteration, while the other iterations search the same nodes.
+    /// Children of equal f-costs keep the order of the move generator, or of the move history
+    #[must_use]
+    pub fn with_child_ordering(mut self, child_ordering: bool) -> Self {
+        self.child_ordering = child_ordering;
+        self
+    }
+
+    /// Credits the moves of the found path, and shares the credits of the search with the next boards
+    fn record_solution(&mut self) {
+        if let Some(history) = &mut self.history {
+            history.record_path(&self.board, &self.path);
+            history.publish();
+        }
+    }
+
+    /// Moves the board back to the initial position, keeping the path to resume the iteration from.
+    /// The move history changes the order of the moves only between the iterations, so the path stays valid
+    fn suspend(&mut self) {
+        let path = std::mem::take(&mut self.path);
+        for &board_move in path.iter().rev() {
+            self.board.exec_move(board_move.opposite());
+        }
+        self.path_cost = 0;
+        self.resume_path = path;
+    }
+
+    /// Returns the heuristic value of the position the moves lead to, updated from the value of the current position
+    /// by the deltas of the moves, or `None` if the heuristic cannot be updated incrementally
+    fn child_h_cost(&mut self, h_cost: u64, move_sequence: MoveSequence) -> Option<u64> {
+        match move_sequence {
+            MoveSequence::Single(board_move) => {
+                h_cost.checked_add_signed(self.heuristic.delta(&self.board, board_move)?)
+            }
+            MoveSequence::Double(first, second) => {
+                let h_cost =
+                    h_cost.checked_add_signed(self.heuristic.delta(&self.board, first)?)?;
+                self.board.exec_move(first);
+                let delta = self.heuristic.delta(&self.board, second);
+                self.board.exec_move(first.opposite());
+                h_cost.checked_add_signed(delta?)
+            }
+        }
+    }
+
+    /// Pairs the moves with the heuristic values of the positions they lead to, from the lowest f-cost
+    fn order_children(
+        &mut self,
+        moves: Vec<MoveSequence>,
+        h_cost: Option<u64>,
+    ) -> Vec<(MoveSequence, Option<u64>)> {
+        let mut children: Vec<_> = moves
+            .into_iter()
+            .map(|next_move| {
+                let move_cost = cost::sequence_cost(&*self.cost_model, &self.board, next_move);
+                let h_cost = h_cost.and_then(|h_cost| self.child_h_cost(h_cost, next_move));
+                util::apply_move_sequence(&mut self.board, &mut self.path, next_move);
+                let h_cost = h_cost.unwrap_or_else(|| self.heuristic.evaluate(&self.board));
+                util::undo_move_sequence(&mut self.board, &mut self.path, next_move);
+                (next_move, move_cost + self.weighted_h_cost(h_cost), h_cost)
+            })
+            .collect();
+        // the sort is stable, keeping the order of the moves of equal f-costs
+        children.sort_by_key(|&(_, f_cost, _)| f_cost);
+        children
+            .into_iter()
+            .map(|(next_move, _, h_cost)| (next_move, Some(h_cost)))
+            .collect()
+    }
+
+    /// Searches the current position, whose heuristic value may already be evaluated by the parent
+    fn search(&mut self, max_f_cost: u64, mut h_cost: Option<u64>) -> IDAStarResult {
+        // positions on the path of a resumed iteration were expanded before it was suspended
+        let resuming = self.path.len() < self.resume_path.len();
+        if !resuming {
+            self.resume_path.clear();
+            let h_cost = *h_cost.get_or_insert_with(|| self.heuristic.evaluate(&self.board));
+            if let Some(convergence_log) = &self.convergence_log {
+                convergence_log.record(h_cost);
+            }
+            let f_cost = self.path_cost + self.weighted_h_cost(h_cost);
+            if f_cost > max_f_cost {
+                self.next_bound = Some(self.next_bound.map_or(f_cost, |bound| bound.min(f_cost)));
+                return IDAStarResult::Exceeded(f_cost);
+            }
+            if self.goal.is_reached(&self.board) {
+                return IDAStarResult::Ok;
+            }
+            if self.expanded_before_deadline && util::is_past(self.deadline) {
+                return IDAStarResult::Suspended;
+            }
+            if !self.progress.expand() {
+                return IDAStarResult::LimitReached;
+            }
+            self.expanded_before_deadline = true;
+            self.pruning_rules.record_expansion();
+            // only the current board and the path are stored, the path is the frontier
+            let depth = self.path.len();
+            self.progress
+                .observe(depth, 1, util::board_bytes(&self.board) + depth);
         }
         let mut minimum = None;
-        for next_move in self
+        // the move leading to the minimum, credited in the history table
+        let mut minimum_move = None;
+        let mut skipping = resuming;
+        let mut moves = self
             .move_generator
-            .generate_moves(&self.board, self.path.last().copied())
-        {
+            .generate_moves(&self.board, self.path.last().copied());
+        if let Some(history) = &self.history {
+            history.order(&self.board, &mut moves);
+        }
+        let children = if self.child_ordering {
+            self.order_children(moves, h_cost)
+        } else {
+            moves
+                .into_iter()
+                .map(|next_move| {
+                    let h_cost = h_cost.and_then(|h_cost| self.child_h_cost(h_cost, next_move));
+                    (next_move, h_cost)
+                })
+                .collect()
+        };
+        for (next_move, h_cost) in children {
+            let move_cost = cost::sequence_cost(&*self.cost_model, &self.board, next_move);
             util::apply_move_sequence(&mut self.board, &mut self.path, next_move);
-            let result = self.search(max_f_cost);
+            // branches before the path of a resumed iteration were searched before it was suspended
+            if skipping {
+                if !self.resume_path.starts_with(&self.path) {
+                    util::undo_move_sequence(&mut self.board, &mut self.path, next_move);
+                    continue;
+                }
+                skipping = false;
+            }
+            if self.pruning_rules.prunes(&self.board, &self.path) {
+                util::undo_move_sequence(&mut self.board, &mut self.path, next_move);
+                continue;
+            }
+            self.path_cost += move_cost;
+            self.progress.generate();
+            let result = self.search(max_f_cost, h_cost);
             match (minimum, result) {
-                (_, ok @ IDAStarResult::Ok) => return ok,
+                (
+                    _,
+                    result @ (IDAStarResult::Ok
+                    | IDAStarResult::LimitReached
+                    | IDAStarResult::Suspended),
+                ) => return result,
                 (None, IDAStarResult::Exceeded(x)) => {
                     minimum = Some(x);
+                    minimum_move = Some(next_move.first());
                 }
                 (Some(y), IDAStarResult::Exceeded(x)) if x < y => {
                     minimum = Some(x);
+                    minimum_move = Some(next_move.first());
                 }
                 (_, _) => {}
             }
             util::undo_move_sequence(&mut self.board, &mut self.path, next_move);
+            self.path_cost -= move_cost;
+        }
+        if let (Some(history), Some(board_move)) = (&mut self.history, minimum_move) {
+            history.record(&self.board, board_move);
         }
         minimum.map_or(IDAStarResult::NotFound, IDAStarResult::Exceeded)
     }
 }
 
+impl Iterator for IterativeAStarSolver {
+    type Item = IntermediateResult;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let bound = match self.bound {
+            Some(bound) => bound,
+            None if !self.goal.is_reachable_from(&self.board) => {
+                self.finished = true;
+                return Some(IntermediateResult::Failed(SolvingError::UnsolvableBoard(
+                    self.goal.diagnose_unreachable(&self.board),
+                )));
+            }
+            None => self.weighted_h_cost(self.heuristic.evaluate(&self.board)),
+        };
+        self.bound = Some(bound);
+
+        match self.search(bound, None) {
+            IDAStarResult::Ok => {
+                self.finished = true;
+                self.record_solution();
+                Some(IntermediateResult::Solved(std::mem::take(&mut self.path)))
+            }
+            IDAStarResult::LimitReached => {
+                self.finished = true;
+                if let Some(history) = &mut self.history {
+                    history.publish();
+                }
+                let best_partial = std::mem::take(&mut self.path);
+                Some(IntermediateResult::Failed(
+                    self.progress.error(best_partial),
+                ))
+            }
+            // the deadline is set only by `solve_for`, which tells the suspension from the end of the search
+            IDAStarResult::Suspended => {
+                self.suspend();
+                None
+            }
+            IDAStarResult::NotFound | IDAStarResult::Exceeded(_) => {
+                // a resumed iteration also counts the costs found before it was suspended
+                let Some(next_bound) = self.next_bound.take() else {
+                    unreachable!("Should always return some heuristic")
+                };
+                log::trace!("Increasing f-cost bound to {}", next_bound);
+                self.bound = Some(next_bound);
+                if let Some(history) = &mut self.history {
+                    history.end_iteration();
+                }
+                Some(IntermediateResult::BoundExhausted { bound, next_bound })
+            }
+        }
+    }
+}
+
 impl Solver for IterativeAStarSolver {
-    fn solve(mut self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
-        if !is_solvable(&self.board) {
-            return Err(SolvingError::UnsolvableBoard);
-        }
-        let mut bound = self.heuristic.evaluate(&self.board);
-        loop {
-            match self.search(bound) {
-                IDAStarResult::Ok => break Ok(self.path),
-                IDAStarResult::NotFound => unreachable!("Should always return some heuristic"),
-                IDAStarResult::Exceeded(x) => {
-                    log::trace!("Increasing f-cost bound to {}", x);
-                    bound = x;
+    fn solve(self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
+        util::finish_iterations(*self)
+    }
+
+    fn add_pruning_rule(&mut self, rule: Box<dyn PruningRule>) -> Result<(), PruningUnsupported> {
+        self.pruning_rules.add(rule);
+        Ok(())
+    }
+
+    fn solve_with(self: Box<Self>, config: &SolverConfig) -> Result<Vec<BoardMove>, SolvingError> {
+        self.solve_with_stats(config).map(|report| report.solution)
+    }
+
+    /// Counters add up over all of the iterations
+    fn solve_with_stats(
+        mut self: Box<Self>,
+        config: &SolverConfig,
+    ) -> Result<SolveReport, SolvingError> {
+        self.progress = SearchProgress::new(config);
+        let solution = util::finish_iterations(&mut *self)?;
+        Ok(self.progress.report(solution))
+    }
+
+    fn is_suspendable(&self) -> bool {
+        true
+    }
+
+    /// Suspends the search in the middle of an iteration, which continues from the position it stopped at
+    fn solve_for(mut self: Box<Self>, duration: Duration) -> SolveStep {
+        self.deadline = Some(Instant::now() + duration);
+        self.expanded_before_deadline = false;
+        let result = loop {
+            match self.next() {
+                Some(IntermediateResult::BoundExhausted { .. }) => {}
+                Some(IntermediateResult::Solved(solution)) => {
+                    break Ok(self.progress.report(solution))
+                }
+                Some(IntermediateResult::Failed(e)) => break Err(e),
+                None if self.finished => break Err(SolvingError::UnsolvableBoard(None)),
+                None => {
+                    self.deadline = None;
+                    return SolveStep::Pending(self);
                 }
             }
+        };
+        SolveStep::Done(result)
+    }
+
+    /// Returns the path to the position the suspended iteration continues from
+    fn best_partial(&self) -> Vec<BoardMove> {
+        self.resume_path.clone()
+    }
+
+    fn checkpoint(&self) -> Option<Checkpoint> {
+        if self.finished {
+            return None;
+        }
+        Some(Checkpoint::IterativeAStar {
+            board: self.board.clone(),
+            bound: self.bound?,
+            next_bound: self.next_bound,
+            path: self.resume_path.clone(),
+        })
+    }
+
+    /// The move history is not part of the checkpoint, so with a history the iteration of the checkpoint starts over,
+    /// as the moves may be searched in another order
+    fn resume(&mut self, checkpoint: Checkpoint) -> Result<(), CheckpointError> {
+        let Checkpoint::IterativeAStar {
+            board,
+            bound,
+            next_bound,
+            path,
+        } = checkpoint
+        else {
+            return Err(CheckpointError::Unsupported);
+        };
+        if !board.same_state(&self.board) {
+            return Err(CheckpointError::DifferentBoard);
+        }
+        self.bound = Some(bound);
+        if self.history.is_none() {
+            self.next_bound = next_bound;
+            self.resume_path = path;
         }
+        Ok(())
     }
 }
 
@@ -175,6 +751,7 @@ mod tests {
     use std::cmp::Reverse;
     use std::collections::BinaryHeap;
 
+    use crate::board::Board;
     use crate::solving::algorithm::heuristic::heuristics;
 
     use super::*;
@@ -196,11 +773,13 @@ mod tests {
         heap.push(Reverse(SearchNode {
             board: simple_board.clone(),
             path: vec![],
+            path_cost: 0,
             heuristic: Rc::clone(&heuristic),
         }));
         heap.push(Reverse(SearchNode {
             board: worse_board.clone(),
             path: vec![],
+            path_cost: 0,
             heuristic: Rc::clone(&heuristic),
         }));
 
@@ -229,11 +808,13 @@ mod tests {
         heap.push(Reverse(SearchNode {
             board: board.clone(),
             path: vec![],
+            path_cost: 0,
             heuristic: Rc::clone(&heuristic),
         }));
         heap.push(Reverse(SearchNode {
             board: board.clone(),
             path: vec![BoardMove::Up],
+            path_cost: 1,
             heuristic: Rc::clone(&heuristic),
         }));
 
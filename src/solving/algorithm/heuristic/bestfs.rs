@@ -1,56 +1,67 @@
 use std::cmp::Ordering;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
-use crate::board::{BoardMove, OwnedBoard};
+use crate::board::{BoardMove, OwnedBoard, SearchBoard};
 use crate::solving::algorithm::heuristic::heuristics::Heuristic;
-use crate::solving::algorithm::heuristic::{HeuristicSearchNode, HeuristicSolver};
-use crate::solving::algorithm::{Solver, SolvingError};
-
-pub struct SearchNode {
-    board: OwnedBoard,
+use crate::solving::algorithm::heuristic::{DuplicatePolicy, HeuristicSearchNode, HeuristicSolver};
+use crate::solving::algorithm::stepper::{FrontierNode, SearchStepper, StepEvent};
+use crate::solving::algorithm::{
+    PruningUnsupported, SolveReport, SolveStep, Solver, SolverConfig, SolvingError,
+};
+use crate::solving::convergence::ConvergenceLog;
+use crate::solving::plateau::PlateauMonitor;
+use crate::solving::pruning::PruningRule;
+
+pub struct SearchNode<B = OwnedBoard> {
+    board: B,
     path: Vec<BoardMove>,
+    path_cost: u64,
     heuristic: Rc<dyn Heuristic>,
 }
 
-impl SearchNode {
-    fn h_cost(&self) -> u64 {
-        self.heuristic.evaluate(&self.board)
-    }
-}
-
-impl PartialEq for SearchNode {
+impl<B: SearchBoard> PartialEq for SearchNode<B> {
     fn eq(&self, other: &Self) -> bool {
         self.board == other.board && self.path == other.path
     }
 }
 
-impl Eq for SearchNode {}
+impl<B: SearchBoard> Eq for SearchNode<B> {}
 
-impl PartialOrd for SearchNode {
+impl<B: SearchBoard> PartialOrd for SearchNode<B> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl Ord for SearchNode {
+impl<B: SearchBoard> Ord for SearchNode<B> {
     fn cmp(&self, other: &Self) -> Ordering {
         self.h_cost().cmp(&other.h_cost())
     }
 }
 
-impl HeuristicSearchNode for SearchNode {
-    fn create(board: OwnedBoard, heuristic: Rc<dyn Heuristic>) -> Self {
+impl<B: SearchBoard> HeuristicSearchNode for SearchNode<B> {
+    type Board = B;
+
+    fn create(board: B, heuristic: Rc<dyn Heuristic>) -> Self {
         Self {
             board,
             path: vec![],
+            path_cost: 0,
             heuristic,
         }
     }
 
-    fn with_path(board: OwnedBoard, path: Vec<BoardMove>, heuristic: Rc<dyn Heuristic>) -> Self {
+    fn with_path(
+        board: B,
+        path: Vec<BoardMove>,
+        path_cost: u64,
+        heuristic: Rc<dyn Heuristic>,
+    ) -> Self {
         Self {
             board,
             path,
+            path_cost,
             heuristic,
         }
     }
@@ -59,28 +70,136 @@ impl HeuristicSearchNode for SearchNode {
         self.h_cost()
     }
 
-    fn destructure(self) -> (OwnedBoard, Vec<BoardMove>) {
-        let Self { board, path, .. } = self;
-        (board, path)
+    fn h_cost(&self) -> u64 {
+        self.heuristic.evaluate(&self.board)
+    }
+
+    fn board(&self) -> &B {
+        &self.board
+    }
+
+    fn path(&self) -> &[BoardMove] {
+        &self.path
+    }
+
+    fn path_cost(&self) -> u64 {
+        self.path_cost
+    }
+
+    fn destructure(self) -> (B, Vec<BoardMove>, u64) {
+        let Self {
+            board,
+            path,
+            path_cost,
+            ..
+        } = self;
+        (board, path, path_cost)
     }
 }
 
-pub struct BestFSSolver {
-    solver: HeuristicSolver<SearchNode>,
+pub struct BestFSSolver<B: SearchBoard = OwnedBoard> {
+    solver: HeuristicSolver<SearchNode<B>>,
 }
 
 impl BestFSSolver {
     pub fn new(board: OwnedBoard, heuristic: Box<dyn Heuristic>) -> Self {
+        Self::with_representation(board, heuristic)
+    }
+}
+
+impl<B: SearchBoard> BestFSSolver<B> {
+    /// Creates solver which stores the boards of the queued nodes as `B`
+    #[must_use]
+    pub fn with_representation(board: OwnedBoard, heuristic: Box<dyn Heuristic>) -> Self {
         Self {
             solver: HeuristicSolver::new(board, heuristic),
         }
     }
+
+    /// Records the progress of the lowest heuristic value in the given log
+    #[must_use]
+    pub fn with_convergence_log(mut self, convergence_log: ConvergenceLog) -> Self {
+        self.solver.convergence_log = Some(convergence_log);
+        self
+    }
+
+    /// Sets how positions which are reached again by another path are handled
+    #[must_use]
+    pub fn with_duplicate_policy(mut self, policy: DuplicatePolicy) -> Self {
+        self.solver.set_duplicate_policy(policy);
+        self
+    }
+
+    /// Queues the nodes in buckets indexed by their heuristic values instead of a binary heap.
+    /// Ignored with [`DuplicatePolicy::DecreaseKey`], which needs the indexed heap
+    #[must_use]
+    pub fn with_bucket_queue(mut self) -> Self {
+        self.solver.set_bucket_queue(true);
+        self
+    }
+
+    /// Detects heuristic plateaus during the search and applies the monitor's escape policy
+    #[must_use]
+    pub fn with_plateau_monitor(mut self, plateau_monitor: PlateauMonitor) -> Self {
+        self.solver.plateau_monitor = Some(plateau_monitor);
+        self
+    }
+
+    /// Leaves out of the search the positions cut off by the [`PruningRule`]
+    #[must_use]
+    pub fn with_pruning_rule(mut self, rule: impl PruningRule + 'static) -> Self {
+        self.solver.pruning_rules.add(Box::new(rule));
+        self
+    }
 }
 
-impl Solver for BestFSSolver {
+impl<B: SearchBoard + 'static> SearchStepper for BestFSSolver<B> {
+    fn step(&mut self) -> StepEvent {
+        self.solver.step()
+    }
+
+    fn frontier(&self) -> Vec<FrontierNode> {
+        self.solver.frontier()
+    }
+}
+
+impl<B: SearchBoard + 'static> Solver for BestFSSolver<B> {
     fn solve(self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
         Box::new(self.solver).solve()
     }
+
+    fn add_pruning_rule(&mut self, rule: Box<dyn PruningRule>) -> Result<(), PruningUnsupported> {
+        self.solver.pruning_rules.add(rule);
+        Ok(())
+    }
+
+    fn solve_with(self: Box<Self>, config: &SolverConfig) -> Result<Vec<BoardMove>, SolvingError> {
+        Box::new(self.solver).solve_with(config)
+    }
+
+    fn solve_with_stats(
+        self: Box<Self>,
+        config: &SolverConfig,
+    ) -> Result<SolveReport, SolvingError> {
+        Box::new(self.solver).solve_with_stats(config)
+    }
+
+    fn is_suspendable(&self) -> bool {
+        true
+    }
+
+    fn solve_for(mut self: Box<Self>, duration: Duration) -> SolveStep {
+        match self.solver.advance(Some(Instant::now() + duration)) {
+            Some(result) => {
+                SolveStep::Done(result.map(|solution| self.solver.progress.report(solution)))
+            }
+            None => SolveStep::Pending(self),
+        }
+    }
+
+    fn best_partial(&self) -> Vec<BoardMove> {
+        self.solver.best_partial()
+    }
 }
 
 #[cfg(test)]
@@ -110,11 +229,13 @@ mod tests {
         heap.push(Reverse(SearchNode {
             board: simple_board.clone(),
             path: vec![],
+            path_cost: 0,
             heuristic: Rc::clone(&heuristic),
         }));
         heap.push(Reverse(SearchNode {
             board: worse_board.clone(),
             path: vec![],
+            path_cost: 0,
             heuristic: Rc::clone(&heuristic),
         }));
 
@@ -0,0 +1,90 @@
+use crate::board::{Board, BoardMove, OwnedBoard};
+use crate::solving::algorithm::heuristic::heuristics::Heuristic;
+use crate::solving::algorithm::layered::{LayerResult, LayeredSearch};
+use crate::solving::algorithm::{SolveReport, Solver, SolverConfig, SolvingError};
+use crate::solving::diagnosis::UnsolvableDiagnosis;
+use crate::solving::goal::{Goal, GoalSet};
+
+/// Solved state estimated by the heuristic
+struct HeuristicGoal<'a>(&'a dyn Heuristic);
+
+impl Goal for HeuristicGoal<'_> {
+    fn is_reached(&self, board: &dyn Board) -> bool {
+        board.is_solved()
+    }
+
+    fn estimate(&self, board: &dyn Board) -> u64 {
+        self.0.evaluate(board)
+    }
+}
+
+/// Breadth-first heuristic search (BFHS), which searches the positions layer by layer, by their distance from the board,
+/// leaving out the positions whose f-cost exceeds the bound. The bound is raised as in IDA*,
+/// so the solution is the shortest one if the heuristic is admissible.
+///
+/// The layers are searched as by the [`FrontierBFSSolver`]: only the layer being expanded and the next one are stored,
+/// with the moves leading back to the previous layer, and a relay layer in the middle of the bound.
+/// Nodes store their ancestor in the relay layer instead of their path,
+/// and the solution is reconstructed by recursively searching the path to the relay ancestor and from it to the goal.
+/// It stores far fewer positions than [`AStarSolver`], at the cost of repeating the search of the halves of the solution
+///
+/// [`AStarSolver`]: crate::solving::algorithm::heuristic::astar::AStarSolver
+/// [`FrontierBFSSolver`]: crate::solving::algorithm::frontier::FrontierBFSSolver
+pub struct BreadthFirstHeuristicSolver {
+    board: OwnedBoard,
+    heuristic: Box<dyn Heuristic>,
+}
+
+impl BreadthFirstHeuristicSolver {
+    #[must_use]
+    pub fn new(board: OwnedBoard, heuristic: Box<dyn Heuristic>) -> Self {
+        Self { board, heuristic }
+    }
+}
+
+impl Solver for BreadthFirstHeuristicSolver {
+    fn solve(self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
+        self.solve_with(&SolverConfig::default())
+    }
+
+    fn solve_with(self: Box<Self>, config: &SolverConfig) -> Result<Vec<BoardMove>, SolvingError> {
+        self.solve_with_stats(config).map(|report| report.solution)
+    }
+
+    /// Counters add up over all of the iterations and the searches reconstructing the solution
+    fn solve_with_stats(
+        self: Box<Self>,
+        config: &SolverConfig,
+    ) -> Result<SolveReport, SolvingError> {
+        if let Some(diagnosis) = UnsolvableDiagnosis::diagnose(&self.board) {
+            return Err(SolvingError::UnsolvableBoard(Some(diagnosis)));
+        }
+        let mut search = LayeredSearch::new(&self.board, config);
+
+        let target = HeuristicGoal(&*self.heuristic);
+        // the halves of the solution are searched with the Manhattan distance, as the heuristic may be inadmissible
+        let goal = GoalSet::new(vec![self.board.goal()]);
+        let mut bound = self.heuristic.evaluate(&self.board);
+        let solution = loop {
+            let relay_depth = bound / 2;
+            match search.search_layers(&self.board, &target, Some(bound), Some(relay_depth), |_| {})
+            {
+                LayerResult::Found {
+                    depth,
+                    relay: Some(relay),
+                } => break search.connect(&self.board, &goal, depth, relay, relay_depth)?,
+                // an inadmissible heuristic may raise the bound above twice the depth of the solution
+                LayerResult::Found { depth, relay: None } => {
+                    break search.path(&self.board, &goal, depth)?
+                }
+                LayerResult::Exceeded(Some(next_bound)) => {
+                    log::trace!("Increasing f-cost bound to {next_bound}");
+                    bound = next_bound;
+                }
+                LayerResult::Exceeded(None) => return Err(SolvingError::UnsolvableBoard(None)),
+                LayerResult::LimitReached => return Err(search.progress.error(vec![])),
+            }
+        };
+        Ok(search.progress.report(solution))
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use crate::board::fingerprint::Fingerprint;
+use crate::board::{goal_cells, Board};
+use crate::solving::algorithm::heuristic::heuristics::Heuristic;
+use crate::solving::algorithm::heuristic::pattern_database::Partition;
+
+/// Identity of a heuristic artifact, which can be reused for every board with the same key
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HeuristicKey {
+    pub id: String,
+    pub dimensions: (u8, u8),
+    /// Fingerprint of the goal configuration the artifact was built for
+    pub goal: Fingerprint,
+    pub partition: Option<Partition>,
+}
+
+impl HeuristicKey {
+    /// Creates the key of the heuristic with the given id for the board
+    #[must_use]
+    pub fn for_board(id: &str, board: &dyn Board, partition: Option<&Partition>) -> Self {
+        let (rows, columns) = board.dimensions();
+        Self {
+            id: id.to_string(),
+            dimensions: (rows, columns),
+            goal: Fingerprint::of_cells(rows, columns, goal_cells(board)),
+            partition: partition.cloned(),
+        }
+    }
+}
+
+/// Cache of heuristics, together with their tables, for processes solving boards of different sizes.
+///
+/// At most `capacity` heuristics are kept, and the least recently used one is evicted
+/// when another one is inserted, so memory stays bounded however many kinds of boards are solved
+pub struct HeuristicLru {
+    capacity: usize,
+    /// Cached heuristics, from the most recently used
+    entries: VecDeque<(HeuristicKey, Arc<dyn Heuristic + Send + Sync>)>,
+}
+
+impl HeuristicLru {
+    /// Creates a cache holding at most `capacity` heuristics. Zero capacity disables caching
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the heuristic with the key, marking it as the most recently used
+    pub fn get(&mut self, key: &HeuristicKey) -> Option<Arc<dyn Heuristic + Send + Sync>> {
+        let index = self.entries.iter().position(|(cached, _)| cached == key)?;
+        let entry = self.entries.remove(index)?;
+        let heuristic = Arc::clone(&entry.1);
+        self.entries.push_front(entry);
+        Some(heuristic)
+    }
+
+    /// Inserts the heuristic as the most recently used, replacing the one with the same key.
+    /// Returns the key of the evicted heuristic, if the cache was full
+    pub fn insert(
+        &mut self,
+        key: HeuristicKey,
+        heuristic: Arc<dyn Heuristic + Send + Sync>,
+    ) -> Option<HeuristicKey> {
+        if self.capacity == 0 {
+            return None;
+        }
+        self.entries.retain(|(cached, _)| *cached != key);
+        self.entries.push_front((key, heuristic));
+        if self.entries.len() > self.capacity {
+            self.entries.pop_back().map(|(evicted, heuristic)| {
+                heuristic.reset_hard();
+                evicted
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the cached heuristic with the key, or creates and caches a new one
+    ///
+    /// # Errors
+    /// Returns the error of `create`
+    pub fn get_or_insert_with<E>(
+        &mut self,
+        key: HeuristicKey,
+        create: impl FnOnce() -> Result<Box<dyn Heuristic + Send + Sync>, E>,
+    ) -> Result<Arc<dyn Heuristic + Send + Sync>, E> {
+        if let Some(heuristic) = self.get(&key) {
+            log::debug!(
+                "Reusing heuristic {} for {:?} boards",
+                key.id,
+                key.dimensions
+            );
+            return Ok(heuristic);
+        }
+        let heuristic: Arc<dyn Heuristic + Send + Sync> = Arc::from(create()?);
+        if let Some(evicted) = self.insert(key, Arc::clone(&heuristic)) {
+            log::debug!(
+                "Evicted heuristic {} for {:?} boards",
+                evicted.id,
+                evicted.dimensions
+            );
+        }
+        Ok(heuristic)
+    }
+
+    /// Prepares the cached heuristics for another board, keeping the data valid for other boards
+    pub fn reset_soft(&self) {
+        for (_, heuristic) in &self.entries {
+            heuristic.reset_soft();
+        }
+    }
+
+    /// Discards all cached heuristics
+    pub fn clear(&mut self) {
+        for (_, heuristic) in self.entries.drain(..) {
+            heuristic.reset_hard();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::sync::Arc;
+
+    use crate::board::OwnedBoard;
+    use crate::solving::algorithm::heuristic::heuristics::{Heuristic, ManhattanDistance};
+
+    use super::{HeuristicKey, HeuristicLru};
+
+    fn key(rows: u8, columns: u8) -> HeuristicKey {
+        HeuristicKey::for_board("MD", &OwnedBoard::new_solved(rows, columns), None)
+    }
+
+    fn create() -> impl Fn() -> Result<Box<dyn Heuristic + Send + Sync>, Infallible> + Copy {
+        || Ok(Box::new(ManhattanDistance))
+    }
+
+    #[test]
+    fn evicts_least_recently_used_heuristic() {
+        let mut cache = HeuristicLru::new(2);
+
+        let first = cache.get_or_insert_with(key(3, 3), create()).unwrap();
+        cache.get_or_insert_with(key(4, 4), create()).unwrap();
+        // using the first heuristic makes the second one the least recently used
+        let reused = cache.get_or_insert_with(key(3, 3), create()).unwrap();
+        assert!(Arc::ptr_eq(&first, &reused));
+
+        assert_eq!(
+            Some(key(4, 4)),
+            cache.insert(key(5, 5), Arc::new(ManhattanDistance))
+        );
+        assert_eq!(2, cache.len());
+        assert!(cache.get(&key(4, 4)).is_none());
+        assert!(cache.get(&key(3, 3)).is_some());
+    }
+
+    #[test]
+    fn zero_capacity_disables_caching() {
+        let mut cache = HeuristicLru::new(0);
+
+        cache.get_or_insert_with(key(3, 3), create()).unwrap();
+
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,164 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap};
+
+use crate::board::{Board, BoardMove, OwnedBoard};
+use crate::solving::algorithm::heuristic::heuristics::Heuristic;
+use crate::solving::algorithm::util::SearchProgress;
+use crate::solving::algorithm::{util, SolveReport, Solver, SolverConfig, SolvingError};
+use crate::solving::diagnosis::UnsolvableDiagnosis;
+use crate::solving::movegen::MoveGenerator;
+
+/// Node of the search, taken out of the arena when it is expanded
+struct FocalNode {
+    board: OwnedBoard,
+    path: Vec<BoardMove>,
+    h_cost: u64,
+}
+
+impl FocalNode {
+    fn f_cost(&self) -> u64 {
+        self.path.len() as u64 + self.h_cost
+    }
+}
+
+/// Focal search (A*<sub>ε</sub>), which finds a solution at most `1 + ε` times longer than the shortest one.
+///
+/// Besides the open list ordered by the f-cost, it keeps the focal list of the open nodes
+/// whose f-costs are within `1 + ε` times the lowest one, and expands the node of the focal list
+/// closest to the goal by the heuristic. The lowest f-cost never exceeds the length of the shortest solution,
+/// so the guarantee holds for any admissible heuristic, while the search heads to the goal almost greedily.
+///
+/// Positions reached again by a shorter path are queued again, even if they were already expanded
+pub struct FocalSolver {
+    board: OwnedBoard,
+    heuristic: Box<dyn Heuristic>,
+    epsilon: f64,
+    move_generator: MoveGenerator,
+}
+
+impl FocalSolver {
+    /// Creates solver whose solutions are at most `1 + epsilon` times longer than the shortest ones.
+    /// Negative values are treated as 0, which makes the solutions the shortest
+    #[must_use]
+    pub fn new(board: OwnedBoard, heuristic: Box<dyn Heuristic>, epsilon: f64) -> Self {
+        Self {
+            board,
+            heuristic,
+            epsilon: epsilon.max(0.0),
+            move_generator: MoveGenerator::default(),
+        }
+    }
+
+    /// Returns the highest f-cost of the nodes in the focal list while the lowest f-cost is `lowest`
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn focal_bound(&self, lowest: u64) -> u64 {
+        (lowest as f64 * (1.0 + self.epsilon)).floor() as u64
+    }
+}
+
+impl Solver for FocalSolver {
+    fn solve(self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
+        self.solve_with(&SolverConfig::default())
+    }
+
+    fn solve_with(self: Box<Self>, config: &SolverConfig) -> Result<Vec<BoardMove>, SolvingError> {
+        self.solve_with_stats(config).map(|report| report.solution)
+    }
+
+    fn solve_with_stats(
+        self: Box<Self>,
+        config: &SolverConfig,
+    ) -> Result<SolveReport, SolvingError> {
+        let mut progress = SearchProgress::new(config);
+        if let Some(diagnosis) = UnsolvableDiagnosis::diagnose(&self.board) {
+            return Err(SolvingError::UnsolvableBoard(Some(diagnosis)));
+        }
+        let node_bytes = std::mem::size_of::<FocalNode>() + util::board_bytes(&self.board);
+
+        // arena of the queued nodes, referred to by the lists
+        let mut nodes = vec![];
+        let mut open = BTreeSet::new();
+        // ordered by the heuristic value, then from the highest f-cost, which is the deepest node
+        let mut focal = BTreeSet::new();
+        let mut best_costs = HashMap::new();
+
+        let h_cost = self.heuristic.evaluate(&self.board);
+        best_costs.insert(self.board.clone(), 0);
+        open.insert((h_cost, 0));
+        focal.insert((h_cost, Reverse(h_cost), 0));
+        // the lowest f-cost never exceeds the length of the shortest solution,
+        // so the bound is not lowered when the lowest f-cost drops after reaching a position by a shorter path
+        let mut focal_bound = self.focal_bound(h_cost);
+        nodes.push(Some(FocalNode {
+            board: self.board.clone(),
+            path: vec![],
+            h_cost,
+        }));
+
+        loop {
+            let Some(&(lowest, _)) = open.first() else {
+                return Err(SolvingError::UnsolvableBoard(None));
+            };
+            let bound = self.focal_bound(lowest);
+            if bound > focal_bound {
+                for &(f_cost, index) in open.range((focal_bound + 1, 0)..=(bound, usize::MAX)) {
+                    let node: &FocalNode = nodes[index].as_ref().expect("Open node is queued");
+                    focal.insert((node.h_cost, Reverse(f_cost), index));
+                }
+                focal_bound = bound;
+            }
+            let (_, Reverse(f_cost), index) = focal
+                .pop_first()
+                .expect("Node of the lowest f-cost is in the focal list");
+            open.remove(&(f_cost, index));
+            let FocalNode {
+                mut board,
+                mut path,
+                ..
+            } = nodes[index].take().expect("Focal node is queued");
+
+            // a shorter path to the position was found after the node was queued
+            if best_costs
+                .get(&board)
+                .is_some_and(|&best| best < path.len() as u64)
+            {
+                continue;
+            }
+            if board.is_solved() {
+                return Ok(progress.report(path));
+            }
+            if !progress.expand() {
+                return Err(progress.error(path));
+            }
+
+            for next_move in self
+                .move_generator
+                .generate_moves(&board, path.last().copied())
+            {
+                util::apply_move_sequence(&mut board, &mut path, next_move);
+                let path_cost = path.len() as u64;
+                if best_costs.get(&board).is_none_or(|&best| path_cost < best) {
+                    best_costs.insert(board.clone(), path_cost);
+                    let child = FocalNode {
+                        board: board.clone(),
+                        path: path.clone(),
+                        h_cost: self.heuristic.evaluate(&board),
+                    };
+                    let f_cost = child.f_cost();
+                    open.insert((f_cost, nodes.len()));
+                    if f_cost <= focal_bound {
+                        focal.insert((child.h_cost, Reverse(f_cost), nodes.len()));
+                    }
+                    nodes.push(Some(child));
+                    progress.generate();
+                }
+                util::undo_move_sequence(&mut board, &mut path, next_move);
+            }
+            progress.observe(open.len(), best_costs.len(), node_bytes);
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::board::{Board, BoardMove, OwnedBoard};
+use crate::solving::algorithm::heuristic::heuristics::Heuristic;
+use crate::solving::algorithm::{Solver, SolvingError};
+use crate::solving::diagnosis::UnsolvableDiagnosis;
+
+/// Time an idle worker waits for a message before checking if the search is finished
+const IDLE_WAIT: Duration = Duration::from_millis(1);
+
+/// Creates the heuristic of a single worker
+pub type HeuristicFactory = dyn Fn() -> Box<dyn Heuristic> + Send + Sync;
+
+/// Node sent to the worker owning its board
+struct Message {
+    board: OwnedBoard,
+    path: Vec<BoardMove>,
+}
+
+struct QueuedNode {
+    f_cost: u64,
+    h_cost: u64,
+    board: OwnedBoard,
+    path: Vec<BoardMove>,
+}
+
+impl PartialEq for QueuedNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueuedNode {}
+
+impl PartialOrd for QueuedNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.f_cost
+            .cmp(&other.f_cost)
+            .then(self.h_cost.cmp(&other.h_cost))
+    }
+}
+
+/// State shared by all workers
+struct Shared {
+    senders: Vec<Sender<Message>>,
+    /// Number of nodes sent and not yet processed by their owners, the search ends when it drops to zero
+    pending: AtomicUsize,
+    /// Length of the best solution found so far
+    incumbent_cost: AtomicU64,
+    incumbent: Mutex<Option<Vec<BoardMove>>>,
+}
+
+impl Shared {
+    fn owner(&self, board: &OwnedBoard) -> usize {
+        let mut hasher = DefaultHasher::new();
+        board.hash(&mut hasher);
+        (hasher.finish() % self.senders.len() as u64) as usize
+    }
+
+    fn send(&self, board: OwnedBoard, path: Vec<BoardMove>) {
+        self.pending.fetch_add(1, AtomicOrdering::SeqCst);
+        let owner = self.owner(&board);
+        // receivers live until all workers finish, which requires this node to be processed
+        let _ = self.senders[owner].send(Message { board, path });
+    }
+
+    fn processed(&self, count: usize) {
+        self.pending.fetch_sub(count, AtomicOrdering::SeqCst);
+    }
+
+    fn offer_solution(&self, path: Vec<BoardMove>) {
+        let mut incumbent = self.incumbent.lock().expect("Worker does not panic");
+        let cost = path.len() as u64;
+        if cost < self.incumbent_cost.load(AtomicOrdering::SeqCst) {
+            self.incumbent_cost.store(cost, AtomicOrdering::SeqCst);
+            *incumbent = Some(path);
+        }
+    }
+}
+
+/// Part of the search owning the boards hashed to it
+struct Worker<'a> {
+    shared: &'a Shared,
+    receiver: Receiver<Message>,
+    heuristic: Box<dyn Heuristic>,
+    open: BinaryHeap<Reverse<QueuedNode>>,
+    /// Length of the shortest path to every owned board found so far
+    best_costs: HashMap<OwnedBoard, u64>,
+    expanded: usize,
+}
+
+impl Worker<'_> {
+    fn run(mut self) -> usize {
+        loop {
+            while let Ok(message) = self.receiver.try_recv() {
+                self.accept(message);
+            }
+            if let Some(Reverse(node)) = self.open.pop() {
+                self.process(node);
+                continue;
+            }
+            if self.shared.pending.load(AtomicOrdering::SeqCst) == 0 {
+                return self.expanded;
+            }
+            match self.receiver.recv_timeout(IDLE_WAIT) {
+                Ok(message) => self.accept(message),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return self.expanded,
+            }
+        }
+    }
+
+    /// Queues the node, unless the board was already reached by a path at most as long
+    fn accept(&mut self, Message { board, path }: Message) {
+        let path_cost = path.len() as u64;
+        match self.best_costs.entry(board.clone()) {
+            Entry::Occupied(entry) if *entry.get() <= path_cost => {
+                self.shared.processed(1);
+                return;
+            }
+            Entry::Occupied(mut entry) => {
+                entry.insert(path_cost);
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(path_cost);
+            }
+        }
+        let h_cost = self.heuristic.evaluate(&board);
+        self.open.push(Reverse(QueuedNode {
+            f_cost: path_cost + h_cost,
+            h_cost,
+            board,
+            path,
+        }));
+    }
+
+    fn process(&mut self, node: QueuedNode) {
+        let incumbent_cost = self.shared.incumbent_cost.load(AtomicOrdering::SeqCst);
+        if node.f_cost >= incumbent_cost {
+            // the open list is ordered by cost, so none of the remaining nodes can improve the solution
+            self.shared.processed(self.open.len() + 1);
+            self.open.clear();
+            return;
+        }
+        if self.best_costs.get(&node.board) != Some(&(node.path.len() as u64)) {
+            // a shorter path to the board was found after the node was queued
+            self.shared.processed(1);
+            return;
+        }
+        if node.board.is_solved() {
+            self.shared.offer_solution(node.path);
+            self.shared.processed(1);
+            return;
+        }
+
+        self.expanded += 1;
+        let previous = node.path.last().copied();
+        for board_move in BoardMove::ALL {
+            if !node.board.can_move(board_move) || previous == Some(board_move.opposite()) {
+                continue;
+            }
+            let mut board = node.board.clone();
+            board.exec_move(board_move);
+            let mut path = node.path.clone();
+            path.push(board_move);
+            self.shared.send(board, path);
+        }
+        self.shared.processed(1);
+    }
+}
+
+/// Hash distributed A* (HDA*), which runs the search on multiple threads.
+///
+/// Every board is owned by the worker chosen by its hash, which keeps its own open list
+/// and the shortest paths to its boards. Generated nodes are sent to their owners through channels,
+/// so the workers never share the search data. After the first solution is found,
+/// the workers continue until no node could lead to a shorter one, so the solution is the shortest one
+/// for an admissible heuristic. Among solutions of the same length, the one found first is returned.
+///
+/// Heuristics are not shared between threads, so every worker creates its own with the factory.
+/// Heuristics which are safe to share, e.g. pattern databases, can be shared by returning an [`Arc`](std::sync::Arc) of them
+pub struct HashDistributedAStarSolver {
+    board: OwnedBoard,
+    heuristic_factory: Box<HeuristicFactory>,
+    threads: usize,
+}
+
+impl HashDistributedAStarSolver {
+    #[must_use]
+    pub fn new(
+        board: OwnedBoard,
+        heuristic_factory: impl Fn() -> Box<dyn Heuristic> + Send + Sync + 'static,
+        threads: usize,
+    ) -> Self {
+        Self {
+            board,
+            heuristic_factory: Box::new(heuristic_factory),
+            threads: threads.max(1),
+        }
+    }
+}
+
+impl Solver for HashDistributedAStarSolver {
+    fn solve(self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
+        if let Some(diagnosis) = UnsolvableDiagnosis::diagnose(&self.board) {
+            return Err(SolvingError::UnsolvableBoard(Some(diagnosis)));
+        }
+
+        let (senders, receivers): (Vec<_>, Vec<_>) =
+            (0..self.threads).map(|_| mpsc::channel()).unzip();
+        let shared = Shared {
+            senders,
+            pending: AtomicUsize::new(0),
+            incumbent_cost: AtomicU64::new(u64::MAX),
+            incumbent: Mutex::new(None),
+        };
+        shared.send(self.board, vec![]);
+
+        let heuristic_factory = &self.heuristic_factory;
+        let expanded: usize = std::thread::scope(|scope| {
+            let handles: Vec<_> = receivers
+                .into_iter()
+                .map(|receiver| {
+                    let shared = &shared;
+                    scope.spawn(move || {
+                        Worker {
+                            shared,
+                            receiver,
+                            heuristic: heuristic_factory(),
+                            open: BinaryHeap::new(),
+                            best_costs: HashMap::new(),
+                            expanded: 0,
+                        }
+                        .run()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("Worker does not panic"))
+                .sum()
+        });
+        log::debug!("Expanded {expanded} nodes on {} threads", self.threads);
+
+        shared
+            .incumbent
+            .into_inner()
+            .expect("Worker does not panic")
+            .ok_or(SolvingError::UnsolvableBoard(None))
+    }
+}
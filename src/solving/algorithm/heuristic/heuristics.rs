@@ -1,99 +1,555 @@
-use crate::board::Board;
+use crate::board::{goal_cells, Board, BoardMove, OwnedBoard};
+use crate::solving::algorithm::heuristic::pattern_database::{
+    Partition, PartitionError, PatternDatabase,
+};
+use crate::solving::algorithm::heuristic::registry::{self, LoadError};
+use crate::solving::cost::CostModel;
 use std::cmp::{max, min};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
 
 pub trait Heuristic {
     /// Calculates the heuristic for a given board setting.
     /// The heuristic is the lower bound on the required number of moves
     fn evaluate(&self, board: &dyn Board) -> u64;
+
+    /// Called between searches when the heuristic is reused for another board.
+    /// Cached data which stays valid for other boards (e.g. data derived from the goal) should be kept
+    fn reset_soft(&self) {}
+
+    /// Discards all cached data, returning the heuristic to its initial state
+    fn reset_hard(&self) {}
+
+    /// Returns the dimensions of the only boards the heuristic can evaluate,
+    /// or `None` if it evaluates boards of any dimensions
+    fn dimensions(&self) -> Option<(u8, u8)> {
+        None
+    }
+
+    /// Returns the contribution of every tile to the heuristic value, ordered by tile,
+    /// or `None` if the value cannot be attributed to individual tiles
+    fn breakdown(&self, _board: &dyn Board) -> Option<Vec<TileContribution>> {
+        None
+    }
+
+    /// Returns by how much the heuristic value changes when the move is executed on the board,
+    /// without evaluating the whole board again, or `None` if the heuristic cannot be updated incrementally.
+    /// Solvers which execute the moves on a single board use it to update the value of the parent.
+    ///
+    /// The move has to be legal on the board, the implementations locate the moved tile without checking it
+    fn delta(&self, _board_before: &dyn Board, _board_move: BoardMove) -> Option<i64> {
+        None
+    }
+}
+
+/// Part of the heuristic value caused by a single tile
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct TileContribution {
+    pub tile: u8,
+    /// Manhattan distance of the tile from its goal position
+    pub distance: u64,
+    /// Number of linear conflicts the tile participates in
+    pub conflicts: u64,
+}
+
+impl TileContribution {
+    /// Number of moves attributed to the tile.
+    /// Each conflict requires two additional moves, which are split between both tiles of the conflict
+    #[must_use]
+    pub fn value(&self) -> u64 {
+        self.distance + self.conflicts
+    }
+}
+
+/// Allows sharing a single heuristic, together with its caches, between multiple solvers
+impl<H: Heuristic + ?Sized> Heuristic for std::rc::Rc<H> {
+    fn evaluate(&self, board: &dyn Board) -> u64 {
+        (**self).evaluate(board)
+    }
+
+    fn reset_soft(&self) {
+        (**self).reset_soft();
+    }
+
+    fn reset_hard(&self) {
+        (**self).reset_hard();
+    }
+
+    fn dimensions(&self) -> Option<(u8, u8)> {
+        (**self).dimensions()
+    }
+
+    fn breakdown(&self, board: &dyn Board) -> Option<Vec<TileContribution>> {
+        (**self).breakdown(board)
+    }
+
+    fn delta(&self, board_before: &dyn Board, board_move: BoardMove) -> Option<i64> {
+        (**self).delta(board_before, board_move)
+    }
+}
+
+/// Allows sharing a single heuristic between the threads of a parallel search
+impl<H: Heuristic + ?Sized> Heuristic for std::sync::Arc<H> {
+    fn evaluate(&self, board: &dyn Board) -> u64 {
+        (**self).evaluate(board)
+    }
+
+    fn reset_soft(&self) {
+        (**self).reset_soft();
+    }
+
+    fn reset_hard(&self) {
+        (**self).reset_hard();
+    }
+
+    fn dimensions(&self) -> Option<(u8, u8)> {
+        (**self).dimensions()
+    }
+
+    fn breakdown(&self, board: &dyn Board) -> Option<Vec<TileContribution>> {
+        (**self).breakdown(board)
+    }
+
+    fn delta(&self, board_before: &dyn Board, board_move: BoardMove) -> Option<i64> {
+        (**self).delta(board_before, board_move)
+    }
 }
 
 #[derive(Default)]
 pub struct ManhattanDistance;
 
-fn manhattan_distance((r1, c1): (u8, u8), (r2, c2): (u8, u8)) -> u64 {
+pub(crate) fn manhattan_distance((r1, c1): (u8, u8), (r2, c2): (u8, u8)) -> u64 {
     let row_distance = max(r1, r2) - min(r1, r2);
     let column_distance = max(c1, c2) - min(c1, c2);
     row_distance as u64 + column_distance as u64
 }
 
-fn nonzero_cell_expected_pos(cell: u8, (rows, columns): (u8, u8)) -> (u8, u8) {
-    ((cell - 1) / rows, (cell - 1) % columns)
+/// Returns every tile together with its manhattan distance from the goal position, ordered by tile
+#[must_use]
+pub fn tile_displacements(board: &dyn Board) -> Vec<(u8, u64)> {
+    let mut displacements: Vec<(u8, u64)> = board
+        .iter_cells()
+        .filter(|&(_, value)| value != 0)
+        .map(|(position, value)| {
+            (
+                value,
+                manhattan_distance(position, board.goal_position(value)),
+            )
+        })
+        .collect();
+    displacements.sort_unstable_by_key(|&(tile, _)| tile);
+    displacements
+}
+
+/// Returns the position of the tile which is moved into the empty cell by the move
+fn moved_tile_position(board: &dyn Board, board_move: BoardMove) -> (u8, u8) {
+    let (row, column) = board.empty_cell_pos();
+    match board_move {
+        BoardMove::Up => (row - 1, column),
+        BoardMove::Down => (row + 1, column),
+        BoardMove::Left => (row, column - 1),
+        BoardMove::Right => (row, column + 1),
+    }
 }
 
 impl Heuristic for ManhattanDistance {
     fn evaluate(&self, board: &dyn Board) -> u64 {
-        let (rows, columns) = board.dimensions();
+        board
+            .iter_cells()
+            .filter(|&(_, value)| value != 0)
+            .map(|(position, value)| manhattan_distance(position, board.goal_position(value)))
+            .sum()
+    }
+
+    /// Only the moved tile changes its distance, by one cell
+    fn delta(&self, board_before: &dyn Board, board_move: BoardMove) -> Option<i64> {
+        let (row, column) = moved_tile_position(board_before, board_move);
+        let goal = board_before.goal_position(board_before.at(row, column));
+        let before = manhattan_distance((row, column), goal);
+        let after = manhattan_distance(board_before.empty_cell_pos(), goal);
+        Some(after as i64 - before as i64)
+    }
+
+    fn breakdown(&self, board: &dyn Board) -> Option<Vec<TileContribution>> {
+        Some(
+            tile_displacements(board)
+                .into_iter()
+                .map(|(tile, distance)| TileContribution {
+                    tile,
+                    distance,
+                    conflicts: 0,
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Manhattan distance where distance of each tile is multiplied by the cost of moving it.
+/// Every move displaces a single tile by one cell, so it stays admissible under the cost model
+pub struct WeightedManhattanDistance {
+    cost_model: Box<dyn CostModel>,
+}
+
+impl WeightedManhattanDistance {
+    #[must_use]
+    pub fn new(cost_model: Box<dyn CostModel>) -> Self {
+        Self { cost_model }
+    }
+}
+
+impl Heuristic for WeightedManhattanDistance {
+    fn evaluate(&self, board: &dyn Board) -> u64 {
+        board
+            .iter_cells()
+            .filter(|&(_, value)| value != 0)
+            .map(|(position, value)| {
+                let distance = manhattan_distance(position, board.goal_position(value));
+                distance * self.cost_model.tile_cost(value)
+            })
+            .sum()
+    }
+}
+
+#[derive(Default)]
+pub struct LinearConflict {
+    manhattan_distance: ManhattanDistance,
+}
 
-        let mut total_distance = 0;
+/// Calls `on_conflict` with every pair of tiles which are in their goal row or column,
+/// but in the reversed order, so one of them has to leave the line to let the other pass
+fn for_each_linear_conflict(board: &dyn Board, mut on_conflict: impl FnMut(u8, u8)) {
+    let (rows, columns) = board.dimensions();
+    let expected_pos = |cell: u8| board.goal_position(cell);
 
-        for row in 0..rows {
-            for column in 0..columns {
-                let value = board.at(row, column);
-                if value == 0 {
+    // find row conflicts
+    for row in 0..rows {
+        for first_column in 0..(columns - 1) {
+            for second_column in (first_column + 1)..columns {
+                let first_cell = board.at(row, first_column);
+                let second_cell = board.at(row, second_column);
+                if first_cell == 0 || second_cell == 0 {
+                    continue;
+                }
+                let (first_target, second_target) =
+                    (expected_pos(first_cell), expected_pos(second_cell));
+                if first_target.0 != row || second_target.0 != row {
                     continue;
                 }
-                let target = nonzero_cell_expected_pos(value, (rows, columns));
-                let distance = manhattan_distance((row, column), target);
-                total_distance += distance;
+                if first_target.1 > second_target.1 {
+                    on_conflict(first_cell, second_cell);
+                }
             }
         }
+    }
 
-        total_distance
+    // find column conflicts
+    for column in 0..columns {
+        for first_row in 0..(rows - 1) {
+            for second_row in (first_row + 1)..rows {
+                let first_cell = board.at(first_row, column);
+                let second_cell = board.at(second_row, column);
+                if first_cell == 0 || second_cell == 0 {
+                    continue;
+                }
+                let (first_target, second_target) =
+                    (expected_pos(first_cell), expected_pos(second_cell));
+                if first_target.1 != column || second_target.1 != column {
+                    continue;
+                }
+                if first_target.0 > second_target.0 {
+                    on_conflict(first_cell, second_cell);
+                }
+            }
+        }
     }
 }
 
-#[derive(Default)]
-pub struct LinearConflict {
-    manhattan_distance: ManhattanDistance,
+/// Counts the linear conflicts of the tile placed at the position with the other tiles of the row,
+/// or of the column if `in_column` is set. The cell of the tile itself and the empty cell are skipped
+fn tile_conflicts(board: &dyn Board, tile: u8, (row, column): (u8, u8), in_column: bool) -> u64 {
+    let (rows, columns) = board.dimensions();
+    let goal = board.goal_position(tile);
+    let in_line = |(other_row, other_column): (u8, u8)| {
+        if in_column {
+            other_column == column
+        } else {
+            other_row == row
+        }
+    };
+    if !in_line(goal) {
+        return 0;
+    }
+    let length = if in_column { rows } else { columns };
+    (0..length)
+        .map(|index| {
+            if in_column {
+                (index, column)
+            } else {
+                (row, index)
+            }
+        })
+        .filter(|&position| {
+            let other = board.at(position.0, position.1);
+            if other == 0 || other == tile {
+                return false;
+            }
+            let other_goal = board.goal_position(other);
+            // both positions and both goals are in the same line, so they are ordered along it
+            in_line(other_goal) && (position < (row, column)) != (other_goal < goal)
+        })
+        .count() as u64
 }
 
 impl Heuristic for LinearConflict {
     fn evaluate(&self, board: &dyn Board) -> u64 {
-        let (rows, columns) = board.dimensions();
         let mut conflicts = 0;
+        for_each_linear_conflict(board, |_, _| conflicts += 1);
 
-        let expected_pos = |cell: u8| nonzero_cell_expected_pos(cell, (rows, columns));
+        self.manhattan_distance.evaluate(board) + conflicts * 2 // for each conflict we need at least 2 moves
+    }
 
-        // calculate row conflicts
-        for row in 0..rows {
-            for first_column in 0..(columns - 1) {
-                for second_column in (first_column + 1)..columns {
-                    let first_cell = board.at(row, first_column);
-                    let second_cell = board.at(row, second_column);
-                    if first_cell == 0 || second_cell == 0 {
-                        continue;
-                    }
-                    if expected_pos(first_cell).0 != row || expected_pos(second_cell).0 != row {
-                        continue;
-                    }
-                    if first_cell > second_cell {
-                        conflicts += 1;
-                    }
-                }
+    /// The moved tile keeps its order with the tiles of the line it moves along,
+    /// so only its conflicts in the line it leaves and in the line it enters change
+    fn delta(&self, board_before: &dyn Board, board_move: BoardMove) -> Option<i64> {
+        let distance_delta = self.manhattan_distance.delta(board_before, board_move)?;
+        let from = moved_tile_position(board_before, board_move);
+        let tile = board_before.at(from.0, from.1);
+        let in_column = matches!(board_move, BoardMove::Left | BoardMove::Right);
+        let before = tile_conflicts(board_before, tile, from, in_column);
+        let after = tile_conflicts(board_before, tile, board_before.empty_cell_pos(), in_column);
+        Some(distance_delta + 2 * (after as i64 - before as i64))
+    }
+
+    fn breakdown(&self, board: &dyn Board) -> Option<Vec<TileContribution>> {
+        let mut contributions = self.manhattan_distance.breakdown(board)?;
+        // contributions are ordered by tile, and tiles are numbered from 1
+        for_each_linear_conflict(board, |first, second| {
+            contributions[first as usize - 1].conflicts += 1;
+            contributions[second as usize - 1].conflicts += 1;
+        });
+        Some(contributions)
+    }
+}
+
+/// Linear conflict heuristic tightened with the "last moves" and "corner tiles" corrections of Korf and Taylor.
+///
+/// Conflicts are counted per line, as the least number of tiles which have to leave the line so the remaining ones
+/// are in order, each of them moving out and back. The last move brings the blank into its goal cell, so just before it
+/// one of the tiles next to that cell stands in it, and reaching it from outside the blank's row or column takes
+/// two more moves. A misplaced corner tile enters its cell through a neighbouring one, so if both neighbours are
+/// in their goal cells, one of them has to step aside and back.
+/// The corrections count only the moves of tiles not counted by the other terms, which keeps the sum admissible.
+/// The tiles checked by the corrections are found once for the goal
+#[derive(Default)]
+pub struct EnhancedLinearConflict {
+    manhattan_distance: ManhattanDistance,
+    cache: std::sync::RwLock<Option<CorrectionTiles>>,
+}
+
+/// Number of values of a tile, the size of the buffers indexed by tiles or by the cells of a line
+const TILE_VALUES: usize = u8::MAX as usize + 1;
+
+/// Tile together with its goal cell
+type GoalTile = (u8, (u8, u8));
+
+/// Tiles of the goal checked by the corrections, with their goal cells, derived once for the goal
+struct CorrectionTiles {
+    dimensions: (u8, u8),
+    blank_goal: (u8, u8),
+    /// Tiles whose goal cells are next to the goal cell of the blank
+    last_tiles: Vec<GoalTile>,
+    /// Tile of every corner which is not the blank's, with the tiles of the neighbouring cells
+    corners: Vec<(GoalTile, Vec<GoalTile>)>,
+}
+
+impl CorrectionTiles {
+    fn new(board: &dyn Board) -> Self {
+        let dimensions = board.dimensions();
+        let goal = goal_cells(board);
+        let with_tile = |cell: (u8, u8)| {
+            (
+                goal[cell.0 as usize * dimensions.1 as usize + cell.1 as usize],
+                cell,
+            )
+        };
+        let blank_goal = board.goal_position(0);
+        let (rows, columns) = dimensions;
+        let corners = if rows > 1 && columns > 1 {
+            [
+                (0, 0),
+                (0, columns - 1),
+                (rows - 1, 0),
+                (rows - 1, columns - 1),
+            ]
+            .into_iter()
+            .map(|corner| {
+                let guards = neighbours(corner, dimensions).map(with_tile).collect();
+                (with_tile(corner), guards)
+            })
+            .filter(|&((tile, _), _)| tile != 0)
+            .collect()
+        } else {
+            vec![]
+        };
+        Self {
+            dimensions,
+            blank_goal,
+            last_tiles: neighbours(blank_goal, dimensions).map(with_tile).collect(),
+            corners,
+        }
+    }
+
+    /// Checks that the tiles were derived from the goal of the board
+    fn matches(&self, board: &dyn Board) -> bool {
+        let in_goal = |&(tile, cell): &GoalTile| board.goal_position(tile) == cell;
+        self.dimensions == board.dimensions()
+            && self.blank_goal == board.goal_position(0)
+            && self.last_tiles.iter().all(in_goal)
+            && self
+                .corners
+                .iter()
+                .all(|(corner, guards)| in_goal(corner) && guards.iter().all(in_goal))
+    }
+
+    /// Adds the last moves and corner tiles corrections to the value of the conflicts
+    fn corrected(&self, board: &dyn Board, value: u64, used: &mut [bool; TILE_VALUES]) -> u64 {
+        let mut positions = [(0, 0); TILE_VALUES];
+        for (position, tile) in board.iter_cells() {
+            positions[tile as usize] = position;
+        }
+        let mut value = value;
+
+        let detour_needed = |tile: u8, goal: (u8, u8)| {
+            let position = positions[tile as usize];
+            manhattan_distance(position, self.blank_goal) > manhattan_distance(position, goal)
+        };
+        if self
+            .last_tiles
+            .iter()
+            .all(|&(tile, goal)| !used[tile as usize] && detour_needed(tile, goal))
+        {
+            value += 2;
+            for &(tile, _) in &self.last_tiles {
+                used[tile as usize] = true;
             }
         }
 
-        // calculate column conflicts
-        for column in 0..columns {
-            for first_row in 0..(rows - 1) {
-                for second_row in (first_row + 1)..rows {
-                    let first_cell = board.at(first_row, column);
-                    let second_cell = board.at(second_row, column);
-                    if first_cell == 0 || second_cell == 0 {
-                        continue;
-                    }
-                    if expected_pos(first_cell).1 != column || expected_pos(second_cell).1 != column
-                    {
-                        continue;
-                    }
-                    if first_cell > second_cell {
-                        conflicts += 1;
-                    }
+        for ((tile, corner), guards) in &self.corners {
+            if positions[*tile as usize] == *corner {
+                continue;
+            }
+            let blocked = guards.iter().all(|&(guard, cell)| {
+                guard != 0 && !used[guard as usize] && positions[guard as usize] == cell
+            });
+            if blocked {
+                value += 2;
+                for &(guard, _) in guards {
+                    used[guard as usize] = true;
                 }
             }
         }
+        value
+    }
+}
 
-        self.manhattan_distance.evaluate(board) + conflicts * 2 // for each conflict we need at least 2 moves
+/// Returns how many of the tiles have to leave the line, so the remaining ones are in the order of the goal indices,
+/// which is the number of tiles outside the longest increasing subsequence
+fn tiles_to_remove(goal_indices: &[u8]) -> u64 {
+    // longest increasing subsequence ending at every tile, the lines are too short to need anything faster
+    let mut longest = [1_u8; TILE_VALUES];
+    for i in 0..goal_indices.len() {
+        for j in 0..i {
+            if goal_indices[j] < goal_indices[i] {
+                longest[i] = max(longest[i], longest[j] + 1);
+            }
+        }
+    }
+    let kept = longest[..goal_indices.len()]
+        .iter()
+        .max()
+        .copied()
+        .unwrap_or(0);
+    (goal_indices.len() - kept as usize) as u64
+}
+
+/// Number of tiles which have to leave their goal rows and columns to resolve all linear conflicts
+fn removed_conflicting_tiles(board: &dyn Board) -> u64 {
+    let (rows, columns) = board.dimensions();
+    let mut goal_indices = [0; TILE_VALUES];
+    let mut removed = 0;
+    for row in 0..rows {
+        let mut length = 0;
+        for tile in (0..columns).map(|column| board.at(row, column)) {
+            let (goal_row, goal_column) = board.goal_position(tile);
+            if tile != 0 && goal_row == row {
+                goal_indices[length] = goal_column;
+                length += 1;
+            }
+        }
+        removed += tiles_to_remove(&goal_indices[..length]);
+    }
+    for column in 0..columns {
+        let mut length = 0;
+        for tile in (0..rows).map(|row| board.at(row, column)) {
+            let (goal_row, goal_column) = board.goal_position(tile);
+            if tile != 0 && goal_column == column {
+                goal_indices[length] = goal_row;
+                length += 1;
+            }
+        }
+        removed += tiles_to_remove(&goal_indices[..length]);
+    }
+    removed
+}
+
+/// Cells adjacent to the position
+fn neighbours(
+    (row, column): (u8, u8),
+    (rows, columns): (u8, u8),
+) -> impl Iterator<Item = (u8, u8)> {
+    [
+        row.checked_sub(1).map(|above| (above, column)),
+        (row + 1 < rows).then_some((row + 1, column)),
+        column.checked_sub(1).map(|left| (row, left)),
+        (column + 1 < columns).then_some((row, column + 1)),
+    ]
+    .into_iter()
+    .flatten()
+}
+
+impl Heuristic for EnhancedLinearConflict {
+    fn evaluate(&self, board: &dyn Board) -> u64 {
+        let distance = self.manhattan_distance.evaluate(board);
+        if distance == 0 {
+            // the board is solved, so there is no last move
+            return 0;
+        }
+        // tiles whose moves may be counted by the conflicts, and cannot be used by the corrections
+        let mut used = [false; TILE_VALUES];
+        for_each_linear_conflict(board, |first, second| {
+            used[first as usize] = true;
+            used[second as usize] = true;
+        });
+        let value = distance + 2 * removed_conflicting_tiles(board);
+
+        if let Ok(cache) = self.cache.read() {
+            if let Some(tiles) = cache.as_ref().filter(|tiles| tiles.matches(board)) {
+                return tiles.corrected(board, value, &mut used);
+            }
+        }
+        let tiles = CorrectionTiles::new(board);
+        let value = tiles.corrected(board, value, &mut used);
+        if let Ok(mut cache) = self.cache.try_write() {
+            *cache = Some(tiles);
+        }
+        value
+    }
+
+    fn reset_hard(&self) {
+        if let Ok(mut cache) = self.cache.try_write() {
+            *cache = None;
+        }
     }
 }
 
@@ -101,34 +557,26 @@ impl Heuristic for LinearConflict {
 /// Description of the heuristic can be found at <https://computerpuzzle.net/puzzle/15puzzle/index.html>
 #[derive(Default)]
 pub struct InversionDistance {
-    cache: std::cell::RefCell<Option<InversionDistanceCache>>,
+    cache: std::sync::RwLock<Option<InversionDistanceCache>>,
 }
 
 struct InversionDistanceCache {
-    rows: u8,
-    columns: u8,
     row_first_order: Box<[u8]>,
     column_first_order: Box<[u8]>,
 }
 
 impl InversionDistanceCache {
-    pub fn new(board: &dyn Board) -> Self {
-        let (rows, columns) = board.dimensions();
-        let rows_first_order: Vec<_> = (1..(rows * columns)).chain(std::iter::once(0)).collect();
+    /// Creates the expected orders of the tiles from the goal, read by rows and by columns
+    pub fn new(goal_cells: Vec<u8>, (rows, columns): (u8, u8)) -> Self {
         let mut column_first_order = vec![];
-        for c in 0..columns {
-            for r in 0..rows {
-                column_first_order.push(r * rows + c + 1);
+        for column in 0..columns as usize {
+            for row in 0..rows as usize {
+                column_first_order.push(goal_cells[row * columns as usize + column]);
             }
         }
 
-        // last cell should be 0
-        column_first_order[(rows * columns - 1) as usize] = 0;
-
         Self {
-            rows,
-            columns,
-            row_first_order: rows_first_order.into_boxed_slice(),
+            row_first_order: goal_cells.into_boxed_slice(),
             column_first_order: column_first_order.into_boxed_slice(),
         }
     }
@@ -149,45 +597,24 @@ impl InversionDistance {
                     continue; // empty cell does not contribute to inversions
                 }
 
-                // check if they are in invalid inversion
-                if expected_order
-                    .iter()
-                    .position(|&x| x == first)
-                    .expect("Element has to be in expected order, since it comes from a board")
-                    > expected_order
-                        .iter()
-                        .position(|&x| x == second)
-                        .expect("Element has to be in expected order, since it comes from a board")
+                // check if they are in invalid inversion,
+                // tiles missing from the expected order (e.g. foreign tiles of a sub-board) are skipped
+                let expected_index = |tile| expected_order.iter().position(|&x| x == tile);
+                if let (Some(first), Some(second)) = (expected_index(first), expected_index(second))
                 {
-                    num_inversions += 1;
+                    if first > second {
+                        num_inversions += 1;
+                    }
                 }
             }
         }
 
         num_inversions
     }
-}
 
-impl Heuristic for InversionDistance {
-    fn evaluate(&self, board: &dyn Board) -> u64 {
-        let dimensions = board.dimensions();
-
-        // instantiate cache if empty or has wrong dimensions
-        let mut cache = self.cache.try_borrow_mut().unwrap();
-        if !matches!(*cache, Some(InversionDistanceCache{rows, columns, ..}) if (rows, columns) == dimensions )
-        {
-            // if cache is empty or invalid size
-            *cache = Some(InversionDistanceCache::new(board));
-        }
-        let cache = cache.as_ref().expect("Cache was just instantiated");
-
-        let (rows, columns) = dimensions;
-        let mut row_first_order = vec![];
-        for row in 0..rows {
-            for column in 0..columns {
-                row_first_order.push(board.at(row, column));
-            }
-        }
+    fn evaluate_with_cache(board: &dyn Board, cache: &InversionDistanceCache) -> u64 {
+        let (rows, columns) = board.dimensions();
+        let row_first_order: Vec<_> = board.iter_cells().map(|(_, value)| value).collect();
         let mut column_first_order = vec![];
         for column in 0..columns {
             for row in 0..rows {
@@ -220,12 +647,521 @@ impl Heuristic for InversionDistance {
     }
 }
 
+impl Heuristic for InversionDistance {
+    fn evaluate(&self, board: &dyn Board) -> u64 {
+        let dimensions = board.dimensions();
+
+        // instantiate cache if empty or created for a different goal
+        let goal_cells = goal_cells(board);
+        let is_current = |cache: &InversionDistanceCache| *cache.row_first_order == *goal_cells;
+        if let Ok(cache) = self.cache.read() {
+            if let Some(cache) = cache.as_ref().filter(|cache| is_current(cache)) {
+                return Self::evaluate_with_cache(board, cache);
+            }
+        }
+        let Ok(mut cache) = self.cache.try_write() else {
+            // re-entrant evaluation, e.g. from a nested heuristic, uses a temporary cache
+            let cache = InversionDistanceCache::new(goal_cells, dimensions);
+            return Self::evaluate_with_cache(board, &cache);
+        };
+        let cache = cache.insert(InversionDistanceCache::new(goal_cells, dimensions));
+        Self::evaluate_with_cache(board, cache)
+    }
+
+    fn reset_hard(&self) {
+        if let Ok(mut cache) = self.cache.try_write() {
+            *cache = None;
+        }
+    }
+}
+
+/// Walking distance heuristic developed by Ken'ichiro Takahashi.
+/// Description of the heuristic can be found at <https://computerpuzzle.net/puzzle/15puzzle/index.html>
+///
+/// Vertical moves are counted on an abstract board, where only the number of tiles of every goal row
+/// in every row is known, and horizontal moves likewise with columns. The distances of all abstract
+/// positions are computed once for the goal, and looked up during the search
+#[derive(Default)]
+pub struct WalkingDistance {
+    cache: std::sync::RwLock<Option<WalkingDistanceTables>>,
+}
+
+struct WalkingDistanceTables {
+    dimensions: (u8, u8),
+    goal_cells: Vec<u8>,
+    rows: LineTable,
+    columns: LineTable,
+}
+
+/// Distances of the abstract positions of a single direction, keyed by the number of tiles
+/// of every goal line in every line, followed by the line of the empty cell
+struct LineTable {
+    lines: usize,
+    distances: std::collections::HashMap<Box<[u8]>, u8>,
+}
+
+impl LineTable {
+    /// Runs breadth-first search from the goal, where each line holds all tiles of its own goal line.
+    /// `tile_counts` contains the number of tiles of every goal line
+    fn generate(tile_counts: &[u8], empty_line: usize) -> Self {
+        let lines = tile_counts.len();
+        let mut goal = vec![0; lines * lines + 1];
+        for (line, &count) in tile_counts.iter().enumerate() {
+            goal[line * lines + line] = count;
+        }
+        goal[lines * lines] = empty_line as u8;
+
+        let mut distances = std::collections::HashMap::new();
+        distances.insert(goal.clone().into_boxed_slice(), 0);
+        let mut queue = std::collections::VecDeque::from([(goal, 0)]);
+        while let Some((state, distance)) = queue.pop_front() {
+            let empty = state[lines * lines] as usize;
+            // the empty cell swaps with a tile of the neighbouring line, of any goal line
+            for neighbour in [empty.wrapping_sub(1), empty + 1] {
+                if neighbour >= lines {
+                    continue;
+                }
+                for goal_line in 0..lines {
+                    if state[neighbour * lines + goal_line] == 0 {
+                        continue;
+                    }
+                    let mut next = state.clone();
+                    next[neighbour * lines + goal_line] -= 1;
+                    next[empty * lines + goal_line] += 1;
+                    next[lines * lines] = neighbour as u8;
+                    if !distances.contains_key(next.as_slice()) {
+                        distances.insert(next.clone().into_boxed_slice(), distance + 1);
+                        queue.push_back((next, distance + 1));
+                    }
+                }
+            }
+        }
+
+        Self { lines, distances }
+    }
+
+    fn distance(&self, state: &[u8]) -> u64 {
+        // every position of the board has its abstract position in the table
+        self.distances.get(state).copied().map_or(0, u64::from)
+    }
+}
+
+impl WalkingDistanceTables {
+    fn new(board: &dyn Board) -> Self {
+        let (rows, columns) = board.dimensions();
+        let goal_cells = goal_cells(board);
+        let mut row_counts = vec![0; rows as usize];
+        let mut column_counts = vec![0; columns as usize];
+        for tile in goal_cells.iter().copied().filter(|&tile| tile != 0) {
+            let (row, column) = board.goal_position(tile);
+            row_counts[row as usize] += 1;
+            column_counts[column as usize] += 1;
+        }
+        let (empty_row, empty_column) = board.goal_position(0);
+
+        Self {
+            dimensions: (rows, columns),
+            goal_cells,
+            rows: LineTable::generate(&row_counts, empty_row as usize),
+            columns: LineTable::generate(&column_counts, empty_column as usize),
+        }
+    }
+
+    /// Checks if the tables were built for the dimensions and the goal of the board,
+    /// comparing the stored goal layout with the goal positions without collecting them
+    fn is_built_for(&self, board: &dyn Board) -> bool {
+        let columns = self.dimensions.1;
+        self.dimensions == board.dimensions()
+            && (0..).zip(&self.goal_cells).all(|(index, &tile)| {
+                board.goal_position(tile) == (index / columns, index % columns)
+            })
+    }
+
+    fn evaluate(&self, board: &dyn Board) -> u64 {
+        let (rows, columns) = (self.rows.lines, self.columns.lines);
+        let mut row_state = vec![0; rows * rows + 1];
+        let mut column_state = vec![0; columns * columns + 1];
+        for ((row, column), tile) in board.iter_cells() {
+            if tile == 0 {
+                row_state[rows * rows] = row;
+                column_state[columns * columns] = column;
+                continue;
+            }
+            let (goal_row, goal_column) = board.goal_position(tile);
+            row_state[row as usize * rows + goal_row as usize] += 1;
+            column_state[column as usize * columns + goal_column as usize] += 1;
+        }
+        self.rows.distance(&row_state) + self.columns.distance(&column_state)
+    }
+}
+
+impl Heuristic for WalkingDistance {
+    fn evaluate(&self, board: &dyn Board) -> u64 {
+        if let Ok(cache) = self.cache.read() {
+            if let Some(tables) = cache.as_ref().filter(|tables| tables.is_built_for(board)) {
+                return tables.evaluate(board);
+            }
+        }
+        let Ok(mut cache) = self.cache.try_write() else {
+            // re-entrant evaluation, e.g. from a nested heuristic, uses temporary tables
+            return WalkingDistanceTables::new(board).evaluate(board);
+        };
+        cache
+            .insert(WalkingDistanceTables::new(board))
+            .evaluate(board)
+    }
+
+    fn reset_hard(&self) {
+        if let Ok(mut cache) = self.cache.try_write() {
+            *cache = None;
+        }
+    }
+}
+
+/// Heuristic which can be created by [`from_id`] under either of its ids
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HeuristicInfo {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+/// Heuristics available without the pattern database registry
+pub const BUILTIN_HEURISTICS: &[HeuristicInfo] = &[
+    HeuristicInfo {
+        id: "MD",
+        name: "manhattan_distance",
+        description: "Sum of the distances of the tiles from their goal positions",
+    },
+    HeuristicInfo {
+        id: "LC",
+        name: "linear_conflict",
+        description:
+            "Manhattan distance with the conflicts of tiles in their goal rows and columns",
+    },
+    HeuristicInfo {
+        id: "ELC",
+        name: "enhanced_linear_conflict",
+        description: "Linear conflict with the last moves and corner tiles corrections",
+    },
+    HeuristicInfo {
+        id: "ID",
+        name: "inversion_distance",
+        description: "Bound derived from the inversions of the tiles in rows and columns",
+    },
+    HeuristicInfo {
+        id: "WD",
+        name: "walking_distance",
+        description: "Moves of the tiles between rows and between columns, counted separately",
+    },
+    HeuristicInfo {
+        id: "PDB",
+        name: "pattern_database",
+        description:
+            "Pattern database of the tile groups of the given partition, registered for later runs",
+    },
+    HeuristicInfo {
+        id: "DPDB",
+        name: "disjoint_pattern_database",
+        description:
+            "Additive pattern databases of the standard disjoint partition, 6-6-3 for 4x4, unless a partition is given",
+    },
+    HeuristicInfo {
+        id: "IPDB",
+        name: "instance_pattern_database",
+        description: "Pattern database of the most displaced tiles of the board",
+    },
+];
+
+#[derive(Debug)]
+pub enum HeuristicIdError {
+    /// The id is neither built-in nor registered
+    UnknownId {
+        known_ids: Vec<String>,
+    },
+    /// The pattern database heuristic requires the partition of the tiles
+    MissingPartition,
+    InvalidPartition(PartitionError),
+    /// The registered database cannot be loaded
+    LoadingFailed {
+        id: String,
+        error: std::io::Error,
+    },
+}
+
+impl Display for HeuristicIdError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HeuristicIdError::UnknownId { known_ids } => write!(
+                f,
+                "Unknown heuristic id. Possible values are: {}.",
+                known_ids.join(", ")
+            ),
+            HeuristicIdError::MissingPartition => {
+                write!(f, "Pattern database requires the partition of the tiles")
+            }
+            HeuristicIdError::InvalidPartition(e) => write!(f, "Invalid partition: {e}"),
+            HeuristicIdError::LoadingFailed { id, error } => {
+                write!(f, "Unable to load pattern database {id}: {error}")
+            }
+        }
+    }
+}
+
+impl Error for HeuristicIdError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            HeuristicIdError::InvalidPartition(e) => Some(e),
+            HeuristicIdError::LoadingFailed { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Returns all heuristic ids accepted by [`from_id`]:
+/// both ids of every built-in heuristic, followed by the ids of the registered pattern databases.
+/// The registry directory is scanned on every call
+#[must_use]
+pub fn known_ids() -> Vec<String> {
+    BUILTIN_HEURISTICS
+        .iter()
+        .flat_map(|info| [info.id, info.name])
+        .map(ToString::to_string)
+        .chain(
+            registry::default_databases()
+                .into_iter()
+                .map(|database| database.id),
+        )
+        .collect()
+}
+
+/// Checks if the heuristic with the id can exist, without creating it.
+///
+/// The registry is not scanned, so ids of the form of registered databases are accepted
+/// and [`from_id`] reports the ones which are not registered
+///
+/// # Errors
+/// Returns [`HeuristicIdError::UnknownId`] if the id is neither built-in nor of a registered database
+pub fn validate_id(heuristic_id: &str) -> Result<(), HeuristicIdError> {
+    let is_builtin = BUILTIN_HEURISTICS
+        .iter()
+        .any(|info| info.id == heuristic_id || info.name == heuristic_id);
+    if is_builtin || registry::is_database_id(heuristic_id) {
+        Ok(())
+    } else {
+        Err(HeuristicIdError::UnknownId {
+            known_ids: BUILTIN_HEURISTICS
+                .iter()
+                .flat_map(|info| [info.id, info.name])
+                .map(ToString::to_string)
+                .collect(),
+        })
+    }
+}
+
+/// Creates the heuristic with the given id for the board.
+///
+/// Pattern databases are looked up in the default registry directory, but the generated ones
+/// are not stored there, see [`from_id_registering`]
+///
+/// # Errors
+/// Returns error if the id is unknown, or the heuristic cannot be created for the board
+pub fn from_id(
+    heuristic_id: &str,
+    board: &OwnedBoard,
+    partition: Option<&Partition>,
+) -> Result<Box<dyn Heuristic + Send + Sync>, HeuristicIdError> {
+    create(heuristic_id, board, partition, None)
+}
+
+/// Creates the heuristic with the given id for the board like [`from_id`].
+/// Pattern databases generated for the standard goal are registered in the directory,
+/// and are available under their own ids afterwards when it is the default registry directory
+///
+/// # Errors
+/// Returns error if the id is unknown, or the heuristic cannot be created for the board
+pub fn from_id_registering(
+    heuristic_id: &str,
+    board: &OwnedBoard,
+    partition: Option<&Partition>,
+    directory: &Path,
+) -> Result<Box<dyn Heuristic + Send + Sync>, HeuristicIdError> {
+    create(heuristic_id, board, partition, Some(directory))
+}
+
+fn create(
+    heuristic_id: &str,
+    board: &OwnedBoard,
+    partition: Option<&Partition>,
+    registry: Option<&Path>,
+) -> Result<Box<dyn Heuristic + Send + Sync>, HeuristicIdError> {
+    match heuristic_id {
+        "MD" | "manhattan_distance" => Ok(Box::<ManhattanDistance>::default()),
+        "LC" | "linear_conflict" => Ok(Box::<LinearConflict>::default()),
+        "ELC" | "enhanced_linear_conflict" => Ok(Box::<EnhancedLinearConflict>::default()),
+        "ID" | "inversion_distance" => Ok(Box::<InversionDistance>::default()),
+        "WD" | "walking_distance" => Ok(Box::<WalkingDistance>::default()),
+        "PDB" | "pattern_database" => {
+            let partition = database_partition(heuristic_id, board, partition)?;
+            generate_registered(board, &partition, registry)
+        }
+        "DPDB" | "disjoint_pattern_database" => {
+            let (rows, columns) = board.dimensions();
+            let partition = database_partition(heuristic_id, board, partition)?;
+            // generating the 6-6-3 databases takes much longer than solving, so the registered ones are reused
+            let id = registry::database_id(rows, columns, &partition);
+            if let Some(registered) = registry::default_databases()
+                .into_iter()
+                .find(|database| database.id == id)
+            {
+                log::info!("Loading pattern database {}", registered.path.display());
+                match registered.load_for(board) {
+                    Ok(loaded) => return Ok(Box::new(loaded)),
+                    Err(e) => log::warn!("Pattern database {id} cannot be used for the board: {e}"),
+                }
+            }
+            generate_registered(board, &partition, registry)
+        }
+        "IPDB" | "instance_pattern_database" => {
+            const GROUP_SIZE: usize = 5;
+            const GROUP_COUNT: usize = 2;
+            Ok(Box::new(PatternDatabase::for_instance(
+                board,
+                GROUP_SIZE,
+                GROUP_COUNT,
+            )))
+        }
+        _ => {
+            let database = registry::default_databases()
+                .into_iter()
+                .find(|database| database.id == heuristic_id)
+                .ok_or_else(|| HeuristicIdError::UnknownId {
+                    known_ids: known_ids(),
+                })?;
+            log::info!("Loading pattern database {}", database.path.display());
+            match database.load_for(board) {
+                Ok(loaded) => Ok(Box::new(loaded)),
+                Err(LoadError::Io(error)) => Err(HeuristicIdError::LoadingFailed {
+                    id: database.id,
+                    error,
+                }),
+                Err(mismatch) => {
+                    // the database would give wrong estimates, so a heuristic valid for any board is used instead
+                    log::warn!(
+                        "Pattern database {} cannot be used for the board: {mismatch}. \
+                        Falling back to walking distance",
+                        database.id
+                    );
+                    Ok(Box::<WalkingDistance>::default())
+                }
+            }
+        }
+    }
+}
+
+/// Returns the partition of the pattern database heuristic: the given one,
+/// which the PDB requires, while the DPDB defaults to [`Partition::disjoint`]
+fn database_partition(
+    heuristic_id: &str,
+    board: &OwnedBoard,
+    partition: Option<&Partition>,
+) -> Result<Partition, HeuristicIdError> {
+    match heuristic_id {
+        "PDB" | "pattern_database" => partition.cloned().ok_or(HeuristicIdError::MissingPartition),
+        "DPDB" | "disjoint_pattern_database" => {
+            let (rows, columns) = board.dimensions();
+            Ok(partition
+                .cloned()
+                .unwrap_or_else(|| Partition::disjoint(rows, columns)))
+        }
+        _ => Err(HeuristicIdError::UnknownId {
+            known_ids: known_ids(),
+        }),
+    }
+}
+
+/// Loads the pattern database of the PDB or DPDB heuristic stored in the file, or generates it
+/// and stores it in the file if it does not exist yet, so that the database is generated only by the first run.
+/// The partition is chosen like without the file, and the stored database must have it
+///
+/// # Errors
+/// Returns error if the id is not one of the pattern database heuristics, the PDB is given no partition,
+/// the stored database cannot be read or does not match the board or the partition,
+/// or the partition is invalid for the board
+pub fn pattern_database_file(
+    path: &Path,
+    heuristic_id: &str,
+    board: &OwnedBoard,
+    partition: Option<&Partition>,
+) -> Result<Box<dyn Heuristic + Send + Sync>, HeuristicIdError> {
+    let id = path.display().to_string();
+    let partition = database_partition(heuristic_id, board, partition)?;
+    if path.exists() {
+        log::info!("Loading pattern database {id}");
+        let database = registry::load_file(path, board).map_err(|e| match e {
+            LoadError::Io(error) => HeuristicIdError::LoadingFailed {
+                id: id.clone(),
+                error,
+            },
+            mismatch => HeuristicIdError::LoadingFailed {
+                id: id.clone(),
+                error: std::io::Error::new(std::io::ErrorKind::InvalidData, mismatch.to_string()),
+            },
+        })?;
+        if &partition != database.partition() {
+            return Err(HeuristicIdError::LoadingFailed {
+                id,
+                error: std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!(
+                        "Database has partition {}, but partition {partition} was given",
+                        database.partition()
+                    ),
+                ),
+            });
+        }
+        return Ok(Box::new(database));
+    }
+
+    log::info!("Generating pattern database for partition {partition}");
+    let database = PatternDatabase::generate_for_goal(board, partition)
+        .map_err(HeuristicIdError::InvalidPartition)?;
+    match registry::save_file(path, &database) {
+        Ok(()) => log::info!("Saved pattern database to {id}"),
+        Err(e) => log::warn!("Unable to save pattern database to {id}: {e}"),
+    }
+    Ok(Box::new(database))
+}
+
+/// Generates the pattern database of the partition, registering it in the directory for later runs
+fn generate_registered(
+    board: &OwnedBoard,
+    partition: &Partition,
+    registry: Option<&Path>,
+) -> Result<Box<dyn Heuristic + Send + Sync>, HeuristicIdError> {
+    log::info!("Generating pattern database for partition {partition}");
+    let database = PatternDatabase::generate_for_goal(board, partition.clone())
+        .map_err(HeuristicIdError::InvalidPartition)?;
+    // databases for custom goals are not reusable, as the registry assumes the standard goal
+    if let Some(directory) = registry.filter(|_| board.goal().is_solved()) {
+        match registry::register(directory, &database) {
+            Ok(registered) => {
+                log::info!("Registered pattern database as heuristic {}", registered.id);
+            }
+            Err(e) => log::warn!("Unable to register pattern database: {e}"),
+        }
+    }
+    Ok(Box::new(database))
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::board::{Board, OwnedBoard};
+    use crate::board::{parse_moves, Board, BoardMove, OwnedBoard};
     use crate::solving::algorithm::dfs::IncrementalDFSSolver;
     use crate::solving::algorithm::heuristic::heuristics::{
-        Heuristic, InversionDistance, LinearConflict, ManhattanDistance,
+        from_id, from_id_registering, tile_displacements, validate_id, EnhancedLinearConflict,
+        Heuristic, HeuristicIdError, InversionDistance, LinearConflict, ManhattanDistance,
+        TileContribution, WalkingDistance, BUILTIN_HEURISTICS,
     };
     use crate::solving::algorithm::Solver;
     use crate::solving::movegen::MoveGenerator;
@@ -259,6 +1195,132 @@ mod tests {
         }
     }
 
+    #[test]
+    fn heuristics_are_created_from_ids() {
+        let board = create_board();
+
+        for info in BUILTIN_HEURISTICS {
+            assert!(validate_id(info.id).is_ok());
+            assert!(validate_id(info.name).is_ok());
+        }
+        assert_eq!(
+            ManhattanDistance.evaluate(&board),
+            from_id("manhattan_distance", &board, None)
+                .unwrap()
+                .evaluate(&board)
+        );
+        assert!(matches!(
+            validate_id("unknown"),
+            Err(HeuristicIdError::UnknownId { known_ids }) if known_ids.contains(&"LC".to_string())
+        ));
+        // registered ids are checked only when the heuristic is created
+        assert!(validate_id("4x4-555-716ed040").is_ok());
+        assert!(matches!(
+            from_id("PDB", &board, None),
+            Err(HeuristicIdError::MissingPartition)
+        ));
+    }
+
+    #[test]
+    fn generated_database_is_registered_on_request() {
+        let directory =
+            std::env::temp_dir().join(format!("pdb-registering-{}", std::process::id()));
+        let board = OwnedBoard::new_solved(2, 3);
+        let partition = "1-3|4-5".parse().unwrap();
+
+        from_id_registering("PDB", &board, Some(&partition), &directory).unwrap();
+        let registered = crate::solving::algorithm::heuristic::registry::discover(&directory);
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(1, registered.len());
+        assert_eq!((2, 3), (registered[0].rows, registered[0].columns));
+    }
+
+    #[test]
+    fn breakdown_adds_up_to_heuristic_value() {
+        let board = create_board();
+
+        for heuristic in [
+            &ManhattanDistance as &dyn Heuristic,
+            &LinearConflict::default(),
+        ] {
+            let breakdown = heuristic.breakdown(&board).unwrap();
+            assert_eq!(15, breakdown.len());
+            assert_eq!(
+                heuristic.evaluate(&board),
+                breakdown.iter().map(TileContribution::value).sum::<u64>()
+            );
+        }
+        assert_eq!(None, InversionDistance::default().breakdown(&board));
+    }
+
+    #[test]
+    fn breakdown_attributes_conflicts_to_both_tiles() {
+        let board: OwnedBoard = "2 2\n2 1\n3 0".parse().unwrap();
+
+        let breakdown = LinearConflict::default().breakdown(&board).unwrap();
+
+        assert_eq!(
+            vec![
+                TileContribution {
+                    tile: 1,
+                    distance: 1,
+                    conflicts: 1
+                },
+                TileContribution {
+                    tile: 2,
+                    distance: 1,
+                    conflicts: 1
+                },
+                TileContribution {
+                    tile: 3,
+                    distance: 0,
+                    conflicts: 0
+                },
+            ],
+            breakdown
+        );
+    }
+
+    #[test]
+    fn deltas_match_evaluation_after_move() {
+        let scramble = parse_moves("LUURULLLDRDDRRULLDLUUURRRDDDLUURDDLUUULD").unwrap();
+        let mut board = OwnedBoard::from_scramble(4, 4, &scramble).unwrap();
+        let heuristics = [
+            &ManhattanDistance as &dyn Heuristic,
+            &LinearConflict::default(),
+        ];
+
+        // every move of every position on the way back to the solved board
+        for &next_move in scramble.iter().rev() {
+            for board_move in BoardMove::ALL {
+                if !board.can_move(board_move) {
+                    continue;
+                }
+                let mut moved = board.clone();
+                moved.exec_move(board_move);
+                for heuristic in heuristics {
+                    let expected =
+                        heuristic.evaluate(&moved) as i64 - heuristic.evaluate(&board) as i64;
+                    assert_eq!(Some(expected), heuristic.delta(&board, board_move));
+                }
+            }
+            board.exec_move(next_move.opposite());
+        }
+        assert!(board.is_solved());
+        assert_eq!(
+            None,
+            WalkingDistance::default().delta(&board, BoardMove::Up)
+        );
+    }
+
+    #[test]
+    fn reports_displacement_of_every_tile() {
+        let board: OwnedBoard = "2 2\n3 1\n0 2".parse().unwrap();
+
+        assert_eq!(vec![(1, 1), (2, 1), (3, 1)], tile_displacements(&board));
+    }
+
     #[test]
     fn manhattan_distance_is_admissible() {
         let heuristic = ManhattanDistance;
@@ -271,9 +1333,68 @@ mod tests {
         heuristic_calculates_lower_bound_on_required_moves(&heuristic);
     }
 
+    #[test]
+    fn enhanced_linear_conflict_is_admissible() {
+        let heuristic = EnhancedLinearConflict::default();
+        heuristic_calculates_lower_bound_on_required_moves(&heuristic);
+    }
+
+    #[test]
+    fn enhanced_linear_conflict_adds_corrections() {
+        let heuristic = EnhancedLinearConflict::default();
+        // distances of the boards are 24, 14 and 6
+        for (board, manhattan_distance, expected) in [
+            // three tiles reversed in the top row need two of them to leave it, not three
+            ("3 3\n3 2 1\n4 5 6\n8 7 0", 6, 12),
+            // tile 1 enters its corner through the cell of 2 or 4
+            ("3 3\n0 2 3\n4 1 6\n5 7 8", 6, 8),
+            // neither 6 nor 8 is in the row or column of the blank's goal for the last move
+            ("3 3\n1 2 3\n5 7 6\n4 8 0", 4, 6),
+        ] {
+            let board: OwnedBoard = board.parse().unwrap();
+            assert_eq!(manhattan_distance, ManhattanDistance.evaluate(&board));
+            assert_eq!(expected, heuristic.evaluate(&board), "{board:?}");
+        }
+        assert_eq!(0, heuristic.evaluate(&OwnedBoard::new_solved(3, 3)));
+    }
+
     #[test]
     fn inversion_distance_is_admissible() {
         let heuristic = InversionDistance::default();
         heuristic_calculates_lower_bound_on_required_moves(&heuristic);
     }
+
+    #[test]
+    fn walking_distance_is_admissible() {
+        let heuristic = WalkingDistance::default();
+        heuristic_calculates_lower_bound_on_required_moves(&heuristic);
+    }
+
+    #[test]
+    fn walking_distance_dominates_manhattan_distance() {
+        let heuristic = WalkingDistance::default();
+        let board = create_board();
+
+        assert!(heuristic.evaluate(&board) >= ManhattanDistance.evaluate(&board));
+        assert_eq!(0, heuristic.evaluate(&board.goal()));
+
+        let goal: OwnedBoard = "3 3\n0 1 2\n3 4 5\n6 7 8".parse().unwrap();
+        let board: OwnedBoard = "3 3\n1 0 2\n3 4 5\n6 7 8".parse().unwrap();
+        assert_eq!(1, heuristic.evaluate(&board.with_goal(goal).unwrap()));
+    }
+
+    #[test]
+    fn shared_inversion_distance_keeps_cache_until_hard_reset() {
+        let heuristic = std::rc::Rc::new(InversionDistance::default());
+        let shared: Box<dyn Heuristic> = Box::new(std::rc::Rc::clone(&heuristic));
+        let board = create_board();
+        let expected = heuristic.evaluate(&board);
+
+        assert_eq!(expected, shared.evaluate(&board));
+        shared.reset_soft();
+        assert!(heuristic.cache.read().unwrap().is_some());
+        shared.reset_hard();
+        assert!(heuristic.cache.read().unwrap().is_none());
+        assert_eq!(expected, shared.evaluate(&board));
+    }
 }
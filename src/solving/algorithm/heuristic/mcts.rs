@@ -0,0 +1,367 @@
+use std::collections::{HashSet, VecDeque};
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::board::{Board, BoardMove, OwnedBoard};
+use crate::solving::algorithm::heuristic::heuristics::Heuristic;
+use crate::solving::algorithm::util::SearchProgress;
+use crate::solving::algorithm::{util, SolveReport, Solver, SolverConfig, SolvingError};
+use crate::solving::diagnosis::UnsolvableDiagnosis;
+use crate::solving::solution;
+
+/// Exploration constant of UCT suited to rewards between 0 and 1
+const DEFAULT_EXPLORATION: f64 = std::f64::consts::FRAC_1_SQRT_2;
+const DEFAULT_ROLLOUT_DEPTH: usize = 30;
+const DEFAULT_ITERATIONS_PER_MOVE: usize = 200;
+/// Probability of a random move instead of the one with the lowest heuristic value during the rollout
+const ROLLOUT_RANDOMNESS: f64 = 0.2;
+
+struct Node {
+    board: OwnedBoard,
+    parent: Option<usize>,
+    /// Move leading from the parent to the node
+    board_move: Option<BoardMove>,
+    children: Vec<usize>,
+    /// Moves which were not expanded yet, in random order
+    untried: Vec<BoardMove>,
+    heuristic: u64,
+    visits: u32,
+    total_reward: f64,
+}
+
+impl Node {
+    fn mean_reward(&self) -> f64 {
+        self.total_reward / f64::from(self.visits)
+    }
+}
+
+/// Monte Carlo tree search (MCTS), which grows a tree of paths from the board, selecting the paths to extend
+/// by the UCT formula, and rates every new node by a rollout of moves mostly decreasing the heuristic.
+/// The reward of a rollout is the lowest heuristic value reached by it, relative to the value of the root.
+///
+/// After a fixed number of iterations the most visited move of the root is made, and the search continues
+/// from its subtree, so the tree stays small even on boards whose solutions have hundreds of moves.
+/// The search ends as soon as a rollout reaches the goal, and the loops of the solution are cut out,
+/// but it is usually far from the shortest one. It is meant for comparisons with the other algorithms
+/// on boards too large for them, and is only compiled with the `experimental` feature
+pub struct MonteCarloSolver {
+    board: OwnedBoard,
+    heuristic: Box<dyn Heuristic>,
+    exploration: f64,
+    rollout_depth: usize,
+    iterations_per_move: usize,
+    seed: u64,
+}
+
+impl MonteCarloSolver {
+    #[must_use]
+    pub fn new(board: OwnedBoard, heuristic: Box<dyn Heuristic>) -> Self {
+        Self {
+            board,
+            heuristic,
+            exploration: DEFAULT_EXPLORATION,
+            rollout_depth: DEFAULT_ROLLOUT_DEPTH,
+            iterations_per_move: DEFAULT_ITERATIONS_PER_MOVE,
+            seed: 0,
+        }
+    }
+
+    /// Sets the weight of the visit counts in the UCT formula, higher values explore the rarely visited paths more
+    #[must_use]
+    pub fn with_exploration(mut self, exploration: f64) -> Self {
+        self.exploration = exploration;
+        self
+    }
+
+    /// Sets the number of moves of every rollout
+    #[must_use]
+    pub fn with_rollout_depth(mut self, rollout_depth: usize) -> Self {
+        self.rollout_depth = rollout_depth;
+        self
+    }
+
+    /// Sets the number of iterations searched before every move of the root, at least one
+    #[must_use]
+    pub fn with_iterations_per_move(mut self, iterations_per_move: usize) -> Self {
+        self.iterations_per_move = iterations_per_move.max(1);
+        self
+    }
+
+    /// Seeds the random choices of the expansions and rollouts, the same seed gives the same solution
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+struct Search<'a> {
+    heuristic: &'a dyn Heuristic,
+    exploration: f64,
+    rollout_depth: usize,
+    rng: StdRng,
+    /// Tree of the search, the root is always the first node
+    nodes: Vec<Node>,
+    progress: SearchProgress,
+    node_bytes: usize,
+}
+
+impl Search<'_> {
+    fn add_node(
+        &mut self,
+        board: OwnedBoard,
+        parent: Option<usize>,
+        board_move: Option<BoardMove>,
+    ) -> usize {
+        let mut untried = legal_moves(&board, board_move);
+        untried.shuffle(&mut self.rng);
+        let heuristic = self.heuristic.evaluate(&board);
+        self.nodes.push(Node {
+            board,
+            parent,
+            board_move,
+            children: vec![],
+            untried,
+            heuristic,
+            visits: 0,
+            total_reward: 0.0,
+        });
+        self.progress.generate();
+        let index = self.nodes.len() - 1;
+        if let Some(parent) = parent {
+            self.nodes[parent].children.push(index);
+        }
+        index
+    }
+
+    /// Runs a single selection, expansion, rollout and backpropagation,
+    /// returning the path from the root to the goal if the rollout reached it
+    fn iterate(&mut self) -> Option<Vec<BoardMove>> {
+        let mut index = 0;
+        while self.nodes[index].untried.is_empty() && !self.nodes[index].children.is_empty() {
+            index = self.select(index);
+        }
+        if let Some(board_move) = self.nodes[index].untried.pop() {
+            let mut board = self.nodes[index].board.clone();
+            board.exec_move(board_move);
+            index = self.add_node(board, Some(index), Some(board_move));
+        }
+
+        match self.rollout(index) {
+            Ok(rollout) => {
+                let mut path = self.path_to(index);
+                path.extend(rollout);
+                Some(path)
+            }
+            Err(reward) => {
+                self.backpropagate(index, reward);
+                None
+            }
+        }
+    }
+
+    /// Returns the child with the highest upper confidence bound of its reward
+    fn select(&self, index: usize) -> usize {
+        let log_visits = f64::from(self.nodes[index].visits).ln();
+        let bound = |child: usize| {
+            let node = &self.nodes[child];
+            node.mean_reward() + self.exploration * (log_visits / f64::from(node.visits)).sqrt()
+        };
+        self.nodes[index]
+            .children
+            .iter()
+            .copied()
+            .max_by(|&first, &second| bound(first).total_cmp(&bound(second)))
+            .expect("Node without untried moves has children")
+    }
+
+    /// Makes random moves from the node, mostly those with the lowest heuristic value.
+    /// Returns the moves if they reach the goal, otherwise the reward of the node
+    fn rollout(&mut self, index: usize) -> Result<Vec<BoardMove>, f64> {
+        let node = &self.nodes[index];
+        let mut board = node.board.clone();
+        let mut previous = node.board_move;
+        let mut lowest = node.heuristic;
+        let mut path = vec![];
+        loop {
+            if board.is_solved() {
+                return Ok(path);
+            }
+            if path.len() == self.rollout_depth {
+                break;
+            }
+            let mut evaluated: Vec<_> = legal_moves(&board, previous)
+                .into_iter()
+                .map(|board_move| {
+                    let mut child = board.clone();
+                    child.exec_move(board_move);
+                    (self.heuristic.evaluate(&child), board_move)
+                })
+                .collect();
+            // ties of the lowest values are broken at random as well
+            evaluated.shuffle(&mut self.rng);
+            let (heuristic, board_move) = if self.rng.gen_bool(ROLLOUT_RANDOMNESS) {
+                evaluated[0]
+            } else {
+                evaluated
+                    .into_iter()
+                    .min_by_key(|&(heuristic, _)| heuristic)
+                    .expect("Every position has a move")
+            };
+            lowest = lowest.min(heuristic);
+            board.exec_move(board_move);
+            path.push(board_move);
+            previous = Some(board_move);
+        }
+        let root = self.nodes[0].heuristic as f64;
+        Err((1.0 - lowest as f64 / (2.0 * root)).max(0.0))
+    }
+
+    fn backpropagate(&mut self, mut index: usize, reward: f64) {
+        loop {
+            let node = &mut self.nodes[index];
+            node.visits += 1;
+            node.total_reward += reward;
+            match node.parent {
+                Some(parent) => index = parent,
+                None => break,
+            }
+        }
+    }
+
+    /// Returns the moves from the root to the node
+    fn path_to(&self, mut index: usize) -> Vec<BoardMove> {
+        let mut path = vec![];
+        while let Some(parent) = self.nodes[index].parent {
+            path.push(self.nodes[index].board_move.expect("Child has a move"));
+            index = parent;
+        }
+        path.reverse();
+        path
+    }
+
+    /// Returns the most visited child of the root, preferring the positions which were not the root before
+    fn best_child(&self, visited: &HashSet<OwnedBoard>) -> usize {
+        let children = &self.nodes[0].children;
+        let visits = |child: &&usize| self.nodes[**child].visits;
+        children
+            .iter()
+            .filter(|&&child| !visited.contains(&self.nodes[child].board))
+            .max_by_key(visits)
+            .or_else(|| children.iter().max_by_key(visits))
+            .copied()
+            .expect("Root was expanded")
+    }
+
+    /// Makes the node the root of the tree, discarding every node outside of its subtree
+    fn reroot(&mut self, root: usize) {
+        let mut nodes: Vec<_> = std::mem::take(&mut self.nodes)
+            .into_iter()
+            .map(Some)
+            .collect();
+        let mut queue = VecDeque::from([(root, None)]);
+        while let Some((index, parent)) = queue.pop_front() {
+            let mut node = nodes[index].take().expect("Every node has a single parent");
+            let new_index = self.nodes.len();
+            node.parent = parent;
+            if let Some(parent) = parent {
+                self.nodes[parent].children.push(new_index);
+            }
+            let children = std::mem::take(&mut node.children);
+            queue.extend(children.into_iter().map(|child| (child, Some(new_index))));
+            self.nodes.push(node);
+        }
+    }
+}
+
+impl Solver for MonteCarloSolver {
+    fn solve(self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
+        self.solve_with(&SolverConfig::default())
+    }
+
+    fn solve_with(self: Box<Self>, config: &SolverConfig) -> Result<Vec<BoardMove>, SolvingError> {
+        self.solve_with_stats(config).map(|report| report.solution)
+    }
+
+    /// Every iteration counts as a single expansion, the positions of the rollouts are not counted
+    fn solve_with_stats(
+        self: Box<Self>,
+        config: &SolverConfig,
+    ) -> Result<SolveReport, SolvingError> {
+        if let Some(diagnosis) = UnsolvableDiagnosis::diagnose(&self.board) {
+            return Err(SolvingError::UnsolvableBoard(Some(diagnosis)));
+        }
+        let mut search = Search {
+            heuristic: &*self.heuristic,
+            exploration: self.exploration,
+            rollout_depth: self.rollout_depth,
+            rng: StdRng::seed_from_u64(self.seed),
+            nodes: vec![],
+            progress: SearchProgress::new(config),
+            node_bytes: util::board_bytes(&self.board) + std::mem::size_of::<Node>(),
+        };
+        search.add_node(self.board.clone(), None, None);
+
+        // moves made from the board to the root
+        let mut made = vec![];
+        let mut visited = HashSet::from([self.board.clone()]);
+        let path = 'search: loop {
+            for _ in 0..self.iterations_per_move {
+                if !search.progress.expand() {
+                    return Err(search.progress.error(made));
+                }
+                let solution = search.iterate();
+                search
+                    .progress
+                    .observe(search.nodes.len(), search.nodes.len(), search.node_bytes);
+                if let Some(solution) = solution {
+                    made.extend(solution);
+                    break 'search made;
+                }
+            }
+            let child = search.best_child(&visited);
+            made.push(search.nodes[child].board_move.expect("Child has a move"));
+            visited.insert(search.nodes[child].board.clone());
+            search.reroot(child);
+            log::trace!(
+                "Made move {}, heuristic value {}",
+                made.len(),
+                search.nodes[0].heuristic
+            );
+        };
+        Ok(search.progress.report(solution::optimize(&path)))
+    }
+}
+
+/// Returns the moves which can be made on the board, except for the one undoing the previous move,
+/// unless it is the only one, e.g. at the end of a single row
+fn legal_moves(board: &OwnedBoard, previous: Option<BoardMove>) -> Vec<BoardMove> {
+    let moves: Vec<_> = BoardMove::ALL
+        .into_iter()
+        .filter(|&board_move| board.can_move(board_move))
+        .collect();
+    if moves.len() == 1 {
+        return moves;
+    }
+    moves
+        .into_iter()
+        .filter(|&board_move| previous.as_ref().map(BoardMove::opposite) != Some(board_move))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn move_back_is_legal_at_end_of_row() {
+        let board: OwnedBoard = "1 4\n1 2 3 0".parse().unwrap();
+        let moves = legal_moves(&board, None);
+
+        // the only move undoes the one which brought the empty cell to the end
+        assert_eq!(1, moves.len());
+        assert_eq!(moves, legal_moves(&board, Some(moves[0].opposite())));
+    }
+}
@@ -0,0 +1,909 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io::{self, Read, Write};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::board::fingerprint::Fingerprint;
+use crate::board::{goal_cells, Board, OwnedBoard};
+use crate::solving::algorithm::heuristic::heuristics::{
+    manhattan_distance, tile_displacements, Heuristic,
+};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PartitionError {
+    /// The group or tile range could not be parsed
+    InvalidSyntax(String),
+    EmptyGroup,
+    /// The tile belongs to more than one group
+    DuplicateTile(u8),
+    /// The tile does not exist on the board
+    TileOutOfRange(u8),
+    /// The tile does not belong to any group
+    MissingTile(u8),
+    /// Generating the table of the group would take more states than allowed, see [`Partition::validate_size`]
+    GroupTooLarge(Vec<u8>),
+}
+
+impl Display for PartitionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PartitionError::InvalidSyntax(part) => write!(f, "Invalid tile group '{part}'"),
+            PartitionError::EmptyGroup => write!(f, "Tile groups cannot be empty"),
+            PartitionError::DuplicateTile(tile) => {
+                write!(f, "Tile {tile} belongs to more than one group")
+            }
+            PartitionError::TileOutOfRange(tile) => write!(f, "Tile {tile} is not on the board"),
+            PartitionError::MissingTile(tile) => {
+                write!(f, "Tile {tile} does not belong to any group")
+            }
+            PartitionError::GroupTooLarge(group) => write!(
+                f,
+                "Group of tiles {group:?} is too large, its table would have too many states to generate"
+            ),
+        }
+    }
+}
+
+impl Error for PartitionError {}
+
+/// Largest number of states of a single group, one byte each, which can be generated, as the states are ranked with `u32`.
+/// It allows the groups of 8 tiles of the 15-puzzle, whose 7-8 partition needs about 4 GB to generate
+pub const MAX_GROUP_STATES: usize = u32::MAX as usize;
+/// Number of states of the 6-tile groups of the 15-puzzle, the largest groups of [`Partition::disjoint`]
+const MAX_DISJOINT_STATES: usize = 57_657_600;
+
+/// Division of the tiles into disjoint groups, each of which gets its own pattern database.
+///
+/// The textual form lists groups separated by `|`, where each group is a comma separated list of tiles or tile ranges,
+/// e.g. `1-6|7-12|13-15` or `1,2,5,6|3,4,7,8`. The standard partitions of the 15-puzzle are also accepted by name,
+/// `5-5-5` and `6-6-3`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Partition {
+    groups: Vec<Vec<u8>>,
+}
+
+impl Partition {
+    /// # Errors
+    /// Returns error if any of the groups is empty, or the groups are not disjoint
+    pub fn new(groups: Vec<Vec<u8>>) -> Result<Self, PartitionError> {
+        let mut seen = bit_set::BitSet::new();
+        for group in &groups {
+            if group.is_empty() {
+                return Err(PartitionError::EmptyGroup);
+            }
+            for &tile in group {
+                if tile == 0 {
+                    return Err(PartitionError::TileOutOfRange(tile));
+                }
+                if !seen.insert(tile as usize) {
+                    return Err(PartitionError::DuplicateTile(tile));
+                }
+            }
+        }
+        Ok(Self { groups })
+    }
+
+    /// Returns the standard disjoint partition for additive pattern databases of the board:
+    /// the 6-6-3 partition of Korf and Felner for the 15-puzzle,
+    /// and the tiles in row-major order split into groups of at most six for other dimensions.
+    /// Larger boards get smaller groups, so that no table takes longer to generate than those of the 15-puzzle
+    #[must_use]
+    pub fn disjoint(rows: u8, columns: u8) -> Self {
+        const GROUP_SIZE: usize = 6;
+
+        if (rows, columns) == (4, 4) {
+            return Self::six_six_three();
+        }
+        let cells = rows as usize * columns as usize;
+        let group_size = (1..=GROUP_SIZE.min(cells - 1))
+            .rev()
+            .find(|&size| {
+                checked_permutation_count(cells, size + 1)
+                    .is_some_and(|states| states <= MAX_DISJOINT_STATES)
+            })
+            .unwrap_or(1);
+        let tiles: Vec<u8> = (1..=(cells - 1) as u8).collect();
+        let groups = tiles.chunks(group_size).map(<[u8]>::to_vec).collect();
+        Self { groups }
+    }
+
+    /// Returns the 5-5-5 partition of the 15-puzzle, whose three tables of 524,160 entries each
+    /// are generated in seconds, while giving weaker estimates than the 6-6-3 partition
+    #[must_use]
+    pub fn five_five_five() -> Self {
+        Self {
+            groups: vec![
+                vec![1, 2, 5, 6, 9],
+                vec![3, 4, 7, 8, 12],
+                vec![10, 11, 13, 14, 15],
+            ],
+        }
+    }
+
+    /// Returns the 6-6-3 partition of Korf and Felner for the 15-puzzle,
+    /// with two tables of 5,765,760 entries and one of 3,360 entries
+    #[must_use]
+    pub fn six_six_three() -> Self {
+        Self {
+            groups: vec![
+                vec![1, 5, 6, 9, 10, 13],
+                vec![7, 8, 11, 12, 14, 15],
+                vec![2, 3, 4],
+            ],
+        }
+    }
+
+    #[must_use]
+    pub fn groups(&self) -> &[Vec<u8>] {
+        &self.groups
+    }
+
+    /// Returns the tiles of the board which do not belong to any group
+    fn remaining_tiles(&self, rows: u8, columns: u8) -> Result<Box<[u8]>, PartitionError> {
+        Ok(self
+            .covered_tiles(rows, columns)?
+            .into_iter()
+            .zip(1..)
+            .filter_map(|(is_covered, tile)| (!is_covered).then_some(tile))
+            .collect())
+    }
+
+    /// Returns for every tile of the board whether it belongs to any group
+    fn covered_tiles(&self, rows: u8, columns: u8) -> Result<Vec<bool>, PartitionError> {
+        let tile_count = rows as usize * columns as usize - 1;
+        let mut covered = vec![false; tile_count];
+        for &tile in self.groups.iter().flatten() {
+            match covered.get_mut(tile as usize - 1) {
+                Some(is_covered) => *is_covered = true,
+                None => return Err(PartitionError::TileOutOfRange(tile)),
+            }
+        }
+        Ok(covered)
+    }
+
+    /// Checks that the table of every group has at most `max_states` states, including the position of the empty cell,
+    /// so that generating the database takes at most `max_states` bytes per group.
+    /// Generation allows [`MAX_GROUP_STATES`], callers with less memory can check a lower limit first
+    ///
+    /// # Errors
+    /// Returns error with the first group with more states
+    pub fn validate_size(
+        &self,
+        rows: u8,
+        columns: u8,
+        max_states: usize,
+    ) -> Result<(), PartitionError> {
+        let cells = rows as usize * columns as usize;
+        let max_states = max_states.min(MAX_GROUP_STATES);
+        self.groups
+            .iter()
+            .find(|group| {
+                checked_permutation_count(cells, group.len() + 1)
+                    .is_none_or(|states| states > max_states)
+            })
+            .map_or(Ok(()), |group| {
+                Err(PartitionError::GroupTooLarge(group.clone()))
+            })
+    }
+
+    /// Checks that the groups contain every tile of the board of given dimensions exactly once
+    ///
+    /// # Errors
+    /// Returns error with the first tile which is missing or not on the board
+    pub fn validate(&self, rows: u8, columns: u8) -> Result<(), PartitionError> {
+        let covered = self.covered_tiles(rows, columns)?;
+        covered
+            .iter()
+            .position(|&is_covered| !is_covered)
+            .map_or(Ok(()), |index| {
+                Err(PartitionError::MissingTile(index as u8 + 1))
+            })
+    }
+}
+
+impl FromStr for Partition {
+    type Err = PartitionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "5-5-5" => return Ok(Self::five_five_five()),
+            "6-6-3" => return Ok(Self::six_six_three()),
+            _ => {}
+        }
+        let parse_tile = |tile: &str, part: &str| {
+            tile.trim()
+                .parse::<u8>()
+                .map_err(|_| PartitionError::InvalidSyntax(part.to_string()))
+        };
+
+        let mut groups = vec![];
+        for group in s.split('|') {
+            let mut tiles = vec![];
+            for part in group.split(',').filter(|part| !part.trim().is_empty()) {
+                if let Some((first, last)) = part.split_once('-') {
+                    let (first, last) = (parse_tile(first, part)?, parse_tile(last, part)?);
+                    if first > last {
+                        return Err(PartitionError::InvalidSyntax(part.to_string()));
+                    }
+                    tiles.extend(first..=last);
+                } else {
+                    tiles.push(parse_tile(part, part)?);
+                }
+            }
+            groups.push(tiles);
+        }
+        Self::new(groups)
+    }
+}
+
+impl Display for Partition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let groups: Vec<String> = self
+            .groups
+            .iter()
+            .map(|group| {
+                let tiles: Vec<_> = group.iter().map(ToString::to_string).collect();
+                tiles.join(",")
+            })
+            .collect();
+        write!(f, "{}", groups.join("|"))
+    }
+}
+
+const MAGIC: &[u8] = b"15PDB";
+const FORMAT_VERSION: u8 = 2;
+
+/// Lexicographic rank of a sequence of distinct cell indices, among all such sequences of the same length
+fn rank(positions: &[u8], cells: usize) -> usize {
+    let mut rank = 0;
+    for (i, &position) in positions.iter().enumerate() {
+        let smaller_used = positions[..i].iter().filter(|&&p| p < position).count();
+        rank = rank * (cells - i) + (position as usize - smaller_used);
+    }
+    rank
+}
+
+/// Inverse of [`rank`]. The `used` buffer is only scratch space, so it can be reused between calls
+fn unrank(mut rank: usize, cells: usize, positions: &mut [u8], used: &mut Vec<bool>) {
+    // digits are smaller than the number of cells, so they can be stored in place of the positions
+    for i in (0..positions.len()).rev() {
+        positions[i] = (rank % (cells - i)) as u8;
+        rank /= cells - i;
+    }
+    used.clear();
+    used.resize(cells, false);
+    for position in positions.iter_mut() {
+        let cell = (0..cells)
+            .filter(|&cell| !used[cell])
+            .nth(*position as usize)
+            .expect("Digit is smaller than the number of unused cells");
+        used[cell] = true;
+        *position = cell as u8;
+    }
+}
+
+fn permutation_count(cells: usize, length: usize) -> usize {
+    (cells - length + 1..=cells).product()
+}
+
+/// Returns the [`permutation_count`], or `None` if it does not fit in `usize`
+fn checked_permutation_count(cells: usize, length: usize) -> Option<usize> {
+    (cells.checked_sub(length)? + 1..=cells).try_fold(1, usize::checked_mul)
+}
+
+/// Smallest part of the frontier worth expanding on a separate thread
+const MIN_CHUNK: usize = 4096;
+/// Size of the parts in which the tables are written, so that progress can be logged for large databases
+const WRITE_CHUNK: usize = 1 << 20;
+
+/// Dimensions of the abstract board of a single group, shared by the generating threads
+#[derive(Clone, Copy)]
+struct Geometry {
+    rows: u8,
+    columns: u8,
+    cells: usize,
+    group_size: usize,
+}
+
+impl Geometry {
+    /// Expands the states of the frontier, which are all at the given distance.
+    /// Returns the states reached by free moves, which are at the same distance,
+    /// and the states reached by moving a group tile, which may be at the next distance
+    fn expand(
+        self,
+        frontier: &[u32],
+        distance: u8,
+        distances: &[AtomicU8],
+    ) -> (Vec<u32>, Vec<u32>) {
+        let mut state = vec![0; self.group_size + 1];
+        let mut next = state.clone();
+        let mut used = Vec::with_capacity(self.cells);
+        let (mut same_level, mut next_level) = (Vec::new(), Vec::new());
+
+        for &current in frontier {
+            unrank(current as usize, self.cells, &mut state, &mut used);
+            let empty = state[self.group_size];
+            let (row, column) = (empty / self.columns, empty % self.columns);
+
+            let neighbours = [
+                (row > 0).then(|| empty - self.columns),
+                (row + 1 < self.rows).then(|| empty + self.columns),
+                (column > 0).then(|| empty - 1),
+                (column + 1 < self.columns).then(|| empty + 1),
+            ];
+            for neighbour in neighbours.into_iter().flatten() {
+                next.copy_from_slice(&state);
+                next[self.group_size] = neighbour;
+                let moved_tile = state[..self.group_size]
+                    .iter()
+                    .position(|&p| p == neighbour);
+                if let Some(index) = moved_tile {
+                    next[index] = empty;
+                }
+
+                let next_distance = distance + u8::from(moved_tile.is_some());
+                let next_rank = rank(&next, self.cells);
+                // only the thread which lowers the distance queues the state
+                if distances[next_rank].fetch_min(next_distance, Ordering::Relaxed) > next_distance
+                {
+                    if moved_tile.is_some() {
+                        next_level.push(next_rank as u32);
+                    } else {
+                        same_level.push(next_rank as u32);
+                    }
+                }
+            }
+        }
+        (same_level, next_level)
+    }
+
+    /// Expands the frontier on up to `threads` threads
+    fn expand_parallel(
+        self,
+        frontier: &[u32],
+        distance: u8,
+        distances: &[AtomicU8],
+        threads: usize,
+    ) -> (Vec<u32>, Vec<u32>) {
+        let chunk_size = frontier.len().div_ceil(threads).max(MIN_CHUNK);
+        if frontier.len() <= chunk_size {
+            return self.expand(frontier, distance, distances);
+        }
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = frontier
+                .chunks(chunk_size)
+                .map(|chunk| scope.spawn(move || self.expand(chunk, distance, distances)))
+                .collect();
+            let (mut same_level, mut next_level) = (Vec::new(), Vec::new());
+            for handle in handles {
+                let (same, next) = handle.join().expect("Generating thread does not panic");
+                same_level.extend(same);
+                next_level.extend(next);
+            }
+            (same_level, next_level)
+        })
+    }
+}
+
+/// Pattern database of a single group of tiles
+struct GroupTable {
+    tiles: Box<[u8]>,
+    /// Minimal number of moves of the group tiles required to place them, indexed by rank of their positions
+    distances: Box<[u8]>,
+}
+
+impl GroupTable {
+    /// Runs breadth-first search backwards from the solved position on the abstract board,
+    /// where only the group tiles and the empty cell are distinguishable.
+    /// Moving any other tile is free, so the tables of disjoint groups can be added.
+    ///
+    /// The search proceeds one distance at a time, and the frontier of every distance is split between `threads` threads.
+    /// `goal_positions` contains the goal cell index of every tile
+    fn generate(
+        (rows, columns): (u8, u8),
+        goal_positions: &[u8],
+        tiles: &[u8],
+        threads: usize,
+    ) -> Self {
+        let cells = rows as usize * columns as usize;
+        let group_size = tiles.len();
+        let geometry = Geometry {
+            rows,
+            columns,
+            cells,
+            group_size,
+        };
+
+        // the empty cell position is stored after the tile positions
+        let mut state: Vec<u8> = tiles
+            .iter()
+            .map(|&tile| goal_positions[tile as usize])
+            .collect();
+        state.push(goal_positions[0]);
+
+        let distances: Vec<AtomicU8> = (0..permutation_count(cells, group_size + 1))
+            .map(|_| AtomicU8::new(u8::MAX))
+            .collect();
+        let start = rank(&state, cells);
+        distances[start].store(0, Ordering::Relaxed);
+
+        let mut frontier = vec![start as u32];
+        let mut distance = 0;
+        let mut reached = 0;
+        while !frontier.is_empty() {
+            let mut next_level = Vec::new();
+            // free moves do not change the distance, so they are explored before the next distance
+            while !frontier.is_empty() {
+                reached += frontier.len();
+                let (same, next) =
+                    geometry.expand_parallel(&frontier, distance, &distances, threads);
+                frontier = same;
+                next_level.extend(next);
+            }
+            log::debug!(
+                "Pattern database of tiles {tiles:?}: reached {reached} of {} states up to distance {distance}",
+                distances.len()
+            );
+
+            distance += 1;
+            // states queued for this distance may have been reached by free moves from the previous one
+            frontier = next_level;
+            frontier.retain(|&state| distances[state as usize].load(Ordering::Relaxed) == distance);
+        }
+
+        // the position of the empty cell is not needed for lookups, so keep only the best distance
+        let empty_positions = cells - group_size;
+        let pattern_distances: Vec<u8> = distances
+            .chunks(empty_positions)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .map(|distance| distance.load(Ordering::Relaxed))
+                    .min()
+                    .expect("Chunks are not empty")
+            })
+            .collect();
+
+        Self {
+            tiles: tiles.into(),
+            distances: pattern_distances.into_boxed_slice(),
+        }
+    }
+}
+
+/// Additive pattern database heuristic.
+///
+/// For each group of the partition it stores the exact number of moves of the group tiles
+/// needed to place them, and sums those values. It is admissible and dominates the manhattan distance,
+/// but can only evaluate boards of the dimensions it was generated for.
+/// Tiles outside of the partition contribute their manhattan distance
+pub struct PatternDatabase {
+    rows: u8,
+    columns: u8,
+    partition: Partition,
+    tables: Vec<GroupTable>,
+    remaining_tiles: Box<[u8]>,
+    /// Fingerprint of the goal configuration the database was generated for
+    goal: Fingerprint,
+}
+
+impl PatternDatabase {
+    /// Generates the database for every group of the partition, for the standard solved board.
+    /// Generation time and memory grow very quickly with the size of the largest group
+    ///
+    /// # Errors
+    /// Returns error if the partition does not cover exactly the tiles of the board
+    pub fn generate(rows: u8, columns: u8, partition: Partition) -> Result<Self, PartitionError> {
+        Self::generate_for_goal(&OwnedBoard::new_solved(rows, columns), partition)
+    }
+
+    /// Generates the database for the goal configuration of the given board.
+    /// It then gives correct estimates only for boards with the same goal
+    ///
+    /// # Errors
+    /// Returns error if the partition does not cover exactly the tiles of the board
+    pub fn generate_for_goal(
+        board: &dyn Board,
+        partition: Partition,
+    ) -> Result<Self, PartitionError> {
+        let (rows, columns) = board.dimensions();
+        partition.validate(rows, columns)?;
+        Self::generate_partial(board, partition)
+    }
+
+    /// Generates the database for the goal configuration of the given board,
+    /// allowing the partition to leave some of the tiles out
+    ///
+    /// # Errors
+    /// Returns error if the partition contains tiles which are not on the board
+    pub fn generate_partial(
+        board: &dyn Board,
+        partition: Partition,
+    ) -> Result<Self, PartitionError> {
+        let (rows, columns) = board.dimensions();
+        let remaining_tiles = partition.remaining_tiles(rows, columns)?;
+        partition.validate_size(rows, columns, MAX_GROUP_STATES)?;
+
+        let mut goal_positions = vec![0; rows as usize * columns as usize];
+        for (tile, position) in goal_positions.iter_mut().enumerate() {
+            let (row, column) = board.goal_position(tile as u8);
+            *position = row * columns + column;
+        }
+
+        // groups are generated at the same time, sharing the available threads
+        let threads = std::thread::available_parallelism().map_or(1, usize::from);
+        let threads_per_group = (threads / partition.groups().len().max(1)).max(1);
+        let tables = std::thread::scope(|scope| {
+            let handles: Vec<_> = partition
+                .groups()
+                .iter()
+                .map(|group| {
+                    let goal_positions = &goal_positions;
+                    scope.spawn(move || {
+                        log::debug!("Generating pattern database for tiles {group:?}");
+                        let table = GroupTable::generate(
+                            (rows, columns),
+                            goal_positions,
+                            group,
+                            threads_per_group,
+                        );
+                        log::info!("Generated pattern database for tiles {group:?}");
+                        table
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("Generating thread does not panic"))
+                .collect()
+        });
+
+        Ok(Self {
+            rows,
+            columns,
+            partition,
+            tables,
+            remaining_tiles,
+            goal: Fingerprint::of_cells(rows, columns, goal_cells(board)),
+        })
+    }
+
+    /// Generates the additive databases of the 5-5-5 partition for the standard 15-puzzle
+    #[must_use]
+    pub fn five_five_five() -> Self {
+        Self::generate(4, 4, Partition::five_five_five()).expect("Partition covers the 15-puzzle")
+    }
+
+    /// Generates the additive databases of the 6-6-3 partition for the standard 15-puzzle,
+    /// which takes much longer than the 5-5-5 partition, but gives stronger estimates
+    #[must_use]
+    pub fn six_six_three() -> Self {
+        Self::generate(4, 4, Partition::six_six_three()).expect("Partition covers the 15-puzzle")
+    }
+
+    /// Generates a database tailored to the board: groups of `group_size` tiles are formed
+    /// from the tiles furthest away from their goal positions, up to `group_count` groups.
+    /// The remaining tiles are usually close to their positions, so estimating them with the manhattan distance
+    /// loses little accuracy, while the database stays small enough to be built right before solving
+    #[must_use]
+    pub fn for_instance(board: &dyn Board, group_size: usize, group_count: usize) -> Self {
+        let mut displacements = tile_displacements(board);
+        // stable sort keeps the tiles with equal displacement in order, so the groups stay compact
+        displacements.sort_by_key(|&(_, displacement)| std::cmp::Reverse(displacement));
+
+        let selected: Vec<u8> = displacements
+            .into_iter()
+            .take(group_size.max(1) * group_count)
+            .map(|(tile, _)| tile)
+            .collect();
+        let groups = selected
+            .chunks(group_size.max(1))
+            .map(|group| {
+                let mut group = group.to_vec();
+                group.sort_unstable();
+                group
+            })
+            .collect();
+
+        let partition = Partition::new(groups).expect("Selected tiles are distinct");
+        log::info!("Generating pattern database for the most displaced tiles: {partition}");
+        Self::generate_partial(board, partition).expect("Selected tiles are on the board")
+    }
+
+    #[must_use]
+    pub fn dimensions(&self) -> (u8, u8) {
+        (self.rows, self.columns)
+    }
+
+    #[must_use]
+    pub fn partition(&self) -> &Partition {
+        &self.partition
+    }
+
+    #[must_use]
+    pub fn goal_fingerprint(&self) -> Fingerprint {
+        self.goal
+    }
+
+    /// Writes the database in a binary format:
+    /// the magic bytes, format version, dimensions, the goal [`Fingerprint`], and then every group as its size,
+    /// its tiles, the length of its table as a little-endian `u32`, and the table itself
+    ///
+    /// # Errors
+    /// Returns error if writing fails
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[FORMAT_VERSION, self.rows, self.columns])?;
+        writer.write_all(&self.goal.to_bytes())?;
+        writer.write_all(&[self.tables.len() as u8])?;
+        for table in &self.tables {
+            writer.write_all(&[table.tiles.len() as u8])?;
+            writer.write_all(&table.tiles)?;
+            writer.write_all(&(table.distances.len() as u32).to_le_bytes())?;
+            for (index, chunk) in table.distances.chunks(WRITE_CHUNK).enumerate() {
+                writer.write_all(chunk)?;
+                log::trace!(
+                    "Written {} of {} entries of tiles {:?}",
+                    index * WRITE_CHUNK + chunk.len(),
+                    table.distances.len(),
+                    table.tiles
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the database written by [`write`](PatternDatabase::write)
+    ///
+    /// # Errors
+    /// Returns error if reading fails, or the data is not a valid database
+    pub fn read(reader: &mut impl Read) -> io::Result<Self> {
+        let invalid =
+            |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+        let mut read_bytes = |count: usize| -> io::Result<Vec<u8>> {
+            let mut buffer = vec![0; count];
+            reader.read_exact(&mut buffer)?;
+            Ok(buffer)
+        };
+
+        if read_bytes(MAGIC.len())? != MAGIC {
+            return Err(invalid("Not a pattern database file"));
+        }
+        let header = read_bytes(3)?;
+        let [version, rows, columns] = header[..] else {
+            unreachable!("Exactly 3 bytes were read")
+        };
+        if version != FORMAT_VERSION {
+            return Err(invalid("Unsupported pattern database version"));
+        }
+        let goal_bytes = read_bytes(9)?;
+        let goal = Fingerprint::from_bytes(goal_bytes.try_into().expect("Read 9 bytes"));
+        let group_count = read_bytes(1)?[0];
+        if rows == 0 || columns == 0 {
+            return Err(invalid("Invalid dimensions"));
+        }
+        let cells = rows as usize * columns as usize;
+
+        let mut tables = vec![];
+        for _ in 0..group_count {
+            let group_size = read_bytes(1)?[0] as usize;
+            let tiles = read_bytes(group_size)?;
+            let length_bytes = read_bytes(4)?;
+            let length =
+                u32::from_le_bytes(length_bytes.try_into().expect("Read 4 bytes")) as usize;
+            if group_size >= cells || length != permutation_count(cells, group_size) {
+                return Err(invalid("Table size does not match the dimensions"));
+            }
+            let distances = read_bytes(length)?;
+            tables.push(GroupTable {
+                tiles: tiles.into_boxed_slice(),
+                distances: distances.into_boxed_slice(),
+            });
+        }
+
+        let partition = Partition::new(tables.iter().map(|table| table.tiles.to_vec()).collect())
+            .map_err(|e| invalid(&e.to_string()))?;
+        let remaining_tiles = partition
+            .remaining_tiles(rows, columns)
+            .map_err(|e| invalid(&e.to_string()))?;
+
+        Ok(Self {
+            rows,
+            columns,
+            partition,
+            tables,
+            remaining_tiles,
+            goal,
+        })
+    }
+}
+
+impl Heuristic for PatternDatabase {
+    fn evaluate(&self, board: &dyn Board) -> u64 {
+        assert_eq!(
+            (self.rows, self.columns),
+            board.dimensions(),
+            "Pattern database was generated for different dimensions"
+        );
+        let cells = self.rows as usize * self.columns as usize;
+
+        let mut position_of = vec![0; cells];
+        for ((row, column), value) in board.iter_cells() {
+            position_of[value as usize] = row * self.columns + column;
+        }
+
+        let mut positions = vec![];
+        let pattern_distance: u64 = self
+            .tables
+            .iter()
+            .map(|table| {
+                positions.clear();
+                positions.extend(table.tiles.iter().map(|&tile| position_of[tile as usize]));
+                u64::from(table.distances[rank(&positions, cells)])
+            })
+            .sum();
+        let remaining_distance: u64 = self
+            .remaining_tiles
+            .iter()
+            .map(|&tile| {
+                let position = position_of[tile as usize];
+                let position = (position / self.columns, position % self.columns);
+                manhattan_distance(position, board.goal_position(tile))
+            })
+            .sum();
+
+        pattern_distance + remaining_distance
+    }
+
+    fn dimensions(&self) -> Option<(u8, u8)> {
+        Some((self.rows, self.columns))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solving::algorithm::heuristic::heuristics::ManhattanDistance;
+
+    use super::*;
+
+    #[test]
+    fn parses_partition() {
+        let partition: Partition = "1-3|4, 6 |5,7-8".parse().unwrap();
+
+        assert_eq!(
+            &[vec![1, 2, 3], vec![4, 6], vec![5, 7, 8]],
+            partition.groups()
+        );
+        assert_eq!("1,2,3|4,6|5,7,8", partition.to_string());
+        assert_eq!(Ok(()), partition.validate(3, 3));
+    }
+
+    #[test]
+    fn disjoint_partition_covers_board() {
+        let partition = Partition::disjoint(4, 4);
+        let sizes: Vec<_> = partition.groups().iter().map(Vec::len).collect();
+        assert_eq!(vec![6, 6, 3], sizes);
+
+        for (rows, columns) in [(4, 4), (3, 3), (3, 4), (5, 5), (2, 2), (10, 10)] {
+            let partition = Partition::disjoint(rows, columns);
+            assert_eq!(Ok(()), partition.validate(rows, columns));
+            assert!(partition.groups().iter().all(|group| group.len() <= 6));
+        }
+        // the 6-tile groups of the 24-puzzle would have 2.4e9 states
+        let sizes: Vec<_> = Partition::disjoint(5, 5)
+            .groups()
+            .iter()
+            .map(Vec::len)
+            .collect();
+        assert_eq!(vec![4; 6], sizes);
+    }
+
+    #[test]
+    fn too_large_group_is_rejected() {
+        let partition: Partition = "1-7|8-14|15-21|22-24".parse().unwrap();
+
+        assert_eq!(
+            Err(PartitionError::GroupTooLarge(vec![1, 2, 3, 4, 5, 6, 7])),
+            PatternDatabase::generate(5, 5, partition).map(|_| ())
+        );
+
+        let partition: Partition = "1-6|7-12|13-18|19-24".parse().unwrap();
+        assert_eq!(Ok(()), partition.validate_size(5, 5, MAX_GROUP_STATES));
+        assert_eq!(
+            Err(PartitionError::GroupTooLarge(vec![1, 2, 3, 4, 5, 6])),
+            partition.validate_size(5, 5, 1 << 30)
+        );
+    }
+
+    #[test]
+    fn seven_eight_partition_can_be_generated() {
+        let partition: Partition = "1-7|8-15".parse().unwrap();
+
+        assert_eq!(Ok(()), partition.validate(4, 4));
+        assert_eq!(Ok(()), partition.validate_size(4, 4, MAX_GROUP_STATES));
+    }
+
+    #[test]
+    fn standard_partitions_cover_fifteen_puzzle() {
+        for (name, partition, sizes) in [
+            ("5-5-5", Partition::five_five_five(), vec![5, 5, 5]),
+            ("6-6-3", Partition::six_six_three(), vec![6, 6, 3]),
+        ] {
+            assert_eq!(Ok(()), partition.validate(4, 4));
+            let group_sizes: Vec<_> = partition.groups().iter().map(Vec::len).collect();
+            assert_eq!(sizes, group_sizes);
+            assert_eq!(Ok(partition), name.parse());
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_partitions() {
+        assert_eq!(
+            Err(PartitionError::DuplicateTile(3)),
+            "1-3|3-8".parse::<Partition>()
+        );
+        assert_eq!(Err(PartitionError::EmptyGroup), "1-8|".parse::<Partition>());
+        assert!(matches!(
+            "1-x".parse::<Partition>(),
+            Err(PartitionError::InvalidSyntax(_))
+        ));
+
+        let partition: Partition = "1-4|6-9".parse().unwrap();
+        assert_eq!(
+            Err(PartitionError::TileOutOfRange(9)),
+            partition.validate(3, 3)
+        );
+        let partition: Partition = "1-4|6-8".parse().unwrap();
+        assert_eq!(
+            Err(PartitionError::MissingTile(5)),
+            partition.validate(3, 3)
+        );
+    }
+
+    #[test]
+    fn rank_is_inverse_of_unrank() {
+        let mut positions = [0; 3];
+        let mut used = vec![];
+        for r in 0..permutation_count(5, 3) {
+            unrank(r, 5, &mut positions, &mut used);
+            assert_eq!(r, rank(&positions, 5));
+        }
+    }
+
+    #[test]
+    fn parallel_generation_produces_same_table() {
+        let goal_positions: Vec<u8> = (0..16).map(|tile| (tile + 15) % 16).collect();
+
+        let sequential = GroupTable::generate((4, 4), &goal_positions, &[1, 2, 3], 1);
+        let parallel = GroupTable::generate((4, 4), &goal_positions, &[1, 2, 3], 4);
+
+        assert_eq!(sequential.distances, parallel.distances);
+        assert_eq!(Some(&0), sequential.distances.iter().min());
+    }
+
+    #[test]
+    fn dominates_manhattan_distance() {
+        let database =
+            PatternDatabase::generate(3, 3, "1-4|5-8".parse().unwrap()).expect("Valid partition");
+
+        let solved = OwnedBoard::new_solved(3, 3);
+        assert_eq!(0, database.evaluate(&solved));
+
+        // linear conflict of tiles 1 and 2 requires two additional moves
+        let board: OwnedBoard = "3 3\n2 1 3\n4 5 6\n7 8 0".parse().unwrap();
+        let manhattan_distance = ManhattanDistance.evaluate(&board);
+        assert!(database.evaluate(&board) >= manhattan_distance + 2);
+    }
+
+    #[test]
+    fn instance_database_groups_most_displaced_tiles() {
+        let board: OwnedBoard = "3 3\n2 1 3\n4 5 6\n7 8 0".parse().unwrap();
+        let database = PatternDatabase::for_instance(&board, 2, 1);
+
+        assert_eq!(&[vec![1, 2]], database.partition().groups());
+        // the swapped tiles are in linear conflict, the rest is in place
+        assert!(database.evaluate(&board) > ManhattanDistance.evaluate(&board));
+        assert_eq!(0, database.evaluate(&OwnedBoard::new_solved(3, 3)));
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+
+use crate::board::{Board, BoardMove, OwnedBoard};
+use crate::solving::algorithm::heuristic::astar::IterativeAStarSolver;
+use crate::solving::algorithm::heuristic::heuristics::Heuristic;
+use crate::solving::algorithm::{SolveReport, Solver, SolverConfig, SolvingError};
+use crate::solving::diagnosis::UnsolvableDiagnosis;
+use crate::solving::goal::{Goal, SolvedState};
+
+/// Every position within the given number of moves of the goal configuration,
+/// found by a breadth-first search from the goal
+struct Perimeter {
+    depth: u64,
+    /// Distance of every position to the goal, and the move towards the goal, keyed by the cells
+    positions: HashMap<Vec<u8>, (u64, Option<BoardMove>)>,
+    /// Position of every tile in each of the positions on the boundary, indexed by the tile
+    boundary: Vec<Vec<(u8, u8)>>,
+    heuristic: Box<dyn Heuristic>,
+}
+
+impl Perimeter {
+    fn new(goal: &OwnedBoard, depth: u64, heuristic: Box<dyn Heuristic>) -> Self {
+        let mut positions = HashMap::from([(cells(goal), (0, None))]);
+        let mut boundary = vec![];
+        let mut queue = VecDeque::from([(goal.clone(), 0)]);
+        while let Some((board, distance)) = queue.pop_front() {
+            if distance == depth {
+                boundary.push(tile_positions(&board));
+                continue;
+            }
+            for board_move in BoardMove::ALL {
+                if !board.can_move(board_move) {
+                    continue;
+                }
+                let mut next = board.clone();
+                next.exec_move(board_move);
+                let key = cells(&next);
+                if positions.contains_key(&key) {
+                    continue;
+                }
+                positions.insert(key, (distance + 1, Some(board_move.opposite())));
+                queue.push_back((next, distance + 1));
+            }
+        }
+        Self {
+            depth,
+            positions,
+            boundary,
+            heuristic,
+        }
+    }
+
+    /// Returns the lowest Manhattan distance between the board and any position on the boundary
+    fn boundary_distance(&self, board: &dyn Board) -> u64 {
+        let positions = tile_positions(board);
+        let mut lowest = u64::MAX;
+        for boundary_positions in &self.boundary {
+            let mut distance = 0;
+            // the empty cell is skipped, as for the Manhattan distance to the goal
+            for (&(row, column), &(boundary_row, boundary_column)) in
+                positions.iter().zip(boundary_positions).skip(1)
+            {
+                distance +=
+                    u64::from(row.abs_diff(boundary_row) + column.abs_diff(boundary_column));
+                if distance >= lowest {
+                    break;
+                }
+            }
+            lowest = lowest.min(distance);
+        }
+        lowest
+    }
+
+    fn distance(&self, board: &dyn Board) -> Option<u64> {
+        self.positions
+            .get(&cells(board))
+            .map(|&(distance, _)| distance)
+    }
+
+    /// Returns the shortest path from the position inside the perimeter to the goal
+    fn path_to_goal(&self, board: &dyn Board) -> Option<Vec<BoardMove>> {
+        let mut board = OwnedBoard::from_board(board);
+        let mut path = vec![];
+        loop {
+            match *self.positions.get(&cells(&board))? {
+                (_, Some(board_move)) => {
+                    board.exec_move(board_move);
+                    path.push(board_move);
+                }
+                (_, None) => return Some(path),
+            }
+        }
+    }
+}
+
+/// Goal of the search from outside of the perimeter: the positions on its boundary.
+/// Every path from outside of the perimeter to the goal configuration crosses the boundary
+struct Boundary(Rc<Perimeter>);
+
+impl Goal for Boundary {
+    fn is_reached(&self, board: &dyn Board) -> bool {
+        self.0.distance(board) == Some(self.0.depth)
+    }
+
+    /// Outside of the perimeter, the distance to the nearest position on the boundary is estimated
+    /// by the Manhattan distance to it, and by the heuristic, which tells the distance to the goal,
+    /// at most `depth` moves further than the boundary
+    fn estimate(&self, board: &dyn Board) -> u64 {
+        let perimeter = &self.0;
+        if let Some(distance) = perimeter.distance(board) {
+            return perimeter.depth - distance;
+        }
+        let through_goal = perimeter
+            .heuristic
+            .evaluate(board)
+            .saturating_sub(perimeter.depth);
+        perimeter.boundary_distance(board).max(through_goal).max(1)
+    }
+
+    fn is_reachable_from(&self, board: &dyn Board) -> bool {
+        SolvedState.is_reachable_from(board)
+    }
+
+    fn diagnose_unreachable(&self, board: &dyn Board) -> Option<UnsolvableDiagnosis> {
+        SolvedState.diagnose_unreachable(board)
+    }
+}
+
+/// Perimeter search: IDA* from the board towards the perimeter of positions
+/// within `depth` moves of the goal, which are stored when the solver is created.
+///
+/// The shortest solution is the shortest path to the boundary of the perimeter followed by its `depth` moves
+/// to the goal, so the search ends `depth` moves earlier than plain IDA*, where its tree is widest.
+/// Positions outside of the perimeter are estimated by their Manhattan distance to the nearest position
+/// on the boundary, which is compared with every one of them, so deeper perimeters expand fewer nodes,
+/// but evaluate every node slower.
+/// The solution is the shortest one if the heuristic is admissible
+pub struct PerimeterSearchSolver {
+    board: OwnedBoard,
+    perimeter: Rc<Perimeter>,
+    search: IterativeAStarSolver,
+}
+
+impl PerimeterSearchSolver {
+    /// Creates solver storing every position within `depth` moves of the goal of the board.
+    /// The number of stored positions grows exponentially with the depth, e.g. about 2.1 times per move on 4x4 boards
+    #[must_use]
+    pub fn new(board: OwnedBoard, heuristic: Box<dyn Heuristic>, depth: u64) -> Self {
+        let perimeter = Rc::new(Perimeter::new(&board.goal(), depth, heuristic));
+        log::debug!(
+            "Stored {} positions of the perimeter",
+            perimeter.positions.len()
+        );
+        let search = IterativeAStarSolver::with_goal(
+            board.clone(),
+            Box::new(Boundary(Rc::clone(&perimeter))),
+        );
+        Self {
+            board,
+            perimeter,
+            search,
+        }
+    }
+}
+
+impl Solver for PerimeterSearchSolver {
+    fn solve(self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
+        self.solve_with(&SolverConfig::default())
+    }
+
+    fn solve_with(self: Box<Self>, config: &SolverConfig) -> Result<Vec<BoardMove>, SolvingError> {
+        self.solve_with_stats(config).map(|report| report.solution)
+    }
+
+    /// Counters are those of the search to the boundary, without building the perimeter
+    fn solve_with_stats(
+        self: Box<Self>,
+        config: &SolverConfig,
+    ) -> Result<SolveReport, SolvingError> {
+        if let Some(path) = self.perimeter.path_to_goal(&self.board) {
+            return Ok(SolveReport {
+                solution: path,
+                nodes_expanded: Some(0),
+                nodes_generated: Some(0),
+                max_frontier: Some(0),
+                peak_memory_estimate: Some(0),
+                ..SolveReport::default()
+            });
+        }
+        let mut report = Box::new(self.search).solve_with_stats(config)?;
+        let mut board = self.board;
+        board
+            .apply_moves(&report.solution)
+            .expect("Solution of the search can be applied to the board");
+        let rest = self
+            .perimeter
+            .path_to_goal(&board)
+            .expect("Search ends on the boundary of the perimeter");
+        report.solution.extend(rest);
+        Ok(report)
+    }
+}
+
+fn cells(board: &(impl Board + ?Sized)) -> Vec<u8> {
+    board.iter_cells().map(|(_, value)| value).collect()
+}
+
+/// Returns the position of every tile, indexed by the tile
+fn tile_positions(board: &(impl Board + ?Sized)) -> Vec<(u8, u8)> {
+    let mut positions = vec![(0, 0); board.iter_cells().len()];
+    for (position, value) in board.iter_cells() {
+        positions[value as usize] = position;
+    }
+    positions
+}
@@ -0,0 +1,364 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use crate::board::fingerprint::Fingerprint;
+use crate::board::{goal_cells, Board};
+use crate::solving::algorithm::heuristic::pattern_database::{Partition, PatternDatabase};
+
+const EXTENSION: &str = "bin";
+
+/// Pattern database file found in the registry directory.
+///
+/// Files are named `<rows>x<columns>-<label>.bin`, and the file stem (e.g. `4x4-663-d39815b8`) is used as the heuristic id
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RegisteredDatabase {
+    pub id: String,
+    pub rows: u8,
+    pub columns: u8,
+    pub path: PathBuf,
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    /// The file cannot be read or is not a valid pattern database
+    Io(io::Error),
+    /// The database was generated for boards of other dimensions
+    DimensionsMismatch { expected: (u8, u8), found: (u8, u8) },
+    /// The database was generated for another goal configuration
+    GoalMismatch {
+        expected: Fingerprint,
+        found: Fingerprint,
+    },
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "{e}"),
+            LoadError::DimensionsMismatch { expected, found } => write!(
+                f,
+                "Database is for {}x{} boards, but the board is {}x{}",
+                found.0, found.1, expected.0, expected.1
+            ),
+            LoadError::GoalMismatch { expected, found } => write!(
+                f,
+                "Database is for goal {found}, but the board has goal {expected}"
+            ),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl RegisteredDatabase {
+    /// # Errors
+    /// Returns error if the file cannot be read or is not a valid pattern database
+    pub fn load(&self) -> io::Result<PatternDatabase> {
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        PatternDatabase::read(&mut reader)
+    }
+
+    /// Loads the database, checking that the dimensions and the goal stored in its header match the board.
+    /// The dimensions in the file name are checked first, so databases for other sizes are not read at all
+    ///
+    /// # Errors
+    /// Returns error if the file cannot be read, or the database gives wrong estimates for the board
+    pub fn load_for(&self, board: &dyn Board) -> Result<PatternDatabase, LoadError> {
+        let expected = board.dimensions();
+        let dimensions_mismatch = |found| LoadError::DimensionsMismatch { expected, found };
+        if (self.rows, self.columns) != expected {
+            return Err(dimensions_mismatch((self.rows, self.columns)));
+        }
+
+        check_database(self.load().map_err(LoadError::Io)?, board)
+    }
+}
+
+/// Checks that the dimensions and the goal of the database match the board
+fn check_database(
+    database: PatternDatabase,
+    board: &dyn Board,
+) -> Result<PatternDatabase, LoadError> {
+    let expected = board.dimensions();
+    if database.dimensions() != expected {
+        return Err(LoadError::DimensionsMismatch {
+            expected,
+            found: database.dimensions(),
+        });
+    }
+    let goal = Fingerprint::of_cells(expected.0, expected.1, goal_cells(board));
+    if database.goal_fingerprint() != goal {
+        return Err(LoadError::GoalMismatch {
+            expected: goal,
+            found: database.goal_fingerprint(),
+        });
+    }
+    Ok(database)
+}
+
+/// Loads the database from a file at any location, checking that it matches the board
+///
+/// # Errors
+/// Returns error if the file cannot be read, or the database gives wrong estimates for the board
+pub fn load_file(path: &Path, board: &dyn Board) -> Result<PatternDatabase, LoadError> {
+    let mut reader = BufReader::new(File::open(path).map_err(LoadError::Io)?);
+    let database = PatternDatabase::read(&mut reader).map_err(LoadError::Io)?;
+    check_database(database, board)
+}
+
+/// Writes the database to the file, creating its directory if needed.
+///
+/// The database is written to a temporary file next to it first, and renamed once complete,
+/// so that other processes never load a partially written database
+///
+/// # Errors
+/// Returns error if the directory cannot be created or the file cannot be written
+pub fn save_file(path: &Path, database: &PatternDatabase) -> io::Result<()> {
+    if let Some(directory) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(directory)?;
+    }
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(format!(".{}.tmp", std::process::id()));
+    let temporary = PathBuf::from(temporary);
+    let written = File::create(&temporary).and_then(|file| {
+        let mut writer = BufWriter::new(file);
+        database.write(&mut writer)?;
+        io::Write::flush(&mut writer)
+    });
+    match written.and_then(|()| std::fs::rename(&temporary, path)) {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            let _ = std::fs::remove_file(&temporary);
+            Err(e)
+        }
+    }
+}
+
+/// Returns the standard registry location, `$XDG_CACHE_HOME/15-puzzle-solver/pdb`,
+/// falling back to `~/.cache` when the variable is not set
+#[must_use]
+pub fn default_directory() -> Option<PathBuf> {
+    let cache = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))?;
+    Some(cache.join("15-puzzle-solver").join("pdb"))
+}
+
+/// Returns the pattern databases stored in the standard registry location,
+/// which are available as additional heuristic ids
+#[must_use]
+pub fn default_databases() -> Vec<RegisteredDatabase> {
+    default_directory()
+        .map(|directory| discover(&directory))
+        .unwrap_or_default()
+}
+
+/// Returns the id under which a database with the given partition is registered,
+/// e.g. `4x4-663-d39815b8` for a 6-6-3 partition of the 15-puzzle.
+/// The group sizes are followed by the hash of the tiles of the groups,
+/// so that partitions with the same sizes but different tiles get different ids
+#[must_use]
+pub fn database_id(rows: u8, columns: u8, partition: &Partition) -> String {
+    const OFFSET_BASIS: u32 = 0x811c_9dc5;
+    const PRIME: u32 = 0x0100_0193;
+    let sizes: Vec<String> = partition
+        .groups()
+        .iter()
+        .map(|group| group.len().to_string())
+        .collect();
+    let separator = if sizes.iter().all(|size| size.len() == 1) {
+        ""
+    } else {
+        "-"
+    };
+    // FNV-1a of the groups, each preceded by its size, which is the same in every version of Rust
+    let hash = partition
+        .groups()
+        .iter()
+        .flat_map(|group| std::iter::once(group.len() as u8).chain(group.iter().copied()))
+        .fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ u32::from(byte)).wrapping_mul(PRIME)
+        });
+    format!("{rows}x{columns}-{}-{hash:08x}", sizes.join(separator))
+}
+
+/// Checks if the id has the form of the registered database ids, without looking into the registry
+#[must_use]
+pub fn is_database_id(id: &str) -> bool {
+    parse_id(id).is_some()
+}
+
+/// Parses the dimensions of the database with the id
+fn parse_id(id: &str) -> Option<(u8, u8)> {
+    let (dimensions, label) = id.split_once('-')?;
+    let (rows, columns) = dimensions.split_once('x')?;
+    if label.is_empty() {
+        return None;
+    }
+    Some((rows.parse().ok()?, columns.parse().ok()?))
+}
+
+/// Parses the file stem of a registered database into its id and dimensions
+fn parse_file_name(path: &Path) -> Option<RegisteredDatabase> {
+    if path.extension()? != EXTENSION {
+        return None;
+    }
+    let id = path.file_stem()?.to_str()?;
+    let (rows, columns) = parse_id(id)?;
+
+    Some(RegisteredDatabase {
+        id: id.to_string(),
+        rows,
+        columns,
+        path: path.to_path_buf(),
+    })
+}
+
+/// Lists the pattern databases stored in the directory, sorted by id.
+/// A missing or unreadable directory results in an empty registry
+#[must_use]
+pub fn discover(directory: &Path) -> Vec<RegisteredDatabase> {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return vec![];
+    };
+    let mut databases: Vec<_> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| parse_file_name(&entry.path()))
+        .collect();
+    databases.sort_by(|a, b| a.id.cmp(&b.id));
+    databases
+}
+
+/// Stores the database in the directory under its id, so that it is discovered on the next run
+///
+/// # Errors
+/// Returns error if the directory cannot be created or the file cannot be written
+pub fn register(directory: &Path, database: &PatternDatabase) -> io::Result<RegisteredDatabase> {
+    let (rows, columns) = database.dimensions();
+    let id = database_id(rows, columns, database.partition());
+    let path = directory.join(format!("{id}.{EXTENSION}"));
+    save_file(&path, database)?;
+
+    Ok(RegisteredDatabase {
+        id,
+        rows,
+        columns,
+        path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::OwnedBoard;
+    use crate::solving::algorithm::heuristic::heuristics::Heuristic;
+
+    use super::*;
+
+    #[test]
+    fn names_databases_by_group_sizes() {
+        let partition: Partition = "1-6|7-12|13-15".parse().unwrap();
+        let id = database_id(4, 4, &partition);
+        assert_eq!("4x4-663-d39815b8", id);
+        assert!(is_database_id(&id));
+
+        let partition: Partition = "1-10|11-15".parse().unwrap();
+        assert!(database_id(4, 4, &partition).starts_with("4x4-10-5-"));
+        assert!(!is_database_id("LC"));
+    }
+
+    #[test]
+    fn partitions_with_same_group_sizes_have_different_ids() {
+        let rows: Partition = "1-5|6-10|11-15".parse().unwrap();
+        let five_five_five: Partition = "5-5-5".parse().unwrap();
+
+        assert_ne!(database_id(4, 4, &rows), database_id(4, 4, &five_five_five));
+        // ids name the registered files, so they must not change between versions
+        assert_eq!("4x4-555-716ed040", database_id(4, 4, &rows));
+    }
+
+    #[test]
+    fn discovers_registered_databases() {
+        let directory = std::env::temp_dir().join(format!("pdb-registry-{}", std::process::id()));
+        let database = PatternDatabase::generate(2, 3, "1-3|4-5".parse().unwrap()).unwrap();
+
+        let registered = register(&directory, &database).unwrap();
+        std::fs::write(directory.join("notes.txt"), "not a database").unwrap();
+        let discovered = discover(&directory);
+        let loaded = discovered[0].load().unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(vec![registered], discovered);
+        assert!(discovered[0].id.starts_with("2x3-32-"));
+        let board: OwnedBoard = "2 3\n4 1 3\n0 2 5".parse().unwrap();
+        assert_eq!(database.evaluate(&board), loaded.evaluate(&board));
+        assert_eq!(database.goal_fingerprint(), loaded.goal_fingerprint());
+    }
+
+    #[test]
+    fn saved_file_is_loaded_for_board() {
+        let directory = std::env::temp_dir().join(format!("pdb-file-{}", std::process::id()));
+        let path = directory.join("nested").join("custom.pdb");
+        let database = PatternDatabase::generate(2, 3, "1-3|4-5".parse().unwrap()).unwrap();
+
+        save_file(&path, &database).unwrap();
+        let written: Vec<_> = std::fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        let board: OwnedBoard = "2 3\n4 1 3\n0 2 5".parse().unwrap();
+        let loaded = load_file(&path, &board);
+        let transposed: OwnedBoard = "3 2\n1 2\n3 4\n5 0".parse().unwrap();
+        let other_size = load_file(&path, &transposed);
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        // the temporary file is renamed to the database
+        assert_eq!(vec![std::ffi::OsString::from("custom.pdb")], written);
+        let loaded = loaded.unwrap();
+        assert_eq!(database.partition(), loaded.partition());
+        assert_eq!(database.evaluate(&board), loaded.evaluate(&board));
+        assert!(matches!(
+            other_size,
+            Err(LoadError::DimensionsMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn checked_loading_detects_other_boards() {
+        let directory =
+            std::env::temp_dir().join(format!("pdb-registry-checked-{}", std::process::id()));
+        let database = PatternDatabase::generate(2, 3, "1-3|4-5".parse().unwrap()).unwrap();
+        let registered = register(&directory, &database).unwrap();
+
+        let board: OwnedBoard = "2 3\n4 1 3\n0 2 5".parse().unwrap();
+        let loaded = registered.load_for(&board);
+        let transposed: OwnedBoard = "3 2\n1 2\n3 4\n5 0".parse().unwrap();
+        let other_size = registered.load_for(&transposed);
+        let goal: OwnedBoard = "2 3\n0 1 2\n3 4 5".parse().unwrap();
+        let other_goal = registered.load_for(&board.with_goal(goal).unwrap());
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert!(loaded.is_ok());
+        assert!(matches!(
+            other_size,
+            Err(LoadError::DimensionsMismatch {
+                expected: (3, 2),
+                found: (2, 3)
+            })
+        ));
+        assert!(matches!(other_goal, Err(LoadError::GoalMismatch { .. })));
+    }
+}
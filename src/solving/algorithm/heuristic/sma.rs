@@ -0,0 +1,355 @@
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use crate::board::{Board, BoardMove, OwnedBoard};
+use crate::solving::algorithm::heuristic::heuristics::Heuristic;
+use crate::solving::algorithm::util::SearchProgress;
+use crate::solving::algorithm::{util, SolveReport, Solver, SolverConfig, SolvingError};
+use crate::solving::diagnosis::UnsolvableDiagnosis;
+
+/// Cost of the nodes which cannot lead to a solution within the node limit
+const UNREACHABLE: u64 = u64::MAX;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MemoryBoundedError {
+    /// Every solution is longer than the search tree can hold with the node limit
+    NodeLimitExceeded { node_limit: usize },
+}
+
+impl Display for MemoryBoundedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MemoryBoundedError::NodeLimitExceeded { node_limit } => {
+                write!(
+                    f,
+                    "No solution can be found with at most {node_limit} nodes in memory"
+                )
+            }
+        }
+    }
+}
+
+impl Error for MemoryBoundedError {}
+
+/// Node of the search tree kept in memory
+struct TreeNode {
+    board: OwnedBoard,
+    parent: Option<usize>,
+    /// Move leading from the parent to the node
+    parent_move: Option<BoardMove>,
+    depth: usize,
+    path_cost: u64,
+    /// Lower bound of the cost of the solutions passing through the node,
+    /// backed up from the children once all of them were generated
+    f_cost: u64,
+    /// Indices of the children kept in memory
+    children: Vec<usize>,
+    /// Moves of the children which were never generated, the next one is last
+    ungenerated: Vec<BoardMove>,
+    /// Moves of the children which were removed from memory, with their costs at that time
+    forgotten: Vec<(BoardMove, u64)>,
+}
+
+impl TreeNode {
+    /// Key ordering the nodes from the lowest cost, and the deepest among the same cost
+    fn key(&self, index: usize) -> (u64, Reverse<usize>, usize) {
+        (self.f_cost, Reverse(self.depth), index)
+    }
+
+    fn has_pending_successors(&self) -> bool {
+        !self.ungenerated.is_empty() || !self.forgotten.is_empty()
+    }
+}
+
+/// Simplified memory-bounded A* (SMA*), which keeps at most the given number of nodes in memory.
+///
+/// The search tree is stored explicitly, with parent pointers and the children of every node.
+/// Successors are generated one at a time, and when the memory is full the shallowest leaf
+/// with the highest cost is removed, remembering its cost in the parent so that it can be regenerated later.
+/// Once all children of a node are generated, the lowest of their costs is backed up to the node and its ancestors.
+///
+/// The solution is the shortest one if the heuristic is admissible and the path to it fits in memory.
+/// Ties are broken by the depth and the order of creation of the nodes, so the search is deterministic
+pub struct MemoryBoundedAStarSolver {
+    heuristic: Box<dyn Heuristic>,
+    node_limit: usize,
+    /// Arena of the nodes, where removed nodes leave empty slots for reuse
+    nodes: Vec<Option<TreeNode>>,
+    free_slots: Vec<usize>,
+    /// Nodes with successors which are not in memory
+    open: BTreeSet<(u64, Reverse<usize>, usize)>,
+    /// Nodes without children in memory, which can be removed when the memory is full
+    leaves: BTreeSet<(u64, Reverse<usize>, usize)>,
+    node_count: usize,
+}
+
+impl MemoryBoundedAStarSolver {
+    /// Creates solver which keeps at most `node_limit` nodes in memory.
+    /// The limit has to exceed the length of the shortest solution for it to be found
+    #[must_use]
+    pub fn new(board: OwnedBoard, heuristic: Box<dyn Heuristic>, node_limit: usize) -> Self {
+        let mut solver = Self {
+            heuristic,
+            node_limit: node_limit.max(1),
+            nodes: vec![],
+            free_slots: vec![],
+            open: BTreeSet::new(),
+            leaves: BTreeSet::new(),
+            node_count: 0,
+        };
+        let f_cost = solver.bounded_cost(&board, 0, 0);
+        let ungenerated = successor_moves(&board, None);
+        solver.insert(TreeNode {
+            board,
+            parent: None,
+            parent_move: None,
+            depth: 0,
+            path_cost: 0,
+            f_cost,
+            children: vec![],
+            ungenerated,
+            forgotten: vec![],
+        });
+        solver
+    }
+
+    fn node(&self, index: usize) -> &TreeNode {
+        self.nodes[index]
+            .as_ref()
+            .expect("Index refers to a node in memory")
+    }
+
+    fn node_mut(&mut self, index: usize) -> &mut TreeNode {
+        self.nodes[index]
+            .as_mut()
+            .expect("Index refers to a node in memory")
+    }
+
+    /// Returns the cost of the node, or [`UNREACHABLE`] if it is not solved and its children would not fit in memory
+    fn bounded_cost(&self, board: &OwnedBoard, depth: usize, path_cost: u64) -> u64 {
+        if board.is_solved() {
+            path_cost
+        } else if depth + 1 >= self.node_limit {
+            UNREACHABLE
+        } else {
+            path_cost + self.heuristic.evaluate(board)
+        }
+    }
+
+    fn insert(&mut self, node: TreeNode) -> usize {
+        let index = match self.free_slots.pop() {
+            Some(index) => index,
+            None => {
+                self.nodes.push(None);
+                self.nodes.len() - 1
+            }
+        };
+        self.leaves.insert(node.key(index));
+        if node.has_pending_successors() {
+            self.open.insert(node.key(index));
+        }
+        self.nodes[index] = Some(node);
+        self.node_count += 1;
+        index
+    }
+
+    /// Changes the cost of the node, keeping the sets ordered by it consistent
+    fn set_cost(&mut self, index: usize, f_cost: u64) {
+        let node = self.node(index);
+        let old_key = node.key(index);
+        let in_open = self.open.remove(&old_key);
+        let in_leaves = self.leaves.remove(&old_key);
+
+        let node = self.node_mut(index);
+        node.f_cost = f_cost;
+        let new_key = node.key(index);
+        if in_open {
+            self.open.insert(new_key);
+        }
+        if in_leaves {
+            self.leaves.insert(new_key);
+        }
+    }
+
+    /// Recomputes the costs of the node and its ancestors from their children
+    fn back_up(&mut self, mut index: usize) {
+        loop {
+            let node = self.node(index);
+            // a child which was never generated may be as cheap as the node itself
+            if !node.ungenerated.is_empty() {
+                return;
+            }
+            let lowest = node
+                .children
+                .iter()
+                .map(|&child| self.node(child).f_cost)
+                .chain(node.forgotten.iter().map(|&(_, f_cost)| f_cost))
+                .min()
+                .unwrap_or(UNREACHABLE);
+            if lowest == node.f_cost {
+                return;
+            }
+            let parent = node.parent;
+            self.set_cost(index, lowest);
+            match parent {
+                Some(parent) => index = parent,
+                None => return,
+            }
+        }
+    }
+
+    /// Removes the shallowest leaf with the highest cost, other than `keep`, remembering its cost in the parent
+    fn forget_worst_leaf(&mut self, keep: usize) {
+        let &key = self
+            .leaves
+            .iter()
+            .rev()
+            .find(|&&(_, _, index)| index != keep && self.node(index).parent.is_some())
+            .expect("Tree holding more nodes than its deepest path has another leaf");
+        let (_, _, index) = key;
+        self.leaves.remove(&key);
+        self.open.remove(&key);
+        let node = self.nodes[index].take().expect("Leaf is in memory");
+        self.free_slots.push(index);
+        self.node_count -= 1;
+
+        let parent_index = node.parent.expect("Leaf is not the root");
+        let parent = self.node_mut(parent_index);
+        parent.children.retain(|&child| child != index);
+        parent.forgotten.push((
+            node.parent_move.expect("Child has a move from its parent"),
+            node.f_cost,
+        ));
+        let became_leaf = parent.children.is_empty();
+        let key = parent.key(parent_index);
+        self.open.insert(key);
+        if became_leaf {
+            self.leaves.insert(key);
+        }
+    }
+
+    /// Generates the next successor of the node, either a new one or the forgotten one with the lowest cost
+    fn expand(&mut self, index: usize) {
+        while self.node_count >= self.node_limit {
+            self.forget_worst_leaf(index);
+        }
+
+        let node = self.node_mut(index);
+        let (board_move, remembered_cost) = match node.ungenerated.pop() {
+            Some(board_move) => (board_move, 0),
+            None => {
+                let (position, _) = node
+                    .forgotten
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, &(_, f_cost))| f_cost)
+                    .expect("Expanded node has pending successors");
+                node.forgotten.remove(position)
+            }
+        };
+        let mut board = node.board.clone();
+        board.exec_move(board_move);
+        let (depth, path_cost) = (node.depth + 1, node.path_cost + 1);
+        let parent_cost = node.f_cost;
+        let f_cost = self
+            .bounded_cost(&board, depth, path_cost)
+            .max(parent_cost)
+            .max(remembered_cost);
+        let ungenerated = successor_moves(&board, Some(board_move));
+
+        let child = self.insert(TreeNode {
+            board,
+            parent: Some(index),
+            parent_move: Some(board_move),
+            depth,
+            path_cost,
+            f_cost,
+            children: vec![],
+            ungenerated,
+            forgotten: vec![],
+        });
+        let node = self.node(index);
+        let key = node.key(index);
+        let finished = !node.has_pending_successors();
+        self.leaves.remove(&key);
+        if finished {
+            self.open.remove(&key);
+        }
+        self.node_mut(index).children.push(child);
+        self.back_up(index);
+    }
+
+    fn path_to(&self, mut index: usize) -> Vec<BoardMove> {
+        let mut path = Vec::with_capacity(self.node(index).depth);
+        while let Some(parent_move) = self.node(index).parent_move {
+            path.push(parent_move);
+            index = self
+                .node(index)
+                .parent
+                .expect("Node with a move has a parent");
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Returns the moves which can be made on the board, except the one undoing the previous move.
+/// The moves are reversed, so that they are generated in the order of [`BoardMove::ALL`]
+fn successor_moves(board: &OwnedBoard, previous: Option<BoardMove>) -> Vec<BoardMove> {
+    BoardMove::ALL
+        .into_iter()
+        .rev()
+        .filter(|&m| board.can_move(m) && previous.is_none_or(|p| m != p.opposite()))
+        .collect()
+}
+
+impl Solver for MemoryBoundedAStarSolver {
+    fn solve(self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
+        self.solve_with(&SolverConfig::default())
+    }
+
+    fn solve_with(self: Box<Self>, config: &SolverConfig) -> Result<Vec<BoardMove>, SolvingError> {
+        self.solve_with_stats(config).map(|report| report.solution)
+    }
+
+    /// Every expansion generates a single successor, forgotten nodes are counted again when regenerated
+    fn solve_with_stats(
+        mut self: Box<Self>,
+        config: &SolverConfig,
+    ) -> Result<SolveReport, SolvingError> {
+        let mut progress = SearchProgress::new(config);
+        let root = &self.node(0).board;
+        if let Some(diagnosis) = UnsolvableDiagnosis::diagnose(root) {
+            return Err(SolvingError::UnsolvableBoard(Some(diagnosis)));
+        }
+        let node_bytes = std::mem::size_of::<TreeNode>() + util::board_bytes(root);
+
+        let mut expansions = 0_usize;
+        loop {
+            let Some(&(_, _, best)) = self
+                .open
+                .first()
+                .filter(|&&(f_cost, ..)| f_cost != UNREACHABLE)
+            else {
+                return Err(SolvingError::AlgorithmError(Box::new(
+                    MemoryBoundedError::NodeLimitExceeded {
+                        node_limit: self.node_limit,
+                    },
+                )));
+            };
+            if self.node(best).board.is_solved() {
+                log::debug!("Solved after generating {expansions} nodes");
+                return Ok(progress.report(self.path_to(best)));
+            }
+            if !progress.expand() {
+                return Err(progress.error(self.path_to(best)));
+            }
+            self.expand(best);
+            progress.generate();
+            progress.observe(self.open.len(), self.node_count, node_bytes);
+            expansions += 1;
+        }
+    }
+}
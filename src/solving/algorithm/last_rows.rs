@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::board::sub_board::SubBoard;
+use crate::board::{Board, BoardMove, OwnedBoard};
+use crate::solving::algorithm::heuristic::astar::IterativeAStarSolver;
+use crate::solving::algorithm::heuristic::pattern_database::{
+    Partition, PatternDatabase, MAX_GROUP_STATES,
+};
+use crate::solving::algorithm::{Solver, SolvingError};
+
+/// Databases of the last two rows, keyed by the number of columns, shared by all solvers in the process
+static DATABASES: OnceLock<Mutex<HashMap<u8, Arc<PatternDatabase>>>> = OnceLock::new();
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LastTwoRowsError {
+    /// The board has less than two rows
+    TooFewRows,
+    /// Some tile outside of the last two rows is not in its solved position
+    TopRowsNotSolved,
+    /// The database was generated for a different number of columns
+    DatabaseMismatch,
+    /// The board has a custom goal, while the database is generated for the standard one
+    CustomGoal,
+}
+
+impl Display for LastTwoRowsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LastTwoRowsError::TooFewRows => write!(f, "The board has less than two rows"),
+            LastTwoRowsError::TopRowsNotSolved => {
+                write!(f, "The rows above the last two are not solved")
+            }
+            LastTwoRowsError::DatabaseMismatch => {
+                write!(
+                    f,
+                    "The database was generated for a different number of columns"
+                )
+            }
+            LastTwoRowsError::CustomGoal => {
+                write!(
+                    f,
+                    "Only the last two rows of the standard goal can be solved"
+                )
+            }
+        }
+    }
+}
+
+impl Error for LastTwoRowsError {}
+
+/// Optimal solver of the last two rows of a board whose other rows are already solved,
+/// which is the final step of solving the board row by row.
+///
+/// The last two rows are solved as a standalone `2xN` puzzle by IDA*,
+/// using a pattern database of all the tiles of the puzzle,
+/// which gives nearly exact estimates and lets IDA* go straight to the solution.
+/// Puzzles too large for a single table use several smaller ones, see [`LastTwoRowsSolver::partition`].
+/// The empty cell never leaves the last two rows, so the moves of the sub-puzzle are also the moves of the full board.
+///
+/// The database is generated by the first solver created for the number of columns,
+/// and shared with the later ones, see [`LastTwoRowsSolver::standard_database`]
+pub struct LastTwoRowsSolver {
+    board: OwnedBoard,
+    database: Arc<PatternDatabase>,
+}
+
+impl LastTwoRowsSolver {
+    #[must_use]
+    pub fn new(board: OwnedBoard) -> Self {
+        let database = Self::standard_database(board.columns());
+        Self { board, database }
+    }
+
+    /// Creates solver using the database created by [`LastTwoRowsSolver::database`]
+    #[must_use]
+    pub fn with_database(board: OwnedBoard, database: Arc<PatternDatabase>) -> Self {
+        Self { board, database }
+    }
+
+    /// Returns the database of the given number of columns, generated on first use
+    #[must_use]
+    pub fn standard_database(columns: u8) -> Arc<PatternDatabase> {
+        let databases = DATABASES.get_or_init(Mutex::default);
+        let mut databases = databases.lock().expect("Last rows databases lock");
+        Arc::clone(
+            databases
+                .entry(columns)
+                .or_insert_with(|| Arc::new(Self::database(columns))),
+        )
+    }
+
+    /// Generates the pattern database of the `2xN` puzzle of the last two rows, see [`LastTwoRowsSolver::partition`]
+    #[must_use]
+    pub fn database(columns: u8) -> PatternDatabase {
+        PatternDatabase::generate(2, columns, Self::partition(columns))
+            .expect("Partition covers the tiles and fits the limit")
+    }
+
+    /// Returns the partition of the database of the `2xN` puzzle.
+    /// It is a single group of all tiles, whose table has `(2N)!` entries, while it fits [`MAX_GROUP_STATES`],
+    /// which is up to 6 columns. Larger puzzles use the smaller groups of [`Partition::disjoint`] instead
+    #[must_use]
+    pub fn partition(columns: u8) -> Partition {
+        let tiles = (1..2 * columns).collect();
+        let partition = Partition::new(vec![tiles]).expect("Single group is valid");
+        match partition.validate_size(2, columns, MAX_GROUP_STATES) {
+            Ok(()) => partition,
+            Err(_) => Partition::disjoint(2, columns),
+        }
+    }
+
+    /// Extracts the `2xN` puzzle of the last two rows
+    fn last_two_rows(&mut self) -> Result<OwnedBoard, LastTwoRowsError> {
+        let (rows, columns) = self.board.dimensions();
+        if rows < 2 {
+            return Err(LastTwoRowsError::TooFewRows);
+        }
+        if self.database.dimensions() != (2, columns) {
+            return Err(LastTwoRowsError::DatabaseMismatch);
+        }
+        if !self.board.goal().is_solved() {
+            return Err(LastTwoRowsError::CustomGoal);
+        }
+        let top_rows_solved = self
+            .board
+            .iter_cells()
+            .take_while(|&((row, _), _)| row < rows - 2)
+            .all(|(position, value)| value != 0 && self.board.goal_position(value) == position);
+        if !top_rows_solved {
+            return Err(LastTwoRowsError::TopRowsNotSolved);
+        }
+
+        let sub_board =
+            SubBoard::suffix(&mut self.board, rows - 2, 0).expect("Empty cell is in the last rows");
+        let cells = sub_board.iter_cells().map(|(_, value)| value).collect();
+        Ok(OwnedBoard::with_cells(2, columns, cells)
+            .expect("Last rows contain only their own tiles"))
+    }
+}
+
+impl Solver for LastTwoRowsSolver {
+    fn solve(mut self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
+        let puzzle = self
+            .last_two_rows()
+            .map_err(|e| SolvingError::AlgorithmError(Box::new(e)))?;
+        // moves of the sub-puzzle translate directly, as it spans whole rows of the board
+        Box::new(IterativeAStarSolver::new(puzzle, Box::new(self.database))).solve()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::parse_moves;
+    use crate::solving::algorithm::bfs::BFSSolver;
+    use crate::solving::movegen::MoveGenerator;
+
+    use super::*;
+
+    #[test]
+    fn solves_last_two_rows_optimally() {
+        let scramble = parse_moves("LULDRURDLU").unwrap();
+        let mut board = OwnedBoard::from_scramble(4, 3, &scramble).unwrap();
+        let solution = Box::new(LastTwoRowsSolver::new(board.clone()))
+            .solve()
+            .unwrap();
+        let optimal = Box::new(BFSSolver::new(board.clone(), MoveGenerator::default()))
+            .solve()
+            .unwrap();
+
+        assert_eq!(optimal.len(), solution.len());
+        board.apply_moves(&solution).unwrap();
+        assert!(board.is_solved());
+    }
+
+    #[test]
+    fn rejects_board_with_unsolved_top_rows() {
+        let board: OwnedBoard = "3 3\n1 2 3\n4 5 6\n7 0 8".parse().unwrap();
+        let solver =
+            LastTwoRowsSolver::with_database(board, Arc::new(LastTwoRowsSolver::database(3)));
+        assert!(Box::new(solver).solve().is_ok());
+
+        let board: OwnedBoard = "3 3\n1 2 3\n4 5 6\n8 0 7".parse().unwrap();
+        assert!(matches!(
+            Box::new(LastTwoRowsSolver::new(board)).solve(),
+            Err(SolvingError::UnsolvableBoard(_))
+        ));
+
+        let board: OwnedBoard = "3 3\n2 1 3\n4 5 6\n7 0 8".parse().unwrap();
+        assert!(matches!(
+            Box::new(LastTwoRowsSolver::new(board)).solve(),
+            Err(SolvingError::AlgorithmError(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_board_with_custom_goal() {
+        let goal: OwnedBoard = "3 3\n1 2 3\n4 5 6\n7 0 8".parse().unwrap();
+        let mut board = OwnedBoard::new_solved(3, 3).with_goal(goal).unwrap();
+        board.exec_move(BoardMove::Up);
+
+        let mut solver = LastTwoRowsSolver::new(board);
+        assert_eq!(Err(LastTwoRowsError::CustomGoal), solver.last_two_rows());
+    }
+
+    #[test]
+    fn wide_puzzles_split_the_tiles() {
+        assert_eq!(1, LastTwoRowsSolver::partition(6).groups().len());
+        for columns in [7, 8, 12] {
+            let partition = LastTwoRowsSolver::partition(columns);
+            assert!(partition.groups().len() > 1);
+            assert_eq!(Ok(()), partition.validate(2, columns));
+            assert_eq!(
+                Ok(()),
+                partition.validate_size(2, columns, MAX_GROUP_STATES)
+            );
+        }
+    }
+
+    #[test]
+    fn database_is_generated_once() {
+        assert!(Arc::ptr_eq(
+            &LastTwoRowsSolver::standard_database(2),
+            &LastTwoRowsSolver::standard_database(2)
+        ));
+    }
+}
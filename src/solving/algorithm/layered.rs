@@ -0,0 +1,203 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use crate::board::{Board, BoardMove, OwnedBoard};
+use crate::solving::algorithm::util::SearchProgress;
+use crate::solving::algorithm::{util, SolverConfig, SolvingError};
+use crate::solving::goal::{Goal, GoalSet};
+
+/// Bit of the move in the used operators of a node
+fn move_bit(board_move: BoardMove) -> u8 {
+    match board_move {
+        BoardMove::Up => 1,
+        BoardMove::Down => 1 << 1,
+        BoardMove::Left => 1 << 2,
+        BoardMove::Right => 1 << 3,
+    }
+}
+
+#[derive(Clone, Copy)]
+struct LayerNode {
+    /// Moves leading back to the previous layer, which are never generated again
+    used_moves: u8,
+    /// Ancestor of the node in the relay layer, as its index
+    relay: Option<usize>,
+}
+
+pub enum LayerResult {
+    /// The target was found in the layer of the given depth, together with its ancestor in the relay layer
+    Found {
+        depth: u64,
+        relay: Option<OwnedBoard>,
+    },
+    /// The target is further than the bound, the lowest f-cost exceeding it is the next bound.
+    /// `None` if no position exceeded the bound, so every reachable position was searched
+    Exceeded(Option<u64>),
+    LimitReached,
+}
+
+/// Breadth-first search which keeps only the layer being expanded and the next one, shared by the frontier
+/// and the heuristic breadth-first searches.
+///
+/// Instead of the closed positions, every node stores the moves leading back to the previous layer,
+/// which are not generated again. Every move changes the color of the empty cell on the checkerboard,
+/// so a move never leads to a position in the same layer, and these moves are enough to detect all of the duplicates.
+/// Positions are left out only by their own f-cost, so the pruning does not hide any duplicates.
+///
+/// As the paths are not stored either, nodes store their ancestor in a relay layer instead,
+/// and a path is reconstructed by recursively searching the path to the relay ancestor of the target and from it
+pub struct LayeredSearch {
+    pub progress: SearchProgress,
+    node_bytes: usize,
+}
+
+impl LayeredSearch {
+    pub fn new(board: &OwnedBoard, config: &SolverConfig) -> Self {
+        Self {
+            progress: SearchProgress::new(config),
+            node_bytes: util::board_bytes(board) + std::mem::size_of::<LayerNode>(),
+        }
+    }
+
+    /// Searches the layers from the start until the target is reached, keeping the relay layer at the given depth.
+    /// With a bound, positions whose depth and estimate of the target exceed it are left out,
+    /// and the search ends at the layer of the bound.
+    /// The number of positions in every searched layer is passed to `on_layer`
+    pub fn search_layers(
+        &mut self,
+        start: &OwnedBoard,
+        target: &dyn Goal,
+        bound: Option<u64>,
+        relay_depth: Option<u64>,
+        mut on_layer: impl FnMut(usize),
+    ) -> LayerResult {
+        let mut relays = vec![];
+        let start_relay = (relay_depth == Some(0)).then(|| {
+            relays.push(start.clone());
+            0
+        });
+        let mut current = HashMap::from([(
+            start.clone(),
+            LayerNode {
+                used_moves: 0,
+                relay: start_relay,
+            },
+        )]);
+        let mut next_bound: Option<u64> = None;
+
+        for depth in 0.. {
+            on_layer(current.len());
+            if let Some(node) = current
+                .iter()
+                .find_map(|(board, node)| target.is_reached(board).then_some(node))
+            {
+                return LayerResult::Found {
+                    depth,
+                    relay: node.relay.map(|index| relays[index].clone()),
+                };
+            }
+            if bound == Some(depth) {
+                break;
+            }
+
+            let mut next: HashMap<OwnedBoard, LayerNode> = HashMap::new();
+            for (board, node) in &current {
+                if !self.progress.expand() {
+                    return LayerResult::LimitReached;
+                }
+                for board_move in BoardMove::ALL {
+                    if node.used_moves & move_bit(board_move) != 0 || !board.can_move(board_move) {
+                        continue;
+                    }
+                    let mut child = board.clone();
+                    child.exec_move(board_move);
+                    let back = move_bit(board_move.opposite());
+                    match next.entry(child) {
+                        Entry::Occupied(mut entry) => entry.get_mut().used_moves |= back,
+                        Entry::Vacant(entry) => {
+                            if let Some(bound) = bound {
+                                let f_cost = depth + 1 + target.estimate(entry.key());
+                                if f_cost > bound {
+                                    next_bound = Some(
+                                        next_bound.map_or(f_cost, |lowest| lowest.min(f_cost)),
+                                    );
+                                    continue;
+                                }
+                            }
+                            let relay = if relay_depth == Some(depth + 1) {
+                                relays.push(entry.key().clone());
+                                Some(relays.len() - 1)
+                            } else {
+                                node.relay
+                            };
+                            entry.insert(LayerNode {
+                                used_moves: back,
+                                relay,
+                            });
+                            self.progress.generate();
+                        }
+                    }
+                }
+            }
+            let stored = current.len() + next.len() + relays.len();
+            self.progress.observe(next.len(), stored, self.node_bytes);
+            if next.is_empty() {
+                break;
+            }
+            current = next;
+        }
+        LayerResult::Exceeded(next_bound)
+    }
+
+    /// Returns the shortest path from the start to the target, which is at most `distance` moves away.
+    /// The estimate of the target has to be admissible, so that no position on the path is left out.
+    /// The relays are searched for with the Manhattan distance to them
+    pub fn path(
+        &mut self,
+        start: &OwnedBoard,
+        target: &dyn Goal,
+        distance: u64,
+    ) -> Result<Vec<BoardMove>, SolvingError> {
+        if target.is_reached(start) {
+            return Ok(vec![]);
+        }
+        if distance == 1 {
+            let board_move = BoardMove::ALL
+                .into_iter()
+                .find(|&board_move| {
+                    let mut board = start.clone();
+                    board.can_move(board_move) && {
+                        board.exec_move(board_move);
+                        target.is_reached(&board)
+                    }
+                })
+                .expect("Target is a single move away");
+            return Ok(vec![board_move]);
+        }
+        let relay_depth = distance / 2;
+        match self.search_layers(start, target, Some(distance), Some(relay_depth), |_| {}) {
+            LayerResult::Found {
+                depth,
+                relay: Some(relay),
+            } => self.connect(start, target, depth, relay, relay_depth),
+            // found before the relay layer, so closer than the distance
+            LayerResult::Found { depth, relay: None } => self.path(start, target, depth),
+            LayerResult::Exceeded(_) => unreachable!("Target is at most the distance away"),
+            LayerResult::LimitReached => Err(self.progress.error(vec![])),
+        }
+    }
+
+    /// Joins the paths from the start to the relay at the given depth, and from the relay to the target found at `depth`
+    pub fn connect(
+        &mut self,
+        start: &OwnedBoard,
+        target: &dyn Goal,
+        depth: u64,
+        relay: OwnedBoard,
+        relay_depth: u64,
+    ) -> Result<Vec<BoardMove>, SolvingError> {
+        let mut path = self.path(start, &GoalSet::new(vec![relay.clone()]), relay_depth)?;
+        path.extend(self.path(&relay, target, depth - relay_depth)?);
+        Ok(path)
+    }
+}
@@ -0,0 +1,366 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+
+use crate::board::{Board, BoardMove, OwnedBoard};
+use crate::solving::algorithm::{Solver, SolvingError};
+use crate::solving::diagnosis::UnsolvableDiagnosis;
+
+/// Marks the cells whose tiles are not tracked by the search of a macro
+const UNTRACKED: u8 = u8::MAX;
+/// Marks the cells of the groups placed before the one of the macro, which the empty cell never enters
+const FROZEN: u8 = u8::MAX - 1;
+
+/// Step, position of the empty cell and position of the tile placed by a macro
+type MacroKey = (usize, (u8, u8), (u8, u8));
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MacroError {
+    /// The table was created for a board of different dimensions or with a different goal
+    TableMismatch,
+}
+
+impl Display for MacroError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MacroError::TableMismatch => write!(
+                f,
+                "The macro table was created for a board of different dimensions or goal"
+            ),
+        }
+    }
+}
+
+impl Error for MacroError {}
+
+/// Macro operators of a board, in the style of Korf's macro tables.
+///
+/// The tiles are placed one at a time in a fixed order: row by row until two rows remain,
+/// and then column by column. The macro of a step brings its tile from any position to its goal position,
+/// given the position of the empty cell, and leaves the tiles placed by the previous steps where they were.
+/// Tiles of the row or column being placed may be moved out of place on the way,
+/// while the tiles of the finished rows and columns are never moved.
+///
+/// A sequence of moves is the path of the empty cell, so its effect on the positions of the tiles
+/// does not depend on the other tiles, and one macro serves every board with the same positions of the tile and the empty cell.
+/// Macros are found by IDA* over the positions of the placed tiles when they are first needed,
+/// or all at once with [`MacroTable::precompute`]
+pub struct MacroTable {
+    dimensions: (u8, u8),
+    /// Goal position of every tile, indexed by the tile
+    goal_positions: Vec<(u8, u8)>,
+    /// Tiles in the order of placement
+    order: Vec<u8>,
+    /// First step of the row or column of every step
+    group_starts: Vec<usize>,
+    macros: RefCell<HashMap<MacroKey, Rc<[BoardMove]>>>,
+}
+
+impl MacroTable {
+    /// Creates an empty table for boards with the dimensions and goal of the board
+    #[must_use]
+    pub fn new(board: &(impl Board + ?Sized)) -> Self {
+        let (rows, columns) = board.dimensions();
+        let goal_positions: Vec<_> = (0..rows as usize * columns as usize)
+            .map(|tile| board.goal_position(tile as u8))
+            .collect();
+
+        // row by row until two rows remain, then column by column
+        let groups = (0..rows.saturating_sub(2))
+            .map(|row| (0..columns).map(|column| (row, column)).collect::<Vec<_>>())
+            .chain((0..columns).map(|column| {
+                (rows.saturating_sub(2)..rows)
+                    .map(|row| (row, column))
+                    .collect()
+            }));
+        let (mut order, mut group_starts) = (vec![], vec![]);
+        for group in groups {
+            let start = order.len();
+            for cell in group {
+                let tile = goal_positions
+                    .iter()
+                    .position(|&goal| goal == cell)
+                    .expect("Every cell is the goal of a tile") as u8;
+                if tile != 0 {
+                    order.push(tile);
+                    group_starts.push(start);
+                }
+            }
+        }
+
+        Self {
+            dimensions: (rows, columns),
+            goal_positions,
+            order,
+            group_starts,
+            macros: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Number of macros found so far
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.macros.borrow().len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.macros.borrow().is_empty()
+    }
+
+    /// Finds the macros of all steps and reachable positions.
+    /// There are about `cells³` of them, so it is practical only for small boards
+    pub fn precompute(&self) {
+        let (rows, columns) = self.dimensions;
+        let cells: Vec<_> = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (row, column)))
+            .collect();
+        for step in 0..self.order.len() {
+            let placed: Vec<_> = self.order[..step]
+                .iter()
+                .map(|&tile| self.goal_positions[tile as usize])
+                .collect();
+            for &empty in cells.iter().filter(|cell| !placed.contains(cell)) {
+                for &tile in cells
+                    .iter()
+                    .filter(|&&cell| cell != empty && !placed.contains(&cell))
+                {
+                    if self.is_reachable(step, empty, tile) {
+                        self.macro_for(step, empty, tile);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks if the position of the tile and the empty cell occurs on a solvable board after the previous steps.
+    /// With at least two other tiles left they can be swapped to fix the parity, so only the last steps are restricted
+    fn is_reachable(&self, step: usize, empty: (u8, u8), tile: (u8, u8)) -> bool {
+        let (rows, columns) = self.dimensions;
+        let index = |(row, column): (u8, u8)| row as usize * columns as usize + column as usize;
+        let cell_count = rows as usize * columns as usize;
+        let free: Vec<_> = (1..cell_count as u8)
+            .filter(|tile| !self.order[..=step].contains(tile))
+            .collect();
+        if free.len() >= 2 {
+            return true;
+        }
+
+        let mut goal_cells = vec![0; cell_count];
+        let mut cells = vec![None; cell_count];
+        for (tile, &goal) in self.goal_positions.iter().enumerate() {
+            goal_cells[index(goal)] = tile as u8;
+        }
+        for &placed in &self.order[..step] {
+            cells[index(self.goal_positions[placed as usize])] = Some(placed);
+        }
+        cells[index(tile)] = Some(self.order[step]);
+        cells[index(empty)] = Some(0);
+        let mut free = free.into_iter();
+        let cells = cells
+            .into_iter()
+            .map(|cell| {
+                cell.or_else(|| free.next())
+                    .expect("Every cell gets a tile")
+            })
+            .collect();
+
+        OwnedBoard::with_cells(rows, columns, goal_cells)
+            .and_then(|goal| OwnedBoard::with_cells(rows, columns, cells)?.with_goal(goal))
+            .is_ok_and(|board| UnsolvableDiagnosis::diagnose(&board).is_none())
+    }
+
+    fn matches(&self, board: &(impl Board + ?Sized)) -> bool {
+        board.dimensions() == self.dimensions
+            && self
+                .goal_positions
+                .iter()
+                .enumerate()
+                .all(|(tile, &goal)| board.goal_position(tile as u8) == goal)
+    }
+
+    /// Returns the macro placing the tile of the step, found when it is first needed
+    fn macro_for(&self, step: usize, empty: (u8, u8), tile: (u8, u8)) -> Rc<[BoardMove]> {
+        let key = (step, empty, tile);
+        if let Some(found) = self.macros.borrow().get(&key) {
+            return Rc::clone(found);
+        }
+        let found: Rc<[BoardMove]> = MacroSearch::new(self, step, empty, tile).run().into();
+        self.macros.borrow_mut().insert(key, Rc::clone(&found));
+        found
+    }
+}
+
+/// IDA* over the positions of the empty cell and the tiles of the row or column placed up to the step
+struct MacroSearch {
+    columns: u8,
+    rows: u8,
+    /// Index of the tracked tile in every cell, [`UNTRACKED`] or [`FROZEN`]
+    cells: Vec<u8>,
+    /// Goal position of every tracked tile
+    goals: Vec<(u8, u8)>,
+    empty: (u8, u8),
+    path: Vec<BoardMove>,
+}
+
+impl MacroSearch {
+    fn new(table: &MacroTable, step: usize, empty: (u8, u8), tile: (u8, u8)) -> Self {
+        let (rows, columns) = table.dimensions;
+        let index = |(row, column): (u8, u8)| row as usize * columns as usize + column as usize;
+        let group_start = table.group_starts[step];
+        let goal = |tile: &u8| table.goal_positions[*tile as usize];
+
+        let mut cells = vec![UNTRACKED; rows as usize * columns as usize];
+        for frozen in table.order[..group_start].iter().map(goal) {
+            cells[index(frozen)] = FROZEN;
+        }
+        let goals: Vec<_> = table.order[group_start..=step].iter().map(goal).collect();
+        for (tracked, &cell) in goals[..goals.len() - 1].iter().enumerate() {
+            cells[index(cell)] = tracked as u8;
+        }
+        cells[index(tile)] = (goals.len() - 1) as u8;
+        Self {
+            columns,
+            rows,
+            cells,
+            goals,
+            empty,
+            path: vec![],
+        }
+    }
+
+    fn index(&self, (row, column): (u8, u8)) -> usize {
+        row as usize * self.columns as usize + column as usize
+    }
+
+    /// Sum of the distances of the tracked tiles from their goal positions
+    fn estimate(&self) -> usize {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, &tracked)| tracked != UNTRACKED && tracked != FROZEN)
+            .map(|(index, &tracked)| {
+                let (row, column) = (index / self.columns as usize, index % self.columns as usize);
+                let (goal_row, goal_column) = self.goals[tracked as usize];
+                row.abs_diff(goal_row as usize) + column.abs_diff(goal_column as usize)
+            })
+            .sum()
+    }
+
+    /// Returns the cell the empty cell moves to, unless it is outside the board or frozen
+    fn target(&self, board_move: BoardMove) -> Option<(u8, u8)> {
+        let (row, column) = self.empty;
+        match board_move {
+            BoardMove::Up => row.checked_sub(1).map(|row| (row, column)),
+            BoardMove::Down => (row + 1 < self.rows).then_some((row + 1, column)),
+            BoardMove::Left => column.checked_sub(1).map(|column| (row, column)),
+            BoardMove::Right => (column + 1 < self.columns).then_some((row, column + 1)),
+        }
+        .filter(|&target| self.cells[self.index(target)] != FROZEN)
+    }
+
+    fn exec_move(&mut self, board_move: BoardMove) {
+        let target = self
+            .target(board_move)
+            .expect("Move stays inside the board");
+        let (from, to) = (self.index(self.empty), self.index(target));
+        self.cells.swap(from, to);
+        self.empty = target;
+    }
+
+    fn run(mut self) -> Vec<BoardMove> {
+        let mut bound = self.estimate();
+        loop {
+            match self.search(bound) {
+                Ok(()) => return self.path,
+                Err(next_bound) => bound = next_bound,
+            }
+        }
+    }
+
+    /// Searches the paths up to the bound, returning the lowest estimate exceeding it if none reaches the goal
+    fn search(&mut self, bound: usize) -> Result<(), usize> {
+        let estimate = self.path.len() + self.estimate();
+        if estimate > bound {
+            return Err(estimate);
+        }
+        if estimate == self.path.len() {
+            return Ok(());
+        }
+        let mut next_bound = usize::MAX;
+        for board_move in BoardMove::ALL {
+            if self.target(board_move).is_none() || self.path.last() == Some(&board_move.opposite())
+            {
+                continue;
+            }
+            self.exec_move(board_move);
+            self.path.push(board_move);
+            match self.search(bound) {
+                Ok(()) => return Ok(()),
+                Err(exceeded) => next_bound = next_bound.min(exceeded),
+            }
+            self.path.pop();
+            self.exec_move(board_move.opposite());
+        }
+        Err(next_bound)
+    }
+}
+
+/// Solver which places the tiles one at a time with the macros of a [`MacroTable`],
+/// returning the concatenation of the macros.
+///
+/// The solution is far from the shortest, but it is found in a time linear in the number of tiles
+/// once the macros are known. The table can be shared by solvers of multiple boards with [`MacroOperatorSolver::with_table`]
+pub struct MacroOperatorSolver {
+    board: OwnedBoard,
+    table: Rc<MacroTable>,
+}
+
+impl MacroOperatorSolver {
+    #[must_use]
+    pub fn new(board: OwnedBoard) -> Self {
+        let table = Rc::new(MacroTable::new(&board));
+        Self { board, table }
+    }
+
+    /// Creates solver using the table created for boards with the same dimensions and goal
+    #[must_use]
+    pub fn with_table(board: OwnedBoard, table: Rc<MacroTable>) -> Self {
+        Self { board, table }
+    }
+}
+
+impl Solver for MacroOperatorSolver {
+    fn solve(mut self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
+        if !self.table.matches(&self.board) {
+            return Err(SolvingError::AlgorithmError(Box::new(
+                MacroError::TableMismatch,
+            )));
+        }
+        if let Some(diagnosis) = UnsolvableDiagnosis::diagnose(&self.board) {
+            return Err(SolvingError::UnsolvableBoard(Some(diagnosis)));
+        }
+
+        let mut solution = vec![];
+        for (step, &tile) in self.table.order.iter().enumerate() {
+            let position = self
+                .board
+                .iter_cells()
+                .find(|&(_, value)| value == tile)
+                .map(|(position, _)| position)
+                .expect("Every tile is on the board");
+            if position == self.table.goal_positions[tile as usize] {
+                continue;
+            }
+            let found = self
+                .table
+                .macro_for(step, self.board.empty_cell_pos(), position);
+            self.board.apply_moves(&found)?;
+            solution.extend_from_slice(&found);
+        }
+        debug_assert!(self.board.is_solved());
+        Ok(solution)
+    }
+}
@@ -1,48 +1,483 @@
 use std::error::Error;
 use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
 
-use crate::board::BoardMove;
+use crate::board::{BoardMove, MoveError};
+use crate::solving::checkpoint::{Checkpoint, CheckpointError};
+use crate::solving::diagnosis::UnsolvableDiagnosis;
+use crate::solving::pruning::PruningRule;
 
 pub mod bfs;
 pub mod dfs;
+pub mod eight;
+pub mod frontier;
+pub mod last_rows;
+pub mod macros;
+pub mod phased;
+pub mod portfolio;
+pub mod retrograde;
+pub mod stepper;
 
 pub mod heuristic;
+mod layered;
 
 pub mod solvers {
     pub use super::bfs::BFSSolver;
     pub use super::dfs::DFSSolver;
     pub use super::dfs::IncrementalDFSSolver;
+    pub use super::eight::EightPuzzleSolver;
+    pub use super::eight::Optimal8Solver;
+    pub use super::frontier::FrontierBFSSolver;
+    pub use super::last_rows::LastTwoRowsSolver;
+    pub use super::macros::MacroOperatorSolver;
+    pub use super::phased::PhasedSolver;
+    pub use super::portfolio::PortfolioSolver;
+    pub use super::retrograde::RetrogradeSolver;
     pub use crate::solving::algorithm::heuristic::astar::AStarSolver;
     pub use crate::solving::algorithm::heuristic::astar::IterativeAStarSolver;
+    pub use crate::solving::algorithm::heuristic::bfhs::BreadthFirstHeuristicSolver;
+    pub use crate::solving::algorithm::heuristic::focal::FocalSolver;
+    pub use crate::solving::algorithm::heuristic::hda::HashDistributedAStarSolver;
+    #[cfg(feature = "experimental")]
+    pub use crate::solving::algorithm::heuristic::mcts::MonteCarloSolver;
+    pub use crate::solving::algorithm::heuristic::perimeter::PerimeterSearchSolver;
+    pub use crate::solving::algorithm::heuristic::sma::MemoryBoundedAStarSolver;
 }
 
 #[derive(Debug)]
 pub enum SolvingError {
-    UnsolvableBoard,
-    AlgorithmError(Box<dyn Error>),
+    /// The goal cannot be reached from the board, with the explanation if the solver can tell it
+    UnsolvableBoard(Option<UnsolvableDiagnosis>),
+    AlgorithmError(Box<dyn Error + Send + Sync>),
+    /// The search ran past the time limit of the [`SolverConfig`]
+    TimedOut {
+        /// Moves leading to the most promising position the search reached
+        best_partial: Vec<BoardMove>,
+        elapsed: Duration,
+    },
+    /// The search expanded the maximum number of positions of the [`SolverConfig`]
+    ExpansionLimitReached {
+        /// Moves leading to the most promising position the search reached
+        best_partial: Vec<BoardMove>,
+        max_expansions: u64,
+    },
+    /// No solver ran to the end, as the portfolio had no members or all of them panicked
+    NoSolverFinished,
 }
 
 impl Display for SolvingError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            SolvingError::UnsolvableBoard => write!(f, "Board is unsolvable"),
+            SolvingError::UnsolvableBoard(Some(diagnosis)) => write!(f, "{diagnosis}"),
+            SolvingError::UnsolvableBoard(None) => write!(f, "Board is unsolvable"),
             SolvingError::AlgorithmError(inner) => {
                 write!(f, "Solving error: {inner}")
             }
+            SolvingError::TimedOut {
+                best_partial,
+                elapsed,
+            } => write!(
+                f,
+                "Time limit exceeded after {:.2}s, best partial solution has {} moves",
+                elapsed.as_secs_f64(),
+                best_partial.len()
+            ),
+            SolvingError::ExpansionLimitReached {
+                best_partial,
+                max_expansions,
+            } => write!(
+                f,
+                "Expansion limit reached after {max_expansions} positions, best partial solution has {} moves",
+                best_partial.len()
+            ),
+            SolvingError::NoSolverFinished => write!(f, "No solver finished the search"),
         }
     }
 }
 
 impl Error for SolvingError {}
 
+impl SolvingError {
+    /// Returns the moves towards the goal found by the search stopped by a limit of the [`SolverConfig`]
+    #[must_use]
+    pub fn best_partial(&self) -> Option<&[BoardMove]> {
+        match self {
+            SolvingError::TimedOut { best_partial, .. }
+            | SolvingError::ExpansionLimitReached { best_partial, .. } => Some(best_partial),
+            SolvingError::UnsolvableBoard(_)
+            | SolvingError::AlgorithmError(_)
+            | SolvingError::NoSolverFinished => None,
+        }
+    }
+}
+
+/// Error of a solver which does not count its expansions, so it cannot stop at the expansion limit
+#[derive(Debug)]
+pub struct ExpansionLimitUnsupported;
+
+impl Display for ExpansionLimitUnsupported {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Solver does not count its expansions, so it cannot stop at the expansion limit"
+        )
+    }
+}
+
+impl Error for ExpansionLimitUnsupported {}
+
+/// Error of a solver which does not check the positions it generates against the pruning rules
+#[derive(Debug)]
+pub struct PruningUnsupported;
+
+impl Display for PruningUnsupported {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Solver does not support pruning rules")
+    }
+}
+
+impl Error for PruningUnsupported {}
+
+/// Error of a solver which runs the whole search in [`Solver::solve_for`], so it cannot stop at the time limit
+#[derive(Debug)]
+pub struct TimeLimitUnsupported;
+
+impl Display for TimeLimitUnsupported {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Solver cannot suspend its search, so it cannot stop at the time limit"
+        )
+    }
+}
+
+impl Error for TimeLimitUnsupported {}
+
+/// Error of a solver which runs the whole search in [`Solver::solve_for`], so it cannot be stopped by others
+#[derive(Debug)]
+pub struct SuspendUnsupported;
+
+impl Display for SuspendUnsupported {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Solver cannot suspend its search")
+    }
+}
+
+impl Error for SuspendUnsupported {}
+
+impl From<MoveError> for SolvingError {
+    fn from(value: MoveError) -> Self {
+        Self::AlgorithmError(Box::new(value))
+    }
+}
+
 pub trait Solver {
     fn solve(self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError>;
+
+    /// Advances the search for about the given duration, e.g. a few milliseconds per frame of a game.
+    /// The search can be continued by calling the method again on the solver returned in [`SolveStep::Pending`].
+    ///
+    /// Solvers which cannot be suspended run the whole search, as with [`Solver::solve_with_stats`]
+    fn solve_for(self: Box<Self>, duration: Duration) -> SolveStep {
+        let _ = duration;
+        SolveStep::Done(self.solve_with_stats(&SolverConfig::default()))
+    }
+
+    /// Returns `true` if [`Solver::solve_for`] suspends the search after the given duration,
+    /// instead of running the whole search
+    fn is_suspendable(&self) -> bool {
+        false
+    }
+
+    /// Solves the board within the limits of the configuration.
+    ///
+    /// By default the time limit is applied by running the search with [`Solver::solve_for`],
+    /// and solvers which cannot be suspended fail with [`TimeLimitUnsupported`].
+    /// Solvers which count their expansions override the method to apply the expansion limit,
+    /// by default it fails with [`ExpansionLimitUnsupported`]
+    fn solve_with(self: Box<Self>, config: &SolverConfig) -> Result<Vec<BoardMove>, SolvingError> {
+        if config.max_expansions.is_some() {
+            return Err(SolvingError::AlgorithmError(Box::new(
+                ExpansionLimitUnsupported,
+            )));
+        }
+        let Some(time_limit) = config.time_limit else {
+            return self.solve();
+        };
+        if !self.is_suspendable() {
+            return Err(SolvingError::AlgorithmError(Box::new(TimeLimitUnsupported)));
+        }
+        let start = Instant::now();
+        match self.solve_for(time_limit) {
+            SolveStep::Done(result) => result.map(|report| report.solution),
+            SolveStep::Pending(solver) => Err(SolvingError::TimedOut {
+                best_partial: solver.best_partial(),
+                elapsed: start.elapsed(),
+            }),
+        }
+    }
+
+    /// Solves the board within the limits of the configuration, reporting the counters of the search.
+    ///
+    /// Solvers which do not count their nodes report only the solution and the duration, leaving the counters empty,
+    /// see [`SolveReport`]
+    fn solve_with_stats(
+        self: Box<Self>,
+        config: &SolverConfig,
+    ) -> Result<SolveReport, SolvingError> {
+        let start = Instant::now();
+        let solution = self.solve_with(config)?;
+        Ok(SolveReport {
+            solution,
+            duration: start.elapsed(),
+            ..SolveReport::default()
+        })
+    }
+
+    /// Registers the rule, leaving out of the search the positions it cuts off, see [`PruningRule`]
+    ///
+    /// # Errors
+    /// Returns error if the solver does not check the positions it generates against the rules
+    fn add_pruning_rule(&mut self, rule: Box<dyn PruningRule>) -> Result<(), PruningUnsupported> {
+        let _ = rule;
+        Err(PruningUnsupported)
+    }
+
+    /// Returns the moves leading to the most promising position of a suspended search
+    fn best_partial(&self) -> Vec<BoardMove> {
+        vec![]
+    }
+
+    /// Returns the state of a search suspended by [`Solver::solve_for`], or `None` if the solver cannot resume it
+    fn checkpoint(&self) -> Option<Checkpoint> {
+        None
+    }
+
+    /// Continues the search from the checkpoint instead of starting it over
+    ///
+    /// # Errors
+    /// Returns error if the solver cannot resume the search of the checkpoint, or it was written for another board
+    fn resume(&mut self, checkpoint: Checkpoint) -> Result<(), CheckpointError> {
+        let _ = checkpoint;
+        Err(CheckpointError::Unsupported)
+    }
+}
+
+/// Solution with the counters of the search which found it, comparable between the algorithms.
+///
+/// The counters are `None` if the solver does not count its nodes. Those are HDA*, whose threads do not share counters,
+/// the solver looking the solution up in a table ([`RetrogradeSolver`], also named [`EightPuzzleSolver`]),
+/// and the solvers combining other ones ([`MacroOperatorSolver`], [`PhasedSolver`], [`LastTwoRowsSolver`], [`PortfolioSolver`]).
+/// They reject the expansion limit with [`ExpansionLimitUnsupported`]
+///
+/// [`EightPuzzleSolver`]: solvers::EightPuzzleSolver
+/// [`RetrogradeSolver`]: solvers::RetrogradeSolver
+/// [`MacroOperatorSolver`]: solvers::MacroOperatorSolver
+/// [`PhasedSolver`]: solvers::PhasedSolver
+/// [`LastTwoRowsSolver`]: solvers::LastTwoRowsSolver
+/// [`PortfolioSolver`]: solvers::PortfolioSolver
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SolveReport {
+    pub solution: Vec<BoardMove>,
+    /// Number of positions whose moves were generated
+    pub nodes_expanded: Option<u64>,
+    /// Number of positions created by the expansions, except the pruned ones
+    pub nodes_generated: Option<u64>,
+    /// Largest number of positions waiting for expansion at once, or the deepest path of a depth-first search
+    pub max_frontier: Option<usize>,
+    pub duration: Duration,
+    /// Estimate of the memory taken by the positions stored at the peak of the search, in bytes
+    pub peak_memory_estimate: Option<usize>,
+}
+
+/// Limits shared by all of the solvers, applied with [`Solver::solve_with`]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SolverConfig {
+    /// Time after which the search stops with [`SolvingError::TimedOut`]
+    pub time_limit: Option<Duration>,
+    /// Number of expanded positions after which the search stops with [`SolvingError::ExpansionLimitReached`].
+    /// Unlike the time limit, it stops the search at the same point on every machine
+    pub max_expansions: Option<u64>,
+}
+
+impl SolverConfig {
+    #[must_use]
+    pub fn with_time_limit(mut self, time_limit: Duration) -> Self {
+        self.time_limit = Some(time_limit);
+        self
+    }
+
+    #[must_use]
+    pub fn with_max_expansions(mut self, max_expansions: u64) -> Self {
+        self.max_expansions = Some(max_expansions);
+        self
+    }
+}
+
+/// Outcome of advancing the search with [`Solver::solve_for`]
+pub enum SolveStep {
+    /// The search finished, reporting the counters of all of the slices it was advanced by
+    Done(Result<SolveReport, SolvingError>),
+    /// The time ran out, the solver continues from where it stopped
+    Pending(Box<dyn Solver>),
+}
+
+/// Outcome of a single iteration of a progressive deepening search.
+/// Iterative solvers yield one for every iteration, ending after `Solved` or `Failed`
+#[derive(Debug)]
+pub enum IntermediateResult {
+    /// No solution exists within the bound of the iteration, the next one searches up to `next_bound`
+    BoundExhausted { bound: u64, next_bound: u64 },
+    /// The iteration found the solution
+    Solved(Vec<BoardMove>),
+    /// The search cannot continue
+    Failed(SolvingError),
 }
 
 mod util {
+    use std::time::Instant;
+
     use crate::board::{Board, BoardMove};
+    use crate::solving::algorithm::{IntermediateResult, SolveReport, SolverConfig, SolvingError};
     use crate::solving::movegen::MoveSequence;
 
+    /// Checks if the search of a time-sliced solver should be suspended
+    pub fn is_past(deadline: Option<Instant>) -> bool {
+        deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Counters of the search reported in the [`SolveReport`], and the limits of the configuration
+    /// checked by the solver for every expanded position
+    #[derive(Clone, Debug)]
+    pub struct SearchProgress {
+        start: Instant,
+        deadline: Option<Instant>,
+        max_expansions: Option<u64>,
+        expanded: u64,
+        generated: u64,
+        max_frontier: usize,
+        peak_memory: usize,
+    }
+
+    impl SearchProgress {
+        /// Starts counting the time and the expansions
+        pub fn new(config: &SolverConfig) -> Self {
+            let start = Instant::now();
+            Self {
+                start,
+                deadline: config.time_limit.map(|time_limit| start + time_limit),
+                max_expansions: config.max_expansions,
+                expanded: 0,
+                generated: 0,
+                max_frontier: 0,
+                peak_memory: 0,
+            }
+        }
+
+        pub fn expanded(&self) -> u64 {
+            self.expanded
+        }
+
+        /// Returns the number of positions which can still be expanded, `None` without the expansion limit
+        pub fn remaining_expansions(&self) -> Option<u64> {
+            self.max_expansions
+                .map(|max_expansions| max_expansions.saturating_sub(self.expanded))
+        }
+
+        /// Returns the time at which the time limit stops the search
+        pub fn deadline(&self) -> Option<Instant> {
+            self.deadline
+        }
+
+        pub fn generate(&mut self) {
+            self.generated += 1;
+        }
+
+        /// Records the number of positions waiting for expansion,
+        /// and the number of all stored positions taking about `node_bytes` each
+        pub fn observe(&mut self, frontier: usize, stored: usize, node_bytes: usize) {
+            self.max_frontier = self.max_frontier.max(frontier);
+            self.peak_memory = self.peak_memory.max(stored * node_bytes);
+        }
+
+        pub fn report(&self, solution: Vec<BoardMove>) -> SolveReport {
+            SolveReport {
+                solution,
+                nodes_expanded: Some(self.expanded),
+                nodes_generated: Some(self.generated),
+                max_frontier: Some(self.max_frontier),
+                duration: self.start.elapsed(),
+                peak_memory_estimate: Some(self.peak_memory),
+            }
+        }
+
+        /// Counts the expansion of a position, returning `false` if the search has to stop before it
+        pub fn expand(&mut self) -> bool {
+            self.expanded += 1;
+            !self.expansion_limit_reached() && !is_past(self.deadline)
+        }
+
+        fn expansion_limit_reached(&self) -> bool {
+            self.max_expansions.is_some_and(|max| self.expanded > max)
+        }
+
+        /// Returns the error of the limit which stopped the search
+        pub fn error(&self, best_partial: Vec<BoardMove>) -> SolvingError {
+            match self.max_expansions {
+                Some(max_expansions) if self.expansion_limit_reached() => {
+                    SolvingError::ExpansionLimitReached {
+                        best_partial,
+                        max_expansions,
+                    }
+                }
+                _ => SolvingError::TimedOut {
+                    best_partial,
+                    elapsed: self.start.elapsed(),
+                },
+            }
+        }
+    }
+
+    impl Default for SearchProgress {
+        fn default() -> Self {
+            Self::new(&SolverConfig::default())
+        }
+    }
+
+    /// Returns the approximate size of a stored board, with its cells stored separately
+    pub fn board_bytes<B: Board>(board: &B) -> usize {
+        let (rows, columns) = board.dimensions();
+        std::mem::size_of::<B>() + rows as usize * columns as usize
+    }
+
+    /// Runs the iterations until one of them finishes the search
+    pub fn finish_iterations(
+        iterations: impl Iterator<Item = IntermediateResult>,
+    ) -> Result<Vec<BoardMove>, SolvingError> {
+        for result in iterations {
+            match result {
+                IntermediateResult::BoundExhausted { .. } => {}
+                IntermediateResult::Solved(solution) => return Ok(solution),
+                IntermediateResult::Failed(error) => return Err(error),
+            }
+        }
+        Err(SolvingError::UnsolvableBoard(None))
+    }
+
+    /// Checks if the path ending at the board never passes through the same position twice
+    pub fn is_simple_path<B: Board + Clone + Eq + std::hash::Hash>(
+        end: &B,
+        path: &[BoardMove],
+    ) -> bool {
+        let mut board = end.clone();
+        let mut positions = std::collections::HashSet::with_capacity(path.len() + 1);
+        positions.insert(board.clone());
+        path.iter().rev().all(|&m| {
+            board.exec_move(m.opposite());
+            positions.insert(board.clone())
+        })
+    }
+
     pub fn apply_move_sequence(
         board: &mut impl Board,
         path: &mut Vec<BoardMove>,
@@ -0,0 +1,98 @@
+use crate::board::{Board, BoardMove, OwnedBoard};
+use crate::solving::algorithm::heuristic::astar::AStarSolver;
+use crate::solving::algorithm::last_rows::LastTwoRowsSolver;
+use crate::solving::algorithm::{Solver, SolvingError};
+use crate::solving::goal::{Goal, TilesPlaced};
+
+/// Single step of the [`PhasedSolver`]
+pub struct Phase {
+    goal: Box<dyn Goal>,
+    frozen_tiles: Vec<u8>,
+}
+
+impl Phase {
+    /// Creates phase which reaches the given goal, without freezing any tiles afterward
+    #[must_use]
+    pub fn new(goal: Box<dyn Goal>) -> Self {
+        Self {
+            goal,
+            frozen_tiles: vec![],
+        }
+    }
+
+    /// Marks tiles that must not be moved by any of the following phases
+    #[must_use]
+    pub fn freezing(mut self, tiles: impl IntoIterator<Item = u8>) -> Self {
+        self.frozen_tiles.extend(tiles);
+        self
+    }
+
+    /// Creates phase which places the given tiles at their solved positions and freezes them afterward
+    #[must_use]
+    pub fn place_tiles(tiles: impl IntoIterator<Item = u8>) -> Self {
+        let tiles: Vec<u8> = tiles.into_iter().collect();
+        Self::new(Box::new(TilesPlaced::new(tiles.iter().copied()))).freezing(tiles)
+    }
+}
+
+/// Solves the board by reaching a sequence of sub-goals, one after another.
+/// Each phase is solved optimally with A* using the goal estimate,
+/// but the concatenated plan is generally not the shortest solution.
+///
+/// Frozen tiles restrict the moves available to later phases,
+/// so a phase whose goal cannot be reached without moving them will not terminate.
+pub struct PhasedSolver {
+    board: OwnedBoard,
+    phases: Vec<Phase>,
+    finish_last_two_rows: bool,
+}
+
+impl PhasedSolver {
+    #[must_use]
+    pub fn new(board: OwnedBoard, phases: Vec<Phase>) -> Self {
+        Self {
+            board,
+            phases,
+            finish_last_two_rows: false,
+        }
+    }
+
+    /// Solves the last two rows with [`LastTwoRowsSolver`] after the phases,
+    /// which then only need to solve the rows above them
+    #[must_use]
+    pub fn finishing_last_two_rows(mut self) -> Self {
+        self.finish_last_two_rows = true;
+        self
+    }
+}
+
+impl Solver for PhasedSolver {
+    fn solve(self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
+        let Self {
+            mut board,
+            phases,
+            finish_last_two_rows,
+        } = *self;
+        let mut plan = vec![];
+        let mut frozen_tiles = vec![];
+
+        for (index, phase) in phases.into_iter().enumerate() {
+            let solver = AStarSolver::with_goal(board.clone(), phase.goal)
+                .with_frozen_tiles(frozen_tiles.iter().copied());
+            let moves = Box::new(solver).solve()?;
+            log::debug!("Phase {} finished in {} moves", index + 1, moves.len());
+
+            board.apply_moves(&moves)?;
+            plan.extend(moves);
+            frozen_tiles.extend(phase.frozen_tiles);
+        }
+
+        if finish_last_two_rows {
+            let moves = Box::new(LastTwoRowsSolver::new(board)).solve()?;
+            log::debug!("Last two rows finished in {} moves", moves.len());
+            plan.extend(moves);
+        }
+
+        Ok(plan)
+    }
+}
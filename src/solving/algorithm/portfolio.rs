@@ -0,0 +1,162 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::board::{BoardMove, OwnedBoard};
+use crate::solving::algorithm::{
+    ExpansionLimitUnsupported, SolveStep, Solver, SolverConfig, SolvingError, SuspendUnsupported,
+};
+
+/// Time a member searches before checking if the portfolio was already solved by another one
+const SLICE: Duration = Duration::from_millis(10);
+
+/// Creates the solver of a member on its own thread, as the solvers cannot be sent between threads
+pub type SolverFactory = dyn FnOnce(OwnedBoard) -> Box<dyn Solver> + Send;
+
+struct Member {
+    name: String,
+    factory: Box<SolverFactory>,
+}
+
+/// Runs several solvers of the same board on their own threads, and returns the first solution found by any of them.
+///
+/// Which algorithm is the fastest differs a lot between boards, so the portfolio takes about as long
+/// as the best of its members, without guessing it up front. Members search in short slices with [`Solver::solve_for`],
+/// and stop after the slice in which another member finished. The portfolio returns once all of them stopped,
+/// so members which cannot be suspended are rejected with [`SuspendUnsupported`] instead of running to the end.
+///
+/// The portfolio fails only when every member failed, with the error of the last one,
+/// except that an unsolvable board is reported as soon as any member finds it.
+/// A portfolio without members, or whose members all panicked, fails with [`SolvingError::NoSolverFinished`]
+pub struct PortfolioSolver {
+    board: OwnedBoard,
+    members: Vec<Member>,
+}
+
+impl PortfolioSolver {
+    #[must_use]
+    pub fn new(board: OwnedBoard) -> Self {
+        Self {
+            board,
+            members: vec![],
+        }
+    }
+
+    /// Adds the solver created by the factory, named in the log when it finds the solution
+    #[must_use]
+    pub fn with_member(
+        mut self,
+        name: impl Into<String>,
+        factory: impl FnOnce(OwnedBoard) -> Box<dyn Solver> + Send + 'static,
+    ) -> Self {
+        self.members.push(Member {
+            name: name.into(),
+            factory: Box::new(factory),
+        });
+        self
+    }
+}
+
+impl Solver for PortfolioSolver {
+    fn solve(self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
+        self.solve_with(&SolverConfig::default())
+    }
+
+    /// Applies the time limit to the whole portfolio. The members do not share their counters,
+    /// so the expansion limit fails with [`ExpansionLimitUnsupported`]
+    fn solve_with(self: Box<Self>, config: &SolverConfig) -> Result<Vec<BoardMove>, SolvingError> {
+        if config.max_expansions.is_some() {
+            return Err(SolvingError::AlgorithmError(Box::new(
+                ExpansionLimitUnsupported,
+            )));
+        }
+        let start = Instant::now();
+        let deadline = config.time_limit.map(|time_limit| start + time_limit);
+        let finished = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+        let member_count = self.members.len();
+        let mut names = Vec::with_capacity(member_count);
+        let mut threads = Vec::with_capacity(member_count);
+
+        for (index, member) in self.members.into_iter().enumerate() {
+            names.push(member.name);
+            let board = self.board.clone();
+            let finished = Arc::clone(&finished);
+            let sender = sender.clone();
+            threads.push(std::thread::spawn(move || {
+                let mut solver = (member.factory)(board);
+                if !solver.is_suspendable() {
+                    let error = SolvingError::AlgorithmError(Box::new(SuspendUnsupported));
+                    let _ = sender.send((index, Err(error)));
+                    return;
+                }
+                loop {
+                    match solver.solve_for(SLICE) {
+                        SolveStep::Done(result) => {
+                            // the portfolio does not wait for the result if it already returned
+                            let _ = sender.send((index, result.map(|report| report.solution)));
+                            return;
+                        }
+                        SolveStep::Pending(pending) if !finished.load(Ordering::Relaxed) => {
+                            solver = pending;
+                        }
+                        SolveStep::Pending(_) => return,
+                    }
+                }
+            }));
+        }
+        drop(sender);
+
+        let result = Self::first_result(&receiver, &names, deadline, start);
+        finished.store(true, Ordering::Relaxed);
+        // every member stops after its current slice
+        for thread in threads {
+            let _ = thread.join();
+        }
+        result
+    }
+}
+
+impl PortfolioSolver {
+    /// Waits for the first solution of the members, or until all of them failed
+    fn first_result(
+        receiver: &mpsc::Receiver<(usize, Result<Vec<BoardMove>, SolvingError>)>,
+        names: &[String],
+        deadline: Option<Instant>,
+        start: Instant,
+    ) -> Result<Vec<BoardMove>, SolvingError> {
+        let mut last_error = SolvingError::NoSolverFinished;
+        for _ in 0..names.len() {
+            let received = deadline.map_or_else(
+                || receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                |deadline| {
+                    receiver.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                },
+            );
+            let (index, result) = match received {
+                Ok(received) => received,
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(SolvingError::TimedOut {
+                        best_partial: vec![],
+                        elapsed: start.elapsed(),
+                    });
+                }
+                // the remaining members panicked
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+            match result {
+                Ok(solution) => {
+                    log::info!("Portfolio solved by {}", names[index]);
+                    return Ok(solution);
+                }
+                Err(e @ SolvingError::UnsolvableBoard(_)) => return Err(e),
+                Err(e) => {
+                    log::debug!("Portfolio member {} failed: {e}", names[index]);
+                    last_error = e;
+                }
+            }
+        }
+        Err(last_error)
+    }
+}
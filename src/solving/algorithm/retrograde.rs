@@ -0,0 +1,524 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::board::{goal_cells, Board, BoardMove, OwnedBoard};
+use crate::solving::algorithm::{Solver, SolvingError};
+use crate::solving::diagnosis::UnsolvableDiagnosis;
+
+/// Largest number of cells of the boards whose state space is enumerated, e.g. 3x3 and 2x4 boards.
+/// The `9!` permutations of the largest ones take a byte each
+pub const MAX_CELLS: usize = 9;
+/// Distance of the states which cannot be reached from the goal
+const UNREACHABLE: u8 = u8::MAX;
+
+const MAGIC: &[u8] = b"15RTB";
+const FORMAT_VERSION: u8 = 1;
+
+/// Tables of the standard goals, keyed by the dimensions of the board
+type StandardTables = HashMap<(u8, u8), Arc<RetrogradeTable>>;
+
+/// Tables of the standard goals, shared by all solvers in the process
+static STANDARD_TABLES: OnceLock<Mutex<StandardTables>> = OnceLock::new();
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RetrogradeError {
+    /// The board has more than [`MAX_CELLS`] cells
+    TooLarge((u8, u8)),
+}
+
+impl Display for RetrogradeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RetrogradeError::TooLarge((rows, columns)) => write!(
+                f,
+                "State space of a {rows}x{columns} board is too large to enumerate, at most {MAX_CELLS} cells are supported"
+            ),
+        }
+    }
+}
+
+impl Error for RetrogradeError {}
+
+/// Exact number of moves required to reach the goal from every state of a small board,
+/// computed by a breadth-first search backwards from the goal over the whole state space.
+///
+/// Every move can be undone, so the states one move before a state are its neighbours.
+/// Besides solving the boards optimally, the table is the ground truth for checking the heuristics
+pub struct RetrogradeTable {
+    dimensions: (u8, u8),
+    /// Goal the distances lead to, in row-major order
+    goal: Vec<u8>,
+    /// Distance of every state, indexed by the rank of the permutation of the cells
+    distances: Box<[u8]>,
+    /// Number of states at every distance from the goal
+    distance_counts: Vec<usize>,
+}
+
+impl RetrogradeTable {
+    /// Computes the table of the goal of the board
+    ///
+    /// # Errors
+    /// Returns error if the board has more than [`MAX_CELLS`] cells
+    pub fn generate(board: &(impl Board + ?Sized)) -> Result<Self, RetrogradeError> {
+        let dimensions = board.dimensions();
+        let goal = goal_cells(board);
+        if goal.len() > MAX_CELLS {
+            return Err(RetrogradeError::TooLarge(dimensions));
+        }
+        let states = (1..=goal.len()).product();
+        let mut distances = vec![UNREACHABLE; states].into_boxed_slice();
+        distances[rank(&goal) as usize] = 0;
+
+        let mut distance_counts = vec![];
+        let mut layer = vec![goal.clone()];
+        while !layer.is_empty() {
+            distance_counts.push(layer.len());
+            let distance = u8::try_from(distance_counts.len()).expect("Distances fit in a byte");
+            let mut next_layer = vec![];
+            for cells in layer {
+                for (_, previous) in neighbours(&cells, dimensions) {
+                    let previous_rank = rank(&previous) as usize;
+                    if distances[previous_rank] == UNREACHABLE {
+                        distances[previous_rank] = distance;
+                        next_layer.push(previous);
+                    }
+                }
+            }
+            layer = next_layer;
+        }
+        log::debug!(
+            "Enumerated {} states of the {}x{} board, the farthest ones are {} moves away",
+            distance_counts.iter().sum::<usize>(),
+            dimensions.0,
+            dimensions.1,
+            distance_counts.len() - 1
+        );
+
+        Ok(Self {
+            dimensions,
+            goal,
+            distances,
+            distance_counts,
+        })
+    }
+
+    /// Returns the table of the standard goal of the given dimensions, stored in [`default_directory`]
+    ///
+    /// # Errors
+    /// Returns error if the board has more than [`MAX_CELLS`] cells
+    pub fn standard(rows: u8, columns: u8) -> Result<Arc<Self>, RetrogradeError> {
+        Self::standard_in(rows, columns, default_directory().as_deref())
+    }
+
+    /// Returns the table of the standard goal of the given dimensions, shared by the whole process.
+    /// On first use it is read from the file in the directory, or computed and written there
+    /// when the file is missing or cannot be read. Without a directory the table is only kept in memory.
+    /// Failing to write the file is only logged, as the table can still be used
+    ///
+    /// # Errors
+    /// Returns error if the board has more than [`MAX_CELLS`] cells
+    pub fn standard_in(
+        rows: u8,
+        columns: u8,
+        directory: Option<&Path>,
+    ) -> Result<Arc<Self>, RetrogradeError> {
+        let path = directory.map(|directory| directory.join(format!("{rows}x{columns}.bin")));
+        let tables = STANDARD_TABLES.get_or_init(Mutex::default);
+        let mut tables = tables.lock().expect("Retrograde tables lock");
+        let table = match tables.get(&(rows, columns)) {
+            Some(table) => Arc::clone(table),
+            None => {
+                let standard_goal = goal_cells(&OwnedBoard::new_solved(rows, columns));
+                let loaded = path.as_ref().and_then(|path| {
+                    File::open(path)
+                        .and_then(|file| Self::read(&mut BufReader::new(file)))
+                        .inspect_err(|e| {
+                            log::debug!("Retrograde table {} not loaded: {e}", path.display());
+                        })
+                        .ok()
+                        .filter(|table| {
+                            table.dimensions == (rows, columns) && table.goal == standard_goal
+                        })
+                });
+                let table = match loaded {
+                    Some(table) => table,
+                    None => Self::generate(&OwnedBoard::new_solved(rows, columns))?,
+                };
+                let table = Arc::new(table);
+                tables.insert((rows, columns), Arc::clone(&table));
+                table
+            }
+        };
+        drop(tables);
+
+        if let Some(path) = path.filter(|path| !path.exists()) {
+            table.save(&path);
+        }
+        Ok(table)
+    }
+
+    /// Writes the table to the file, creating its directory, and logs the failure.
+    ///
+    /// The table is written to a temporary file next to it first, and renamed once complete,
+    /// so that other processes never read a partially written table
+    fn save(&self, path: &Path) {
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(format!(".{}.tmp", std::process::id()));
+        let temporary = PathBuf::from(temporary);
+        let written = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| {
+                let mut writer = BufWriter::new(File::create(&temporary)?);
+                self.write(&mut writer)?;
+                writer.flush()
+            })
+            .and_then(|()| std::fs::rename(&temporary, path));
+        match written {
+            Ok(()) => log::info!("Stored retrograde table in {}", path.display()),
+            Err(e) => {
+                let _ = std::fs::remove_file(&temporary);
+                log::warn!(
+                    "Retrograde table cannot be stored in {}: {e}",
+                    path.display()
+                );
+            }
+        }
+    }
+
+    /// Writes the dimensions, the goal and the distances of the table
+    ///
+    /// # Errors
+    /// Returns error if writing fails
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[FORMAT_VERSION, self.dimensions.0, self.dimensions.1])?;
+        writer.write_all(&self.goal)?;
+        writer.write_all(&self.distances)
+    }
+
+    /// Reads the table written by [`write`](RetrogradeTable::write)
+    ///
+    /// # Errors
+    /// Returns error if reading fails, or the data is not a valid table
+    pub fn read(reader: &mut impl Read) -> io::Result<Self> {
+        let invalid =
+            |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+        let mut read_bytes = |count: usize| -> io::Result<Vec<u8>> {
+            let mut buffer = vec![0; count];
+            reader.read_exact(&mut buffer)?;
+            Ok(buffer)
+        };
+
+        if read_bytes(MAGIC.len())? != MAGIC {
+            return Err(invalid("Not a retrograde table file"));
+        }
+        let header = read_bytes(3)?;
+        let [version, rows, columns] = header[..] else {
+            unreachable!("Exactly 3 bytes were read")
+        };
+        if version != FORMAT_VERSION {
+            return Err(invalid("Unsupported retrograde table version"));
+        }
+        let cells = rows as usize * columns as usize;
+        if cells == 0 || cells > MAX_CELLS {
+            return Err(invalid("Invalid dimensions"));
+        }
+        let goal = read_bytes(cells)?;
+        OwnedBoard::with_cells(rows, columns, goal.clone())
+            .map_err(|_| invalid("Goal is not a valid board"))?;
+        let distances = read_bytes((1..=cells).product())?.into_boxed_slice();
+
+        let mut distance_counts = vec![];
+        for &distance in distances
+            .iter()
+            .filter(|&&distance| distance != UNREACHABLE)
+        {
+            let distance = distance as usize;
+            if distance >= distance_counts.len() {
+                distance_counts.resize(distance + 1, 0);
+            }
+            distance_counts[distance] += 1;
+        }
+        let table = Self {
+            dimensions: (rows, columns),
+            goal,
+            distances,
+            distance_counts,
+        };
+        if !table.has_consistent_layers() {
+            return Err(invalid("Distances do not form layers around the goal"));
+        }
+        Ok(table)
+    }
+
+    /// Checks that the goal is the only state at distance 0, and that the distances of the neighbouring states
+    /// differ by one, so every state but the goal has a neighbour closer to the goal
+    fn has_consistent_layers(&self) -> bool {
+        if self.distance_counts.first() != Some(&1)
+            || self.distance_counts.contains(&0)
+            || self.cells_distance(&self.goal) != Some(0)
+        {
+            return false;
+        }
+        (0..).zip(self.distances.iter()).all(|(state, &distance)| {
+            if distance == UNREACHABLE {
+                return true;
+            }
+            let neighbour_distances: Vec<_> =
+                neighbours(&unrank(state, self.goal.len()), self.dimensions)
+                    .map(|(_, neighbour)| self.cells_distance(&neighbour))
+                    .collect();
+            neighbour_distances
+                .iter()
+                .all(|other| other.is_some_and(|other| other.abs_diff(distance) == 1))
+                && (distance == 0 || neighbour_distances.contains(&Some(distance - 1)))
+        })
+    }
+
+    /// Returns the number of moves required to reach the goal of the table from the board,
+    /// or `None` if the goal cannot be reached from it, or the board has other dimensions
+    #[must_use]
+    pub fn distance(&self, board: &(impl Board + ?Sized)) -> Option<u64> {
+        if board.dimensions() != self.dimensions {
+            return None;
+        }
+        let cells: Vec<u8> = board.iter_cells().map(|(_, value)| value).collect();
+        self.cells_distance(&cells).map(u64::from)
+    }
+
+    /// Returns the shortest solution of the board, found by repeatedly moving to a state closer to the goal,
+    /// or `None` if the goal cannot be reached from it
+    #[must_use]
+    pub fn solution(&self, board: &(impl Board + ?Sized)) -> Option<Vec<BoardMove>> {
+        if board.dimensions() != self.dimensions {
+            return None;
+        }
+        let mut cells: Vec<u8> = board.iter_cells().map(|(_, value)| value).collect();
+        let mut distance = self.cells_distance(&cells)?;
+
+        let mut solution = Vec::with_capacity(distance as usize);
+        while distance > 0 {
+            let (board_move, next) = neighbours(&cells, self.dimensions)
+                .find(|(_, next)| self.cells_distance(next) == Some(distance - 1))
+                .expect("Some neighbour is closer to the goal");
+            solution.push(board_move);
+            cells = next;
+            distance -= 1;
+        }
+        Some(solution)
+    }
+
+    /// Returns the number of states at every distance from the goal, the last one being the farthest
+    #[must_use]
+    pub fn distance_counts(&self) -> &[usize] {
+        &self.distance_counts
+    }
+
+    /// Returns the number of moves required to solve the hardest states
+    #[must_use]
+    pub fn max_distance(&self) -> u64 {
+        self.distance_counts.len() as u64 - 1
+    }
+
+    /// Returns every state from which the goal can be reached, with the goal of the table, and its distance
+    pub fn positions(&self) -> impl Iterator<Item = (OwnedBoard, u64)> + '_ {
+        let (rows, columns) = self.dimensions;
+        let goal = OwnedBoard::with_cells(rows, columns, self.goal.clone())
+            .expect("Goal of the table is a valid board");
+        let is_standard = goal_cells(&OwnedBoard::new_solved(rows, columns)) == self.goal;
+        (0..)
+            .zip(self.distances.iter())
+            .filter(|&(_, &distance)| distance != UNREACHABLE)
+            .map(move |(state, &distance)| {
+                let board = OwnedBoard::with_cells(rows, columns, unrank(state, self.goal.len()))
+                    .expect("Permutation of the cells is a valid board");
+                let board = if is_standard {
+                    board
+                } else {
+                    board
+                        .with_goal(goal.clone())
+                        .expect("Goal has the dimensions of the board")
+                };
+                (board, u64::from(distance))
+            })
+    }
+
+    fn cells_distance(&self, cells: &[u8]) -> Option<u8> {
+        Some(self.distances[rank(cells) as usize]).filter(|&distance| distance != UNREACHABLE)
+    }
+}
+
+/// Returns the standard location of the stored tables, `$XDG_CACHE_HOME/15-puzzle-solver/retrograde`,
+/// next to the registry of the pattern databases
+#[must_use]
+pub fn default_directory() -> Option<PathBuf> {
+    let registry = crate::solving::algorithm::heuristic::registry::default_directory()?;
+    Some(registry.parent()?.join("retrograde"))
+}
+
+/// Packs the permutation of the cells into its rank in the lexicographic order,
+/// which is below `9!` and fits in `u32`
+fn rank(cells: &[u8]) -> u32 {
+    let mut rank = 0;
+    for (index, &cell) in cells.iter().enumerate() {
+        let smaller_after = cells[index + 1..].iter().filter(|&&c| c < cell).count();
+        rank = rank * (cells.len() - index) as u32 + smaller_after as u32;
+    }
+    rank
+}
+
+/// Inverse of [`rank`] for the permutations of `length` cells
+fn unrank(mut rank: u32, length: usize) -> Vec<u8> {
+    let mut digits = vec![0; length];
+    for (index, digit) in digits.iter_mut().enumerate().rev() {
+        let base = (length - index) as u32;
+        *digit = (rank % base) as usize;
+        rank /= base;
+    }
+
+    let mut remaining: Vec<u8> = (0..length as u8).collect();
+    digits
+        .into_iter()
+        .map(|digit| remaining.remove(digit))
+        .collect()
+}
+
+/// Returns the states reachable by a single move, with the moves leading to them
+fn neighbours(
+    cells: &[u8],
+    (rows, columns): (u8, u8),
+) -> impl Iterator<Item = (BoardMove, Vec<u8>)> + '_ {
+    let empty = cells
+        .iter()
+        .position(|&c| c == 0)
+        .expect("Board has an empty cell");
+    let (row, column) = (empty as u8 / columns, empty as u8 % columns);
+    BoardMove::ALL.into_iter().filter_map(move |board_move| {
+        let target = match board_move {
+            BoardMove::Up => row.checked_sub(1).map(|r| (r, column)),
+            BoardMove::Down => (row + 1 < rows).then_some((row + 1, column)),
+            BoardMove::Left => column.checked_sub(1).map(|c| (row, c)),
+            BoardMove::Right => (column + 1 < columns).then_some((row, column + 1)),
+        }?;
+        let mut next = cells.to_vec();
+        next.swap(empty, (target.0 * columns + target.1) as usize);
+        Some((board_move, next))
+    })
+}
+
+/// Optimal solver of the boards with at most [`MAX_CELLS`] cells, which looks the solution up in the [`RetrogradeTable`].
+///
+/// Tables of the standard goals are read from the cache directory, and computed and stored there only
+/// when they are missing, so they are computed once per machine, and shared by all solvers in the process.
+/// Tables of other goals are computed for every solved board
+pub struct RetrogradeSolver {
+    board: OwnedBoard,
+    /// Directory of the stored tables, `None` if the tables are only kept in memory
+    cache_directory: Option<PathBuf>,
+}
+
+impl RetrogradeSolver {
+    /// Creates solver storing the tables in [`default_directory`]
+    #[must_use]
+    pub fn new(board: OwnedBoard) -> Self {
+        Self {
+            board,
+            cache_directory: default_directory(),
+        }
+    }
+
+    /// Stores the tables in the given directory instead of the default one
+    #[must_use]
+    pub fn with_cache_directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.cache_directory = Some(directory.into());
+        self
+    }
+
+    /// Keeps the tables only in memory, computing them once per process
+    #[must_use]
+    pub fn without_cache_directory(mut self) -> Self {
+        self.cache_directory = None;
+        self
+    }
+
+    /// Returns the number of moves of the shortest solution of the board,
+    /// or `None` if the board is unsolvable or has more than [`MAX_CELLS`] cells
+    #[must_use]
+    pub fn distance(&self) -> Option<u64> {
+        self.table().ok()?.distance(&self.board)
+    }
+
+    fn table(&self) -> Result<Arc<RetrogradeTable>, RetrogradeError> {
+        let (rows, columns) = self.board.dimensions();
+        if self.board.goal() == OwnedBoard::new_solved(rows, columns) {
+            RetrogradeTable::standard_in(rows, columns, self.cache_directory.as_deref())
+        } else {
+            RetrogradeTable::generate(&self.board).map(Arc::new)
+        }
+    }
+}
+
+impl Solver for RetrogradeSolver {
+    fn solve(self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
+        let table = self
+            .table()
+            .map_err(|e| SolvingError::AlgorithmError(Box::new(e)))?;
+
+        table.solution(&self.board).ok_or_else(|| {
+            SolvingError::UnsolvableBoard(UnsolvableDiagnosis::diagnose(&self.board))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn written_table_is_read_back() {
+        let table = RetrogradeTable::generate(&OwnedBoard::new_solved(2, 3)).unwrap();
+        let mut bytes = vec![];
+        table.write(&mut bytes).unwrap();
+
+        let read = RetrogradeTable::read(&mut bytes.as_slice()).unwrap();
+
+        assert_eq!(table.distance_counts(), read.distance_counts());
+        assert_eq!(table.goal, read.goal);
+        assert_eq!(table.distances, read.distances);
+        assert!(RetrogradeTable::read(&mut &bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn inconsistent_distances_are_rejected() {
+        let table = RetrogradeTable::generate(&OwnedBoard::new_solved(2, 3)).unwrap();
+        let mut bytes = vec![];
+        table.write(&mut bytes).unwrap();
+        let distances = bytes.len() - table.distances.len();
+        let far = bytes[distances..]
+            .iter()
+            .position(|&distance| distance == 5)
+            .unwrap();
+
+        // the state keeps its layer non-empty, but has no neighbour one move closer
+        bytes[distances + far] = 3;
+
+        assert!(RetrogradeTable::read(&mut bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    fn rank_is_inverted_by_unrank() {
+        const STATES: u32 = 362_880;
+        for state in [0, 1, 4_321, 181_440, STATES - 1] {
+            assert_eq!(state, rank(&unrank(state, 9)));
+        }
+        assert_eq!(0, rank(&[0, 1, 2, 3, 4, 5, 6, 7, 8]));
+        assert_eq!(STATES - 1, rank(&[8, 7, 6, 5, 4, 3, 2, 1, 0]));
+        assert_eq!(5, rank(&[2, 1, 0]));
+    }
+}
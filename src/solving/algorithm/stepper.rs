@@ -0,0 +1,48 @@
+use crate::board::{Board, BoardMove, OwnedBoard};
+use crate::solving::algorithm::SolvingError;
+
+/// Node of the frontier: position of the search together with the path leading to it
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FrontierNode {
+    pub board: OwnedBoard,
+    pub path: Vec<BoardMove>,
+}
+
+impl FrontierNode {
+    #[must_use]
+    pub fn new(board: &(impl Board + ?Sized), path: &[BoardMove]) -> Self {
+        Self {
+            board: OwnedBoard::from_board(board),
+            path: path.to_vec(),
+        }
+    }
+}
+
+/// Outcome of a single step of a [`SearchStepper`]
+#[derive(Debug)]
+pub enum StepEvent {
+    /// The node was taken from the frontier and expanded, pushing the children onto the frontier.
+    /// Children dropped by the pruning rules or the duplicate detection are not listed
+    Expanded {
+        node: FrontierNode,
+        children: Vec<FrontierNode>,
+    },
+    /// The node was taken from the frontier, but not expanded, as its position was already expanded
+    Skipped(FrontierNode),
+    /// The node taken from the frontier satisfies the goal, the search is finished
+    Solved(Vec<BoardMove>),
+    /// The search cannot continue, e.g. the frontier is empty or a limit was reached
+    Failed(SolvingError),
+}
+
+/// Search which can be driven one expansion at a time, e.g. to visualize how it explores the board.
+///
+/// Implemented by the solvers keeping an explicit frontier: BFS, A* and best-first search.
+/// The search is finished once a step returns [`StepEvent::Solved`] or [`StepEvent::Failed`]
+pub trait SearchStepper {
+    /// Takes the next node from the frontier and expands it
+    fn step(&mut self) -> StepEvent;
+
+    /// Returns the nodes waiting for expansion, the next one to be expanded first
+    fn frontier(&self) -> Vec<FrontierNode>;
+}
@@ -0,0 +1,74 @@
+use crate::board::{Board, BoardMove, OwnedBoard};
+use crate::solving::algorithm::heuristic::heuristics::Heuristic;
+
+/// Returns every legal move of the board together with the change of the heuristic value it causes.
+/// Moves with negative change bring the board closer to the goal according to the heuristic,
+/// which makes them the natural hints for a player.
+/// The change is computed incrementally if the heuristic supports it, otherwise both boards are evaluated
+#[must_use]
+pub fn analyze_moves(board: &OwnedBoard, heuristic: &dyn Heuristic) -> Vec<(BoardMove, i64)> {
+    let before = std::cell::OnceCell::new();
+    // a single copy is moved back and forth, to avoid cloning the board for every move
+    let mut board = board.clone();
+    BoardMove::ALL
+        .into_iter()
+        .filter_map(|board_move| {
+            if !board.can_move(board_move) {
+                return None;
+            }
+            if let Some(delta) = heuristic.delta(&board, board_move) {
+                return Some((board_move, delta));
+            }
+            let before = *before.get_or_init(|| heuristic.evaluate(&board) as i64);
+            board.exec_move(board_move);
+            let after = heuristic.evaluate(&board) as i64;
+            board.exec_move(board_move.opposite());
+            Some((board_move, after - before))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, BoardMove, OwnedBoard};
+    use crate::solving::algorithm::heuristic::heuristics::{
+        EnhancedLinearConflict, Heuristic, LinearConflict, ManhattanDistance,
+    };
+
+    use super::analyze_moves;
+
+    #[test]
+    fn reports_heuristic_change_of_every_legal_move() {
+        let board: OwnedBoard = "3 3\n1 2 3\n4 5 6\n7 0 8".parse().unwrap();
+
+        let analysis = analyze_moves(&board, &ManhattanDistance);
+
+        assert_eq!(
+            vec![
+                (BoardMove::Up, 1),
+                (BoardMove::Left, 1),
+                (BoardMove::Right, -1)
+            ],
+            analysis
+        );
+    }
+
+    #[test]
+    fn incremental_and_evaluated_changes_agree() {
+        let board: OwnedBoard = "3 3\n4 1 3\n7 2 5\n8 0 6".parse().unwrap();
+        // linear conflict is updated incrementally, the enhanced one is evaluated on both boards
+        let heuristics: [&dyn Heuristic; 2] = [
+            &LinearConflict::default(),
+            &EnhancedLinearConflict::default(),
+        ];
+
+        for heuristic in heuristics {
+            let before = heuristic.evaluate(&board) as i64;
+            for (board_move, change) in analyze_moves(&board, heuristic) {
+                let mut after = board.clone();
+                after.exec_move(board_move);
+                assert_eq!(heuristic.evaluate(&after) as i64 - before, change);
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+/// Min-priority queue with a bucket for every priority, for values whose priorities are small integers.
+///
+/// Pushing and popping take constant time, apart from skipping the empty buckets below the lowest queued priority.
+/// Values of the same priority are popped from the most recently pushed one
+pub(crate) struct BucketQueue<T> {
+    buckets: Vec<Vec<T>>,
+    /// Index of the lowest bucket which may be non-empty
+    lowest: usize,
+    len: usize,
+}
+
+impl<T> BucketQueue<T> {
+    pub fn new() -> Self {
+        Self {
+            buckets: vec![],
+            lowest: 0,
+            len: 0,
+        }
+    }
+
+    pub fn push(&mut self, priority: u64, value: T) {
+        let priority = usize::try_from(priority).expect("Priority fits in memory");
+        if priority >= self.buckets.len() {
+            self.buckets.resize_with(priority + 1, Vec::new);
+        }
+        self.buckets[priority].push(value);
+        self.lowest = self.lowest.min(priority);
+        self.len += 1;
+    }
+
+    /// Removes the value with the lowest priority
+    pub fn pop(&mut self) -> Option<T> {
+        self.skip_empty();
+        let value = self.buckets.get_mut(self.lowest)?.pop()?;
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Returns the value with the lowest priority without removing it
+    pub fn peek(&self) -> Option<&T> {
+        self.buckets
+            .get(self.lowest..)?
+            .iter()
+            .find_map(|bucket| bucket.last())
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the queued values, from the next one to be popped
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.buckets.iter().flat_map(|bucket| bucket.iter().rev())
+    }
+
+    fn skip_empty(&mut self) {
+        while self
+            .buckets
+            .get(self.lowest)
+            .is_some_and(|bucket| bucket.is_empty())
+        {
+            self.lowest += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pops_lowest_priority_first() {
+        let mut queue = BucketQueue::new();
+        for (priority, value) in [(5, 'a'), (3, 'b'), (8, 'c'), (3, 'd')] {
+            queue.push(priority, value);
+        }
+        assert_eq!(Some('d'), queue.pop());
+
+        queue.push(1, 'e');
+
+        assert_eq!(4, queue.len());
+        assert_eq!(
+            vec![&'e', &'b', &'a', &'c'],
+            queue.values().collect::<Vec<_>>()
+        );
+        let order: Vec<_> = std::iter::from_fn(|| queue.pop()).collect();
+        assert_eq!(vec!['e', 'b', 'a', 'c'], order);
+        assert_eq!(0, queue.len());
+    }
+}
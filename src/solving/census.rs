@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+use std::io::{self, Write};
+
+use crate::board::{Board, BoardMove, OwnedBoard};
+
+/// Number of distinct positions at every distance from a starting position,
+/// i.e. the local growth profile of the state space around it.
+///
+/// A position is counted at the length of the shortest path leading to it,
+/// so the counts can be used as a reference for validating pruning of the solvers
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GrowthProfile {
+    counts: Vec<u64>,
+}
+
+impl GrowthProfile {
+    /// Counts the positions up to `max_depth` moves away from the board with breadth-first search.
+    /// The search stops earlier if the whole state space gets explored
+    #[must_use]
+    pub fn compute(board: &OwnedBoard, max_depth: usize) -> Self {
+        let mut counts = vec![1];
+        // The state graph is bipartite, as every move changes the parity of the empty cell position.
+        // Neighbours of the positions at depth `d` are therefore only at depths `d - 1` and `d + 1`,
+        // so the two most recent layers are enough to recognize the visited positions
+        let mut previous_layer = HashSet::new();
+        let mut current_layer = HashSet::from([board.clone()]);
+
+        while counts.len() <= max_depth {
+            let mut next_layer = HashSet::new();
+            for position in &current_layer {
+                for board_move in BoardMove::ALL {
+                    if !position.can_move(board_move) {
+                        continue;
+                    }
+                    let mut next = position.clone();
+                    next.exec_move(board_move);
+                    if !previous_layer.contains(&next) {
+                        next_layer.insert(next);
+                    }
+                }
+            }
+            if next_layer.is_empty() {
+                break;
+            }
+            counts.push(next_layer.len() as u64);
+            previous_layer = std::mem::replace(&mut current_layer, next_layer);
+        }
+
+        Self { counts }
+    }
+
+    /// Number of positions at every depth, starting with the starting position at depth 0
+    #[must_use]
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// Number of positions within the explored depth
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Writes the profile as CSV with `depth` and `states` columns, preceded by the header
+    ///
+    /// # Errors
+    /// Returns error if writing fails
+    pub fn write_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "depth,states")?;
+        for (depth, count) in self.counts.iter().enumerate() {
+            writeln!(writer, "{depth},{count}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_match_known_eight_puzzle_distribution() {
+        let profile = GrowthProfile::compute(&OwnedBoard::new_solved(3, 3), 12);
+
+        assert_eq!(
+            profile.counts(),
+            [1, 2, 4, 8, 16, 20, 39, 62, 116, 152, 286, 396, 748]
+        );
+    }
+
+    #[test]
+    fn whole_state_space_is_counted() {
+        let profile = GrowthProfile::compute(&OwnedBoard::new_solved(2, 3), 100);
+
+        // half of the permutations of 6 cells are reachable
+        assert_eq!(360, profile.total());
+        assert_eq!(22, profile.counts().len());
+
+        let mut csv = vec![];
+        profile.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(csv.starts_with("depth,states\n0,1\n1,2\n"));
+    }
+}
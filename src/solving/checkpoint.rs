@@ -0,0 +1,340 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io::{self, BufRead, Write};
+
+use crate::board::{parse_moves, Board, BoardMove, OwnedBoard};
+use crate::solving::run_info::RunInfo;
+
+/// State of a suspended search, which can be written to disk and resumed later with [`Solver::resume`].
+///
+/// The checkpoint holds only the state of the search, the solver resuming it has to be created
+/// for the same board with the same heuristic and options
+///
+/// [`Solver::resume`]: crate::solving::algorithm::Solver::resume
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Checkpoint {
+    /// IDA* suspended in the middle of an iteration
+    IterativeAStar {
+        board: OwnedBoard,
+        /// Bound of the suspended iteration
+        bound: u64,
+        /// Lowest cost exceeding the bound found by the iteration so far
+        next_bound: Option<u64>,
+        /// Moves leading to the position the iteration continues from.
+        /// The branches generated before it were already searched
+        path: Vec<BoardMove>,
+    },
+    /// A* suspended between two expansions
+    AStar {
+        board: OwnedBoard,
+        /// Paths of the queued nodes with their path costs
+        open: Vec<(Vec<BoardMove>, u64)>,
+        /// Lowest path cost of every generated position, kept by the duplicate policies
+        best_costs: Vec<(OwnedBoard, u64)>,
+        /// Positions which were already expanded, kept by [`DuplicatePolicy::DecreaseKey`]
+        ///
+        /// [`DuplicatePolicy::DecreaseKey`]: crate::solving::algorithm::heuristic::DuplicatePolicy::DecreaseKey
+        expanded: Vec<OwnedBoard>,
+    },
+}
+
+/// Checkpoint together with the [`RunInfo::identity`] of the run which wrote it,
+/// so that the search is not resumed with another heuristic or other options
+///
+/// [`RunInfo::identity`]: crate::solving::run_info::RunInfo::identity
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RunCheckpoint {
+    pub run: String,
+    pub checkpoint: Checkpoint,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum CheckpointError {
+    /// The solver cannot resume the search of the checkpoint, e.g. it was written by another algorithm
+    Unsupported,
+    /// The checkpoint was written for another board
+    DifferentBoard,
+    /// The checkpoint was written by a run with other options, described by the contained identity
+    DifferentRun(String),
+}
+
+impl Display for CheckpointError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckpointError::Unsupported => {
+                write!(f, "Solver cannot resume the search of the checkpoint")
+            }
+            CheckpointError::DifferentBoard => {
+                write!(f, "Checkpoint was written for another board")
+            }
+            CheckpointError::DifferentRun(run) => {
+                write!(f, "Checkpoint was written by another run: {run}")
+            }
+        }
+    }
+}
+
+impl Error for CheckpointError {}
+
+impl RunCheckpoint {
+    #[must_use]
+    pub fn new(run: &RunInfo, checkpoint: Checkpoint) -> Self {
+        Self {
+            run: run.identity(),
+            checkpoint,
+        }
+    }
+
+    /// Returns the checkpoint if it was written by a run with the same identity
+    ///
+    /// # Errors
+    /// Returns [`CheckpointError::DifferentRun`] if the identities differ
+    pub fn into_checkpoint(self, run: &RunInfo) -> Result<Checkpoint, CheckpointError> {
+        if self.run == run.identity() {
+            Ok(self.checkpoint)
+        } else {
+            Err(CheckpointError::DifferentRun(self.run))
+        }
+    }
+
+    /// Writes the identity of the run in the first line, followed by the checkpoint
+    ///
+    /// # Errors
+    /// Returns error if writing fails
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        writeln!(writer, "run {}", self.run)?;
+        self.checkpoint.write(writer)
+    }
+
+    /// Reads the checkpoint written by [`write`](RunCheckpoint::write)
+    ///
+    /// # Errors
+    /// Returns error if reading fails, or the checkpoint is malformed
+    pub fn read(mut reader: impl BufRead) -> io::Result<Self> {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let run = line
+            .trim_end()
+            .strip_prefix("run ")
+            .ok_or_else(|| invalid(line.trim_end()))?
+            .to_string();
+        let checkpoint = Checkpoint::read(reader)?;
+        Ok(Self { run, checkpoint })
+    }
+}
+
+impl Checkpoint {
+    #[must_use]
+    pub fn board(&self) -> &OwnedBoard {
+        match self {
+            Checkpoint::IterativeAStar { board, .. } | Checkpoint::AStar { board, .. } => board,
+        }
+    }
+
+    /// Writes the checkpoint as lines of a key followed by its values,
+    /// starting with the line naming the algorithm
+    ///
+    /// # Errors
+    /// Returns error if writing fails
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        match self {
+            Checkpoint::IterativeAStar {
+                board,
+                bound,
+                next_bound,
+                path,
+            } => {
+                writeln!(writer, "ida")?;
+                writeln!(writer, "board {}", board_fields(board))?;
+                writeln!(writer, "bound {bound}")?;
+                if let Some(next_bound) = next_bound {
+                    writeln!(writer, "next_bound {next_bound}")?;
+                }
+                writeln!(writer, "path {}", moves_field(path))?;
+            }
+            Checkpoint::AStar {
+                board,
+                open,
+                best_costs,
+                expanded,
+            } => {
+                writeln!(writer, "astar")?;
+                writeln!(writer, "board {}", board_fields(board))?;
+                for (path, path_cost) in open {
+                    writeln!(writer, "open {path_cost} {}", moves_field(path))?;
+                }
+                for (board, path_cost) in best_costs {
+                    writeln!(writer, "cost {path_cost} {}", board_fields(board))?;
+                }
+                for board in expanded {
+                    writeln!(writer, "expanded {}", board_fields(board))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the checkpoint written by [`write`](Checkpoint::write)
+    ///
+    /// # Errors
+    /// Returns error if reading fails, or the checkpoint is malformed
+    pub fn read(reader: impl BufRead) -> io::Result<Self> {
+        let mut lines = reader.lines();
+        let kind = lines.next().transpose()?.unwrap_or_default();
+        let mut board = None;
+        let (mut bound, mut next_bound, mut path) = (None, None, vec![]);
+        let (mut open, mut best_costs, mut expanded) = (vec![], vec![], vec![]);
+        for line in lines {
+            let line = line?;
+            let (key, values) = line.split_once(' ').unwrap_or((&line, ""));
+            match key {
+                "board" => board = Some(parse_board(values)?),
+                "bound" => bound = Some(parse_number(values)?),
+                "next_bound" => next_bound = Some(parse_number(values)?),
+                "path" => path = parse_moves_field(values)?,
+                "open" => {
+                    let (path_cost, path) = values.split_once(' ').ok_or_else(|| invalid(&line))?;
+                    open.push((parse_moves_field(path)?, parse_number(path_cost)?));
+                }
+                "cost" => {
+                    let (path_cost, board) =
+                        values.split_once(' ').ok_or_else(|| invalid(&line))?;
+                    best_costs.push((parse_board(board)?, parse_number(path_cost)?));
+                }
+                "expanded" => expanded.push(parse_board(values)?),
+                _ => return Err(invalid(&line)),
+            }
+        }
+        let board = board.ok_or_else(|| invalid("missing board"))?;
+        match kind.as_str() {
+            "ida" => Ok(Checkpoint::IterativeAStar {
+                board,
+                bound: bound.ok_or_else(|| invalid("missing bound"))?,
+                next_bound,
+                path,
+            }),
+            "astar" => Ok(Checkpoint::AStar {
+                board,
+                open,
+                best_costs,
+                expanded,
+            }),
+            _ => Err(invalid(&kind)),
+        }
+    }
+}
+
+fn invalid(line: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid checkpoint entry '{line}'"),
+    )
+}
+
+/// Writes the dimensions and the cells of the board in a single line
+fn board_fields(board: &OwnedBoard) -> String {
+    let (rows, columns) = board.dimensions();
+    let cells: Vec<_> = board
+        .iter_cells()
+        .map(|(_, value)| value.to_string())
+        .collect();
+    format!("{rows} {columns} {}", cells.join(" "))
+}
+
+fn parse_board(fields: &str) -> io::Result<OwnedBoard> {
+    let numbers = fields
+        .split_whitespace()
+        .map(str::parse)
+        .collect::<Result<Vec<u8>, _>>()
+        .map_err(|_| invalid(fields))?;
+    let [rows, columns, ref cells @ ..] = numbers[..] else {
+        return Err(invalid(fields));
+    };
+    OwnedBoard::with_cells(rows, columns, cells.to_vec()).map_err(|_| invalid(fields))
+}
+
+fn parse_number(field: &str) -> io::Result<u64> {
+    field.trim().parse().map_err(|_| invalid(field))
+}
+
+/// Writes the moves as letters, or `-` if there are none
+fn moves_field(moves: &[BoardMove]) -> String {
+    if moves.is_empty() {
+        return "-".to_string();
+    }
+    moves.iter().map(ToString::to_string).collect()
+}
+
+fn parse_moves_field(field: &str) -> io::Result<Vec<BoardMove>> {
+    match field.trim() {
+        "-" => Ok(vec![]),
+        moves => parse_moves(moves).map_err(|_| invalid(field)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::BoardMove::*;
+    use crate::solving::algorithm::SolverConfig;
+
+    use super::*;
+
+    fn round_trip(checkpoint: &Checkpoint) -> Checkpoint {
+        let mut buffer = vec![];
+        checkpoint.write(&mut buffer).unwrap();
+        Checkpoint::read(buffer.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn written_checkpoints_are_read_back() {
+        let board = OwnedBoard::from_scramble(3, 3, &[Up, Left]).unwrap();
+        let ida = Checkpoint::IterativeAStar {
+            board: board.clone(),
+            bound: 12,
+            next_bound: Some(14),
+            path: vec![Right, Down],
+        };
+        let astar = Checkpoint::AStar {
+            board: board.clone(),
+            open: vec![(vec![], 0), (vec![Right], 1)],
+            best_costs: vec![(board.clone(), 0)],
+            expanded: vec![board],
+        };
+
+        assert_eq!(ida, round_trip(&ida));
+        assert_eq!(astar, round_trip(&astar));
+    }
+
+    #[test]
+    fn checkpoint_of_another_run_is_rejected() {
+        let board = OwnedBoard::from_scramble(3, 3, &[Up, Left]).unwrap();
+        let config = SolverConfig::default();
+        let run = RunInfo::new("ida", &board, &config).with_heuristic("MD");
+        let checkpoint = Checkpoint::IterativeAStar {
+            board: board.clone(),
+            bound: 2,
+            next_bound: None,
+            path: vec![],
+        };
+        let mut buffer = vec![];
+        RunCheckpoint::new(&run, checkpoint.clone())
+            .write(&mut buffer)
+            .unwrap();
+
+        let read = RunCheckpoint::read(buffer.as_slice()).unwrap();
+        assert_eq!(Ok(checkpoint), read.clone().into_checkpoint(&run));
+        let weighted = run.with_parameter("weight", 2);
+        assert!(matches!(
+            read.into_checkpoint(&weighted),
+            Err(CheckpointError::DifferentRun(_))
+        ));
+    }
+
+    #[test]
+    fn malformed_checkpoint_is_rejected() {
+        assert!(Checkpoint::read("ida\nbound 3".as_bytes()).is_err());
+        assert!(Checkpoint::read("ida\nboard 2 2 1 2 3 0".as_bytes()).is_err());
+        assert!(Checkpoint::read("dfs\nboard 2 2 1 2 3 0".as_bytes()).is_err());
+        assert!(Checkpoint::read("astar\nboard 2 2 1 2 3 0\nopen 1 X".as_bytes()).is_err());
+    }
+}
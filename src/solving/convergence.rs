@@ -0,0 +1,153 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Moment at which the solver first reached a new lowest heuristic value
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ConvergencePoint {
+    /// Number of the board being solved, starting from 1
+    pub board: usize,
+    /// Number of nodes of the board evaluated up to and including this one
+    pub nodes: u64,
+    pub elapsed: Duration,
+    pub heuristic_value: u64,
+}
+
+#[derive(Default)]
+struct ConvergenceState {
+    /// Number of the current board, counted from 0 until the first one is started
+    board: usize,
+    start: Option<Instant>,
+    nodes: u64,
+    best: Option<u64>,
+    points: Vec<ConvergencePoint>,
+}
+
+/// Records how the lowest heuristic value seen by a solver decreases over time.
+/// Only improvements are stored, so the log stays small even for long runs.
+///
+/// The log is a shared handle: clone it before passing to the solver to read it after solving
+#[derive(Clone, Default)]
+pub struct ConvergenceLog {
+    state: Arc<Mutex<ConvergenceState>>,
+}
+
+impl ConvergenceLog {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers evaluation of a node with the given heuristic value
+    pub fn record(&self, heuristic_value: u64) {
+        let mut state = self.state.lock().expect("Convergence log lock");
+        let start = *state.start.get_or_insert_with(Instant::now);
+        state.nodes += 1;
+        state.board = state.board.max(1);
+
+        let improved = state.best.is_none_or(|best| heuristic_value < best);
+        if improved {
+            state.best = Some(heuristic_value);
+            let point = ConvergencePoint {
+                board: state.board,
+                nodes: state.nodes,
+                elapsed: start.elapsed(),
+                heuristic_value,
+            };
+            state.points.push(point);
+        }
+    }
+
+    /// Starts the log of another board of a batch: the clock, the node count and the lowest value start over,
+    /// and the following points are recorded with the next board number
+    pub fn next_board(&self) {
+        let mut state = self.state.lock().expect("Convergence log lock");
+        state.board = state.board.max(1) + 1;
+        state.start = None;
+        state.nodes = 0;
+        state.best = None;
+    }
+
+    #[must_use]
+    pub fn points(&self) -> Vec<ConvergencePoint> {
+        self.state
+            .lock()
+            .expect("Convergence log lock")
+            .points
+            .clone()
+    }
+
+    /// Writes the recorded points as CSV with a header row
+    ///
+    /// # Errors
+    /// Returns error if writing fails
+    pub fn write_csv(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        writeln!(writer, "board,nodes,elapsed_us,heuristic")?;
+        for point in self.points() {
+            writeln!(
+                writer,
+                "{},{},{},{}",
+                point.board,
+                point.nodes,
+                point.elapsed.as_micros(),
+                point.heuristic_value
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_only_improvements() {
+        let log = ConvergenceLog::new();
+        for h in [10, 12, 9, 9, 11, 4] {
+            log.record(h);
+        }
+
+        let summary: Vec<_> = log
+            .points()
+            .iter()
+            .map(|p| (p.nodes, p.heuristic_value))
+            .collect();
+        assert_eq!(vec![(1, 10), (3, 9), (6, 4)], summary);
+    }
+
+    #[test]
+    fn writes_csv() {
+        let log = ConvergenceLog::new();
+        log.record(3);
+        log.record(1);
+
+        let mut output = vec![];
+        log.write_csv(&mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(3, lines.len());
+        assert_eq!("board,nodes,elapsed_us,heuristic", lines[0]);
+        assert!(lines[2].starts_with("1,2,") && lines[2].ends_with(",1"));
+    }
+
+    #[test]
+    fn next_board_starts_over() {
+        let log = ConvergenceLog::new();
+        for h in [10, 4] {
+            log.record(h);
+        }
+        log.next_board();
+        for h in [8, 9, 6] {
+            log.record(h);
+        }
+
+        let summary: Vec<_> = log
+            .points()
+            .iter()
+            .map(|p| (p.board, p.nodes, p.heuristic_value))
+            .collect();
+        assert_eq!(vec![(1, 1, 10), (1, 2, 4), (2, 1, 8), (2, 3, 6)], summary);
+    }
+}
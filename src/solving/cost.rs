@@ -0,0 +1,104 @@
+use crate::board::{Board, BoardMove};
+use crate::solving::movegen::MoveSequence;
+
+/// Cost of executing moves, depending on the tile that gets moved
+pub trait CostModel {
+    fn tile_cost(&self, tile: u8) -> u64;
+}
+
+/// Every move costs the same, so the cost of a solution is its length
+#[derive(Default, Clone, Copy)]
+pub struct UnitCost;
+
+impl CostModel for UnitCost {
+    fn tile_cost(&self, _tile: u8) -> u64 {
+        1
+    }
+}
+
+/// Each tile has its own cost of moving it by a single cell
+#[derive(Clone, Debug)]
+pub struct TileCosts {
+    /// Cost of every tile, indexed by the tile value
+    costs: Box<[u64]>,
+}
+
+impl TileCosts {
+    /// Creates cost model where tile `i` costs `costs[i - 1]`.
+    /// Tiles without the specified cost have unit cost
+    #[must_use]
+    pub fn new(costs: impl IntoIterator<Item = u64>) -> Self {
+        Self {
+            costs: std::iter::once(0).chain(costs).collect(),
+        }
+    }
+}
+
+impl CostModel for TileCosts {
+    fn tile_cost(&self, tile: u8) -> u64 {
+        self.costs.get(tile as usize).copied().unwrap_or(1)
+    }
+}
+
+/// Returns the tile which would be moved by the empty cell moving in the given direction
+fn moved_tile(board: &impl Board, (row, column): (u8, u8), board_move: BoardMove) -> u8 {
+    match board_move {
+        BoardMove::Up => board.at(row - 1, column),
+        BoardMove::Down => board.at(row + 1, column),
+        BoardMove::Left => board.at(row, column - 1),
+        BoardMove::Right => board.at(row, column + 1),
+    }
+}
+
+/// Returns the cost of executing the move sequence on the board, without modifying it
+pub(crate) fn sequence_cost(
+    cost_model: &dyn CostModel,
+    board: &impl Board,
+    move_sequence: MoveSequence,
+) -> u64 {
+    let empty_pos = board.empty_cell_pos();
+    match move_sequence {
+        MoveSequence::Single(m) => cost_model.tile_cost(moved_tile(board, empty_pos, m)),
+        MoveSequence::Double(fst, snd) => {
+            let first_tile = moved_tile(board, empty_pos, fst);
+            // the second move never returns to the original empty cell,
+            // so the tile it moves has not been displaced by the first move
+            let (row, column) = empty_pos;
+            let after_first = match fst {
+                BoardMove::Up => (row - 1, column),
+                BoardMove::Down => (row + 1, column),
+                BoardMove::Left => (row, column - 1),
+                BoardMove::Right => (row, column + 1),
+            };
+            let second_tile = moved_tile(board, after_first, snd);
+            cost_model.tile_cost(first_tile) + cost_model.tile_cost(second_tile)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{BoardMove, OwnedBoard};
+    use crate::solving::movegen::MoveSequence;
+
+    use super::*;
+
+    #[test]
+    fn sequence_cost_sums_costs_of_moved_tiles() {
+        let board: OwnedBoard = "3 3\n1 2 3\n4 5 6\n7 8 0".parse().unwrap();
+        let costs = TileCosts::new([1, 2, 3, 4, 5, 6, 7, 8]);
+
+        assert_eq!(
+            6,
+            sequence_cost(&costs, &board, MoveSequence::Single(BoardMove::Up))
+        );
+        assert_eq!(
+            11,
+            sequence_cost(
+                &costs,
+                &board,
+                MoveSequence::Double(BoardMove::Up, BoardMove::Left)
+            )
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use std::fmt::{Display, Formatter};
+
+use crate::board::Board;
+use crate::solving::parity::{empty_cell_distance_parity, permutation_parity, Parity};
+
+/// Explanation of why the board cannot reach its goal configuration.
+///
+/// Every move changes both the parity of the permutation of the cells and the parity of the distance
+/// of the empty cell from its goal position, so the board is solvable only if the two parities are equal.
+/// Swapping any two tiles changes only the first one, which is often the result of a mistyped board
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct UnsolvableDiagnosis {
+    /// Parity of the permutation of the cells relative to the goal configuration
+    pub permutation_parity: Parity,
+    /// Parity of the distance of the empty cell from its goal position
+    pub blank_distance_parity: Parity,
+    /// Pair of tiles whose swap makes the board solvable, chosen to put the most tiles in their goal positions
+    pub suggested_swap: Option<(u8, u8)>,
+}
+
+impl UnsolvableDiagnosis {
+    /// Returns the diagnosis of the board, or `None` if it can reach its goal configuration
+    #[must_use]
+    pub fn diagnose(board: &(impl Board + ?Sized)) -> Option<Self> {
+        let columns = board.columns() as usize;
+        let flat_goal_index = |tile: u8| {
+            let (row, column) = board.goal_position(tile);
+            row as usize * columns + column as usize
+        };
+
+        let tiles: Vec<_> = board.iter_cells().map(|(_, tile)| tile).collect();
+        let permutation: Vec<_> = tiles.iter().map(|&tile| flat_goal_index(tile)).collect();
+        let permutation_parity = permutation_parity(&permutation);
+        let blank_distance_parity =
+            empty_cell_distance_parity(board.empty_cell_pos(), board.goal_position(0));
+        if permutation_parity == blank_distance_parity {
+            return None;
+        }
+
+        // change in the number of tiles in their goal positions after swapping the tiles at the indices
+        let gain = |first: usize, second: usize| {
+            let placed = |tile: u8, index: usize| i8::from(flat_goal_index(tile) == index);
+            let (a, b) = (tiles[first], tiles[second]);
+            placed(a, second) + placed(b, first) - placed(a, first) - placed(b, second)
+        };
+        let mut best: Option<(i8, (u8, u8))> = None;
+        for first in 0..tiles.len() {
+            for second in first + 1..tiles.len() {
+                if tiles[first] == 0 || tiles[second] == 0 {
+                    continue;
+                }
+                let gain = gain(first, second);
+                if best.is_none_or(|(best_gain, _)| gain > best_gain) {
+                    let (a, b) = (tiles[first], tiles[second]);
+                    best = Some((gain, (a.min(b), a.max(b))));
+                }
+            }
+        }
+
+        Some(Self {
+            permutation_parity,
+            blank_distance_parity,
+            suggested_swap: best.map(|(_, swap)| swap),
+        })
+    }
+}
+
+fn parity_name(parity: Parity) -> &'static str {
+    match parity {
+        Parity::Even => "even",
+        Parity::Odd => "odd",
+    }
+}
+
+impl Display for UnsolvableDiagnosis {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Board is unsolvable: the cells are an {} permutation of the goal, \
+            but the empty cell is an {} number of moves away from its goal position",
+            parity_name(self.permutation_parity),
+            parity_name(self.blank_distance_parity)
+        )?;
+        if let Some((first, second)) = self.suggested_swap {
+            write!(
+                f,
+                ". Swapping tiles {first} and {second} would make it solvable"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::OwnedBoard;
+    use crate::solving::parity::Parity;
+
+    use super::UnsolvableDiagnosis;
+
+    #[test]
+    fn solvable_board_has_no_diagnosis() {
+        let board: OwnedBoard = "3 3\n1 2 3\n4 5 6\n7 0 8".parse().unwrap();
+
+        assert_eq!(None, UnsolvableDiagnosis::diagnose(&board));
+    }
+
+    #[test]
+    fn suggests_swapping_transposed_tiles() {
+        let board: OwnedBoard = "4 4\n1 2 3 4\n5 6 7 8\n9 10 11 12\n13 15 14 0"
+            .parse()
+            .unwrap();
+
+        let diagnosis = UnsolvableDiagnosis::diagnose(&board).unwrap();
+
+        assert_eq!(Parity::Odd, diagnosis.permutation_parity);
+        assert_eq!(Parity::Even, diagnosis.blank_distance_parity);
+        assert_eq!(Some((14, 15)), diagnosis.suggested_swap);
+        assert!(diagnosis.to_string().contains("Swapping tiles 14 and 15"));
+    }
+
+    #[test]
+    fn diagnosis_is_relative_to_the_goal() {
+        let board: OwnedBoard = "2 2\n2 1\n3 0".parse().unwrap();
+        let goal = board.clone();
+
+        assert!(UnsolvableDiagnosis::diagnose(&board).is_some());
+        assert_eq!(
+            None,
+            UnsolvableDiagnosis::diagnose(&board.with_goal(goal).unwrap())
+        );
+    }
+}
@@ -0,0 +1,279 @@
+use crate::board::{Board, OwnedBoard};
+use crate::solving::algorithm::heuristic::heuristics::{
+    manhattan_distance, Heuristic, ManhattanDistance,
+};
+use crate::solving::diagnosis::UnsolvableDiagnosis;
+use crate::solving::parity::{
+    empty_cell_distance_parity, BoardGoalParity, EmptyCellParity, Parity, ParityProvider,
+};
+use crate::solving::{is_reachable, is_solvable};
+
+/// Describes the set of positions that a solver is trying to reach
+pub trait Goal {
+    /// Checks if the board is in one of the goal positions
+    fn is_reached(&self, board: &dyn Board) -> bool;
+
+    /// Lower bound on the number of moves required to reach any of the goal positions
+    fn estimate(&self, board: &dyn Board) -> u64;
+
+    /// Checks if any of the goal positions can be reached from the given board.
+    /// Solvers use it to avoid exhausting the whole state space.
+    fn is_reachable_from(&self, _board: &dyn Board) -> bool {
+        true
+    }
+
+    /// Explains why none of the goal positions can be reached from the board,
+    /// returns `None` if the goal cannot tell it
+    fn diagnose_unreachable(&self, _board: &dyn Board) -> Option<UnsolvableDiagnosis> {
+        None
+    }
+
+    /// Source of the parity of the number of moves to the goal, used by the move generator to pair moves.
+    /// The parity is known only when all of the goal positions have it the same
+    fn parity_provider(&self) -> Option<Box<dyn ParityProvider>> {
+        None
+    }
+}
+
+/// The standard goal: all of the cells are in order, with the empty cell at the end
+#[derive(Default)]
+pub struct SolvedState;
+
+impl Goal for SolvedState {
+    fn is_reached(&self, board: &dyn Board) -> bool {
+        board.is_solved()
+    }
+
+    fn estimate(&self, board: &dyn Board) -> u64 {
+        ManhattanDistance.evaluate(board)
+    }
+
+    fn is_reachable_from(&self, board: &dyn Board) -> bool {
+        is_solvable(board)
+    }
+
+    fn diagnose_unreachable(&self, board: &dyn Board) -> Option<UnsolvableDiagnosis> {
+        UnsolvableDiagnosis::diagnose(board)
+    }
+
+    fn parity_provider(&self) -> Option<Box<dyn ParityProvider>> {
+        Some(Box::new(BoardGoalParity))
+    }
+}
+
+/// Goal that is reached when the board is equal to any of the provided boards
+pub struct GoalSet {
+    goals: Vec<OwnedBoard>,
+    /// For each goal, position of every cell value
+    positions: Vec<Box<[(u8, u8)]>>,
+}
+
+impl GoalSet {
+    #[must_use]
+    pub fn new(goals: Vec<OwnedBoard>) -> Self {
+        let positions = goals
+            .iter()
+            .map(|goal| {
+                let mut positions = vec![(0, 0); goal.iter_cells().len()];
+                for (position, value) in goal.iter_cells() {
+                    positions[value as usize] = position;
+                }
+                positions.into_boxed_slice()
+            })
+            .collect();
+        Self { goals, positions }
+    }
+
+    fn matches(goal: &OwnedBoard, board: &dyn Board) -> bool {
+        goal.dimensions() == board.dimensions() && goal.iter_cells().eq(board.iter_cells())
+    }
+}
+
+impl Goal for GoalSet {
+    fn is_reached(&self, board: &dyn Board) -> bool {
+        self.goals.iter().any(|goal| Self::matches(goal, board))
+    }
+
+    fn estimate(&self, board: &dyn Board) -> u64 {
+        // minimum over all goals of the manhattan distance to that goal
+        self.goals
+            .iter()
+            .zip(&self.positions)
+            .filter(|(goal, _)| goal.dimensions() == board.dimensions())
+            .map(|(_, positions)| {
+                board
+                    .iter_cells()
+                    .filter(|&(_, value)| value != 0)
+                    .map(|(position, value)| {
+                        manhattan_distance(position, positions[value as usize])
+                    })
+                    .sum::<u64>()
+            })
+            .min()
+            .unwrap_or(0)
+    }
+
+    fn is_reachable_from(&self, board: &dyn Board) -> bool {
+        self.goals.iter().any(|goal| is_reachable(board, goal))
+    }
+
+    fn parity_provider(&self) -> Option<Box<dyn ParityProvider>> {
+        // every move changes the parity of the empty cell position,
+        // so it determines the parity of the number of moves if it is the same in all of the goals
+        let (first, others) = self.goals.split_first()?;
+        let target = first.empty_cell_pos();
+        let same_parity = others.iter().all(|goal| {
+            goal.dimensions() == first.dimensions()
+                && empty_cell_distance_parity(goal.empty_cell_pos(), target) == Parity::Even
+        });
+        same_parity.then(|| Box::new(EmptyCellParity { target }) as Box<dyn ParityProvider>)
+    }
+}
+
+/// Goal that is reached when all of the given tiles are at their solved positions,
+/// regardless of the placement of the other tiles
+pub struct TilesPlaced {
+    tiles: Vec<u8>,
+}
+
+impl TilesPlaced {
+    #[must_use]
+    pub fn new(tiles: impl IntoIterator<Item = u8>) -> Self {
+        let mut tiles: Vec<u8> = tiles.into_iter().filter(|&tile| tile != 0).collect();
+        tiles.sort_unstable();
+        tiles.dedup();
+        Self { tiles }
+    }
+
+    fn tile_positions<'a>(
+        &'a self,
+        board: &'a dyn Board,
+    ) -> impl Iterator<Item = ((u8, u8), (u8, u8))> + 'a {
+        board.iter_cells().filter_map(move |(position, value)| {
+            self.tiles
+                .binary_search(&value)
+                .ok()
+                .map(|_| (position, board.goal_position(value)))
+        })
+    }
+}
+
+impl Goal for TilesPlaced {
+    fn is_reached(&self, board: &dyn Board) -> bool {
+        self.tile_positions(board)
+            .all(|(actual, expected)| actual == expected)
+    }
+
+    fn estimate(&self, board: &dyn Board) -> u64 {
+        self.tile_positions(board)
+            .map(|(actual, expected)| manhattan_distance(actual, expected))
+            .sum()
+    }
+
+    fn is_reachable_from(&self, board: &dyn Board) -> bool {
+        let (rows, columns) = board.dimensions();
+        let tile_count = rows as usize * columns as usize - 1;
+        // With two or more free tiles their order can be swapped to satisfy the parity invariant,
+        // so only when (almost) every tile is constrained the goal may be unreachable
+        if self.tiles.len() + 1 < tile_count {
+            return true;
+        }
+        if is_solvable(board) {
+            return true;
+        }
+        if self.tiles.len() == tile_count {
+            return false;
+        }
+        // The only free tile may also end up swapped with the empty cell.
+        // This swap changes the parity invariant only if the free tile's solved position
+        // is an even distance away from the bottom-right corner.
+        let Some(free_tile) =
+            (1..=tile_count as u8).find(|tile| self.tiles.binary_search(tile).is_err())
+        else {
+            return false;
+        };
+        manhattan_distance(board.goal_position(free_tile), board.goal_position(0)).is_multiple_of(2)
+    }
+}
+
+/// Goal defined by an arbitrary predicate.
+/// No estimate of the remaining distance is available, so heuristic solvers degrade to uniform cost search
+pub struct Predicate<F>(pub F);
+
+impl<F: Fn(&dyn Board) -> bool> Goal for Predicate<F> {
+    fn is_reached(&self, board: &dyn Board) -> bool {
+        (self.0)(board)
+    }
+
+    fn estimate(&self, _board: &dyn Board) -> u64 {
+        0
+    }
+}
+
+/// Adapts the goal estimate to be used as a heuristic
+pub(crate) struct GoalEstimate(pub std::rc::Rc<dyn Goal>);
+
+impl Heuristic for GoalEstimate {
+    fn evaluate(&self, board: &dyn Board) -> u64 {
+        self.0.estimate(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, BoardMove, OwnedBoard};
+
+    use super::*;
+
+    const SOLVED_INPUT: &str = r"3 3
+1 2 3
+4 5 6
+7 8 0
+";
+
+    #[test]
+    fn goal_set_is_reached_by_any_member() {
+        let solved: OwnedBoard = SOLVED_INPUT.parse().unwrap();
+        let mut other = solved.clone();
+        other.exec_move(BoardMove::Up);
+        let goal = GoalSet::new(vec![solved.clone(), other.clone()]);
+
+        assert!(goal.is_reached(&solved));
+        assert!(goal.is_reached(&other));
+
+        other.exec_move(BoardMove::Left);
+        assert!(!goal.is_reached(&other));
+        assert_eq!(1, goal.estimate(&other));
+    }
+
+    #[test]
+    fn goal_set_estimate_is_minimum_over_goals() {
+        let solved: OwnedBoard = SOLVED_INPUT.parse().unwrap();
+        let mut far = solved.clone();
+        for m in [
+            BoardMove::Up,
+            BoardMove::Up,
+            BoardMove::Left,
+            BoardMove::Left,
+        ] {
+            far.exec_move(m);
+        }
+        let goal = GoalSet::new(vec![far, solved.clone()]);
+
+        assert_eq!(0, goal.estimate(&solved));
+    }
+
+    #[test]
+    fn tiles_placed_ignores_other_tiles() {
+        let board: OwnedBoard = r"3 3
+1 2 3
+5 0 6
+4 8 7"
+            .parse()
+            .unwrap();
+
+        assert!(TilesPlaced::new(1..=3).is_reached(&board));
+        assert!(!TilesPlaced::new(1..=4).is_reached(&board));
+        assert_eq!(1, TilesPlaced::new(1..=4).estimate(&board));
+    }
+}
@@ -0,0 +1,192 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use crate::board::{Board, BoardMove, MoveError, OwnedBoard};
+use crate::solving::algorithm::heuristic::astar::IterativeAStarSolver;
+use crate::solving::algorithm::heuristic::heuristics::Heuristic;
+use crate::solving::algorithm::{Solver, SolvingError};
+
+/// Comparison of a candidate solution with the optimal one
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradeReport {
+    pub solution_length: usize,
+    /// Length of the optimal solution, or its lower bound if `is_exact` is false
+    pub optimal_length: usize,
+    pub is_exact: bool,
+}
+
+impl GradeReport {
+    /// Number of moves above the optimal solution (or above its lower bound),
+    /// zero if the report claims an optimal length longer than the solution
+    #[must_use]
+    pub fn overhead(&self) -> usize {
+        self.solution_length.saturating_sub(self.optimal_length)
+    }
+
+    /// Ratio of the solution length to the optimal length
+    #[must_use]
+    pub fn ratio(&self) -> f64 {
+        if self.optimal_length == 0 {
+            if self.solution_length == 0 {
+                1.0
+            } else {
+                f64::INFINITY
+            }
+        } else {
+            self.solution_length as f64 / self.optimal_length as f64
+        }
+    }
+}
+
+impl Display for GradeReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let optimal_label = if self.is_exact {
+            "Optimal length"
+        } else {
+            "Optimal length (lower bound)"
+        };
+        writeln!(f, "Solution length: {}", self.solution_length)?;
+        writeln!(f, "{optimal_label}: {}", self.optimal_length)?;
+        write!(f, "Overhead: {} ({:.2}x)", self.overhead(), self.ratio())
+    }
+}
+
+#[derive(Debug)]
+pub enum GradingError {
+    /// A move of the solution cannot be executed, [`MoveError::IllegalMove`] tells which one
+    IllegalMove(MoveError),
+    /// All of the moves were executed, but the board is not solved
+    NotSolved,
+    SolvingError(SolvingError),
+}
+
+impl Display for GradingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GradingError::IllegalMove(err) => write!(f, "Illegal move: {err}"),
+            GradingError::NotSolved => write!(f, "The solution does not solve the board"),
+            GradingError::SolvingError(err) => write!(f, "Unable to find optimal solution: {err}"),
+        }
+    }
+}
+
+impl Error for GradingError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GradingError::IllegalMove(err) => Some(err),
+            GradingError::SolvingError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Verifies the solution, then compares it with the optimal solution.
+///
+/// When `exact` is set, the optimal solution is found with IDA* using the given heuristic.
+/// Otherwise the heuristic value is used as the lower bound on the optimal length,
+/// which is much faster, but the reported overhead is only an upper bound.
+///
+/// # Errors
+/// Returns error if the solution is not valid, or the optimal solution cannot be found
+pub fn grade(
+    board: &OwnedBoard,
+    solution: &[BoardMove],
+    heuristic: Box<dyn Heuristic>,
+    exact: bool,
+) -> Result<GradeReport, GradingError> {
+    let mut verified = board.clone();
+    verified
+        .apply_moves(solution)
+        .map_err(GradingError::IllegalMove)?;
+    if !verified.is_solved() {
+        return Err(GradingError::NotSolved);
+    }
+
+    let (optimal_length, is_exact) = if exact {
+        let optimal = Box::new(IterativeAStarSolver::new(board.clone(), heuristic))
+            .solve()
+            .map_err(GradingError::SolvingError)?;
+        (optimal.len(), true)
+    } else {
+        let mut bound = heuristic.evaluate(board) as usize;
+        // every solution of the board has the same parity of length
+        if bound % 2 != solution.len() % 2 {
+            bound += 1;
+        }
+        (bound.min(solution.len()), false)
+    };
+
+    Ok(GradeReport {
+        solution_length: solution.len(),
+        optimal_length,
+        is_exact,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::parse_moves;
+    use crate::solving::algorithm::heuristic::heuristics::ManhattanDistance;
+
+    use super::*;
+
+    fn create_board() -> OwnedBoard {
+        "3 3\n1 2 3\n4 0 5\n7 8 6".parse().unwrap()
+    }
+
+    #[test]
+    fn reports_overhead_of_valid_solution() {
+        let solution = parse_moves("RDULRD").unwrap();
+
+        let report = grade(
+            &create_board(),
+            &solution,
+            Box::new(ManhattanDistance),
+            true,
+        )
+        .unwrap();
+
+        assert_eq!(6, report.solution_length);
+        assert_eq!(2, report.optimal_length);
+        assert_eq!(4, report.overhead());
+        assert!((report.ratio() - 3.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn overhead_of_report_with_longer_optimal_length_is_zero() {
+        let report = GradeReport {
+            solution_length: 4,
+            optimal_length: 6,
+            is_exact: false,
+        };
+
+        assert_eq!(0, report.overhead());
+    }
+
+    #[test]
+    fn rejects_illegal_and_incomplete_solutions() {
+        let board = create_board();
+
+        assert!(matches!(
+            grade(
+                &board,
+                &parse_moves("RR").unwrap(),
+                Box::new(ManhattanDistance),
+                true
+            ),
+            Err(GradingError::IllegalMove(MoveError::IllegalMove {
+                index: 1,
+                ..
+            }))
+        ));
+        assert!(matches!(
+            grade(
+                &board,
+                &parse_moves("R").unwrap(),
+                Box::new(ManhattanDistance),
+                true
+            ),
+            Err(GradingError::NotSolved)
+        ));
+    }
+}
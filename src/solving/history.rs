@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::sync::{Arc, Mutex};
+
+use crate::board::{parse_moves, Board, BoardMove};
+use crate::solving::movegen::MoveSequence;
+
+/// Dimensions of the board, position of the empty cell and the move made from it
+type HistoryKey = ((u8, u8), (u8, u8), BoardMove);
+
+/// History heuristic: counts how often every move led to a solution or to the next bound of an iteration,
+/// and orders the moves of later iterations and boards from the most successful one.
+///
+/// Moves are told apart by the position of the empty cell they are made from
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct HistoryTable {
+    scores: HashMap<HistoryKey, u64>,
+}
+
+fn key(board: &impl Board, board_move: BoardMove) -> HistoryKey {
+    (board.dimensions(), board.empty_cell_pos(), board_move)
+}
+
+impl HistoryTable {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Credits the move made from the board
+    pub fn record(&mut self, board: &impl Board, board_move: BoardMove) {
+        *self.scores.entry(key(board, board_move)).or_default() += 1;
+    }
+
+    #[must_use]
+    pub fn score(&self, board: &impl Board, board_move: BoardMove) -> u64 {
+        self.scores
+            .get(&key(board, board_move))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Sorts the sequences made from the board from the highest score of their first move.
+    /// Sequences of equal scores keep their order
+    pub fn order(&self, board: &impl Board, moves: &mut [MoveSequence]) {
+        moves.sort_by_key(|&move_sequence| {
+            std::cmp::Reverse(self.score(board, move_sequence.first()))
+        });
+    }
+
+    /// Adds the scores of the other table to this one
+    pub fn merge(&mut self, other: &Self) {
+        for (&key, score) in &other.scores {
+            *self.scores.entry(key).or_default() += score;
+        }
+    }
+
+    /// Number of moves which have been credited
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    /// Writes the scores as lines of the board dimensions, the empty cell position, the move and its score
+    ///
+    /// # Errors
+    /// Returns error if writing fails
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        let mut entries: Vec<_> = self.scores.iter().collect();
+        entries.sort_unstable();
+        for (&((rows, columns), (row, column), board_move), score) in entries {
+            writeln!(
+                writer,
+                "{rows} {columns} {row} {column} {board_move} {score}"
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Reads the scores written by [`write`](HistoryTable::write)
+    ///
+    /// # Errors
+    /// Returns error if reading fails, or a line is not a valid entry
+    pub fn read(reader: impl BufRead) -> io::Result<Self> {
+        let invalid = |line: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid history entry '{line}'"),
+            )
+        };
+        let mut scores = HashMap::new();
+        for line in reader.lines() {
+            let line = line?;
+            let fields: Vec<_> = line.split_whitespace().collect();
+            let [rows, columns, row, column, board_move, score] = fields[..] else {
+                return Err(invalid(&line));
+            };
+            let number = |field: &str| field.parse::<u8>().map_err(|_| invalid(&line));
+            let [board_move] = parse_moves(board_move).map_err(|_| invalid(&line))?[..] else {
+                return Err(invalid(&line));
+            };
+            let score = score.parse().map_err(|_| invalid(&line))?;
+            let key = (
+                (number(rows)?, number(columns)?),
+                (number(row)?, number(column)?),
+                board_move,
+            );
+            scores.insert(key, score);
+        }
+        Ok(Self { scores })
+    }
+}
+
+/// History table shared by the solvers of several boards, e.g. of a batch.
+/// Every solver searches with its own copy of the table, and adds its credits to the shared one after the board,
+/// so the search itself does not wait for the lock
+#[derive(Clone, Default)]
+pub struct SharedHistory {
+    table: Arc<Mutex<HistoryTable>>,
+}
+
+impl SharedHistory {
+    #[must_use]
+    pub fn new(table: HistoryTable) -> Self {
+        Self {
+            table: Arc::new(Mutex::new(table)),
+        }
+    }
+
+    /// Returns a copy of the scores credited so far
+    #[must_use]
+    pub fn snapshot(&self) -> HistoryTable {
+        self.table.lock().expect("History table lock").clone()
+    }
+
+    fn merge(&self, credits: &HistoryTable) {
+        self.table
+            .lock()
+            .expect("History table lock")
+            .merge(credits);
+    }
+}
+
+/// History table of a single search, a copy of the shared table updated by the credits of the search
+pub struct SearchHistory {
+    shared: SharedHistory,
+    table: HistoryTable,
+    /// Credits of the current iteration, which order the moves from the next one,
+    /// so that the order stays the same when an iteration is suspended and resumed
+    iteration: HistoryTable,
+    /// Credits not yet added to the shared table
+    credits: HistoryTable,
+}
+
+impl SearchHistory {
+    #[must_use]
+    pub fn new(shared: SharedHistory) -> Self {
+        Self {
+            table: shared.snapshot(),
+            shared,
+            iteration: HistoryTable::new(),
+            credits: HistoryTable::new(),
+        }
+    }
+
+    /// Credits the move made from the board, see [`SearchHistory::end_iteration`]
+    pub fn record(&mut self, board: &impl Board, board_move: BoardMove) {
+        self.iteration.record(board, board_move);
+        self.credits.record(board, board_move);
+    }
+
+    /// Orders the moves by the credits of the finished iteration as well
+    pub fn end_iteration(&mut self) {
+        self.table.merge(&std::mem::take(&mut self.iteration));
+    }
+
+    /// Credits the moves of the path leading to the board, replaying them back from it
+    pub fn record_path(&mut self, board: &(impl Board + Clone), path: &[BoardMove]) {
+        let mut board = board.clone();
+        for &board_move in path.iter().rev() {
+            board.exec_move(board_move.opposite());
+            self.record(&board, board_move);
+        }
+    }
+
+    /// Sorts the sequences made from the board from the highest score of their first move,
+    /// credited before the current iteration
+    pub fn order(&self, board: &impl Board, moves: &mut [MoveSequence]) {
+        self.table.order(board, moves);
+    }
+
+    /// Adds the credits of the search to the shared table, for the searches of the next boards
+    pub fn publish(&mut self) {
+        self.shared.merge(&std::mem::take(&mut self.credits));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::BoardMove::*;
+    use crate::board::{Board, OwnedBoard};
+    use crate::solving::movegen::MoveSequence::*;
+
+    use super::*;
+
+    #[test]
+    fn moves_are_ordered_by_score() {
+        let mut board = OwnedBoard::new_solved(3, 3);
+        board.apply_moves(&[Up, Left]).unwrap();
+        let mut history = HistoryTable::new();
+        history.record(&board, Left);
+        history.record(&board, Left);
+        history.record(&board, Up);
+
+        let mut moves = [Single(Up), Double(Right, Down), Single(Left)];
+        history.order(&board, &mut moves);
+
+        assert_eq!([Single(Left), Single(Up), Double(Right, Down)], moves);
+    }
+
+    #[test]
+    fn scores_depend_on_empty_cell_position() {
+        let mut board = OwnedBoard::new_solved(3, 3);
+        let mut history = HistoryTable::new();
+        history.record(&board, Up);
+
+        board.exec_move(Left);
+
+        assert_eq!(0, history.score(&board, Up));
+    }
+
+    #[test]
+    fn written_table_is_read_back() {
+        let board = OwnedBoard::new_solved(4, 4);
+        let mut history = HistoryTable::new();
+        history.record(&board, Up);
+        history.record(&board, Up);
+        history.record(&board, Left);
+        let mut buffer = vec![];
+        history.write(&mut buffer).unwrap();
+
+        let read = HistoryTable::read(buffer.as_slice()).unwrap();
+
+        assert_eq!(2, read.score(&board, Up));
+        assert_eq!(1, read.score(&board, Left));
+        assert_eq!(2, read.len());
+        assert!(HistoryTable::read("4 4 3 3 X 1".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn credits_order_moves_from_next_iteration() {
+        let board = OwnedBoard::new_solved(3, 3);
+        let mut history = SearchHistory::new(SharedHistory::default());
+        history.record(&board, Left);
+        let mut moves = [Single(Up), Single(Left)];
+
+        history.order(&board, &mut moves);
+        assert_eq!([Single(Up), Single(Left)], moves);
+
+        history.end_iteration();
+        history.order(&board, &mut moves);
+        assert_eq!([Single(Left), Single(Up)], moves);
+    }
+
+    #[test]
+    fn credits_are_shared_once_published() {
+        let board = OwnedBoard::new_solved(3, 3);
+        let shared = SharedHistory::default();
+        let mut first = SearchHistory::new(shared.clone());
+        let mut second = SearchHistory::new(shared.clone());
+        first.record(&board, Up);
+        second.record(&board, Up);
+
+        assert!(shared.snapshot().is_empty());
+        first.publish();
+        second.publish();
+        first.publish();
+
+        assert_eq!(2, shared.snapshot().score(&board, Up));
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Binary min-heap which tracks the position of every key,
+/// so that the value of a queued key can be decreased in place
+pub(crate) struct IndexedHeap<K: Hash + Eq + Clone, V: Ord> {
+    entries: Vec<(K, V)>,
+    positions: HashMap<K, usize>,
+}
+
+impl<K: Hash + Eq + Clone, V: Ord> IndexedHeap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            entries: vec![],
+            positions: HashMap::new(),
+        }
+    }
+
+    /// Queues the key, or replaces its value if the key is queued with a greater value.
+    /// Returns `false` if the key was already queued with a value not greater than the given one
+    pub fn push_or_decrease(&mut self, key: K, value: V) -> bool {
+        if let Some(&index) = self.positions.get(&key) {
+            if value >= self.entries[index].1 {
+                return false;
+            }
+            self.entries[index].1 = value;
+            self.sift_up(index);
+            return true;
+        }
+        self.positions.insert(key.clone(), self.entries.len());
+        self.entries.push((key, value));
+        self.sift_up(self.entries.len() - 1);
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns the queued values in no particular order
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.entries.iter().map(|(_, value)| value)
+    }
+
+    /// Returns the smallest value without removing it
+    pub fn peek(&self) -> Option<&V> {
+        self.entries.first().map(|(_, value)| value)
+    }
+
+    /// Removes the entry with the smallest value
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let last = self.entries.len() - 1;
+        self.swap(0, last);
+        let (key, value) = self.entries.pop().expect("Heap is not empty");
+        self.positions.remove(&key);
+        if !self.entries.is_empty() {
+            self.sift_down(0);
+        }
+        Some((key, value))
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.entries.swap(a, b);
+        *self
+            .positions
+            .get_mut(&self.entries[a].0)
+            .expect("Key is indexed") = a;
+        *self
+            .positions
+            .get_mut(&self.entries[b].0)
+            .expect("Key is indexed") = b;
+    }
+
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent = (index - 1) / 2;
+            if self.entries[index].1 >= self.entries[parent].1 {
+                break;
+            }
+            self.swap(index, parent);
+            index = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut index: usize) {
+        loop {
+            let mut smallest = index;
+            for child in [2 * index + 1, 2 * index + 2] {
+                if child < self.entries.len() && self.entries[child].1 < self.entries[smallest].1 {
+                    smallest = child;
+                }
+            }
+            if smallest == index {
+                break;
+            }
+            self.swap(index, smallest);
+            index = smallest;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pops_in_order_of_decreased_values() {
+        let mut heap = IndexedHeap::new();
+        for (key, value) in [('a', 5), ('b', 3), ('c', 8), ('d', 1)] {
+            assert!(heap.push_or_decrease(key, value));
+        }
+
+        assert!(heap.push_or_decrease('c', 2));
+        assert!(!heap.push_or_decrease('b', 4));
+
+        let order: Vec<_> = std::iter::from_fn(|| heap.pop()).collect();
+        assert_eq!(vec![('d', 1), ('c', 2), ('b', 3), ('a', 5)], order);
+    }
+}
@@ -1,27 +1,57 @@
-use parity::{permutation_parity, required_moves_parity, solved_board_parity};
+use parity::{empty_cell_distance_parity, permutation_parity, Parity};
 
-use crate::board::Board;
+use crate::board::{goal_cells, Board};
 
 pub mod algorithm;
+pub mod analysis;
+mod bucket_queue;
+pub mod census;
+pub mod checkpoint;
+pub mod convergence;
+pub mod cost;
+pub mod diagnosis;
+pub mod goal;
+pub mod grading;
+pub mod history;
+mod indexed_heap;
 pub mod movegen;
-mod parity;
-mod visited;
+#[cfg(feature = "json")]
+pub mod output;
+pub mod parity;
+pub mod plateau;
+pub mod pool;
+pub mod pruning;
+pub mod run_info;
+pub mod session;
+pub mod similarity;
+pub mod solution;
+mod transposition;
+pub mod verification;
+pub(crate) mod visited;
 
-fn is_solvable(board: &impl Board) -> bool {
-    let (rows, columns) = board.dimensions();
-    let mut cells = vec![];
-
-    for row in 0..rows {
-        for column in 0..columns {
-            cells.push(board.at(row, column));
-        }
-    }
+/// Returns the invariant that is preserved by every move.
+/// Two boards of the same dimensions can be transformed into each other only if their invariants match
+fn solvability_class(board: &(impl Board + ?Sized)) -> Parity {
+    let cells: Vec<_> = board.iter_cells().map(|(_, value)| value).collect();
+    let corner = (board.rows() - 1, board.columns() - 1);
+    permutation_parity(&cells) + empty_cell_distance_parity(board.empty_cell_pos(), corner)
+}
 
-    let board_parity = permutation_parity(&cells);
+/// Returns the invariant of the goal configuration of the board
+fn goal_solvability_class(board: &(impl Board + ?Sized)) -> Parity {
+    let corner = (board.rows() - 1, board.columns() - 1);
+    permutation_parity(&goal_cells(board))
+        + empty_cell_distance_parity(board.goal_position(0), corner)
+}
 
-    let solved_board_parity = solved_board_parity(board);
+fn is_solvable(board: &(impl Board + ?Sized)) -> bool {
+    solvability_class(board) == goal_solvability_class(board)
+}
 
-    board_parity + required_moves_parity(board) == solved_board_parity
+/// Checks if the `target` position can be reached from the `source` position
+fn is_reachable(source: &(impl Board + ?Sized), target: &(impl Board + ?Sized)) -> bool {
+    source.dimensions() == target.dimensions()
+        && solvability_class(source) == solvability_class(target)
 }
 
 #[cfg(test)]
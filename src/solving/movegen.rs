@@ -1,33 +1,169 @@
+use bit_set::BitSet;
 use rand::prelude::SliceRandom;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::error::Error;
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::Mutex;
 
-use crate::board::{Board, BoardMove};
-use crate::solving::parity;
-use crate::solving::parity::Parity;
+use crate::board::{Board, BoardMove, ParseMoveError};
+use crate::solving::algorithm::SolvingError;
+use crate::solving::parity::{BoardGoalParity, Parity, ParityProvider};
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum MoveSequence {
     Single(BoardMove),
     Double(BoardMove, BoardMove),
 }
 
-#[derive(Clone, Debug)]
+impl MoveSequence {
+    /// Returns the move made first, from the position the sequence is generated for
+    #[must_use]
+    pub fn first(self) -> BoardMove {
+        match self {
+            MoveSequence::Single(board_move) | MoveSequence::Double(board_move, _) => board_move,
+        }
+    }
+}
+
+/// Order in which the moves are tried when a position is expanded.
+///
+/// Provided orders may omit the moves which are never possible on the solved board,
+/// e.g. `LR` is enough for a board with a single row. This is checked by [`SearchOrder::validate_for`]
+/// when the search starts
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SearchOrder {
-    Provided([BoardMove; 4]),
+    Provided(Vec<BoardMove>),
     Random,
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SearchOrderError {
+    /// The order does not contain any moves
+    Empty,
+    /// The order contains more than the four moves
+    TooLong(usize),
+    InvalidMove(ParseMoveError),
+    DuplicateMove(BoardMove),
+    /// The order omits the move which is possible on the solved board
+    MissingMove(BoardMove),
+    /// The move is needed to bring the empty cell to its goal position, but the generator does not allow it
+    DisallowedMove(BoardMove),
+    /// The generator allows no moves, so the unsolved board cannot be solved
+    NoAllowedMoves,
+}
+
+impl Display for SearchOrderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SearchOrderError::Empty => write!(f, "Order must contain at least one move"),
+            SearchOrderError::TooLong(length) => {
+                write!(f, "Order must be at most 4 characters, got {length}")
+            }
+            SearchOrderError::InvalidMove(e) => write!(f, "{e}"),
+            SearchOrderError::DuplicateMove(board_move) => {
+                write!(f, "Duplicate move {board_move}")
+            }
+            SearchOrderError::MissingMove(board_move) => {
+                write!(
+                    f,
+                    "Order is missing move {board_move} possible on this board"
+                )
+            }
+            SearchOrderError::DisallowedMove(board_move) => {
+                write!(
+                    f,
+                    "Move {board_move} is needed to reach the goal, but it is not allowed"
+                )
+            }
+            SearchOrderError::NoAllowedMoves => {
+                write!(f, "No moves are allowed, so the board cannot be solved")
+            }
+        }
+    }
+}
+
+impl Error for SearchOrderError {}
+
+impl From<SearchOrderError> for SolvingError {
+    fn from(value: SearchOrderError) -> Self {
+        Self::AlgorithmError(Box::new(value))
+    }
+}
+
+impl SearchOrder {
+    /// Checks that the order contains every move possible on a board of the given dimensions,
+    /// as the moves left out are never generated
+    ///
+    /// # Errors
+    /// Returns the first move which is possible on the board, but missing from the order
+    pub fn validate_for(&self, board: &(impl Board + ?Sized)) -> Result<(), SearchOrderError> {
+        self.validate_moves(board, &BoardMove::ALL)
+    }
+
+    /// Checks that the order contains every one of the moves which is possible on a board of the given dimensions
+    fn validate_moves(
+        &self,
+        board: &(impl Board + ?Sized),
+        moves: &[BoardMove],
+    ) -> Result<(), SearchOrderError> {
+        let SearchOrder::Provided(order) = self else {
+            return Ok(());
+        };
+        let (rows, columns) = board.dimensions();
+        moves
+            .iter()
+            .copied()
+            .filter(|board_move| match board_move {
+                BoardMove::Up | BoardMove::Down => rows > 1,
+                BoardMove::Left | BoardMove::Right => columns > 1,
+            })
+            .find(|board_move| !order.contains(board_move))
+            .map_or(Ok(()), |board_move| {
+                Err(SearchOrderError::MissingMove(board_move))
+            })
+    }
+}
+
+/// Parses the order written as consecutive move letters, e.g. `LRUD`, or `R` for the random order
+impl FromStr for SearchOrder {
+    type Err = SearchOrderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim();
+        if input.eq_ignore_ascii_case("R") {
+            return Ok(SearchOrder::Random);
+        }
+
+        let order: Vec<BoardMove> = input
+            .chars()
+            .map(BoardMove::try_from)
+            .collect::<Result<_, _>>()
+            .map_err(SearchOrderError::InvalidMove)?;
+        if order.is_empty() {
+            return Err(SearchOrderError::Empty);
+        }
+        if order.len() > BoardMove::ALL.len() {
+            return Err(SearchOrderError::TooLong(order.len()));
+        }
+        for (i, current) in order.iter().enumerate() {
+            if order[i + 1..].contains(current) {
+                return Err(SearchOrderError::DuplicateMove(*current));
+            }
+        }
+
+        Ok(SearchOrder::Provided(order))
+    }
+}
+
 impl Display for SearchOrder {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             SearchOrder::Provided(order) => {
                 for m in order {
-                    match m {
-                        BoardMove::Up => write!(f, "U"),
-                        BoardMove::Down => write!(f, "D"),
-                        BoardMove::Left => write!(f, "L"),
-                        BoardMove::Right => write!(f, "R"),
-                    }?;
+                    write!(f, "{m}")?;
                 }
             }
             SearchOrder::Random => write!(f, "Random")?,
@@ -36,94 +172,277 @@ impl Display for SearchOrder {
     }
 }
 
+/// Occasional shuffling of the provided search order, drawn from a seeded generator so that runs can be repeated
+struct Jitter {
+    /// Probability that the order is shuffled for a single expansion
+    probability: f64,
+    seed: u64,
+    rng: Mutex<StdRng>,
+}
+
+impl Jitter {
+    /// Shuffles the order with the probability of the jitter
+    fn apply(&self, order: &mut [BoardMove]) {
+        let mut rng = self.rng.lock().expect("Jitter lock");
+        if rng.gen_bool(self.probability) {
+            order.shuffle(&mut *rng);
+        }
+    }
+}
+
 pub struct MoveGenerator {
     search_order: SearchOrder,
+    /// Source of the parity used to pair moves, `None` disables pairing
+    parity_provider: Option<Box<dyn ParityProvider>>,
+    frozen_tiles: BitSet,
+    /// Moves the generator may produce, all four unless restricted
+    allowed_moves: Vec<BoardMove>,
+    jitter: Option<Jitter>,
 }
 
 impl Default for MoveGenerator {
     fn default() -> Self {
         use crate::board::BoardMove::*;
-        Self::new(SearchOrder::Provided([Up, Down, Left, Right]))
+        Self::new(SearchOrder::Provided(vec![Up, Down, Left, Right]))
     }
 }
 
 impl MoveGenerator {
     #[must_use]
     pub fn new(search_order: SearchOrder) -> Self {
-        MoveGenerator { search_order }
+        MoveGenerator {
+            search_order,
+            parity_provider: Some(Box::new(BoardGoalParity)),
+            frozen_tiles: BitSet::new(),
+            allowed_moves: BoardMove::ALL.to_vec(),
+            jitter: None,
+        }
     }
 
+    /// Shuffles the provided search order of a single expansion with the given probability,
+    /// which breaks the worst cases of a fixed order without making the whole search random.
+    /// The shuffles are drawn from a generator with the given seed, so the search can be repeated
+    ///
+    /// # Panics
+    /// Panics if the probability is not between 0 and 1
+    #[must_use]
+    pub fn with_jitter(mut self, probability: f64, seed: u64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&probability),
+            "Jitter probability must be between 0 and 1"
+        );
+        self.jitter = Some(Jitter {
+            probability,
+            seed,
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+        });
+        self
+    }
+
+    /// Returns the seed of the jitter, if it is enabled
+    #[must_use]
+    pub fn jitter_seed(&self) -> Option<u64> {
+        self.jitter.as_ref().map(|jitter| jitter.seed)
+    }
+
+    /// Restricts the generator to the given moves, e.g. only `Up` and `Left` for a phase
+    /// which must not disturb the tiles to the right of and below the empty cell.
+    /// Whether the goal can still be reached is checked by [`MoveGenerator::validate_for`]
+    #[must_use]
+    pub fn with_allowed_moves(mut self, moves: impl IntoIterator<Item = BoardMove>) -> Self {
+        self.allowed_moves = moves.into_iter().collect();
+        self
+    }
+
+    /// Replaces the source of the parity used for pairing moves,
+    /// which by default is relative to the goal carried by the board. `None` disables pairing
+    #[must_use]
+    pub fn with_parity_provider(
+        mut self,
+        parity_provider: Option<Box<dyn ParityProvider>>,
+    ) -> Self {
+        self.set_parity_provider(parity_provider);
+        self
+    }
+
+    /// Checks that the search order of the generator can reach every position of the board,
+    /// and that the allowed moves can bring the empty cell to its goal position
+    ///
+    /// # Errors
+    /// Returns error if the order omits an allowed move possible on the board,
+    /// if a move needed to reach the goal position of the empty cell is not allowed, or no move is allowed on an unsolved board
+    pub fn validate_for(&self, board: &(impl Board + ?Sized)) -> Result<(), SearchOrderError> {
+        if self.allowed_moves.is_empty() && !board.is_solved() {
+            return Err(SearchOrderError::NoAllowedMoves);
+        }
+        self.search_order
+            .validate_moves(board, &self.allowed_moves)?;
+
+        let (row, column) = board.empty_cell_pos();
+        let (goal_row, goal_column) = board.goal_position(0);
+        [
+            (goal_row < row, BoardMove::Up),
+            (goal_row > row, BoardMove::Down),
+            (goal_column < column, BoardMove::Left),
+            (goal_column > column, BoardMove::Right),
+        ]
+        .into_iter()
+        .find(|&(needed, board_move)| needed && !self.allowed_moves.contains(&board_move))
+        .map_or(Ok(()), |(_, board_move)| {
+            Err(SearchOrderError::DisallowedMove(board_move))
+        })
+    }
+
+    fn is_allowed(&self, board_move: BoardMove) -> bool {
+        self.allowed_moves.contains(&board_move)
+    }
+
+    /// Prevents the generator from producing moves that would displace any of the given tiles
+    pub(crate) fn freeze_tiles(&mut self, tiles: impl IntoIterator<Item = u8>) {
+        self.frozen_tiles.extend(tiles.into_iter().map(usize::from));
+    }
+
+    fn is_frozen(&self, position: (i16, i16), board: &impl Board) -> bool {
+        !self.frozen_tiles.is_empty()
+            && self
+                .frozen_tiles
+                .contains(board.at(position.0 as u8, position.1 as u8) as usize)
+    }
+
+    /// Sets the source of the parity of the number of moves required to reach the goal.
+    /// Pairs of moves are generated for boards which require an even number of moves,
+    /// which is only sound when the parity is relative to the goal the solver is looking for
+    pub(crate) fn set_parity_provider(&mut self, parity_provider: Option<Box<dyn ParityProvider>>) {
+        self.parity_provider = parity_provider;
+    }
+
+    /// Checks if the moves from the board can be generated in pairs: the number of moves to the goal has to be even,
+    /// and the goal cell of the empty cell cannot be frozen, as then the searched goal leaves the empty cell elsewhere
+    /// and the parity relative to it says nothing about the solutions
+    pub fn pairs_moves(&self, board: &impl Board) -> bool {
+        let requires_even_moves = self
+            .parity_provider
+            .as_ref()
+            .and_then(|provider| provider.required_parity(board.as_dyn_board()))
+            == Some(Parity::Even);
+        let (goal_row, goal_column) = board.goal_position(0);
+        requires_even_moves && !self.is_frozen((goal_row as i16, goal_column as i16), board)
+    }
+
+    /// Generates the moves from the board, in pairs if [`MoveGenerator::pairs_moves`] allows it.
+    /// If no pair can be made, e.g. when frozen tiles leave the empty cell only a dead end,
+    /// single moves are generated instead
     pub fn generate_moves(
         &self,
         board: &impl Board,
         previous_move: Option<BoardMove>,
     ) -> Vec<MoveSequence> {
-        let mut next_moves = Vec::new();
-
-        let generate_single_move = parity::required_moves_parity(board) == Parity::Odd;
-
-        let search_order = match self.search_order {
-            SearchOrder::Provided(order) => order,
-            SearchOrder::Random => {
-                let mut rng = rand::thread_rng();
-                let mut order = [
-                    BoardMove::Up,
-                    BoardMove::Down,
-                    BoardMove::Left,
-                    BoardMove::Right,
-                ];
-                order.shuffle(&mut rng);
-                order
+        let mut order_buffer = BoardMove::ALL;
+        let search_order: &[BoardMove] = match (&self.search_order, &self.jitter) {
+            (SearchOrder::Provided(order), None) => order,
+            (SearchOrder::Provided(order), Some(jitter)) => {
+                let jittered_order = &mut order_buffer[..order.len()];
+                jittered_order.copy_from_slice(order);
+                jitter.apply(jittered_order);
+                jittered_order
+            }
+            (SearchOrder::Random, _) => {
+                order_buffer.shuffle(&mut rand::thread_rng());
+                &order_buffer
             }
         };
 
-        for first_move in search_order {
-            let empty_pos = board.empty_cell_pos();
+        if self.pairs_moves(board) {
+            let pairs = self.generate_pairs(board, previous_move, search_order);
+            if !pairs.is_empty() {
+                return pairs;
+            }
+            log::trace!("No pair of moves can be made, generating single moves");
+        }
+        self.first_moves(board, previous_move, search_order)
+            .map(|(first_move, _)| MoveSequence::Single(first_move))
+            .collect()
+    }
+
+    /// Returns the moves which can be made from the board in the search order, with the position of the empty cell after them
+    fn first_moves<'a>(
+        &'a self,
+        board: &'a impl Board,
+        previous_move: Option<BoardMove>,
+        search_order: &'a [BoardMove],
+    ) -> impl Iterator<Item = (BoardMove, (i16, i16))> + 'a {
+        let empty_pos = board.empty_cell_pos();
+        search_order.iter().filter_map(move |&first_move| {
             let first_position =
                 position_after_move((empty_pos.0 as i16, empty_pos.1 as i16), first_move);
-            if !is_inside_board(first_position, board) {
+            if !self.is_allowed(first_move)
+                || !is_inside_board(first_position, board)
+                || self.is_frozen(first_position, board)
+            {
                 // cannot execute move
-                continue;
+                return None;
             }
-            if let Some(previous_move) = previous_move {
-                if first_move == previous_move.opposite() {
-                    // move would undo the previous move
-                    continue;
-                }
+            if previous_move.is_some_and(|previous_move| first_move == previous_move.opposite()) {
+                // move would undo the previous move
+                return None;
             }
+            Some((first_move, first_position))
+        })
+    }
 
-            if generate_single_move {
-                next_moves.push(MoveSequence::Single(first_move));
-            } else {
-                for second_move in search_order {
-                    let second_position = position_after_move(first_position, second_move);
-                    if !is_inside_board(second_position, board) {
-                        // second move is impossible to execute
-                        continue;
-                    }
-                    // Avoid obviously unsound moves
-                    if second_move != first_move.opposite() {
-                        next_moves.push(MoveSequence::Double(first_move, second_move));
-                    }
+    fn generate_pairs(
+        &self,
+        board: &impl Board,
+        previous_move: Option<BoardMove>,
+        search_order: &[BoardMove],
+    ) -> Vec<MoveSequence> {
+        let mut next_moves = Vec::new();
+        for (first_move, first_position) in self.first_moves(board, previous_move, search_order) {
+            for &second_move in search_order {
+                let second_position = position_after_move(first_position, second_move);
+                if !self.is_allowed(second_move)
+                    || !is_inside_board(second_position, board)
+                    || self.is_frozen(second_position, board)
+                {
+                    // second move is impossible to execute
+                    continue;
+                }
+                // Avoid obviously unsound moves
+                if second_move != first_move.opposite() {
+                    next_moves.push(MoveSequence::Double(first_move, second_move));
                 }
             }
         }
-
-        #[cfg(debug_assertions)]
-        {
-            if generate_single_move {
-                assert!(next_moves
-                    .iter()
-                    .all(|m| matches!(m, MoveSequence::Single(_))));
-            } else {
-                assert!(next_moves
-                    .iter()
-                    .all(|m| matches!(m, MoveSequence::Double(_, _))));
-            }
-        }
-
         next_moves
     }
+
+    /// Returns the boards which reach the given board with a single move the generator could produce,
+    /// together with that move. Unlike [`MoveGenerator::generate_moves`], the moves are never paired
+    pub fn generate_predecessors<B: Board + Clone>(&self, board: &B) -> Vec<(B, BoardMove)> {
+        let search_order: &[BoardMove] = match &self.search_order {
+            SearchOrder::Provided(order) => order,
+            SearchOrder::Random => &BoardMove::ALL,
+        };
+        let empty_pos = board.empty_cell_pos();
+        search_order
+            .iter()
+            .copied()
+            .filter(|&board_move| self.is_allowed(board_move))
+            .filter_map(|board_move| {
+                // the tile moved by the move is now where the empty cell was before it
+                let tile_position = position_after_move(
+                    (empty_pos.0 as i16, empty_pos.1 as i16),
+                    board_move.opposite(),
+                );
+                if !is_inside_board(tile_position, board) || self.is_frozen(tile_position, board) {
+                    return None;
+                }
+                let mut predecessor = board.clone();
+                predecessor.exec_move(board_move.opposite());
+                Some((predecessor, board_move))
+            })
+            .collect()
+    }
 }
 
 /// Helper function to check where the empty square would move, to ensure that the move is able to be performed
@@ -148,9 +467,9 @@ fn is_inside_board(position: (i16, i16), board: &impl Board) -> bool {
 #[cfg(test)]
 mod test {
     use crate::board::{Board, BoardMove, OwnedBoard};
-    use crate::solving::parity::{required_moves_parity, Parity};
+    use crate::solving::parity::{required_moves_parity, EmptyCellParity, Parity};
 
-    use super::{MoveGenerator, MoveSequence};
+    use super::{MoveGenerator, MoveSequence, SearchOrder, SearchOrderError};
 
     const SOLVED_INPUT: &str = r"4 4
 1  2  3  4
@@ -194,6 +513,219 @@ mod test {
             .all(|m| matches!(m, MoveSequence::Double(_, _))))
     }
 
+    #[test]
+    fn pairing_follows_goal_with_centered_blank() {
+        let goal: OwnedBoard = "3 3\n1 2 3\n4 0 5\n6 7 8".parse().unwrap();
+        let mut board = goal.clone().with_goal(goal).unwrap();
+        let move_generator = MoveGenerator::default();
+
+        // the empty cell is at its goal position, so an even number of moves is required
+        assert!(move_generator
+            .generate_moves(&board, None)
+            .iter()
+            .all(|m| matches!(m, MoveSequence::Double(_, _))));
+
+        board.exec_move(BoardMove::Up);
+        assert!(move_generator
+            .generate_moves(&board, None)
+            .iter()
+            .all(|m| matches!(m, MoveSequence::Single(_))));
+        assert!(MoveGenerator::default()
+            .with_parity_provider(Some(Box::new(EmptyCellParity { target: (0, 1) })))
+            .generate_moves(&board, None)
+            .iter()
+            .all(|m| matches!(m, MoveSequence::Double(_, _))));
+    }
+
+    #[test]
+    fn search_order_is_parsed_and_printed() {
+        use BoardMove::*;
+
+        let order: SearchOrder = "ulDR".parse().unwrap();
+        assert_eq!(SearchOrder::Provided(vec![Up, Left, Down, Right]), order);
+        assert_eq!("ULDR", order.to_string());
+        assert_eq!(Ok(SearchOrder::Random), "r".parse());
+        assert_eq!(
+            Err(SearchOrderError::DuplicateMove(Left)),
+            "LUL".parse::<SearchOrder>()
+        );
+        assert_eq!(Err(SearchOrderError::Empty), "".parse::<SearchOrder>());
+        assert_eq!(
+            Err(SearchOrderError::TooLong(5)),
+            "ULDRU".parse::<SearchOrder>()
+        );
+        assert!(matches!(
+            "UX".parse::<SearchOrder>(),
+            Err(SearchOrderError::InvalidMove(_))
+        ));
+    }
+
+    #[test]
+    fn shorter_order_is_valid_only_without_omitted_moves() {
+        let row: OwnedBoard = "1 4\n1 2 0 3".parse().unwrap();
+        let order: SearchOrder = "RL".parse().unwrap();
+
+        assert_eq!(Ok(()), order.validate_for(&row));
+        assert_eq!(
+            Err(SearchOrderError::MissingMove(BoardMove::Up)),
+            order.validate_for(&SOLVED_INPUT.parse::<OwnedBoard>().unwrap())
+        );
+        assert_eq!(
+            Err(SearchOrderError::MissingMove(BoardMove::Left)),
+            SearchOrder::Provided(vec![BoardMove::Right]).validate_for(&row)
+        );
+    }
+
+    #[test]
+    fn restricted_generator_produces_only_allowed_moves() {
+        use BoardMove::*;
+        let mut board = SOLVED_INPUT.parse::<OwnedBoard>().unwrap();
+        board.exec_move(Up);
+        board.exec_move(Left);
+        let move_generator = MoveGenerator::new("ULDR".parse().unwrap())
+            .with_allowed_moves([Down, Right])
+            .with_parity_provider(None);
+
+        let next_moves: Vec<_> = move_generator
+            .generate_moves(&board, None)
+            .into_iter()
+            .map(|m| match m {
+                MoveSequence::Single(x) | MoveSequence::Double(x, _) => x,
+            })
+            .collect();
+
+        assert_eq!(vec![Down, Right], next_moves);
+        assert_eq!(Ok(()), move_generator.validate_for(&board));
+    }
+
+    #[test]
+    fn jitter_is_reproducible_with_seed() {
+        use BoardMove::*;
+        let mut board = SOLVED_INPUT.parse::<OwnedBoard>().unwrap();
+        board.exec_move(Up);
+        board.exec_move(Left);
+        let first_moves = |move_generator: &MoveGenerator| -> Vec<_> {
+            (0..20)
+                .map(|_| match move_generator.generate_moves(&board, None)[0] {
+                    MoveSequence::Single(x) | MoveSequence::Double(x, _) => x,
+                })
+                .collect()
+        };
+        let create = |probability| {
+            MoveGenerator::new("ULDR".parse().unwrap())
+                .with_parity_provider(None)
+                .with_jitter(probability, 7)
+        };
+
+        assert_eq!(vec![Up; 20], first_moves(&create(0.0)));
+        let jittered = first_moves(&create(1.0));
+        assert!(jittered.iter().any(|&m| m != Up));
+        assert_eq!(jittered, first_moves(&create(1.0)));
+        assert_eq!(Some(7), create(0.5).jitter_seed());
+    }
+
+    #[test]
+    fn frozen_goal_of_empty_cell_disables_pairing() {
+        use BoardMove::*;
+        let mut board = OwnedBoard::new_solved(3, 3);
+        board.apply_moves(&[Up, Up, Left, Left]).unwrap();
+        let mut move_generator = MoveGenerator::default();
+        assert!(move_generator.pairs_moves(&board));
+
+        move_generator.freeze_tiles([board.at(2, 2)]);
+        assert!(!move_generator.pairs_moves(&board));
+        assert!(move_generator
+            .generate_moves(&board, None)
+            .iter()
+            .all(|m| matches!(m, MoveSequence::Single(_))));
+    }
+
+    #[test]
+    fn dead_end_falls_back_to_single_moves() {
+        use BoardMove::*;
+        let mut board = OwnedBoard::new_solved(3, 3);
+        board.apply_moves(&[Up, Up, Left, Left]).unwrap();
+        let mut move_generator = MoveGenerator::default();
+        // the empty cell can only move right, into a cell from which it can only come back
+        move_generator.freeze_tiles([board.at(1, 0), board.at(1, 1), board.at(0, 2)]);
+
+        assert!(move_generator.pairs_moves(&board));
+        assert!(matches!(
+            move_generator.generate_moves(&board, None)[..],
+            [MoveSequence::Single(Right)]
+        ));
+    }
+
+    #[test]
+    fn restricted_generator_must_reach_goal_of_empty_cell() {
+        use BoardMove::*;
+        let mut board = SOLVED_INPUT.parse::<OwnedBoard>().unwrap();
+        board.exec_move(Up);
+
+        assert_eq!(
+            Err(SearchOrderError::DisallowedMove(Down)),
+            MoveGenerator::default()
+                .with_allowed_moves([Up, Left])
+                .validate_for(&board)
+        );
+        assert_eq!(
+            Err(SearchOrderError::NoAllowedMoves),
+            MoveGenerator::default()
+                .with_allowed_moves([])
+                .validate_for(&board)
+        );
+        // the order only has to contain the allowed moves
+        assert_eq!(
+            Ok(()),
+            MoveGenerator::new("DL".parse().unwrap())
+                .with_allowed_moves([Down, Left])
+                .validate_for(&board)
+        );
+    }
+
+    #[test]
+    fn predecessors_reach_board_in_one_move() {
+        use BoardMove::*;
+        let mut board = SOLVED_INPUT.parse::<OwnedBoard>().unwrap();
+        board.exec_move(Up);
+        board.exec_move(Left);
+
+        let predecessors = MoveGenerator::default().generate_predecessors(&board);
+
+        assert_eq!(4, predecessors.len());
+        for (mut predecessor, board_move) in predecessors {
+            assert_ne!(board, predecessor);
+            predecessor.exec_move(board_move);
+            assert_eq!(board, predecessor);
+        }
+    }
+
+    #[test]
+    fn predecessors_respect_frozen_tiles_and_allowed_moves() {
+        use BoardMove::*;
+        let mut board = SOLVED_INPUT.parse::<OwnedBoard>().unwrap();
+        board.exec_move(Up);
+        board.exec_move(Left);
+        // the empty cell is at (2, 2), and the last move shifted tile 11 into (2, 3)
+        let mut move_generator = MoveGenerator::default();
+        move_generator.freeze_tiles([11]);
+
+        let moves: Vec<_> = move_generator
+            .generate_predecessors(&board)
+            .into_iter()
+            .map(|(_, board_move)| board_move)
+            .collect();
+        assert_eq!(vec![Up, Down, Right], moves);
+
+        let moves: Vec<_> = MoveGenerator::default()
+            .with_allowed_moves([Up, Left])
+            .generate_predecessors(&board)
+            .into_iter()
+            .map(|(_, board_move)| board_move)
+            .collect();
+        assert_eq!(vec![Up, Left], moves);
+    }
+
     #[test]
     fn does_generate_all_moves_that_can_be_executed() {
         use BoardMove::*;
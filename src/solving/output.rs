@@ -0,0 +1,136 @@
+use std::time::Duration;
+
+use crate::board::{BoardMove, OwnedBoard};
+use crate::solving::run_info::RunInfo;
+use crate::solving::verification::OptimalityCheck;
+
+/// Result of solving a board in a form which can be exchanged as JSON
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct SolutionOutput {
+    pub board: OwnedBoard,
+    /// Moves of the solution written as letters, e.g. `ULDR`, or `None` if the board is unsolvable
+    pub moves: Option<String>,
+    pub stats: SolutionStats,
+    /// Description of the run which produced the solution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run: Option<RunInfo>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct SolutionStats {
+    /// Number of moves of the solution
+    pub length: Option<usize>,
+    /// Time spent by the solver in milliseconds
+    pub elapsed_ms: f64,
+    /// Result of the independent check that no shorter solution exists, if it was requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub optimality: Option<OptimalityCheck>,
+    /// Seed of the shuffles of the search order, if the order was jittered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jitter_seed: Option<u64>,
+}
+
+impl SolutionOutput {
+    /// Creates the output from the solution, or from `None` if the board is unsolvable
+    #[must_use]
+    pub fn new(board: OwnedBoard, solution: Option<&[BoardMove]>, elapsed: Duration) -> Self {
+        Self {
+            board,
+            moves: solution.map(|moves| moves.iter().map(ToString::to_string).collect()),
+            stats: SolutionStats {
+                length: solution.map(<[BoardMove]>::len),
+                elapsed_ms: elapsed.as_secs_f64() * 1000.0,
+                optimality: None,
+                jitter_seed: None,
+            },
+            run: None,
+        }
+    }
+
+    /// Attaches the result of the optimality check to the statistics
+    #[must_use]
+    pub fn with_optimality(mut self, optimality: Option<OptimalityCheck>) -> Self {
+        self.stats.optimality = optimality;
+        self
+    }
+
+    /// Records the seed of the jitter, which reproduces the search together with the jitter probability
+    #[must_use]
+    pub fn with_jitter_seed(mut self, jitter_seed: Option<u64>) -> Self {
+        self.stats.jitter_seed = jitter_seed;
+        self
+    }
+
+    #[must_use]
+    pub fn with_run_info(mut self, run: RunInfo) -> Self {
+        self.run = Some(run);
+        self
+    }
+
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Solution output is always serializable")
+    }
+
+    /// # Errors
+    /// Returns error if the JSON is malformed or contains an invalid board
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::BoardMove::*;
+
+    use super::*;
+
+    #[test]
+    fn solution_is_written_as_json() {
+        let board = OwnedBoard::from_scramble(2, 2, &[Up, Left]).unwrap();
+        let output = SolutionOutput::new(board, Some(&[Right, Down]), Duration::from_millis(3));
+
+        assert_eq!(
+            r#"{"board":{"rows":2,"columns":2,"cells":[0,1,3,2]},"moves":"RD","stats":{"length":2,"elapsed_ms":3.0}}"#,
+            output.to_json()
+        );
+        let parsed = SolutionOutput::from_json(&output.to_json()).unwrap();
+        assert_eq!(Some("RD".to_string()), parsed.moves);
+    }
+
+    #[test]
+    fn optimality_check_is_written_when_present() {
+        let board = OwnedBoard::from_scramble(2, 2, &[Up, Left]).unwrap();
+        let output = SolutionOutput::new(board, Some(&[Right, Down]), Duration::from_millis(3))
+            .with_optimality(Some(OptimalityCheck::Confirmed));
+
+        assert!(output.to_json().contains(r#""optimality":"confirmed""#));
+        let parsed = SolutionOutput::from_json(&output.to_json()).unwrap();
+        assert_eq!(Some(OptimalityCheck::Confirmed), parsed.stats.optimality);
+    }
+
+    #[test]
+    fn jitter_seed_is_written_when_present() {
+        let board = OwnedBoard::from_scramble(2, 2, &[Up, Left]).unwrap();
+        let output = SolutionOutput::new(board, Some(&[Right, Down]), Duration::from_millis(3))
+            .with_jitter_seed(Some(42));
+
+        assert!(output.to_json().ends_with(r#""jitter_seed":42}}"#));
+        let parsed = SolutionOutput::from_json(&output.to_json()).unwrap();
+        assert_eq!(Some(42), parsed.stats.jitter_seed);
+    }
+
+    #[test]
+    fn run_info_is_written_when_present() {
+        let board = OwnedBoard::from_scramble(2, 2, &[Up, Left]).unwrap();
+        let run = RunInfo::new("bfs LRUD", &board, &Default::default());
+        let output = SolutionOutput::new(board, Some(&[Right, Down]), Duration::from_millis(3))
+            .with_run_info(run.clone());
+
+        assert!(output
+            .to_json()
+            .ends_with(&format!(r#""run":{}}}"#, run.to_json())));
+        let parsed = SolutionOutput::from_json(&output.to_json()).unwrap();
+        assert_eq!(Some(run), parsed.run);
+    }
+}
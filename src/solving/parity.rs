@@ -19,7 +19,7 @@ impl Parity {
 
 impl From<usize> for Parity {
     fn from(value: usize) -> Self {
-        if value % 2 == 0 {
+        if value.is_multiple_of(2) {
             Parity::Even
         } else {
             Parity::Odd
@@ -67,27 +67,48 @@ pub fn permutation_parity<T: Into<usize> + Copy>(permutation: &[T]) -> Parity {
         .fold(Parity::Even, Parity::add)
 }
 
-/// Returns the parity of the number of moves required to move the empty cell into the solved position
-pub fn required_moves_parity(board: &impl Board) -> Parity {
-    let (rows, columns) = board.dimensions();
+/// Returns the parity of the number of moves required to move the empty cell into its goal position
+pub fn required_moves_parity(board: &(impl Board + ?Sized)) -> Parity {
+    empty_cell_distance_parity(board.empty_cell_pos(), board.goal_position(0))
+}
 
-    let zero_manhattan_distance = {
-        let final_empty_pos = (rows - 1, columns - 1);
-        let current_empty_pos = board.empty_cell_pos();
+/// Source of the parity of the number of moves required to reach the goal.
+/// When it is known, the move generator pairs moves together for positions requiring an even number of moves
+pub trait ParityProvider: Send + Sync {
+    /// Returns the parity of the number of moves required to reach the goal from the board,
+    /// or `None` if it is not determined by the position, which disables move pairing for it
+    fn required_parity(&self, board: &dyn Board) -> Option<Parity>;
+}
 
-        // we know that the final position is in the last row and column, so there is no possibility of overflow
-        (final_empty_pos.0 - current_empty_pos.0) + (final_empty_pos.1 - current_empty_pos.1)
-    };
+/// Parity relative to the goal carried by the board, which is the standard solved board by default
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BoardGoalParity;
 
-    Parity::from(zero_manhattan_distance as usize)
+impl ParityProvider for BoardGoalParity {
+    fn required_parity(&self, board: &dyn Board) -> Option<Parity> {
+        Some(required_moves_parity(board))
+    }
 }
 
-pub fn solved_board_parity(board: &impl Board) -> Parity {
-    let (rows, cols) = board.dimensions();
-    let total_cells = rows as usize * cols as usize;
+/// Parity relative to a fixed goal position of the empty cell
+#[derive(Clone, Copy, Debug)]
+pub struct EmptyCellParity {
+    pub target: (u8, u8),
+}
+
+impl ParityProvider for EmptyCellParity {
+    fn required_parity(&self, board: &dyn Board) -> Option<Parity> {
+        Some(empty_cell_distance_parity(
+            board.empty_cell_pos(),
+            self.target,
+        ))
+    }
+}
 
-    // solved board is one big cycle, so parity is opposite its size
-    Parity::from(total_cells).opposite()
+/// Returns the parity of the manhattan distance between two positions of the empty cell.
+/// Every move changes it, so it is also the parity of the number of moves between them
+pub fn empty_cell_distance_parity((r1, c1): (u8, u8), (r2, c2): (u8, u8)) -> Parity {
+    Parity::from(r1.abs_diff(r2) as usize + c1.abs_diff(c2) as usize)
 }
 
 #[cfg(test)]
@@ -0,0 +1,152 @@
+use std::sync::{Arc, Mutex};
+
+/// Action taken when a plateau is detected
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PlateauPolicy {
+    /// Only count the plateau in the statistics
+    Report,
+    /// Expand a node reached by a random walk of the given length from the current node,
+    /// to move the search to a different region of the state space
+    RandomWalk(usize),
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PlateauStatistics {
+    /// Number of times the search went `length` expansions without improving the heuristic
+    pub plateaus: u64,
+    /// Largest number of consecutive expansions without improving the heuristic
+    pub longest_plateau: u64,
+    /// Number of times the escape policy was applied
+    pub escapes: u64,
+}
+
+#[derive(Default)]
+struct PlateauState {
+    best_heuristic: Option<u64>,
+    expansions_without_improvement: u64,
+    statistics: PlateauStatistics,
+}
+
+/// Detects stretches of the search where the lowest heuristic value does not improve.
+///
+/// The monitor is a shared handle: clone it before passing to the solver to read the statistics after solving
+#[derive(Clone)]
+pub struct PlateauMonitor {
+    length: u64,
+    policy: PlateauPolicy,
+    state: Arc<Mutex<PlateauState>>,
+}
+
+impl PlateauMonitor {
+    /// Creates monitor which reports a plateau after `length` expansions without improvement
+    #[must_use]
+    pub fn new(length: u64, policy: PlateauPolicy) -> Self {
+        Self {
+            length: length.max(1),
+            policy,
+            state: Arc::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn statistics(&self) -> PlateauStatistics {
+        let state = self.state.lock().expect("Plateau monitor lock");
+        let mut statistics = state.statistics;
+        statistics.longest_plateau = statistics
+            .longest_plateau
+            .max(state.expansions_without_improvement);
+        statistics
+    }
+
+    /// Starts observing another board of a batch, keeping the statistics of the previous ones
+    pub fn next_board(&self) {
+        let mut state = self.state.lock().expect("Plateau monitor lock");
+        state.statistics.longest_plateau = state
+            .statistics
+            .longest_plateau
+            .max(state.expansions_without_improvement);
+        state.best_heuristic = None;
+        state.expansions_without_improvement = 0;
+    }
+
+    /// Registers expansion of a node with the given heuristic value.
+    /// Returns the policy to apply if the expansion completed a plateau
+    pub(crate) fn observe(&self, heuristic_value: u64) -> Option<PlateauPolicy> {
+        let mut state = self.state.lock().expect("Plateau monitor lock");
+
+        if state
+            .best_heuristic
+            .is_none_or(|best| heuristic_value < best)
+        {
+            state.best_heuristic = Some(heuristic_value);
+            state.statistics.longest_plateau = state
+                .statistics
+                .longest_plateau
+                .max(state.expansions_without_improvement);
+            state.expansions_without_improvement = 0;
+            return None;
+        }
+
+        state.expansions_without_improvement += 1;
+        if !state
+            .expansions_without_improvement
+            .is_multiple_of(self.length)
+        {
+            return None;
+        }
+
+        state.statistics.plateaus += 1;
+        if matches!(self.policy, PlateauPolicy::RandomWalk(_)) {
+            state.statistics.escapes += 1;
+        }
+        Some(self.policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_plateaus_and_their_length() {
+        let monitor = PlateauMonitor::new(3, PlateauPolicy::Report);
+        let events: Vec<_> = [5, 6, 5, 7, 4, 4, 4, 4, 4, 4, 4, 3]
+            .into_iter()
+            .map(|h| monitor.observe(h))
+            .collect();
+
+        assert_eq!(3, events.iter().flatten().count());
+        assert_eq!(
+            PlateauStatistics {
+                plateaus: 3,
+                longest_plateau: 6,
+                escapes: 0,
+            },
+            monitor.statistics()
+        );
+    }
+
+    #[test]
+    fn escape_policy_is_returned() {
+        let monitor = PlateauMonitor::new(1, PlateauPolicy::RandomWalk(4));
+
+        assert_eq!(None, monitor.observe(2));
+        assert_eq!(Some(PlateauPolicy::RandomWalk(4)), monitor.observe(2));
+        assert_eq!(1, monitor.statistics().escapes);
+    }
+
+    #[test]
+    fn next_board_forgets_best_value() {
+        let monitor = PlateauMonitor::new(2, PlateauPolicy::Report);
+        for h in [1, 3] {
+            monitor.observe(h);
+        }
+        monitor.next_board();
+
+        // the higher values of the next board are improvements, not a plateau
+        assert_eq!(None, monitor.observe(9));
+        assert_eq!(None, monitor.observe(8));
+        assert_eq!(0, monitor.statistics().plateaus);
+        assert_eq!(1, monitor.statistics().longest_plateau);
+    }
+}
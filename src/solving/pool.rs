@@ -0,0 +1,156 @@
+use std::sync::{Arc, Mutex};
+
+use crate::board::{BoardMove, OwnedBoard};
+
+/// Maximum number of released buffers of each kind kept for reuse
+const MAX_FREE_BUFFERS: usize = 4096;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct AllocationStatistics {
+    /// Number of boards created by allocating a new cell buffer
+    pub boards_allocated: u64,
+    /// Number of boards created by reusing the buffer of a released board
+    pub boards_reused: u64,
+    /// Number of paths created by allocating a new buffer
+    pub paths_allocated: u64,
+    /// Number of paths created by reusing the buffer of a released path
+    pub paths_reused: u64,
+}
+
+/// Collects the allocation statistics of the search nodes of a solver.
+///
+/// The counter is a shared handle: clone it before passing to the solver to read the statistics after solving
+#[derive(Clone, Default)]
+pub struct AllocationCounter {
+    statistics: Arc<Mutex<AllocationStatistics>>,
+}
+
+impl AllocationCounter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn statistics(&self) -> AllocationStatistics {
+        *self.statistics.lock().expect("Allocation counter lock")
+    }
+
+    fn add(&self, other: &AllocationStatistics) {
+        let mut statistics = self.statistics.lock().expect("Allocation counter lock");
+        statistics.boards_allocated += other.boards_allocated;
+        statistics.boards_reused += other.boards_reused;
+        statistics.paths_allocated += other.paths_allocated;
+        statistics.paths_reused += other.paths_reused;
+    }
+}
+
+/// Freelist of boards and paths of search nodes which are no longer needed,
+/// reused for new nodes instead of allocating fresh buffers.
+///
+/// Statistics are collected locally and published to the counter when the pool is dropped,
+/// to avoid locking on every node
+pub(crate) struct NodePool<B: Clone = OwnedBoard> {
+    boards: Vec<B>,
+    paths: Vec<Vec<BoardMove>>,
+    statistics: AllocationStatistics,
+    counter: Option<AllocationCounter>,
+}
+
+impl<B: Clone> Default for NodePool<B> {
+    fn default() -> Self {
+        Self {
+            boards: vec![],
+            paths: vec![],
+            statistics: AllocationStatistics::default(),
+            counter: None,
+        }
+    }
+}
+
+impl<B: Clone> NodePool<B> {
+    pub fn set_counter(&mut self, counter: AllocationCounter) {
+        self.counter = Some(counter);
+    }
+
+    /// Returns copy of the board, stored in a released buffer if there is any
+    pub fn board_from(&mut self, source: &B) -> B {
+        if let Some(mut board) = self.boards.pop() {
+            self.statistics.boards_reused += 1;
+            board.clone_from(source);
+            board
+        } else {
+            self.statistics.boards_allocated += 1;
+            source.clone()
+        }
+    }
+
+    /// Returns copy of the path, stored in a released buffer if there is any
+    pub fn path_from(&mut self, source: &[BoardMove]) -> Vec<BoardMove> {
+        if let Some(mut path) = self.paths.pop() {
+            self.statistics.paths_reused += 1;
+            path.clear();
+            path.extend_from_slice(source);
+            path
+        } else {
+            self.statistics.paths_allocated += 1;
+            source.to_vec()
+        }
+    }
+
+    pub fn release_board(&mut self, board: B) {
+        if self.boards.len() < MAX_FREE_BUFFERS {
+            self.boards.push(board);
+        }
+    }
+
+    pub fn release_path(&mut self, path: Vec<BoardMove>) {
+        if self.paths.len() < MAX_FREE_BUFFERS {
+            self.paths.push(path);
+        }
+    }
+}
+
+impl<B: Clone> Drop for NodePool<B> {
+    fn drop(&mut self) {
+        if let Some(counter) = &self.counter {
+            counter.add(&self.statistics);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Board;
+
+    use super::*;
+
+    #[test]
+    fn released_buffers_are_reused() {
+        let counter = AllocationCounter::new();
+        let mut pool = NodePool::default();
+        pool.set_counter(counter.clone());
+
+        let solved = OwnedBoard::new_solved(3, 3);
+        let mut moved = pool.board_from(&solved);
+        moved.exec_move(BoardMove::Up);
+        pool.release_board(moved);
+        let copy = pool.board_from(&solved);
+        let path = pool.path_from(&[BoardMove::Left]);
+        pool.release_path(path);
+        let path = pool.path_from(&[BoardMove::Up, BoardMove::Right]);
+        drop(pool);
+
+        assert_eq!(solved, copy);
+        assert_eq!(vec![BoardMove::Up, BoardMove::Right], path);
+        assert_eq!(
+            AllocationStatistics {
+                boards_allocated: 1,
+                boards_reused: 1,
+                paths_allocated: 1,
+                paths_reused: 1,
+            },
+            counter.statistics()
+        );
+    }
+}
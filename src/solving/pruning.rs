@@ -0,0 +1,167 @@
+use std::collections::HashSet;
+
+use crate::board::{Board, BoardMove};
+
+/// Counters of the running search, given to the pruning rules
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SearchStats {
+    /// Number of positions whose moves were generated
+    pub expanded: u64,
+    /// Number of generated positions cut off by the rules
+    pub pruned: u64,
+}
+
+/// Domain knowledge injected into the search, cutting off the positions which should not be explored.
+///
+/// Rules are registered on any solver with [`Solver::add_pruning_rule`], or with `with_pruning_rule`
+/// of the solvers which check them: BFS, DFS, IDFS, A*, best-first search and IDA*.
+/// The other solvers reject the rules with [`PruningUnsupported`].
+/// A solver using rules which prune all of the shortest solutions finds a longer one, or none at all
+///
+/// [`Solver::add_pruning_rule`]: crate::solving::algorithm::Solver::add_pruning_rule
+/// [`PruningUnsupported`]: crate::solving::algorithm::PruningUnsupported
+pub trait PruningRule {
+    /// Checks if the position reached by the path should be left out of the search
+    fn should_prune(&self, board: &dyn Board, path: &[BoardMove], stats: &SearchStats) -> bool;
+}
+
+/// Cuts off the paths longer than the given number of moves
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MaxPathLength(pub usize);
+
+impl PruningRule for MaxPathLength {
+    fn should_prune(&self, _board: &dyn Board, path: &[BoardMove], _stats: &SearchStats) -> bool {
+        path.len() > self.0
+    }
+}
+
+/// Tiles which must not be moved once they reach their goal position, e.g. the first row of a 4x4 board
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ForbiddenTiles {
+    tiles: HashSet<u8>,
+}
+
+impl ForbiddenTiles {
+    #[must_use]
+    pub fn new(tiles: impl IntoIterator<Item = u8>) -> Self {
+        Self {
+            tiles: tiles.into_iter().collect(),
+        }
+    }
+}
+
+impl PruningRule for ForbiddenTiles {
+    /// Checks the last two moves, as a move pair is generated at once,
+    /// while the positions before them were checked when they were generated
+    fn should_prune(&self, board: &dyn Board, path: &[BoardMove], _stats: &SearchStats) -> bool {
+        let mut empty_after = board.empty_cell_pos();
+        let mut later_move: Option<BoardMove> = None;
+        for &board_move in path.iter().rev().take(2) {
+            // a move undone by the next one leaves the tile where it was
+            if later_move.is_some_and(|later| later == board_move.opposite()) {
+                break;
+            }
+            // the moved tile is where the empty cell was, it was where the empty cell is now
+            let empty_before = neighbour(empty_after, board_move.opposite());
+            let tile = board.at(empty_before.0, empty_before.1);
+            if self.tiles.contains(&tile) && board.goal_position(tile) == empty_after {
+                return true;
+            }
+            empty_after = empty_before;
+            later_move = Some(board_move);
+        }
+        false
+    }
+}
+
+/// Returns the cell next to the given one in the direction of the move
+fn neighbour((row, column): (u8, u8), board_move: BoardMove) -> (u8, u8) {
+    match board_move {
+        BoardMove::Up => (row - 1, column),
+        BoardMove::Down => (row + 1, column),
+        BoardMove::Left => (row, column - 1),
+        BoardMove::Right => (row, column + 1),
+    }
+}
+
+/// Rules registered on a solver, together with the statistics of the search they are given.
+///
+/// Solvers check every position they generate before queueing or visiting it, so a pruned position
+/// is never expanded, and record every expansion, which the rules see in [`SearchStats::expanded`]
+#[derive(Default)]
+pub(crate) struct PruningRules {
+    rules: Vec<Box<dyn PruningRule>>,
+    stats: SearchStats,
+}
+
+impl PruningRules {
+    pub fn add(&mut self, rule: Box<dyn PruningRule>) {
+        self.rules.push(rule);
+    }
+
+    pub fn record_expansion(&mut self) {
+        self.stats.expanded += 1;
+    }
+
+    /// Checks the position reached by the path against the rules, counting it if any of them prunes it
+    pub fn prunes(&mut self, board: &dyn Board, path: &[BoardMove]) -> bool {
+        let pruned = self
+            .rules
+            .iter()
+            .any(|rule| rule.should_prune(board, path, &self.stats));
+        if pruned {
+            self.stats.pruned += 1;
+        }
+        pruned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::BoardMove::*;
+    use crate::board::{Board, OwnedBoard};
+
+    use super::*;
+
+    fn prunes(rule: &impl PruningRule, moves: &[BoardMove]) -> bool {
+        let mut board = OwnedBoard::new_solved(3, 3);
+        board.apply_moves(moves).unwrap();
+        rule.should_prune(&board, moves, &SearchStats::default())
+    }
+
+    #[test]
+    fn paths_longer_than_limit_are_pruned() {
+        let rule = MaxPathLength(2);
+
+        assert!(!prunes(&rule, &[Up, Left]));
+        assert!(prunes(&rule, &[Up, Left, Down]));
+    }
+
+    #[test]
+    fn placed_forbidden_tile_is_not_moved() {
+        // moving up displaces tile 6 from its goal position, moving left displaces tile 8
+        let rule = ForbiddenTiles::new([6]);
+
+        assert!(prunes(&rule, &[Up]));
+        assert!(!prunes(&rule, &[Left]));
+        assert!(prunes(&rule, &[Up, Left]));
+        assert!(prunes(&rule, &[Left, Up, Right]));
+    }
+
+    #[test]
+    fn first_move_of_pair_is_checked() {
+        let rule = ForbiddenTiles::new([8]);
+
+        assert!(prunes(&rule, &[Left, Up]));
+        assert!(!prunes(&rule, &[Up, Left]));
+    }
+
+    #[test]
+    fn forbidden_tile_may_move_to_its_goal_position() {
+        // the path starts from the board with tile 8 right of its goal position, the move places it
+        let board = OwnedBoard::new_solved(3, 3);
+        let rule = ForbiddenTiles::new([8]);
+
+        assert!(!rule.should_prune(&board, &[Right], &SearchStats::default()));
+    }
+}
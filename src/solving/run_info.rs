@@ -0,0 +1,263 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::board::fingerprint::Fingerprint;
+use crate::board::{goal_cells, Board};
+use crate::solving::algorithm::SolverConfig;
+
+/// Description of how a board was solved, attached to the written results so that the run can be repeated
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RunInfo {
+    /// Version of the solver which produced the results
+    pub crate_version: String,
+    /// Name of the algorithm, together with its search order if it has one, e.g. `bfs LRUD`
+    pub algorithm: String,
+    pub heuristic: Option<String>,
+    /// Options of the algorithm which change the search, e.g. the weight of weighted IDA*
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub parameters: BTreeMap<String, String>,
+    /// Seed of the randomized parts of the search, e.g. the jitter of the search order
+    pub seed: Option<u64>,
+    pub time_limit_ms: Option<u64>,
+    pub max_expansions: Option<u64>,
+    /// Hash of the dimensions, the cells and the goal of the board, see [`board_fingerprint`]
+    pub board_fingerprint: String,
+    /// Seconds since the Unix epoch when the run was started
+    pub timestamp: u64,
+}
+
+impl RunInfo {
+    /// Describes the run of the algorithm on the board, starting now
+    #[must_use]
+    pub fn new(
+        algorithm: impl Into<String>,
+        board: &(impl Board + ?Sized),
+        config: &SolverConfig,
+    ) -> Self {
+        Self {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            algorithm: algorithm.into(),
+            heuristic: None,
+            parameters: BTreeMap::new(),
+            seed: None,
+            time_limit_ms: config
+                .time_limit
+                .map(|time_limit| u64::try_from(time_limit.as_millis()).unwrap_or(u64::MAX)),
+            max_expansions: config.max_expansions,
+            board_fingerprint: format!("{:016x}", board_fingerprint(board)),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |since_epoch| since_epoch.as_secs()),
+        }
+    }
+
+    #[must_use]
+    pub fn with_heuristic(mut self, heuristic: impl Into<String>) -> Self {
+        self.heuristic = Some(heuristic.into());
+        self
+    }
+
+    /// Records the value of an option of the algorithm
+    #[must_use]
+    pub fn with_parameter(mut self, name: impl Into<String>, value: impl Display) -> Self {
+        self.parameters.insert(name.into(), value.to_string());
+        self
+    }
+
+    #[must_use]
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Returns the `key=value` pairs of everything but the timestamp, which tell whether two runs
+    /// search the same way, e.g. before resuming the search of a checkpoint
+    #[must_use]
+    pub fn identity(&self) -> String {
+        Identity(self).to_string()
+    }
+
+    /// Writes the description as a single-line JSON object, the same as serialized with serde.
+    /// It is available without the `json` feature, e.g. for the timings of the bench mode
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let string =
+            |value: &Option<String>| value.as_deref().map_or("null".to_string(), json_string);
+        let number =
+            |value: Option<u64>| value.map_or("null".to_string(), |value| value.to_string());
+        let parameters: Vec<_> = self
+            .parameters
+            .iter()
+            .map(|(name, value)| format!("{}:{}", json_string(name), json_string(value)))
+            .collect();
+        format!(
+            r#"{{"crate_version":{},"algorithm":{},"heuristic":{},"parameters":{{{}}},"seed":{},"time_limit_ms":{},"max_expansions":{},"board_fingerprint":{},"timestamp":{}}}"#,
+            json_string(&self.crate_version),
+            json_string(&self.algorithm),
+            string(&self.heuristic),
+            parameters.join(","),
+            number(self.seed),
+            number(self.time_limit_ms),
+            number(self.max_expansions),
+            json_string(&self.board_fingerprint),
+            self.timestamp
+        )
+    }
+}
+
+/// Writes the value as a JSON string literal, escaping the quotes, the backslashes and the control characters
+fn json_string(value: &str) -> String {
+    let mut literal = String::with_capacity(value.len() + 2);
+    literal.push('"');
+    for character in value.chars() {
+        match character {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            control if u32::from(control) < 0x20 => {
+                literal.push_str(&format!("\\u{:04x}", u32::from(control)));
+            }
+            character => literal.push(character),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/// The `key=value` pairs of everything but the timestamp
+struct Identity<'a>(&'a RunInfo);
+
+impl Display for Identity<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let Identity(run) = self;
+        write!(
+            f,
+            "crate_version={} algorithm={}",
+            run.crate_version, run.algorithm
+        )?;
+        if let Some(heuristic) = &run.heuristic {
+            write!(f, " heuristic={heuristic}")?;
+        }
+        for (name, value) in &run.parameters {
+            write!(f, " {name}={value}")?;
+        }
+        if let Some(seed) = run.seed {
+            write!(f, " seed={seed}")?;
+        }
+        if let Some(time_limit_ms) = run.time_limit_ms {
+            write!(f, " time_limit_ms={time_limit_ms}")?;
+        }
+        if let Some(max_expansions) = run.max_expansions {
+            write!(f, " max_expansions={max_expansions}")?;
+        }
+        write!(f, " board_fingerprint={}", run.board_fingerprint)
+    }
+}
+
+/// Writes the description as `key=value` pairs, e.g. for the comment lines of CSV files
+impl Display for RunInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} timestamp={}", Identity(self), self.timestamp)
+    }
+}
+
+/// Returns the [`Fingerprint`] hash of the board, with the cells of its goal following its own cells.
+/// Unlike [`std::hash::Hash`], it is the same in every version of the solver and of Rust
+#[must_use]
+pub fn board_fingerprint(board: &(impl Board + ?Sized)) -> u64 {
+    let (rows, columns) = board.dimensions();
+    let cells = board.iter_cells().map(|(_, value)| value);
+    Fingerprint::of_cells(rows, columns, cells.chain(goal_cells(board))).hash
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::BoardMove::*;
+    use crate::board::OwnedBoard;
+
+    use super::*;
+
+    #[test]
+    fn fingerprint_tells_boards_apart() {
+        let solved = OwnedBoard::new_solved(3, 3);
+        let moved = OwnedBoard::from_scramble(3, 3, &[Up]).unwrap();
+
+        assert_eq!(
+            board_fingerprint(&solved),
+            board_fingerprint(&solved.clone())
+        );
+        assert_ne!(board_fingerprint(&solved), board_fingerprint(&moved));
+        assert_ne!(
+            board_fingerprint(&solved),
+            board_fingerprint(&OwnedBoard::new_solved(4, 4))
+        );
+        // fingerprints are written with the results, so they must not change between versions
+        assert_eq!(0xcc9a_a943_607d_4513, board_fingerprint(&solved));
+    }
+
+    #[test]
+    fn run_info_is_written_as_json() {
+        let board = OwnedBoard::new_solved(3, 3);
+        let config = SolverConfig::default().with_max_expansions(1000);
+        let run = RunInfo::new("ida", &board, &config)
+            .with_heuristic("manhattan")
+            .with_parameter("weight", 1.5)
+            .with_seed(Some(7));
+
+        let json: serde_json::Value = serde_json::from_str(&run.to_json()).unwrap();
+
+        assert_eq!("ida", json["algorithm"]);
+        assert_eq!("manhattan", json["heuristic"]);
+        assert_eq!("1.5", json["parameters"]["weight"]);
+        assert_eq!(7, json["seed"]);
+        assert_eq!(1000, json["max_expansions"]);
+        assert!(json["time_limit_ms"].is_null());
+        assert_eq!(run.board_fingerprint, json["board_fingerprint"]);
+        assert!(run
+            .to_string()
+            .contains("algorithm=ida heuristic=manhattan weight=1.5 seed=7"));
+    }
+
+    #[test]
+    fn identity_ignores_only_the_timestamp() {
+        let board = OwnedBoard::new_solved(3, 3);
+        let run = RunInfo::new("ida", &board, &SolverConfig::default()).with_heuristic("LC");
+        let later = RunInfo {
+            timestamp: run.timestamp + 60,
+            ..run.clone()
+        };
+
+        assert_eq!(run.identity(), later.identity());
+        assert_ne!(
+            run.identity(),
+            run.clone().with_parameter("weight", 2).identity()
+        );
+        assert_ne!(run.identity(), run.with_heuristic("MD").identity());
+    }
+
+    #[test]
+    fn special_characters_are_escaped() {
+        let board = OwnedBoard::new_solved(3, 3);
+        let algorithm = "bfs \"LRUD\" \\ \u{1b}[0m\n";
+        let run = RunInfo::new(algorithm, &board, &SolverConfig::default());
+
+        let json: serde_json::Value = serde_json::from_str(&run.to_json()).unwrap();
+
+        assert_eq!(algorithm, json["algorithm"]);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn written_json_matches_serde() {
+        let board = OwnedBoard::new_solved(3, 3);
+        let config = SolverConfig::default().with_time_limit(std::time::Duration::from_secs(2));
+        let run = RunInfo::new("bfs LRUD", &board, &config).with_parameter("threads", 2);
+
+        assert_eq!(serde_json::to_string(&run).unwrap(), run.to_json());
+        assert_eq!(run, serde_json::from_str(&run.to_json()).unwrap());
+    }
+}
@@ -0,0 +1,393 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::board::generator::scramble;
+use crate::board::{parse_moves, Board, BoardCreationError, BoardMove, MoveError, OwnedBoard};
+use crate::solving::algorithm::heuristic::heuristics::{self, HeuristicIdError};
+use crate::solving::algorithm::solvers::{
+    AStarSolver, BFSSolver, DFSSolver, IncrementalDFSSolver, IterativeAStarSolver,
+};
+use crate::solving::algorithm::{Solver, SolvingError};
+use crate::solving::movegen::{MoveGenerator, SearchOrder, SearchOrderError};
+
+/// Heuristic used by the commands when none is given
+const DEFAULT_HEURISTIC: &str = "MD";
+
+/// Command of an interactive session, written as its name followed by the arguments, e.g. `move ULDR`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SessionCommand {
+    /// Replaces the board with the one read from the file, or written in a single line, e.g. `load 1 2 3 4 5 6 7 0 8`.
+    /// A single word is the path of the file
+    Load(String),
+    /// Shows the board and the number of moves made on it
+    Show,
+    /// Makes the moves, all of them or none
+    Move(Vec<BoardMove>),
+    /// Undoes the given number of the last moves
+    Undo(usize),
+    /// Shows the first move of a shortest solution, found by IDA* with the heuristic
+    Hint(String),
+    /// Solves the board with the algorithm, given the search order or heuristic it needs, without making the moves
+    Solve {
+        algorithm: String,
+        argument: Option<String>,
+    },
+    /// Replaces the board with the solved board of the same dimensions scrambled by the number of random moves
+    Scramble(usize),
+}
+
+impl FromStr for SessionCommand {
+    type Err = SessionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let mut argument = || words.next().map(ToString::to_string);
+        let count = |argument: Option<String>, default| {
+            argument.map_or(Ok(default), |count| {
+                count
+                    .parse()
+                    .map_err(|_| SessionError::InvalidArgument(count))
+            })
+        };
+
+        match name.to_ascii_lowercase().as_str() {
+            "load" => {
+                let source = words.collect::<Vec<_>>().join(" ");
+                if source.is_empty() {
+                    return Err(SessionError::MissingArgument("board file or line"));
+                }
+                Ok(SessionCommand::Load(source))
+            }
+            "show" => Ok(SessionCommand::Show),
+            "move" => {
+                let moves = argument().ok_or(SessionError::MissingArgument("moves"))?;
+                parse_moves(&moves)
+                    .map(SessionCommand::Move)
+                    .map_err(|_| SessionError::InvalidArgument(moves))
+            }
+            "undo" => count(argument(), 1).map(SessionCommand::Undo),
+            "hint" => Ok(SessionCommand::Hint(
+                argument().unwrap_or_else(|| DEFAULT_HEURISTIC.to_string()),
+            )),
+            "solve" => Ok(SessionCommand::Solve {
+                algorithm: argument().ok_or(SessionError::MissingArgument("algorithm"))?,
+                argument: argument(),
+            }),
+            "scramble" => count(argument(), 30).map(SessionCommand::Scramble),
+            _ => Err(SessionError::UnknownCommand(name.to_string())),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SessionError {
+    UnknownCommand(String),
+    MissingArgument(&'static str),
+    InvalidArgument(String),
+    UnknownAlgorithm(String),
+    /// There are fewer moves to undo than requested
+    NothingToUndo,
+    /// The board file cannot be read
+    UnreadableFile {
+        path: String,
+        error: std::io::Error,
+    },
+    InvalidBoard(BoardCreationError),
+    InvalidMove(MoveError),
+    InvalidOrder(SearchOrderError),
+    Heuristic(HeuristicIdError),
+    Solving(SolvingError),
+}
+
+impl Display for SessionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionError::UnknownCommand(name) => write!(
+                f,
+                "Unknown command '{name}', expected load, show, move, undo, hint, solve or scramble"
+            ),
+            SessionError::MissingArgument(argument) => write!(f, "Missing {argument}"),
+            SessionError::InvalidArgument(argument) => write!(f, "Invalid argument '{argument}'"),
+            SessionError::UnknownAlgorithm(name) => write!(
+                f,
+                "Unknown algorithm '{name}', expected bfs, dfs, idfs, astar or ida"
+            ),
+            SessionError::NothingToUndo => write!(f, "No more moves to undo"),
+            SessionError::UnreadableFile { path, error } => {
+                write!(f, "Unable to read board file '{path}': {error}")
+            }
+            SessionError::InvalidBoard(e) => write!(f, "{e}"),
+            SessionError::InvalidMove(e) => write!(f, "{e}"),
+            SessionError::InvalidOrder(e) => write!(f, "{e}"),
+            SessionError::Heuristic(e) => write!(f, "{e}"),
+            SessionError::Solving(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl Error for SessionError {}
+
+/// State of an interactive session: the board and the moves made on it since it was loaded
+pub struct Session {
+    board: OwnedBoard,
+    history: Vec<BoardMove>,
+}
+
+impl Session {
+    #[must_use]
+    pub fn new(board: OwnedBoard) -> Self {
+        Self {
+            board,
+            history: vec![],
+        }
+    }
+
+    #[must_use]
+    pub fn board(&self) -> &OwnedBoard {
+        &self.board
+    }
+
+    /// Returns the moves made since the board was loaded, which can be undone
+    #[must_use]
+    pub fn history(&self) -> &[BoardMove] {
+        &self.history
+    }
+
+    /// Executes the command, returning the text to show to the user
+    ///
+    /// # Errors
+    /// Returns error if the command cannot be executed, leaving the session unchanged
+    pub fn execute(&mut self, command: SessionCommand) -> Result<String, SessionError> {
+        match command {
+            SessionCommand::Load(source) => {
+                let input = if source.contains(char::is_whitespace) {
+                    source
+                } else {
+                    std::fs::read_to_string(&source).map_err(|error| {
+                        SessionError::UnreadableFile {
+                            path: source,
+                            error,
+                        }
+                    })?
+                };
+                let board =
+                    OwnedBoard::parse_lenient(&input).map_err(SessionError::InvalidBoard)?;
+                self.replace_board(board);
+                Ok(self.describe())
+            }
+            SessionCommand::Show => Ok(self.describe()),
+            SessionCommand::Move(moves) => {
+                let mut board = self.board.clone();
+                board
+                    .apply_moves(&moves)
+                    .map_err(SessionError::InvalidMove)?;
+                self.board = board;
+                self.history.extend(moves);
+                Ok(self.describe())
+            }
+            SessionCommand::Undo(count) => {
+                let kept = self
+                    .history
+                    .len()
+                    .checked_sub(count)
+                    .ok_or(SessionError::NothingToUndo)?;
+                for board_move in self.history.drain(kept..).rev() {
+                    self.board.exec_move(board_move.opposite());
+                }
+                Ok(self.describe())
+            }
+            SessionCommand::Hint(heuristic_id) => {
+                let solution = self.solve("ida", Some(&heuristic_id))?;
+                Ok(solution.first().map_or_else(
+                    || "Board is solved".to_string(),
+                    |board_move| {
+                        format!(
+                            "Next move: {board_move}, shortest solution length {}",
+                            solution.len()
+                        )
+                    },
+                ))
+            }
+            SessionCommand::Solve {
+                algorithm,
+                argument,
+            } => {
+                let solution = self.solve(&algorithm, argument.as_deref())?;
+                let moves: String = solution.iter().map(ToString::to_string).collect();
+                Ok(format!("{} moves: {moves}", solution.len()))
+            }
+            SessionCommand::Scramble(moves) => {
+                let (rows, columns) = self.board.dimensions();
+                let (board, _) = scramble(rows, columns, moves, &mut rand::thread_rng());
+                self.replace_board(board);
+                Ok(self.describe())
+            }
+        }
+    }
+
+    fn replace_board(&mut self, board: OwnedBoard) {
+        self.board = board;
+        self.history.clear();
+    }
+
+    fn solve(
+        &self,
+        algorithm: &str,
+        argument: Option<&str>,
+    ) -> Result<Vec<BoardMove>, SessionError> {
+        create_solver(algorithm, argument, self.board.clone())?
+            .solve()
+            .map_err(SessionError::Solving)
+    }
+
+    fn describe(&self) -> String {
+        let status = if self.board.is_solved() {
+            "solved"
+        } else {
+            "not solved"
+        };
+        format!(
+            "{}{} moves made, {status}",
+            self.board.to_input_string(),
+            self.history.len()
+        )
+    }
+}
+
+/// Creates the solver of the algorithm, given the search order or the heuristic it needs
+fn create_solver(
+    algorithm: &str,
+    argument: Option<&str>,
+    board: OwnedBoard,
+) -> Result<Box<dyn Solver>, SessionError> {
+    let move_generator = || {
+        argument.map_or(Ok(MoveGenerator::default()), |order| {
+            order
+                .parse::<SearchOrder>()
+                .map(MoveGenerator::new)
+                .map_err(SessionError::InvalidOrder)
+        })
+    };
+    let heuristic = |board: &OwnedBoard| {
+        heuristics::from_id(argument.unwrap_or(DEFAULT_HEURISTIC), board, None)
+            .map_err(SessionError::Heuristic)
+    };
+
+    Ok(match algorithm.to_ascii_lowercase().as_str() {
+        "bfs" => Box::new(BFSSolver::new(board, move_generator()?)),
+        "dfs" => Box::new(DFSSolver::new(board, move_generator()?)),
+        "idfs" => Box::new(IncrementalDFSSolver::new(board, move_generator()?)),
+        "astar" => {
+            let heuristic = heuristic(&board)?;
+            Box::new(AStarSolver::new(board, heuristic))
+        }
+        "ida" => {
+            let heuristic = heuristic(&board)?;
+            Box::new(IterativeAStarSolver::new(board, heuristic))
+        }
+        _ => return Err(SessionError::UnknownAlgorithm(algorithm.to_string())),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, BoardMove, OwnedBoard};
+
+    use super::{Session, SessionCommand, SessionError};
+
+    fn execute(session: &mut Session, line: &str) -> Result<String, SessionError> {
+        line.parse().and_then(|command| session.execute(command))
+    }
+
+    #[test]
+    fn commands_are_parsed() {
+        use BoardMove::*;
+
+        assert_eq!(
+            SessionCommand::Move(vec![Up, Left]),
+            "move UL".parse().unwrap()
+        );
+        assert_eq!(SessionCommand::Undo(1), "undo".parse().unwrap());
+        assert_eq!(
+            SessionCommand::Solve {
+                algorithm: "astar".to_string(),
+                argument: Some("MD".to_string())
+            },
+            "solve astar MD".parse().unwrap()
+        );
+        assert!(matches!(
+            "jump".parse::<SessionCommand>(),
+            Err(SessionError::UnknownCommand(_))
+        ));
+        assert!(matches!(
+            "scramble many".parse::<SessionCommand>(),
+            Err(SessionError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn board_is_loaded_from_line_or_file() {
+        let mut session = Session::new(OwnedBoard::new_solved(3, 3));
+
+        execute(&mut session, "load 1 2 3 4 5 6 7 0 8").unwrap();
+        assert_eq!(
+            "3 3\n1 2 3\n4 5 6\n7 0 8".parse::<OwnedBoard>().unwrap(),
+            *session.board()
+        );
+
+        let path = std::env::temp_dir().join(format!("session-board-{}.txt", std::process::id()));
+        std::fs::write(&path, "2 2\n1 2\n0 3\n").unwrap();
+        let loaded = execute(&mut session, &format!("load {}", path.display()));
+        std::fs::remove_file(&path).unwrap();
+        loaded.unwrap();
+        assert_eq!((2, 2), session.board().dimensions());
+
+        assert!(matches!(
+            execute(&mut session, "load missing-board.txt"),
+            Err(SessionError::UnreadableFile { .. })
+        ));
+        assert_eq!((2, 2), session.board().dimensions());
+    }
+
+    #[test]
+    fn moves_are_undone_in_reverse_order() {
+        let mut session = Session::new(OwnedBoard::new_solved(3, 3));
+
+        execute(&mut session, "move ULDR").unwrap();
+        assert_eq!(4, session.history().len());
+        assert!(!session.board().is_solved());
+
+        execute(&mut session, "undo 4").unwrap();
+        assert!(session.board().is_solved());
+        assert!(matches!(
+            execute(&mut session, "undo"),
+            Err(SessionError::NothingToUndo)
+        ));
+    }
+
+    #[test]
+    fn illegal_move_leaves_session_unchanged() {
+        let mut session = Session::new(OwnedBoard::new_solved(3, 3));
+
+        assert!(matches!(
+            execute(&mut session, "move UUU"),
+            Err(SessionError::InvalidMove(_))
+        ));
+        assert!(session.history().is_empty());
+        assert!(session.board().is_solved());
+    }
+
+    #[test]
+    fn hint_and_solve_do_not_change_board() {
+        let mut session = Session::new(OwnedBoard::new_solved(3, 3));
+        execute(&mut session, "move ULUL").unwrap();
+
+        assert!(execute(&mut session, "hint")
+            .unwrap()
+            .starts_with("Next move: R"));
+        assert_eq!("4 moves: RDRD", execute(&mut session, "solve bfs").unwrap());
+        assert_eq!(4, session.history().len());
+    }
+}
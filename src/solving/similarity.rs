@@ -0,0 +1,72 @@
+use crate::board::BoardMove;
+
+/// Returns the minimal number of inserted, removed or replaced moves which turn one solution into the other
+#[must_use]
+pub fn edit_distance(a: &[BoardMove], b: &[BoardMove]) -> usize {
+    // only the previous row of the distance matrix is kept
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, &a_move) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, &b_move) in b.iter().enumerate() {
+            let replacement = previous[j] + usize::from(a_move != b_move);
+            current[j + 1] = replacement.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// Returns the number of moves both solutions start with
+#[must_use]
+pub fn shared_prefix_length(a: &[BoardMove], b: &[BoardMove]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+/// Removes the solutions which are closer than `min_distance` edits to any of the solutions before them.
+/// The order of the kept solutions is preserved, so the shortest ones are kept if the solutions are sorted
+#[must_use]
+pub fn deduplicate(solutions: Vec<Vec<BoardMove>>, min_distance: usize) -> Vec<Vec<BoardMove>> {
+    let mut kept: Vec<Vec<BoardMove>> = Vec::with_capacity(solutions.len());
+    for solution in solutions {
+        if kept
+            .iter()
+            .all(|other| edit_distance(other, &solution) >= min_distance)
+        {
+            kept.push(solution);
+        }
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::parse_moves;
+
+    use super::{deduplicate, edit_distance, shared_prefix_length};
+
+    #[test]
+    fn measures_difference_of_solutions() {
+        let a = parse_moves("ULDRUL").unwrap();
+        let b = parse_moves("ULRRU").unwrap();
+
+        assert_eq!(0, edit_distance(&a, &a));
+        assert_eq!(2, edit_distance(&a, &b));
+        assert_eq!(6, edit_distance(&a, &[]));
+        assert_eq!(2, shared_prefix_length(&a, &b));
+        assert_eq!(0, shared_prefix_length(&a, &[]));
+    }
+
+    #[test]
+    fn deduplication_keeps_first_of_similar_solutions() {
+        let solutions: Vec<_> = ["ULDR", "ULDL", "DRUL", "ULDR"]
+            .into_iter()
+            .map(|s| parse_moves(s).unwrap())
+            .collect();
+
+        let kept = deduplicate(solutions.clone(), 2);
+
+        assert_eq!(vec![solutions[0].clone(), solutions[2].clone()], kept);
+        assert_eq!(3, deduplicate(solutions, 1).len());
+    }
+}
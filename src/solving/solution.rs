@@ -0,0 +1,102 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::board::BoardMove;
+
+/// Position reached by the moves, relative to the position they start from.
+///
+/// The moves are followed on an unbounded grid, so the same position is recognized without knowing the board.
+/// Only the tiles which were moved away from their starting cells are stored
+#[derive(Clone, Default, Eq, Hash, PartialEq)]
+struct RelativePosition {
+    empty_cell: (i64, i64),
+    /// Starting cell of the tile in each cell it was moved to
+    moved_tiles: BTreeMap<(i64, i64), (i64, i64)>,
+}
+
+impl RelativePosition {
+    fn exec_move(&mut self, board_move: BoardMove) {
+        let (row, column) = self.empty_cell;
+        let target = match board_move {
+            BoardMove::Up => (row - 1, column),
+            BoardMove::Down => (row + 1, column),
+            BoardMove::Left => (row, column - 1),
+            BoardMove::Right => (row, column + 1),
+        };
+        // the tile from the target cell slides into the empty cell
+        let origin = self.moved_tiles.remove(&target).unwrap_or(target);
+        if origin != self.empty_cell {
+            self.moved_tiles.insert(self.empty_cell, origin);
+        }
+        self.empty_cell = target;
+    }
+}
+
+/// Removes the loops of the solution, i.e. the moves between two visits of the same position,
+/// including the moves immediately undone by the next one.
+///
+/// The result solves the same board, as it passes through a subset of the positions of the solution
+/// in the same order. Solutions which never revisit a position, e.g. the shortest ones, are returned unchanged
+#[must_use]
+pub fn optimize(moves: &[BoardMove]) -> Vec<BoardMove> {
+    let mut optimized = Vec::with_capacity(moves.len());
+    let mut position = RelativePosition::default();
+    // positions after every prefix of the optimized moves, and the length of the prefix reaching each of them
+    let mut visited = vec![position.clone()];
+    let mut prefix_lengths = HashMap::from([(position.clone(), 0)]);
+    for &board_move in moves {
+        position.exec_move(board_move);
+        if let Some(&length) = prefix_lengths.get(&position) {
+            // splice out the loop, forgetting the positions visited inside of it
+            for removed in visited.drain(length + 1..) {
+                prefix_lengths.remove(&removed);
+            }
+            optimized.truncate(length);
+        } else {
+            optimized.push(board_move);
+            visited.push(position.clone());
+            prefix_lengths.insert(position.clone(), optimized.len());
+        }
+    }
+    optimized
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{parse_moves, Board, OwnedBoard};
+
+    use super::*;
+
+    #[test]
+    fn undone_moves_are_removed() {
+        let moves = parse_moves("ULRDDU").unwrap();
+
+        assert!(optimize(&moves).is_empty());
+    }
+
+    #[test]
+    fn loops_are_spliced_out() {
+        // going around the square of four cells twelve times restores the position
+        let cycle = "ULDR".repeat(12);
+        let moves = parse_moves(&format!("UL{cycle}UR")).unwrap();
+
+        assert_eq!(parse_moves("ULUR").unwrap(), optimize(&moves));
+    }
+
+    #[test]
+    fn optimized_solution_solves_same_board() {
+        let scramble = parse_moves("ULULDRDRULURDLLDRUUL").unwrap();
+        let board = OwnedBoard::from_scramble(4, 4, &scramble).unwrap();
+        // undoing the scramble, with a detour around the square of the empty cell
+        let detour = parse_moves("DRULDRULDRUL").unwrap();
+        let mut solution = detour.clone();
+        solution.extend(scramble.iter().rev().map(BoardMove::opposite));
+
+        let optimized = optimize(&solution);
+
+        let mut solved = board.clone();
+        solved.apply_moves(&optimized).unwrap();
+        assert!(solved.is_solved());
+        assert_eq!(optimized, optimize(&optimized));
+        assert!(optimized.len() < solution.len());
+    }
+}
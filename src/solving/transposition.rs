@@ -0,0 +1,84 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Depth at which every position was visited by the current iteration of a depth-limited search.
+///
+/// A revisit at the same or a greater depth has at most as many moves left as the first visit,
+/// whose subtree was already searched, so it can be pruned. A revisit at a shallower depth is searched again.
+/// Entries are stamped with the generation of the iteration which stored them, and the entries of the previous
+/// iterations count as absent, so the table is cleared by starting a new generation instead of reallocating it
+pub(crate) struct TranspositionTable<T: Eq + Hash> {
+    entries: HashMap<T, Visit>,
+    generation: u32,
+}
+
+/// Latest visit of a position
+#[derive(Clone, Copy)]
+struct Visit {
+    generation: u32,
+    depth: usize,
+}
+
+impl<T: Eq + Hash> TranspositionTable<T> {
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+            generation: 0,
+        }
+    }
+
+    /// Forgets the positions visited by the previous iterations, keeping the allocated memory
+    pub fn next_generation(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        if self.generation == 0 {
+            // after the counter wraps around, stale entries could be mistaken for current ones
+            self.entries.clear();
+        }
+    }
+
+    /// Records the visit of the position at the depth, returning `false` if the current iteration
+    /// already visited it at the same or a shallower depth, and the visit can be pruned
+    pub fn visit(&mut self, position: T, depth: usize) -> bool {
+        let generation = self.generation;
+        match self.entries.entry(position) {
+            Entry::Occupied(mut entry) => {
+                let stored = entry.get_mut();
+                if stored.generation == generation && stored.depth <= depth {
+                    return false;
+                }
+                *stored = Visit { generation, depth };
+                true
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(Visit { generation, depth });
+                true
+            }
+        }
+    }
+
+    /// Number of stored positions, including those of the previous iterations
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prunes_only_revisits_of_current_generation_at_same_or_greater_depth() {
+        let mut table = TranspositionTable::new();
+        assert!(table.visit('a', 3));
+        assert!(!table.visit('a', 3));
+        assert!(!table.visit('a', 5));
+        assert!(table.visit('a', 2));
+        assert!(!table.visit('a', 2));
+
+        table.next_generation();
+
+        assert!(table.visit('a', 4));
+        assert_eq!(1, table.len());
+    }
+}
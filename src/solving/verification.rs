@@ -0,0 +1,83 @@
+use crate::board::{BoardMove, OwnedBoard};
+use crate::solving::algorithm::heuristic::astar::IterativeAStarSolver;
+use crate::solving::algorithm::heuristic::heuristics::Heuristic;
+use crate::solving::algorithm::{IntermediateResult, SolvingError};
+
+/// Outcome of the independent check of the optimality of a solution
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum OptimalityCheck {
+    /// No solution is shorter than the checked one
+    Confirmed,
+    /// A shorter solution exists
+    Refuted { shortest: Vec<BoardMove> },
+}
+
+/// Checks that no solution of the board is shorter than `solution_length`, independently of the solver which found it.
+///
+/// IDA* proves the bound by exhausting every path whose estimated length is below the length of the solution,
+/// so the check does not share the pruning and duplicate handling of the other solvers.
+/// The heuristic has to be admissible for the proof to hold
+///
+/// # Errors
+/// Returns error if the search fails, e.g. the board is unsolvable
+pub fn check_optimality(
+    board: OwnedBoard,
+    solution_length: usize,
+    heuristic: Box<dyn Heuristic>,
+) -> Result<OptimalityCheck, SolvingError> {
+    let solution_length = solution_length as u64;
+    for result in IterativeAStarSolver::new(board, heuristic) {
+        match result {
+            IntermediateResult::BoundExhausted { next_bound, .. } => {
+                if next_bound >= solution_length {
+                    return Ok(OptimalityCheck::Confirmed);
+                }
+            }
+            IntermediateResult::Solved(shortest) => {
+                return Ok(if (shortest.len() as u64) < solution_length {
+                    OptimalityCheck::Refuted { shortest }
+                } else {
+                    OptimalityCheck::Confirmed
+                });
+            }
+            IntermediateResult::Failed(error) => return Err(error),
+        }
+    }
+    Err(SolvingError::UnsolvableBoard(None))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, OwnedBoard};
+    use crate::solving::algorithm::heuristic::heuristics::ManhattanDistance;
+
+    use super::{check_optimality, OptimalityCheck};
+
+    const BOARD: &str = "3 3\n0 4 2\n1 7 3\n5 8 6";
+
+    #[test]
+    fn shortest_solution_is_confirmed() {
+        let board: OwnedBoard = BOARD.parse().unwrap();
+
+        let check = check_optimality(board, 12, Box::new(ManhattanDistance)).unwrap();
+
+        assert_eq!(OptimalityCheck::Confirmed, check);
+    }
+
+    #[test]
+    fn longer_solution_is_refuted() {
+        let board: OwnedBoard = BOARD.parse().unwrap();
+
+        let check = check_optimality(board.clone(), 14, Box::new(ManhattanDistance)).unwrap();
+
+        let OptimalityCheck::Refuted { shortest } = check else {
+            unreachable!("12 moves long solution exists");
+        };
+        assert_eq!(12, shortest.len());
+        let mut solved = board;
+        solved.apply_moves(&shortest).unwrap();
+        assert!(solved.is_solved());
+    }
+}
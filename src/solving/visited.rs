@@ -1,37 +1,79 @@
 #![allow(dead_code)]
 
-use crate::board::Board;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashSet;
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
 use std::sync::{Arc, RwLock};
 
-#[derive(Clone, Default)]
-pub struct VisitedPositions<T: Board + Eq + Hash> {
-    visited_states: Arc<RwLock<HashSet<T>>>,
+/// Set of the visited positions, which can be shared between threads.
+/// The positions are split between shards by their hash, each behind its own lock,
+/// so threads marking different positions rarely wait for each other
+#[derive(Clone)]
+pub struct VisitedPositions<T: Eq + Hash> {
+    shards: Arc<[RwLock<HashSet<T>>]>,
 }
 
-impl<T: Board + Eq + Hash> VisitedPositions<T> {
+impl<T: Eq + Hash> Default for VisitedPositions<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Eq + Hash> VisitedPositions<T> {
     pub fn new() -> Self {
+        Self::with_shards(1)
+    }
+
+    /// Creates the set split into the given number of shards, at least one
+    pub fn with_shards(shards: usize) -> Self {
         VisitedPositions {
             // Arc allows multiple threads
-            visited_states: Arc::new(RwLock::new(HashSet::new())),
+            shards: (0..shards.max(1))
+                .map(|_| RwLock::new(HashSet::new()))
+                .collect(),
         }
     }
 
+    fn shard(&self, board: &T) -> &RwLock<HashSet<T>> {
+        if self.shards.len() == 1 {
+            return &self.shards[0];
+        }
+        let mut hasher = DefaultHasher::new();
+        board.hash(&mut hasher);
+        // the remainder is lower than the number of shards, so it fits in usize
+        #[allow(clippy::cast_possible_truncation)]
+        let index = (hasher.finish() % self.shards.len() as u64) as usize;
+        &self.shards[index]
+    }
+
     // Check if a board state has been visited
     pub fn is_visited(&self, board: &T) -> bool {
-        let lock = self.visited_states.read().expect("RwLock read lock");
+        let lock = self.shard(board).read().expect("RwLock read lock");
         lock.contains(board)
     }
 
     // Mark a board state as visited
     pub fn mark_visited(&self, board: T) {
-        let mut lock = self.visited_states.write().expect("RwLock write lock");
-        lock.insert(board);
+        self.insert(board);
+    }
+
+    // Mark a board state as visited, returning whether it was not visited before.
+    // Unlike checking and marking it separately, only one of the threads inserting the same state gets `true`
+    pub fn insert(&self, board: T) -> bool {
+        let mut lock = self.shard(&board).write().expect("RwLock write lock");
+        lock.insert(board)
+    }
+
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.read().expect("RwLock read lock").len())
+            .sum()
     }
 
     pub fn clear(&self) {
-        let mut lock = self.visited_states.write().expect("RwLock write lock");
-        lock.clear();
+        for shard in self.shards.iter() {
+            shard.write().expect("RwLock write lock").clear();
+        }
     }
 }
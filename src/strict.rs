@@ -0,0 +1,273 @@
+//! Fallible entry points which do not panic on invalid input.
+//!
+//! Embedders (WASM, FFI, services) cannot let a panic cross their boundary,
+//! so every problem with the input is reported as [`StrictError`] instead.
+//! Solutions are additionally checked against the board, so a solution which does not solve it
+//! is reported as an error instead of being returned.
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use crate::board::{Board, BoardCreationError, BoardMove, MoveError, OwnedBoard};
+use crate::solving::algorithm::heuristic::astar::IterativeAStarSolver;
+use crate::solving::algorithm::heuristic::heuristics::Heuristic;
+use crate::solving::algorithm::{Solver, SolvingError};
+
+#[derive(Debug)]
+pub enum StrictError {
+    InvalidBoard(BoardCreationError),
+    IllegalMove(MoveError),
+    Solving(SolvingError),
+    /// The heuristic evaluates only boards of other dimensions than the board
+    HeuristicDimensions {
+        heuristic: (u8, u8),
+        board: (u8, u8),
+    },
+    /// The solution returned by the solver contains a move which cannot be executed on the board
+    IllegalSolution(MoveError),
+    /// The solution returned by the solver does not lead to the goal of the board
+    IncompleteSolution,
+}
+
+impl Display for StrictError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StrictError::InvalidBoard(err) => write!(f, "Invalid board: {err}"),
+            StrictError::IllegalMove(err) => write!(f, "Illegal move: {err}"),
+            StrictError::Solving(err) => write!(f, "{err}"),
+            StrictError::HeuristicDimensions { heuristic, board } => write!(
+                f,
+                "Heuristic evaluates {}x{} boards, but the board is {}x{}",
+                heuristic.0, heuristic.1, board.0, board.1
+            ),
+            StrictError::IllegalSolution(err) => write!(f, "Solver returned illegal move: {err}"),
+            StrictError::IncompleteSolution => {
+                write!(f, "Solver returned moves which do not solve the board")
+            }
+        }
+    }
+}
+
+impl Error for StrictError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            StrictError::InvalidBoard(err) => Some(err),
+            StrictError::IllegalMove(err) => Some(err),
+            StrictError::Solving(err) => Some(err),
+            StrictError::IllegalSolution(err) => Some(err),
+            StrictError::HeuristicDimensions { .. } | StrictError::IncompleteSolution => None,
+        }
+    }
+}
+
+impl From<BoardCreationError> for StrictError {
+    fn from(value: BoardCreationError) -> Self {
+        Self::InvalidBoard(value)
+    }
+}
+
+impl From<MoveError> for StrictError {
+    fn from(value: MoveError) -> Self {
+        Self::IllegalMove(value)
+    }
+}
+
+impl From<SolvingError> for StrictError {
+    fn from(value: SolvingError) -> Self {
+        Self::Solving(value)
+    }
+}
+
+/// Parses the board from its text representation
+///
+/// # Errors
+/// Returns error if the text does not describe a valid board
+pub fn parse_board(input: &str) -> Result<OwnedBoard, StrictError> {
+    Ok(input.parse()?)
+}
+
+/// Creates the board from the cell values in row-major order, with `0` marking the empty cell
+///
+/// # Errors
+/// Returns error if the cells do not form a valid board of the given dimensions
+pub fn board_from_cells(rows: u8, columns: u8, cells: Vec<u8>) -> Result<OwnedBoard, StrictError> {
+    Ok(OwnedBoard::with_cells(rows, columns, cells)?)
+}
+
+/// Executes the moves on the board, either all of them or none
+///
+/// # Errors
+/// Returns error if the board is not valid, e.g. it has no empty cell,
+/// or with the index of the first move which cannot be performed, leaving the board unchanged
+pub fn apply_moves(board: &mut OwnedBoard, moves: &[BoardMove]) -> Result<(), StrictError> {
+    board.validate()?;
+    let mut moved = board.clone();
+    moved.apply_moves(moves)?;
+    *board = moved;
+    Ok(())
+}
+
+/// Runs the solver of the board, and checks that the returned solution solves it
+///
+/// # Errors
+/// Returns error if the board is not valid, the solver fails, or its solution does not solve the board
+pub fn solve(board: &OwnedBoard, solver: Box<dyn Solver>) -> Result<Vec<BoardMove>, StrictError> {
+    board.validate()?;
+    let solution = solver.solve()?;
+    let mut solved = board.clone();
+    solved
+        .apply_moves(&solution)
+        .map_err(StrictError::IllegalSolution)?;
+    if !solved.is_solved() {
+        return Err(StrictError::IncompleteSolution);
+    }
+    Ok(solution)
+}
+
+/// Finds the shortest solution of the board with IDA* guided by the heuristic, see [`solve`]
+///
+/// # Errors
+/// Returns error if the heuristic cannot evaluate boards of these dimensions, or for the same reasons as [`solve`]
+pub fn solve_with_heuristic(
+    board: &OwnedBoard,
+    heuristic: Box<dyn Heuristic>,
+) -> Result<Vec<BoardMove>, StrictError> {
+    board.validate()?;
+    if let Some(dimensions) = heuristic.dimensions() {
+        if dimensions != board.dimensions() {
+            return Err(StrictError::HeuristicDimensions {
+                heuristic: dimensions,
+                board: board.dimensions(),
+            });
+        }
+    }
+    solve(
+        board,
+        Box::new(IterativeAStarSolver::new(board.clone(), heuristic)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::BoardMove::*;
+    use crate::solving::algorithm::bfs::BFSSolver;
+    use crate::solving::algorithm::heuristic::heuristics::ManhattanDistance;
+    use crate::solving::algorithm::heuristic::pattern_database::{Partition, PatternDatabase};
+    use crate::solving::movegen::MoveGenerator;
+
+    use super::*;
+
+    /// Solver with a bug, which returns the given moves whatever the board is
+    struct WrongSolver(Vec<BoardMove>);
+
+    impl Solver for WrongSolver {
+        fn solve(self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
+            Ok(self.0)
+        }
+    }
+
+    /// Board of another representation, where every cell holds a tile
+    struct WithoutEmptyCell;
+
+    impl Board for WithoutEmptyCell {
+        fn dimensions(&self) -> (u8, u8) {
+            (2, 2)
+        }
+        fn at(&self, row: u8, column: u8) -> u8 {
+            row * 2 + column + 1
+        }
+        fn empty_cell_pos(&self) -> (u8, u8) {
+            unreachable!("Board has no empty cell")
+        }
+        fn is_solved(&self) -> bool {
+            false
+        }
+        fn can_move(&self, _board_move: BoardMove) -> bool {
+            false
+        }
+        fn exec_move(&mut self, _board_move: BoardMove) {
+            unreachable!("Board has no empty cell")
+        }
+    }
+
+    #[test]
+    fn board_without_empty_cell_is_rejected() {
+        let mut board = OwnedBoard::from_board(&WithoutEmptyCell);
+
+        assert!(matches!(
+            apply_moves(&mut board, &[Up]),
+            Err(StrictError::InvalidBoard(_))
+        ));
+        assert!(matches!(
+            solve(&board, Box::new(WrongSolver(vec![Up]))),
+            Err(StrictError::InvalidBoard(_))
+        ));
+        assert!(matches!(
+            solve_with_heuristic(&board, Box::new(ManhattanDistance)),
+            Err(StrictError::InvalidBoard(_))
+        ));
+    }
+
+    #[test]
+    fn heuristic_of_other_dimensions_is_rejected() {
+        let database = PatternDatabase::generate(3, 3, Partition::disjoint(3, 3)).unwrap();
+        let board = parse_board("2 3\n1 2 3\n4 0 5").unwrap();
+
+        assert!(matches!(
+            solve_with_heuristic(&board, Box::new(database)),
+            Err(StrictError::HeuristicDimensions {
+                heuristic: (3, 3),
+                board: (2, 3)
+            })
+        ));
+        assert_eq!(
+            solve_with_heuristic(&board, Box::new(ManhattanDistance)).unwrap(),
+            [Right]
+        );
+    }
+
+    #[test]
+    fn boards_without_cells_are_rejected() {
+        for input in ["0 0", "3 0\n\n\n"] {
+            assert!(matches!(
+                parse_board(input),
+                Err(StrictError::InvalidBoard(e)) if matches!(e.kind(), BoardCreationError::InvalidHeader)
+            ));
+        }
+        assert!(board_from_cells(0, 4, vec![]).is_err());
+    }
+
+    #[test]
+    fn failed_moves_leave_board_unchanged() {
+        let mut board = OwnedBoard::new_solved(3, 3);
+        let result = apply_moves(&mut board, &[Up, Right]);
+
+        assert!(matches!(
+            result,
+            Err(StrictError::IllegalMove(MoveError::IllegalMove {
+                index: 1,
+                ..
+            }))
+        ));
+        assert!(board.is_solved());
+    }
+
+    #[test]
+    fn wrong_solution_is_returned_as_error() {
+        let board = parse_board("3 3\n1 2 3\n4 5 6\n7 0 8").unwrap();
+        let solver = BFSSolver::new(board.clone(), MoveGenerator::default());
+        assert_eq!(solve(&board, Box::new(solver)).unwrap(), [Right]);
+
+        assert!(matches!(
+            solve(&board, Box::new(WrongSolver(vec![Right, Right]))),
+            Err(StrictError::IllegalSolution(MoveError::IllegalMove {
+                index: 1,
+                ..
+            }))
+        ));
+        assert!(matches!(
+            solve(&board, Box::new(WrongSolver(vec![Left]))),
+            Err(StrictError::IncompleteSolution)
+        ));
+    }
+}
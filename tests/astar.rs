@@ -1,5 +1,11 @@
+use solver::board::{ArrayBoard, Board, CowBoard, OwnedBoard};
 use solver::solving::algorithm::heuristic;
 use solver::solving::algorithm::heuristic::astar::AStarSolver;
+use solver::solving::algorithm::heuristic::focal::FocalSolver;
+use solver::solving::algorithm::heuristic::DuplicatePolicy;
+use solver::solving::algorithm::{PruningUnsupported, Solver, SolvingError};
+use solver::solving::plateau::{PlateauMonitor, PlateauPolicy};
+use solver::solving::pruning::ForbiddenTiles;
 
 use crate::shared::{assert_produces_shortest_solution, assert_produces_valid_solution};
 
@@ -18,3 +24,158 @@ fn produces_shortest_solution() {
         AStarSolver::new(board, Box::new(heuristic::heuristics::ManhattanDistance))
     });
 }
+
+#[test]
+fn plateau_escape_keeps_solution_shortest() {
+    assert_produces_shortest_solution(|board| {
+        AStarSolver::new(board, Box::new(heuristic::heuristics::ManhattanDistance))
+            .with_plateau_monitor(PlateauMonitor::new(2, PlateauPolicy::RandomWalk(3)))
+    });
+}
+
+#[test]
+fn copy_on_write_boards_produce_shortest_solution() {
+    assert_produces_shortest_solution(|board| {
+        AStarSolver::<CowBoard>::with_representation(
+            board,
+            Box::new(heuristic::heuristics::ManhattanDistance),
+        )
+        .with_duplicate_policy(DuplicatePolicy::LazyReinsert)
+    });
+}
+
+#[test]
+fn graph_search_policies_produce_shortest_solution() {
+    for policy in [DuplicatePolicy::DecreaseKey, DuplicatePolicy::LazyReinsert] {
+        assert_produces_shortest_solution(|board| {
+            AStarSolver::new(board, Box::new(heuristic::heuristics::ManhattanDistance))
+                .with_duplicate_policy(policy)
+        });
+    }
+}
+
+#[test]
+fn bucket_queue_produces_shortest_solution() {
+    for policy in [DuplicatePolicy::Keep, DuplicatePolicy::LazyReinsert] {
+        assert_produces_shortest_solution(|board| {
+            AStarSolver::new(board, Box::new(heuristic::heuristics::ManhattanDistance))
+                .with_duplicate_policy(policy)
+                .with_bucket_queue()
+        });
+    }
+}
+
+#[test]
+fn ignoring_duplicates_produces_correct_solution() {
+    assert_produces_valid_solution(|board| {
+        AStarSolver::new(board, Box::new(heuristic::heuristics::ManhattanDistance))
+            .with_duplicate_policy(DuplicatePolicy::Ignore)
+    });
+}
+
+#[test]
+fn array_boards_produce_shortest_solution() {
+    assert_produces_shortest_solution(|board| {
+        AStarSolver::<ArrayBoard<16>>::with_representation(
+            board,
+            Box::new(heuristic::heuristics::ManhattanDistance),
+        )
+    });
+}
+
+#[test]
+fn board_larger_than_array_is_rejected() {
+    let solver = AStarSolver::<ArrayBoard<16>>::with_representation(
+        OwnedBoard::new_solved(5, 5),
+        Box::new(heuristic::heuristics::ManhattanDistance),
+    );
+
+    assert!(matches!(
+        Box::new(solver).solve(),
+        Err(SolvingError::AlgorithmError(_))
+    ));
+}
+
+#[test]
+fn k_shortest_solutions_are_distinct_and_ordered() {
+    let board: OwnedBoard = "3 3\n4 1 3\n7 2 5\n8 0 6".parse().unwrap();
+
+    let solutions = AStarSolver::new(
+        board.clone(),
+        Box::new(heuristic::heuristics::ManhattanDistance),
+    )
+    .solve_k_shortest(5)
+    .unwrap();
+
+    assert_eq!(5, solutions.len());
+    assert_eq!(7, solutions[0].len());
+    for (i, solution) in solutions.iter().enumerate() {
+        let mut solved = board.clone();
+        solved.apply_moves(solution).unwrap();
+        assert!(solved.is_solved());
+        assert!(solutions[..i].iter().all(|other| other != solution));
+        assert!(solutions[..i]
+            .iter()
+            .all(|other| other.len() <= solution.len()));
+    }
+}
+
+#[test]
+fn k_shortest_returns_all_solutions_if_there_are_fewer() {
+    // positions of the 2x2 board form a single cycle, so the goal can be reached from either side
+    let board: OwnedBoard = "2 2\n1 2\n0 3".parse().unwrap();
+
+    let solutions = AStarSolver::new(board, Box::new(heuristic::heuristics::ManhattanDistance))
+        .solve_k_shortest(5)
+        .unwrap();
+
+    let lengths: Vec<_> = solutions.iter().map(Vec::len).collect();
+    assert_eq!(vec![1, 11], lengths);
+}
+
+#[test]
+fn forbidden_tiles_stay_in_place() {
+    use solver::board::BoardMove::*;
+
+    // the empty cell never enters the first row
+    let mut board = OwnedBoard::new_solved(4, 4);
+    board
+        .apply_moves(&[Up, Up, Left, Left, Down, Right, Down, Left, Left, Up])
+        .unwrap();
+    let solver = AStarSolver::new(
+        board.clone(),
+        Box::new(heuristic::heuristics::ManhattanDistance),
+    )
+    .with_pruning_rule(ForbiddenTiles::new([1, 2, 3, 4]));
+
+    let solution = Box::new(solver).solve().unwrap();
+
+    let mut replayed = board;
+    for &board_move in &solution {
+        replayed.exec_move(board_move);
+        assert!((0..4).all(|column| replayed.at(0, column) == column + 1));
+    }
+    assert!(replayed.is_solved());
+}
+
+#[test]
+fn pruning_rules_are_rejected_by_solvers_which_do_not_check_them() {
+    let board = OwnedBoard::new_solved(4, 4);
+    let mut solver = AStarSolver::new(
+        board.clone(),
+        Box::new(heuristic::heuristics::ManhattanDistance),
+    );
+    assert!(solver
+        .add_pruning_rule(Box::new(ForbiddenTiles::new([1])))
+        .is_ok());
+
+    let mut solver = FocalSolver::new(
+        board,
+        Box::new(heuristic::heuristics::ManhattanDistance),
+        0.5,
+    );
+    assert!(matches!(
+        solver.add_pruning_rule(Box::new(ForbiddenTiles::new([1]))),
+        Err(PruningUnsupported)
+    ));
+}
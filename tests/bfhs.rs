@@ -0,0 +1,29 @@
+use solver::solving::algorithm::heuristic::heuristics::ManhattanDistance;
+use solver::solving::algorithm::solvers::{AStarSolver, BreadthFirstHeuristicSolver};
+
+use crate::shared::{assert_counts_fewer, assert_produces_shortest_solution, deep_board};
+
+mod shared;
+
+#[test]
+fn produces_shortest_solution() {
+    assert_produces_shortest_solution(|board| {
+        BreadthFirstHeuristicSolver::new(board, Box::new(ManhattanDistance))
+    });
+}
+
+#[test]
+fn stores_fewer_nodes_than_astar() {
+    let board = deep_board();
+
+    assert_counts_fewer(
+        &board,
+        |report| report.peak_memory_estimate,
+        1.0,
+        Box::new(BreadthFirstHeuristicSolver::new(
+            board.clone(),
+            Box::new(ManhattanDistance),
+        )),
+        Box::new(AStarSolver::new(board.clone(), Box::new(ManhattanDistance))),
+    );
+}
@@ -1,5 +1,10 @@
+use std::time::Duration;
+
+use solver::board::{CowBoard, OwnedBoard};
 use solver::solving::algorithm::bfs::BFSSolver;
+use solver::solving::algorithm::{Solver, SolverConfig, SolvingError};
 use solver::solving::movegen::MoveGenerator;
+use solver::solving::pool::AllocationCounter;
 
 mod shared;
 
@@ -12,3 +17,62 @@ fn produces_correct_solution() {
 fn produces_shortest_solution() {
     shared::assert_produces_shortest_solution(|b| BFSSolver::new(b, MoveGenerator::default()))
 }
+
+#[test]
+fn parallel_search_produces_shortest_solution() {
+    shared::assert_produces_shortest_solution(|b| {
+        BFSSolver::new(b, MoveGenerator::default()).with_threads(4)
+    })
+}
+
+#[test]
+fn copy_on_write_boards_produce_shortest_solution() {
+    shared::assert_produces_shortest_solution(|b| {
+        BFSSolver::<CowBoard>::with_representation(b, MoveGenerator::default())
+    })
+}
+
+#[test]
+fn node_buffers_are_reused() {
+    let board: OwnedBoard = "3 3\n4 1 3\n0 2 5\n7 8 6".parse().unwrap();
+    let counter = AllocationCounter::new();
+
+    let solution = Box::new(
+        BFSSolver::new(board, MoveGenerator::default()).with_allocation_counter(counter.clone()),
+    )
+    .solve()
+    .unwrap();
+
+    let statistics = counter.statistics();
+    assert_eq!(5, solution.len());
+    assert!(statistics.boards_reused > 0);
+    assert!(statistics.paths_reused > 0);
+}
+
+#[test]
+fn shorter_search_order_is_validated_when_solving() {
+    let order = || "LR".parse().unwrap();
+    let row: OwnedBoard = "1 4\n1 0 2 3".parse().unwrap();
+    let solution = Box::new(BFSSolver::new(row, MoveGenerator::new(order())))
+        .solve()
+        .unwrap();
+    assert_eq!(2, solution.len());
+
+    let square: OwnedBoard = "2 2\n1 2\n0 3".parse().unwrap();
+    let result = Box::new(BFSSolver::new(square, MoveGenerator::new(order()))).solve();
+    assert!(matches!(result, Err(SolvingError::AlgorithmError(_))));
+}
+
+#[test]
+fn parallel_search_stops_within_layer() {
+    let config = SolverConfig::default().with_time_limit(Duration::from_millis(20));
+    let solver = BFSSolver::new(shared::hard_board(), MoveGenerator::default()).with_threads(4);
+
+    let result = Box::new(solver).solve_with(&config);
+
+    // a whole layer of the board takes far longer to expand than the time limit
+    let Err(SolvingError::TimedOut { elapsed, .. }) = result else {
+        unreachable!("Search should not finish within the time limit: {result:?}");
+    };
+    assert!(elapsed < Duration::from_secs(1), "{elapsed:?}");
+}
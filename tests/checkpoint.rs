@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use solver::board::{Board, BoardMove, OwnedBoard};
+use solver::solving::algorithm::heuristic::heuristics::ManhattanDistance;
+use solver::solving::algorithm::solvers::{AStarSolver, IterativeAStarSolver};
+use solver::solving::algorithm::{SolveStep, Solver};
+use solver::solving::checkpoint::{Checkpoint, CheckpointError};
+
+const SLICE: Duration = Duration::from_micros(200);
+
+fn board() -> OwnedBoard {
+    use BoardMove::*;
+    let scramble = [
+        Up, Left, Down, Left, Up, Right, Right, Down, Left, Left, Up, Right, Down, Right, Up, Left,
+        Left, Down, Right, Up, Right, Down, Left, Up,
+    ];
+    OwnedBoard::from_scramble(3, 3, &scramble).unwrap()
+}
+
+/// Runs the search in short slices, writing the checkpoint after every one of them
+/// and resuming it with a new solver, as if the process was restarted
+fn solve_with_restarts(create: impl Fn() -> Box<dyn Solver>) -> (Vec<BoardMove>, usize) {
+    let mut solver = create();
+    let mut restarts = 0;
+    loop {
+        match solver.solve_for(SLICE) {
+            SolveStep::Done(result) => return (result.unwrap().solution, restarts),
+            SolveStep::Pending(suspended) => {
+                let mut written = vec![];
+                suspended.checkpoint().unwrap().write(&mut written).unwrap();
+                solver = create();
+                solver
+                    .resume(Checkpoint::read(written.as_slice()).unwrap())
+                    .unwrap();
+                restarts += 1;
+            }
+        }
+    }
+}
+
+fn assert_resumed_search_is_shortest(create: impl Fn() -> Box<dyn Solver>) {
+    let shortest = create().solve().unwrap();
+
+    let (solution, restarts) = solve_with_restarts(create);
+
+    let mut board = board();
+    board.apply_moves(&solution).unwrap();
+    assert!(board.is_solved());
+    assert_eq!(shortest.len(), solution.len(), "{restarts} restarts");
+}
+
+#[test]
+fn ida_resumes_from_checkpoint() {
+    assert_resumed_search_is_shortest(|| {
+        Box::new(IterativeAStarSolver::new(
+            board(),
+            Box::new(ManhattanDistance),
+        ))
+    });
+}
+
+#[test]
+fn astar_resumes_from_checkpoint() {
+    assert_resumed_search_is_shortest(|| {
+        Box::new(AStarSolver::new(board(), Box::new(ManhattanDistance)))
+    });
+}
+
+#[test]
+fn checkpoint_of_other_search_is_rejected() {
+    let checkpoint = Checkpoint::IterativeAStar {
+        board: board(),
+        bound: 20,
+        next_bound: None,
+        path: vec![],
+    };
+    let other_board = Checkpoint::IterativeAStar {
+        board: OwnedBoard::new_solved(4, 4),
+        bound: 20,
+        next_bound: None,
+        path: vec![],
+    };
+
+    let mut astar = AStarSolver::new(board(), Box::new(ManhattanDistance));
+    let mut ida = IterativeAStarSolver::new(board(), Box::new(ManhattanDistance));
+
+    assert_eq!(
+        Err(CheckpointError::Unsupported),
+        astar.resume(checkpoint.clone())
+    );
+    assert_eq!(
+        Err(CheckpointError::DifferentBoard),
+        ida.resume(other_board)
+    );
+    assert_eq!(Ok(()), ida.resume(checkpoint));
+}
@@ -1,5 +1,8 @@
-use solver::solving::algorithm::dfs::DFSSolver;
+use solver::board::OwnedBoard;
+use solver::solving::algorithm::dfs::{DFSError, DFSSolver};
+use solver::solving::algorithm::{Solver, SolvingError};
 use solver::solving::movegen::MoveGenerator;
+use solver::solving::pruning::MaxPathLength;
 
 use crate::shared::assert_produces_valid_solution;
 
@@ -9,3 +12,20 @@ mod shared;
 fn produces_correct_solution() {
     assert_produces_valid_solution(|board| DFSSolver::new(board, MoveGenerator::default()));
 }
+
+#[test]
+fn pruning_every_move_exhausts_search() {
+    let board: OwnedBoard = "3 3\n1 2 3\n4 5 6\n7 0 8".parse().unwrap();
+    let solver =
+        DFSSolver::new(board, MoveGenerator::default()).with_pruning_rule(MaxPathLength(0));
+
+    let result = Box::new(solver).solve();
+
+    let Err(SolvingError::AlgorithmError(error)) = result else {
+        unreachable!("Every move is pruned");
+    };
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(DFSError::StateExhausted(_))
+    ));
+}
@@ -0,0 +1,42 @@
+use solver::solving::algorithm::heuristic::heuristics::ManhattanDistance;
+use solver::solving::algorithm::solvers::{AStarSolver, FocalSolver};
+
+use crate::shared::{
+    assert_counts_fewer, assert_produces_shortest_solution, assert_produces_solution_within,
+    deep_board,
+};
+
+mod shared;
+
+#[test]
+fn produces_solution_within_factor() {
+    for epsilon in [0.25, 1.0] {
+        assert_produces_solution_within(1.0 + epsilon, |board| {
+            FocalSolver::new(board, Box::new(ManhattanDistance), epsilon)
+        });
+    }
+}
+
+#[test]
+fn zero_epsilon_produces_shortest_solution() {
+    assert_produces_shortest_solution(|board| {
+        FocalSolver::new(board, Box::new(ManhattanDistance), 0.0)
+    });
+}
+
+#[test]
+fn expands_fewer_nodes_than_astar() {
+    let board = deep_board();
+
+    assert_counts_fewer(
+        &board,
+        |report| report.nodes_expanded,
+        1.5,
+        Box::new(FocalSolver::new(
+            board.clone(),
+            Box::new(ManhattanDistance),
+            0.5,
+        )),
+        Box::new(AStarSolver::new(board.clone(), Box::new(ManhattanDistance))),
+    );
+}
@@ -0,0 +1,36 @@
+use solver::board::OwnedBoard;
+use solver::solving::algorithm::bfs::BFSSolver;
+use solver::solving::algorithm::frontier::{distance_counts, FrontierBFSSolver};
+use solver::solving::movegen::MoveGenerator;
+
+mod shared;
+
+#[test]
+fn produces_shortest_solution() {
+    shared::assert_produces_shortest_solution(FrontierBFSSolver::new);
+}
+
+#[test]
+fn stores_fewer_nodes_than_bfs() {
+    let board: OwnedBoard = "3 3\n0 4 2\n1 7 3\n5 8 6".parse().unwrap();
+
+    shared::assert_counts_fewer(
+        &board,
+        |report| report.peak_memory_estimate,
+        1.0,
+        Box::new(FrontierBFSSolver::new(board.clone())),
+        Box::new(BFSSolver::new(board.clone(), MoveGenerator::default())),
+    );
+}
+
+#[test]
+fn counts_distances_of_whole_state_space() {
+    let board = OwnedBoard::new_solved(3, 3);
+
+    let counts = distance_counts(&board);
+
+    // half of the permutations of the 3x3 board are reachable, the farthest two are 31 moves away
+    assert_eq!(181_440, counts.iter().sum::<usize>());
+    assert_eq!(32, counts.len());
+    assert_eq!(Some(&2), counts.last());
+}
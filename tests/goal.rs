@@ -0,0 +1,174 @@
+use solver::board::{Board, BoardMove, OwnedBoard};
+use solver::solving::algorithm::solvers::*;
+use solver::solving::algorithm::{Solver, SolvingError};
+use solver::solving::goal::{Goal, GoalSet, Predicate, TilesPlaced};
+use solver::solving::movegen::MoveGenerator;
+
+fn create_board() -> OwnedBoard {
+    r"3 3
+    4 1 3
+    7 2 5
+    8 0 6"
+        .parse()
+        .unwrap()
+}
+
+fn apply(mut board: OwnedBoard, solution: &[BoardMove]) -> OwnedBoard {
+    board.apply_moves(solution).unwrap();
+    board
+}
+
+#[test]
+fn shortest_solvers_agree_on_tiles_placed_goal() {
+    let board = create_board();
+
+    let bfs = Box::new(BFSSolver::with_goal(
+        board.clone(),
+        MoveGenerator::default(),
+        Box::new(TilesPlaced::new(1..=3)),
+    ))
+    .solve()
+    .unwrap();
+    let astar = Box::new(AStarSolver::with_goal(
+        board.clone(),
+        Box::new(TilesPlaced::new(1..=3)),
+    ))
+    .solve()
+    .unwrap();
+    let ida = Box::new(IterativeAStarSolver::with_goal(
+        board.clone(),
+        Box::new(TilesPlaced::new(1..=3)),
+    ))
+    .solve()
+    .unwrap();
+
+    let goal = TilesPlaced::new(1..=3);
+    for solution in [&bfs, &astar, &ida] {
+        assert!(goal.is_reached(&apply(board.clone(), solution)));
+    }
+    assert_eq!(bfs.len(), astar.len());
+    assert_eq!(bfs.len(), ida.len());
+    assert!(bfs.len() < 7);
+}
+
+#[test]
+fn reaches_closest_member_of_goal_set() {
+    let board = create_board();
+    let solved: OwnedBoard = "3 3\n1 2 3\n4 5 6\n7 8 0".parse().unwrap();
+    let mut intermediate = board.clone();
+    intermediate.exec_move(BoardMove::Up);
+    intermediate.exec_move(BoardMove::Up);
+
+    let solution = Box::new(AStarSolver::with_goal(
+        board.clone(),
+        Box::new(GoalSet::new(vec![solved, intermediate.clone()])),
+    ))
+    .solve()
+    .unwrap();
+
+    assert_eq!(2, solution.len());
+    assert_eq!(intermediate, apply(board, &solution));
+}
+
+#[test]
+fn predicate_goal_is_reached() {
+    let board = create_board();
+
+    let solution = Box::new(BFSSolver::with_goal(
+        board.clone(),
+        MoveGenerator::default(),
+        Box::new(Predicate(|b: &dyn Board| b.empty_cell_pos() == (0, 0))),
+    ))
+    .solve()
+    .unwrap();
+
+    assert_eq!(3, solution.len());
+    assert_eq!((0, 0), apply(board, &solution).empty_cell_pos());
+}
+
+#[test]
+fn solvers_reach_goal_carried_by_board() {
+    let target: OwnedBoard = "3 3\n2 5 3\n1 0 6\n4 7 8".parse().unwrap();
+    let board = create_board().with_goal(target.clone()).unwrap();
+
+    let bfs = Box::new(BFSSolver::new(board.clone(), MoveGenerator::default()))
+        .solve()
+        .unwrap();
+    let astar = Box::new(AStarSolver::new(
+        board.clone(),
+        Box::new(solver::solving::algorithm::heuristic::heuristics::LinearConflict::default()),
+    ))
+    .solve()
+    .unwrap();
+    let ida = Box::new(IterativeAStarSolver::new(
+        board.clone(),
+        Box::new(solver::solving::algorithm::heuristic::heuristics::InversionDistance::default()),
+    ))
+    .solve()
+    .unwrap();
+
+    for solution in [&bfs, &astar, &ida] {
+        let reached = apply(board.clone(), solution);
+        assert!(reached.is_solved());
+        assert_eq!(target, reached.goal());
+        assert_eq!(bfs.len(), solution.len());
+    }
+}
+
+#[test]
+fn goal_of_other_solvability_class_is_unsolvable() {
+    let target: OwnedBoard = "3 3\n2 1 3\n4 5 6\n7 8 0".parse().unwrap();
+    let board = create_board().with_goal(target).unwrap();
+
+    assert!(matches!(
+        Box::new(BFSSolver::new(board, MoveGenerator::default())).solve(),
+        Err(SolvingError::UnsolvableBoard(_))
+    ));
+}
+
+#[test]
+fn move_pairing_keeps_solution_shortest_for_goal_with_centered_blank() {
+    let goal: OwnedBoard = "3 3\n1 2 3\n4 0 5\n6 7 8".parse().unwrap();
+    let board = apply(
+        goal.clone(),
+        &[
+            BoardMove::Up,
+            BoardMove::Left,
+            BoardMove::Down,
+            BoardMove::Down,
+            BoardMove::Right,
+            BoardMove::Up,
+            BoardMove::Right,
+        ],
+    );
+    let target = goal.clone();
+    let unpaired = Box::new(AStarSolver::with_goal(
+        board.clone(),
+        Box::new(Predicate(move |board: &dyn Board| {
+            board.iter_cells().eq(target.iter_cells())
+        })),
+    ))
+    .solve()
+    .unwrap();
+
+    let goal_set = Box::new(BFSSolver::with_goal(
+        board.clone(),
+        MoveGenerator::default(),
+        Box::new(GoalSet::new(vec![goal.clone()])),
+    ))
+    .solve()
+    .unwrap();
+    let carried_goal = Box::new(BFSSolver::new(
+        board.clone().with_goal(goal.clone()).unwrap(),
+        MoveGenerator::default(),
+    ))
+    .solve()
+    .unwrap();
+
+    for solution in [&goal_set, &carried_goal] {
+        assert_eq!(unpaired.len(), solution.len());
+        assert!(apply(board.clone(), solution)
+            .iter_cells()
+            .eq(goal.iter_cells()));
+    }
+}
@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use solver::board::OwnedBoard;
+use solver::solving::algorithm::heuristic::heuristics::{Heuristic, ManhattanDistance};
+use solver::solving::algorithm::solvers::{HashDistributedAStarSolver, IterativeAStarSolver};
+use solver::solving::algorithm::{Solver, SolvingError};
+
+use crate::shared::{assert_produces_shortest_solution, assert_produces_valid_solution};
+
+mod shared;
+
+fn manhattan_distance() -> Box<dyn Heuristic> {
+    Box::new(ManhattanDistance)
+}
+
+#[test]
+fn produces_correct_solution() {
+    assert_produces_valid_solution(|board| {
+        HashDistributedAStarSolver::new(board, manhattan_distance, 3)
+    });
+}
+
+#[test]
+fn produces_shortest_solution() {
+    assert_produces_shortest_solution(|board| {
+        HashDistributedAStarSolver::new(board, manhattan_distance, 4)
+    });
+}
+
+#[test]
+fn shared_heuristic_finds_shortest_solution_on_larger_board() {
+    let board: OwnedBoard = r"4 4
+    5 1 3 4
+    9 2 7 8
+    0 6 10 12
+    13 14 11 15"
+        .parse()
+        .unwrap();
+    let shortest = Box::new(IterativeAStarSolver::new(
+        board.clone(),
+        Box::new(ManhattanDistance),
+    ))
+    .solve()
+    .unwrap();
+    let heuristic = Arc::new(ManhattanDistance);
+
+    let solution = Box::new(HashDistributedAStarSolver::new(
+        board,
+        move || Box::new(Arc::clone(&heuristic)),
+        2,
+    ))
+    .solve()
+    .unwrap();
+
+    assert_eq!(shortest.len(), solution.len());
+}
+
+#[test]
+fn reports_unsolvable_board() {
+    let board: OwnedBoard = "3 3\n2 1 3\n4 5 6\n7 8 0".parse().unwrap();
+
+    let result = Box::new(HashDistributedAStarSolver::new(
+        board,
+        manhattan_distance,
+        2,
+    ))
+    .solve();
+
+    assert!(matches!(
+        result,
+        Err(SolvingError::UnsolvableBoard(Some(_)))
+    ));
+}
@@ -1,7 +1,14 @@
+use solver::board::{Board, BoardMove, OwnedBoard};
 use solver::solving::algorithm::heuristic;
 use solver::solving::algorithm::heuristic::astar::IterativeAStarSolver;
+use solver::solving::algorithm::heuristic::heuristics::{Heuristic, LinearConflict};
+use solver::solving::algorithm::{IntermediateResult, Solver, SolverConfig, SolvingError};
+use solver::solving::history::SharedHistory;
 
-use crate::shared::{assert_produces_shortest_solution, assert_produces_valid_solution};
+use crate::shared::{
+    assert_counts_fewer, assert_produces_shortest_solution, assert_produces_solution_within,
+    assert_produces_valid_solution, deep_board,
+};
 
 mod shared;
 
@@ -12,9 +19,164 @@ fn produces_correct_solution() {
     });
 }
 
+#[test]
+fn produces_shortest_solution_with_history_shared_by_boards() {
+    let history = SharedHistory::default();
+
+    assert_produces_shortest_solution(|board| {
+        IterativeAStarSolver::new(board, Box::new(heuristic::heuristics::ManhattanDistance))
+            .with_history(history.clone())
+    });
+
+    assert!(!history.snapshot().is_empty());
+}
+
 #[test]
 fn produces_shortest_solution() {
     assert_produces_shortest_solution(|board| {
         IterativeAStarSolver::new(board, Box::new(heuristic::heuristics::ManhattanDistance))
     });
 }
+
+#[test]
+fn produces_shortest_solution_with_child_ordering() {
+    assert_produces_shortest_solution(|board| {
+        IterativeAStarSolver::new(board, Box::new(heuristic::heuristics::ManhattanDistance))
+            .with_child_ordering(true)
+    });
+}
+
+#[test]
+fn child_ordering_expands_fewer_nodes() {
+    use BoardMove::*;
+    let scramble = [
+        Up, Left, Down, Left, Up, Up, Right, Down, Right, Up, Left, Left, Down, Down, Right, Up,
+        Left, Up, Right, Right, Down, Left, Down, Left, Up, Up, Right, Down,
+    ];
+    let board = OwnedBoard::from_scramble(4, 4, &scramble).unwrap();
+    let solver = |child_ordering| {
+        Box::new(
+            IterativeAStarSolver::new(
+                board.clone(),
+                Box::new(heuristic::heuristics::ManhattanDistance),
+            )
+            .with_child_ordering(child_ordering),
+        )
+    };
+
+    assert_counts_fewer(
+        &board,
+        |report| report.nodes_expanded,
+        1.0,
+        solver(true),
+        solver(false),
+    );
+}
+
+/// Linear conflict evaluated on the whole board after every move
+struct FullyEvaluated(LinearConflict);
+
+impl Heuristic for FullyEvaluated {
+    fn evaluate(&self, board: &dyn Board) -> u64 {
+        self.0.evaluate(board)
+    }
+}
+
+#[test]
+fn incremental_evaluation_searches_same_nodes() {
+    let board = deep_board();
+    for child_ordering in [true, false] {
+        let solve = |heuristic: Box<dyn Heuristic>| {
+            let solver = IterativeAStarSolver::new(board.clone(), heuristic)
+                .with_child_ordering(child_ordering);
+            Box::new(solver)
+                .solve_with_stats(&SolverConfig::default())
+                .unwrap()
+        };
+
+        let incremental = solve(Box::<LinearConflict>::default());
+        let full = solve(Box::new(FullyEvaluated(LinearConflict::default())));
+
+        assert_eq!(full.solution, incremental.solution);
+        assert_eq!(full.nodes_expanded, incremental.nodes_expanded);
+    }
+}
+
+#[test]
+fn weighted_produces_solution_within_weight() {
+    for weight in [1.5, 2.0] {
+        assert_produces_solution_within(weight, |board| {
+            IterativeAStarSolver::weighted(
+                board,
+                Box::new(heuristic::heuristics::ManhattanDistance),
+                weight,
+            )
+        });
+    }
+}
+
+#[test]
+fn weighted_expands_fewer_nodes() {
+    let board = deep_board();
+    let solver = |weight| {
+        Box::new(IterativeAStarSolver::weighted(
+            board.clone(),
+            Box::new(heuristic::heuristics::ManhattanDistance),
+            weight,
+        ))
+    };
+
+    assert_counts_fewer(
+        &board,
+        |report| report.nodes_expanded,
+        1.5,
+        solver(1.5),
+        solver(1.0),
+    );
+}
+
+#[test]
+fn iterations_raise_bound_until_solved() {
+    let board: OwnedBoard = r"3 3
+    0 4 2
+    1 7 3
+    5 8 6"
+        .parse()
+        .unwrap();
+
+    let results: Vec<_> =
+        IterativeAStarSolver::new(board, Box::new(heuristic::heuristics::ManhattanDistance))
+            .collect();
+
+    let mut last_bound = 0;
+    for result in &results[..results.len() - 1] {
+        let IntermediateResult::BoundExhausted { bound, next_bound } = *result else {
+            unreachable!("Only the last iteration should finish the search");
+        };
+        assert!(last_bound <= bound && bound < next_bound);
+        last_bound = next_bound;
+    }
+    assert!(
+        matches!(results.last(), Some(IntermediateResult::Solved(solution)) if solution.len() == 12)
+    );
+}
+
+#[test]
+fn unsolvable_board_fails_first_iteration() {
+    let board: OwnedBoard = r"3 3
+    2 1 3
+    4 5 6
+    7 8 0"
+        .parse()
+        .unwrap();
+
+    let mut iterations =
+        IterativeAStarSolver::new(board, Box::new(heuristic::heuristics::ManhattanDistance));
+    assert!(matches!(
+        iterations.next(),
+        Some(IntermediateResult::Failed(SolvingError::UnsolvableBoard(
+            Some(_)
+        )))
+    ));
+    assert!(iterations.next().is_none());
+}
@@ -1,7 +1,12 @@
-use solver::solving::algorithm::dfs::IncrementalDFSSolver;
+use solver::board::{parse_moves, OwnedBoard};
+use solver::solving::algorithm::dfs::{DFSError, IncrementalDFSSolver};
+use solver::solving::algorithm::{IntermediateResult, Solver, SolvingError};
+use solver::solving::history::SharedHistory;
 use solver::solving::movegen::MoveGenerator;
 
-use crate::shared::{assert_produces_shortest_solution, assert_produces_valid_solution};
+use crate::shared::{
+    assert_counts_fewer, assert_produces_shortest_solution, assert_produces_valid_solution,
+};
 
 mod shared;
 
@@ -18,3 +23,90 @@ fn produces_shortest_solution() {
         IncrementalDFSSolver::new(board, MoveGenerator::default())
     });
 }
+
+#[test]
+fn produces_shortest_solution_with_transposition_table() {
+    assert_produces_shortest_solution(|board| {
+        IncrementalDFSSolver::new(board, MoveGenerator::default()).with_transposition_table()
+    });
+}
+
+#[test]
+fn produces_shortest_solution_with_history_shared_by_boards() {
+    let history = SharedHistory::default();
+
+    assert_produces_shortest_solution(|board| {
+        IncrementalDFSSolver::new(board, MoveGenerator::default()).with_history(history.clone())
+    });
+
+    assert!(!history.snapshot().is_empty());
+}
+
+#[test]
+fn transposition_table_expands_fewer_nodes() {
+    let scramble = parse_moves("ULULDRDRULLDRUUL").unwrap();
+    let board = OwnedBoard::from_scramble(3, 3, &scramble).unwrap();
+
+    assert_counts_fewer(
+        &board,
+        |report| report.nodes_expanded,
+        1.0,
+        Box::new(
+            IncrementalDFSSolver::new(board.clone(), MoveGenerator::default())
+                .with_transposition_table(),
+        ),
+        Box::new(IncrementalDFSSolver::new(
+            board.clone(),
+            MoveGenerator::default(),
+        )),
+    );
+}
+
+#[test]
+fn iterations_deepen_until_solved() {
+    let board: OwnedBoard = r"3 3
+    1 2 3
+    0 4 6
+    7 5 8"
+        .parse()
+        .unwrap();
+
+    let results: Vec<_> = IncrementalDFSSolver::new(board, MoveGenerator::default()).collect();
+
+    let bounds: Vec<_> = results
+        .iter()
+        .filter_map(|result| match result {
+            IntermediateResult::BoundExhausted { bound, .. } => Some(*bound),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(bounds, [1, 2]);
+    assert!(
+        matches!(results.last(), Some(IntermediateResult::Solved(solution)) if solution.len() == 3)
+    );
+}
+
+#[test]
+fn depth_limit_failure_reports_explored_tree() {
+    let board: OwnedBoard = r"3 3
+    0 4 2
+    1 7 3
+    5 8 6"
+        .parse()
+        .unwrap();
+
+    let error =
+        Box::new(IncrementalDFSSolver::new(board, MoveGenerator::default()).with_depth_limit(4))
+            .solve()
+            .expect_err("solution should be deeper than the limit");
+
+    let SolvingError::AlgorithmError(error) = error else {
+        unreachable!("depth limit should fail the search");
+    };
+    let Some(DFSError::MaxDepthReached(report)) = error.downcast_ref::<DFSError>() else {
+        unreachable!("error should report reaching the depth limit");
+    };
+    assert_eq!(report.depth_histogram[0], 4);
+    assert!(report.depth_limit_cutoffs > 0);
+    assert!(report.branching_factor() > 1.0);
+}
@@ -0,0 +1,204 @@
+use std::time::Duration;
+
+use solver::board::{parse_moves, Board, OwnedBoard};
+use solver::solving::algorithm::heuristic::heuristics::ManhattanDistance;
+use solver::solving::algorithm::solvers::{
+    AStarSolver, BFSSolver, DFSSolver, HashDistributedAStarSolver, IncrementalDFSSolver,
+    IterativeAStarSolver, MemoryBoundedAStarSolver, Optimal8Solver, PortfolioSolver,
+};
+use solver::solving::algorithm::{
+    ExpansionLimitUnsupported, Solver, SolverConfig, SolvingError, TimeLimitUnsupported,
+};
+use solver::solving::history::SharedHistory;
+use solver::solving::movegen::MoveGenerator;
+
+use crate::shared::{counting_solvers, hard_board, solve_in_slices};
+
+mod shared;
+
+const TIME_LIMIT: Duration = Duration::from_millis(20);
+
+fn solvers() -> Vec<Box<dyn Solver>> {
+    let mut solvers = counting_solvers(&hard_board());
+    solvers.push(Box::new(DFSSolver::new(
+        hard_board(),
+        MoveGenerator::default(),
+    )));
+    solvers
+}
+
+fn assert_times_out(solver: Box<dyn Solver>) {
+    let config = SolverConfig::default().with_time_limit(TIME_LIMIT);
+
+    let result = solver.solve_with(&config);
+
+    let Err(SolvingError::TimedOut {
+        best_partial,
+        elapsed,
+    }) = &result
+    else {
+        unreachable!("Search should not finish within the time limit: {result:?}");
+    };
+    assert!(*elapsed >= TIME_LIMIT);
+    let mut board = hard_board();
+    board
+        .apply_moves(best_partial)
+        .expect("Partial solution is made of legal moves");
+}
+
+#[test]
+fn depth_first_solvers_time_out() {
+    assert_times_out(Box::new(DFSSolver::new(
+        hard_board(),
+        MoveGenerator::default(),
+    )));
+    assert_times_out(Box::new(IncrementalDFSSolver::new(
+        hard_board(),
+        MoveGenerator::default(),
+    )));
+    assert_times_out(Box::new(IterativeAStarSolver::new(
+        hard_board(),
+        Box::new(ManhattanDistance),
+    )));
+}
+
+#[test]
+fn queue_based_solvers_time_out() {
+    assert_times_out(Box::new(BFSSolver::new(
+        hard_board(),
+        MoveGenerator::default(),
+    )));
+    assert_times_out(Box::new(AStarSolver::new(
+        hard_board(),
+        Box::new(ManhattanDistance),
+    )));
+    assert_times_out(Box::new(MemoryBoundedAStarSolver::new(
+        hard_board(),
+        Box::new(ManhattanDistance),
+        100_000,
+    )));
+}
+
+#[test]
+fn solvers_which_cannot_be_suspended_reject_time_limit() {
+    let config = SolverConfig::default().with_time_limit(TIME_LIMIT);
+    let solver = Box::new(HashDistributedAStarSolver::new(
+        hard_board(),
+        || Box::new(ManhattanDistance),
+        2,
+    ));
+
+    let result = solver.solve_with(&config);
+
+    let Err(SolvingError::AlgorithmError(error)) = &result else {
+        unreachable!("Solver should reject the time limit: {result:?}");
+    };
+    assert!(error.is::<TimeLimitUnsupported>());
+}
+
+#[test]
+fn search_within_time_limit_finishes() {
+    let board: OwnedBoard = "3 3\n1 2 3\n4 5 6\n0 7 8".parse().unwrap();
+    let config = SolverConfig::default().with_time_limit(Duration::from_secs(60));
+
+    let solution = Box::new(IterativeAStarSolver::new(
+        board,
+        Box::new(ManhattanDistance),
+    ))
+    .solve_with(&config)
+    .unwrap();
+
+    assert_eq!(2, solution.len());
+}
+
+#[test]
+fn time_sliced_search_produces_shortest_solution() {
+    // deep enough for IDA* to resume several iterations
+    let scramble = parse_moves("ULDRULDLURDLURRDLLUR").unwrap();
+    let board = OwnedBoard::from_scramble(3, 3, &scramble).unwrap();
+    let optimal = Box::new(Optimal8Solver::new(board.clone()))
+        .solve()
+        .unwrap();
+    let solvers: Vec<Box<dyn Solver>> = vec![
+        Box::new(BFSSolver::new(board.clone(), MoveGenerator::default())),
+        Box::new(BFSSolver::new(board.clone(), MoveGenerator::default()).with_threads(4)),
+        Box::new(AStarSolver::new(board.clone(), Box::new(ManhattanDistance))),
+        Box::new(
+            IterativeAStarSolver::new(board, Box::new(ManhattanDistance))
+                .with_history(SharedHistory::default()),
+        ),
+    ];
+
+    for solver in solvers {
+        let (solution, slices) = solve_in_slices(solver);
+
+        assert_eq!(optimal.len(), solution.len());
+        assert!(slices > 0);
+    }
+}
+
+#[test]
+fn solvers_stop_at_expansion_limit() {
+    let config = SolverConfig::default().with_max_expansions(500);
+
+    for solver in solvers() {
+        let result = solver.solve_with(&config);
+
+        let Err(SolvingError::ExpansionLimitReached {
+            best_partial,
+            max_expansions,
+        }) = &result
+        else {
+            unreachable!("Search should not finish within the expansion limit: {result:?}");
+        };
+        assert_eq!(500, *max_expansions);
+        let mut board = hard_board();
+        board
+            .apply_moves(best_partial)
+            .expect("Partial solution is made of legal moves");
+    }
+}
+
+#[test]
+fn expansion_limit_stops_search_at_same_position() {
+    let config = SolverConfig::default().with_max_expansions(10_000);
+    let partial = || {
+        let solver = IterativeAStarSolver::new(hard_board(), Box::new(ManhattanDistance));
+        Box::new(solver)
+            .solve_with(&config)
+            .unwrap_err()
+            .best_partial()
+            .map(<[_]>::to_vec)
+    };
+
+    let first = partial();
+
+    assert!(first.is_some());
+    assert_eq!(first, partial());
+}
+
+#[test]
+fn solvers_without_counters_reject_expansion_limit() {
+    let board: OwnedBoard = "3 3\n1 2 3\n4 5 6\n0 7 8".parse().unwrap();
+    let config = SolverConfig::default().with_max_expansions(500);
+    let solvers: Vec<Box<dyn Solver>> = vec![
+        Box::new(Optimal8Solver::new(board.clone())),
+        Box::new(HashDistributedAStarSolver::new(
+            board.clone(),
+            || Box::new(ManhattanDistance),
+            2,
+        )),
+        Box::new(PortfolioSolver::new(board).with_member("BFS", |board| {
+            Box::new(BFSSolver::new(board, MoveGenerator::default()))
+        })),
+    ];
+
+    for solver in solvers {
+        let result = solver.solve_with(&config);
+
+        let Err(SolvingError::AlgorithmError(error)) = &result else {
+            unreachable!("Solver should reject the expansion limit: {result:?}");
+        };
+        assert!(error.is::<ExpansionLimitUnsupported>());
+    }
+}
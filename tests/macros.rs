@@ -0,0 +1,86 @@
+use std::rc::Rc;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use solver::board::generator::scramble;
+use solver::board::{Board, OwnedBoard};
+use solver::solving::algorithm::macros::{MacroError, MacroTable};
+use solver::solving::algorithm::solvers::MacroOperatorSolver;
+use solver::solving::algorithm::{Solver, SolvingError};
+
+use crate::shared::assert_produces_valid_solution;
+
+mod shared;
+
+#[test]
+fn produces_correct_solution() {
+    assert_produces_valid_solution(MacroOperatorSolver::new);
+}
+
+#[test]
+fn solves_scrambled_boards_sharing_table() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let table = Rc::new(MacroTable::new(&OwnedBoard::new_solved(4, 4)));
+
+    for _ in 0..5 {
+        let (mut board, _) = scramble(4, 4, 100, &mut rng);
+        let solution = Box::new(MacroOperatorSolver::with_table(
+            board.clone(),
+            Rc::clone(&table),
+        ))
+        .solve()
+        .unwrap();
+
+        board.apply_moves(&solution).unwrap();
+        assert!(board.is_solved());
+    }
+    assert!(!table.is_empty());
+}
+
+#[test]
+fn precomputed_table_is_reused() {
+    let board: OwnedBoard = r"3 3
+    0 4 2
+    1 7 3
+    5 8 6"
+        .parse()
+        .unwrap();
+    let table = Rc::new(MacroTable::new(&board));
+    table.precompute();
+    let size = table.len();
+
+    let solution = Box::new(MacroOperatorSolver::with_table(board, Rc::clone(&table)))
+        .solve()
+        .unwrap();
+
+    assert!(!solution.is_empty());
+    assert_eq!(size, table.len());
+}
+
+#[test]
+fn rejects_table_of_other_dimensions() {
+    let table = Rc::new(MacroTable::new(&OwnedBoard::new_solved(4, 4)));
+
+    let result = Box::new(MacroOperatorSolver::with_table(
+        OwnedBoard::new_solved(3, 3),
+        table,
+    ))
+    .solve();
+
+    let Err(SolvingError::AlgorithmError(error)) = result else {
+        unreachable!("Table does not match the board");
+    };
+    assert_eq!(Some(&MacroError::TableMismatch), error.downcast_ref());
+}
+
+#[test]
+fn reports_unsolvable_board() {
+    let board: OwnedBoard = "3 3\n2 1 3\n4 5 6\n7 8 0".parse().unwrap();
+
+    let result = Box::new(MacroOperatorSolver::new(board)).solve();
+
+    assert!(matches!(
+        result,
+        Err(SolvingError::UnsolvableBoard(Some(_)))
+    ));
+}
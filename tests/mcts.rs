@@ -0,0 +1,90 @@
+#![cfg(feature = "experimental")]
+
+use std::time::Duration;
+
+use solver::board::{Board, OwnedBoard};
+use solver::solving::algorithm::heuristic::heuristics::ManhattanDistance;
+use solver::solving::algorithm::solvers::MonteCarloSolver;
+use solver::solving::algorithm::{Solver, SolverConfig, SolvingError};
+
+mod shared;
+
+#[test]
+fn produces_correct_solution() {
+    shared::assert_produces_valid_solution(|board| {
+        MonteCarloSolver::new(board, Box::new(ManhattanDistance))
+    });
+}
+
+#[test]
+fn solves_scrambled_board() {
+    let board = shared::deep_board();
+
+    let solution = Box::new(MonteCarloSolver::new(
+        board.clone(),
+        Box::new(ManhattanDistance),
+    ))
+    .solve()
+    .unwrap();
+
+    let mut solved = board;
+    solved.apply_moves(&solution).unwrap();
+    assert!(solved.is_solved());
+}
+
+#[test]
+fn solves_single_row() {
+    let board: OwnedBoard = "1 5\n0 1 2 3 4".parse().unwrap();
+
+    let solution = Box::new(
+        MonteCarloSolver::new(board.clone(), Box::new(ManhattanDistance)).with_rollout_depth(2),
+    )
+    .solve()
+    .unwrap();
+
+    let mut solved = board;
+    solved.apply_moves(&solution).unwrap();
+    assert!(solved.is_solved());
+}
+
+#[test]
+fn same_seed_gives_same_solution() {
+    let board = shared::deep_board();
+    let solve = |seed| {
+        Box::new(MonteCarloSolver::new(board.clone(), Box::new(ManhattanDistance)).with_seed(seed))
+            .solve()
+            .unwrap()
+    };
+
+    assert_eq!(solve(7), solve(7));
+}
+
+#[test]
+fn stops_at_expansion_limit() {
+    let board: OwnedBoard = "4 4\n0 12 9 13\n15 11 10 14\n3 7 2 5\n4 8 6 1"
+        .parse()
+        .unwrap();
+    let config = SolverConfig::default()
+        .with_time_limit(Duration::from_secs(10))
+        .with_max_expansions(100);
+
+    let solver =
+        MonteCarloSolver::new(board, Box::new(ManhattanDistance)).with_iterations_per_move(10);
+
+    let result = Box::new(solver).solve_with(&config);
+
+    assert!(matches!(
+        result,
+        Err(SolvingError::ExpansionLimitReached { best_partial, .. }) if best_partial.len() == 10
+    ));
+}
+
+#[test]
+fn detects_unsolvable_board() {
+    let board: OwnedBoard = "3 3\n2 1 3\n4 5 6\n7 8 0".parse().unwrap();
+
+    assert!(matches!(
+        Box::new(MonteCarloSolver::new(board, Box::new(ManhattanDistance))).solve(),
+        Err(SolvingError::UnsolvableBoard(_))
+    ));
+}
@@ -0,0 +1,166 @@
+use std::path::PathBuf;
+
+use solver::board::{parse_moves, Board, BoardMove, OwnedBoard};
+use solver::solving::algorithm::eight::with_fast_path;
+use solver::solving::algorithm::heuristic::heuristics::{ManhattanDistance, WalkingDistance};
+use solver::solving::algorithm::retrograde::RetrogradeTable;
+use solver::solving::algorithm::solvers::{
+    AStarSolver, BFSSolver, BreadthFirstHeuristicSolver, FrontierBFSSolver, IterativeAStarSolver,
+    Optimal8Solver, PerimeterSearchSolver,
+};
+use solver::solving::algorithm::{Solver, SolvingError};
+use solver::solving::movegen::MoveGenerator;
+
+mod shared;
+
+/// Number of positions between the boards compared with the oracle, coprime with the size of the state space
+const SAMPLE_STEP: usize = 9973;
+
+/// Directory of the table, removed when dropped
+struct CacheDirectory(PathBuf);
+
+impl CacheDirectory {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+        Self(path)
+    }
+}
+
+impl Drop for CacheDirectory {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn oracle(board: OwnedBoard) -> Optimal8Solver {
+    Optimal8Solver::new(board).without_cache_directory()
+}
+
+#[test]
+fn produces_shortest_solution() {
+    let scramble = parse_moves("ULULDRDRULLDRUUL").unwrap();
+    let board = OwnedBoard::from_scramble(3, 3, &scramble).unwrap();
+    let shortest = Box::new(BFSSolver::new(board.clone(), MoveGenerator::default()))
+        .solve()
+        .unwrap();
+
+    let solution = Box::new(oracle(board.clone())).solve().unwrap();
+
+    let mut solved = board;
+    solved.apply_moves(&solution).unwrap();
+    assert!(solved.is_solved());
+    assert_eq!(shortest.len(), solution.len());
+}
+
+/// Adapts the boxed solver returned by [`with_fast_path`] to the shared test helpers
+struct FastPath(Box<dyn Solver>);
+
+impl Solver for FastPath {
+    fn solve(self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
+        self.0.solve()
+    }
+}
+
+fn fast_path(board: OwnedBoard) -> FastPath {
+    FastPath(with_fast_path(board, |board| {
+        Box::new(BFSSolver::new(board, MoveGenerator::default()))
+    }))
+}
+
+#[test]
+fn fast_path_produces_correct_solution() {
+    shared::assert_produces_valid_solution(fast_path);
+}
+
+#[test]
+fn fast_path_produces_shortest_solution() {
+    shared::assert_produces_shortest_solution(fast_path);
+}
+
+#[test]
+fn solves_board_with_custom_goal() {
+    let goal: OwnedBoard = "3 3\n1 2 3\n4 0 5\n6 7 8".parse().unwrap();
+    let board: OwnedBoard = "3 3\n1 2 3\n4 5 0\n6 7 8".parse().unwrap();
+    let mut board = board.with_goal(goal).unwrap();
+
+    let solution = Box::new(oracle(board.clone())).solve().unwrap();
+
+    assert_eq!(vec![BoardMove::Left], solution);
+    board.apply_moves(&solution).unwrap();
+    assert!(board.is_solved());
+}
+
+#[test]
+fn stores_table_in_cache_directory() {
+    let directory = CacheDirectory::new("optimal8-cache");
+    let board: OwnedBoard = "3 3\n8 6 7\n2 5 4\n3 0 1".parse().unwrap();
+
+    let solution = Box::new(Optimal8Solver::new(board.clone()).with_cache_directory(&directory.0))
+        .solve()
+        .unwrap();
+
+    assert_eq!(31, solution.len());
+    assert!(directory.0.join("3x3.bin").is_file());
+
+    // the stored table is read back by the next solver
+    let solution = Box::new(Optimal8Solver::new(board).with_cache_directory(&directory.0))
+        .solve()
+        .unwrap();
+    assert_eq!(31, solution.len());
+}
+
+#[test]
+fn detects_unsolvable_board() {
+    let board: OwnedBoard = "3 3\n2 1 3\n4 5 6\n7 8 0".parse().unwrap();
+
+    assert!(matches!(
+        Box::new(oracle(board)).solve(),
+        Err(SolvingError::UnsolvableBoard(_))
+    ));
+}
+
+/// Solves the sampled positions of the whole state space with the solver,
+/// and compares the lengths of the solutions with the distances of the table
+fn assert_matches_oracle<S: Solver>(solver_builder: impl Fn(OwnedBoard) -> S) {
+    let table = RetrogradeTable::standard(3, 3).unwrap();
+
+    for (board, distance) in table.positions().step_by(SAMPLE_STEP) {
+        let solution = Box::new(solver_builder(board.clone())).solve().unwrap();
+
+        let mut solved = board.clone();
+        solved.apply_moves(&solution).unwrap();
+        assert!(solved.is_solved(), "{board:?}");
+        assert_eq!(distance, solution.len() as u64, "{board:?}");
+    }
+}
+
+#[test]
+fn astar_matches_oracle() {
+    assert_matches_oracle(|board| AStarSolver::new(board, Box::new(ManhattanDistance)));
+}
+
+#[test]
+fn iterative_astar_matches_oracle() {
+    assert_matches_oracle(|board| {
+        IterativeAStarSolver::new(board, Box::<WalkingDistance>::default())
+    });
+}
+
+#[test]
+fn bfhs_matches_oracle() {
+    assert_matches_oracle(|board| {
+        BreadthFirstHeuristicSolver::new(board, Box::new(ManhattanDistance))
+    });
+}
+
+#[test]
+fn frontier_bfs_matches_oracle() {
+    assert_matches_oracle(FrontierBFSSolver::new);
+}
+
+#[test]
+fn perimeter_search_matches_oracle() {
+    assert_matches_oracle(|board| {
+        PerimeterSearchSolver::new(board, Box::new(ManhattanDistance), 4)
+    });
+}
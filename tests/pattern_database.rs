@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use solver::board::{Board, OwnedBoard};
+use solver::solving::algorithm::heuristic::astar::{AStarSolver, IterativeAStarSolver};
+use solver::solving::algorithm::heuristic::heuristics::{self, HeuristicIdError};
+use solver::solving::algorithm::heuristic::pattern_database::{Partition, PatternDatabase};
+use solver::solving::algorithm::Solver;
+
+use crate::shared::assert_produces_shortest_solution;
+
+mod shared;
+
+/// Boards with the partition of their database, and the length of their shortest solution
+const CASES: &[(&str, &str, usize)] = &[
+    ("3 3\n1 2 3\n0 4 6\n7 5 8", "1-4|5-8", 3),
+    ("3 3\n4 1 3\n7 2 5\n8 0 6", "1-4|5-8", 7),
+    ("3 3\n0 4 2\n1 7 3\n5 8 6", "1-4|5-8", 12),
+    ("3 4\n1 2 3 4\n5 6 7 8\n9 10 0 11", "1-4|5-8|9-11", 1),
+];
+
+#[test]
+fn astar_produces_shortest_solution() {
+    for &(board, partition, shortest) in CASES {
+        let board: OwnedBoard = board.parse().unwrap();
+        let (rows, columns) = board.dimensions();
+        let database =
+            PatternDatabase::generate(rows, columns, partition.parse().unwrap()).unwrap();
+
+        let solution = Box::new(AStarSolver::new(board.clone(), Box::new(database)))
+            .solve()
+            .unwrap();
+
+        let mut solved = board;
+        solved.apply_moves(&solution).unwrap();
+        assert!(solved.is_solved());
+        assert_eq!(shortest, solution.len());
+    }
+}
+
+#[test]
+fn ida_with_disjoint_partition_produces_shortest_solution() {
+    // the databases of the larger groups take a while to generate, so they are shared by boards of the same size
+    let mut databases = HashMap::new();
+    assert_produces_shortest_solution(|board| {
+        let (rows, columns) = board.dimensions();
+        let database = databases.entry((rows, columns)).or_insert_with(|| {
+            Rc::new(
+                PatternDatabase::generate(rows, columns, Partition::disjoint(rows, columns))
+                    .unwrap(),
+            )
+        });
+        IterativeAStarSolver::new(board, Box::new(Rc::clone(database)))
+    });
+}
+
+#[test]
+fn database_file_is_generated_once() {
+    let path = std::env::temp_dir().join(format!("pdb-file-{}.bin", std::process::id()));
+    let board: OwnedBoard = "3 3\n4 1 3\n7 2 5\n0 8 6".parse().unwrap();
+    let partition: Partition = "1-4|5-8".parse().unwrap();
+
+    let generated =
+        heuristics::pattern_database_file(&path, "PDB", &board, Some(&partition)).unwrap();
+    let stored = path.exists();
+    let loaded = heuristics::pattern_database_file(&path, "PDB", &board, Some(&partition)).unwrap();
+    // the partition is required, and must be the stored one
+    let without_partition = heuristics::pattern_database_file(&path, "PDB", &board, None);
+    let other_partition: Partition = "1-3|4-8".parse().unwrap();
+    let mismatched =
+        heuristics::pattern_database_file(&path, "PDB", &board, Some(&other_partition));
+    // the disjoint partition is not the stored one
+    let disjoint = heuristics::pattern_database_file(&path, "DPDB", &board, None);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(stored);
+    assert_eq!(generated.evaluate(&board), loaded.evaluate(&board));
+    assert!(matches!(
+        without_partition,
+        Err(HeuristicIdError::MissingPartition)
+    ));
+    assert!(matches!(
+        mismatched,
+        Err(HeuristicIdError::LoadingFailed { .. })
+    ));
+    assert!(matches!(
+        disjoint,
+        Err(HeuristicIdError::LoadingFailed { .. })
+    ));
+}
@@ -0,0 +1,53 @@
+use solver::board::{parse_moves, OwnedBoard};
+use solver::solving::algorithm::heuristic::heuristics::ManhattanDistance;
+use solver::solving::algorithm::solvers::{IterativeAStarSolver, PerimeterSearchSolver};
+use solver::solving::algorithm::{Solver, SolverConfig};
+
+use crate::shared::{assert_counts_fewer, assert_produces_shortest_solution, deep_board};
+
+mod shared;
+
+#[test]
+fn produces_shortest_solution() {
+    for depth in [0, 3, 6] {
+        assert_produces_shortest_solution(|board| {
+            PerimeterSearchSolver::new(board, Box::new(ManhattanDistance), depth)
+        });
+    }
+}
+
+#[test]
+fn board_inside_perimeter_is_solved_from_it() {
+    let board = OwnedBoard::from_scramble(4, 4, &parse_moves("ULDLUR").unwrap()).unwrap();
+
+    let report = Box::new(PerimeterSearchSolver::new(
+        board,
+        Box::new(ManhattanDistance),
+        8,
+    ))
+    .solve_with_stats(&SolverConfig::default())
+    .unwrap();
+
+    assert_eq!(parse_moves("LDRURD").unwrap(), report.solution);
+    assert_eq!(Some(0), report.nodes_expanded);
+}
+
+#[test]
+fn expands_fewer_nodes_than_ida() {
+    let board = deep_board();
+
+    assert_counts_fewer(
+        &board,
+        |report| report.nodes_expanded,
+        1.0,
+        Box::new(PerimeterSearchSolver::new(
+            board.clone(),
+            Box::new(ManhattanDistance),
+            8,
+        )),
+        Box::new(IterativeAStarSolver::new(
+            board.clone(),
+            Box::new(ManhattanDistance),
+        )),
+    );
+}
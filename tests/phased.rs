@@ -0,0 +1,52 @@
+use solver::board::{Board, OwnedBoard};
+use solver::solving::algorithm::phased::{Phase, PhasedSolver};
+use solver::solving::algorithm::Solver;
+use solver::solving::goal::{Goal, SolvedState, TilesPlaced};
+
+use crate::shared::assert_produces_valid_solution;
+
+mod shared;
+
+fn row_by_row_phases() -> Vec<Phase> {
+    vec![
+        Phase::place_tiles([1, 2, 3]),
+        Phase::new(Box::new(SolvedState)),
+    ]
+}
+
+#[test]
+fn produces_correct_solution() {
+    assert_produces_valid_solution(|board| PhasedSolver::new(board, row_by_row_phases()));
+}
+
+#[test]
+fn last_two_rows_finish_solves_board() {
+    assert_produces_valid_solution(|board| {
+        PhasedSolver::new(board, vec![Phase::place_tiles([1, 2, 3])]).finishing_last_two_rows()
+    });
+}
+
+#[test]
+fn frozen_tiles_are_not_moved_by_later_phases() {
+    let mut board: OwnedBoard = r"3 3
+    0 4 2
+    1 7 3
+    5 8 6"
+        .parse()
+        .unwrap();
+    let first_phase = TilesPlaced::new([1, 2, 3]);
+
+    let solution = Box::new(PhasedSolver::new(board.clone(), row_by_row_phases()))
+        .solve()
+        .unwrap();
+
+    let mut first_phase_finished = false;
+    for m in solution {
+        board.exec_move(m);
+        if first_phase_finished {
+            assert!(first_phase.is_reached(&board));
+        }
+        first_phase_finished |= first_phase.is_reached(&board);
+    }
+    assert!(board.is_solved());
+}
@@ -0,0 +1,141 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use solver::board::{BoardMove, OwnedBoard};
+use solver::solving::algorithm::heuristic::heuristics::ManhattanDistance;
+use solver::solving::algorithm::solvers::{
+    BFSSolver, DFSSolver, IterativeAStarSolver, PortfolioSolver,
+};
+use solver::solving::algorithm::{SolveStep, Solver, SolverConfig, SolvingError};
+use solver::solving::movegen::MoveGenerator;
+
+mod shared;
+
+fn ida(board: OwnedBoard) -> Box<dyn Solver> {
+    Box::new(IterativeAStarSolver::new(
+        board,
+        Box::new(ManhattanDistance),
+    ))
+}
+
+fn bfs(board: OwnedBoard) -> Box<dyn Solver> {
+    Box::new(BFSSolver::new(board, MoveGenerator::default()))
+}
+
+/// Member which sets the flag when its solver is dropped, i.e. when its thread stopped searching
+struct Stopped {
+    solver: Box<dyn Solver>,
+    flag: Arc<AtomicBool>,
+}
+
+impl Drop for Stopped {
+    fn drop(&mut self) {
+        self.flag.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Solver for Stopped {
+    fn solve(self: Box<Self>) -> Result<Vec<BoardMove>, SolvingError> {
+        unreachable!("Portfolio members are advanced in slices")
+    }
+
+    fn is_suspendable(&self) -> bool {
+        self.solver.is_suspendable()
+    }
+
+    fn solve_for(mut self: Box<Self>, duration: Duration) -> SolveStep {
+        let solver = std::mem::replace(&mut self.solver, bfs(OwnedBoard::new_solved(1, 2)));
+        match solver.solve_for(duration) {
+            SolveStep::Pending(solver) => {
+                self.solver = solver;
+                SolveStep::Pending(self)
+            }
+            done => done,
+        }
+    }
+}
+
+#[test]
+fn produces_shortest_solution_of_optimal_members() {
+    shared::assert_produces_shortest_solution(|board| {
+        PortfolioSolver::new(board)
+            .with_member("IDA*", ida)
+            .with_member("BFS", bfs)
+    });
+}
+
+#[test]
+fn fastest_member_stops_the_others() {
+    // far too deep for the breadth-first search, which would run out of memory
+    let board = shared::deep_board();
+    let bfs_stopped = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&bfs_stopped);
+
+    let solution = Box::new(
+        PortfolioSolver::new(board.clone())
+            .with_member("BFS", move |board| {
+                Box::new(Stopped {
+                    solver: bfs(board),
+                    flag,
+                })
+            })
+            .with_member("IDA*", ida),
+    )
+    .solve()
+    .unwrap();
+
+    assert!(bfs_stopped.load(Ordering::Relaxed));
+    assert_eq!(ida(board).solve().unwrap().len(), solution.len());
+}
+
+#[test]
+fn rejects_members_which_cannot_be_suspended() {
+    let board = shared::deep_board();
+    let dfs =
+        |board| -> Box<dyn Solver> { Box::new(DFSSolver::new(board, MoveGenerator::default())) };
+
+    let result = Box::new(PortfolioSolver::new(board.clone()).with_member("DFS", dfs)).solve();
+    let solution = Box::new(
+        PortfolioSolver::new(board)
+            .with_member("DFS", dfs)
+            .with_member("IDA*", ida),
+    )
+    .solve();
+
+    assert!(matches!(result, Err(SolvingError::AlgorithmError(_))));
+    assert!(solution.is_ok());
+}
+
+#[test]
+fn reports_unsolvable_board() {
+    let board: OwnedBoard = "3 3\n2 1 3\n4 5 6\n7 8 0".parse().unwrap();
+
+    let result = Box::new(PortfolioSolver::new(board).with_member("BFS", bfs)).solve();
+
+    assert!(matches!(result, Err(SolvingError::UnsolvableBoard(_))));
+}
+
+#[test]
+fn fails_without_members() {
+    let board: OwnedBoard = "3 3\n1 2 3\n4 5 6\n7 0 8".parse().unwrap();
+
+    let result = Box::new(PortfolioSolver::new(board)).solve();
+
+    assert!(matches!(result, Err(SolvingError::NoSolverFinished)));
+}
+
+#[test]
+fn stops_at_time_limit() {
+    let board: OwnedBoard = "4 4\n0 12 9 13\n15 11 10 14\n3 7 2 5\n4 8 6 1"
+        .parse()
+        .unwrap();
+    let config = SolverConfig {
+        time_limit: Some(Duration::from_millis(50)),
+        ..SolverConfig::default()
+    };
+
+    let result = Box::new(PortfolioSolver::new(board).with_member("BFS", bfs)).solve_with(&config);
+
+    assert!(matches!(result, Err(SolvingError::TimedOut { .. })));
+}
@@ -0,0 +1,140 @@
+use solver::board::{Board, BoardMove, OwnedBoard};
+use solver::solving::algorithm::heuristic::heuristics::ManhattanDistance;
+use solver::solving::algorithm::phased::Phase;
+use solver::solving::algorithm::solvers::{
+    BreadthFirstHeuristicSolver, DFSSolver, FocalSolver, FrontierBFSSolver,
+    HashDistributedAStarSolver, LastTwoRowsSolver, MacroOperatorSolver, PerimeterSearchSolver,
+    PhasedSolver, PortfolioSolver, RetrogradeSolver,
+};
+use solver::solving::algorithm::{Solver, SolverConfig, SolvingError};
+use solver::solving::goal::SolvedState;
+use solver::solving::movegen::MoveGenerator;
+
+use crate::shared::counting_solvers;
+
+mod shared;
+
+fn board() -> OwnedBoard {
+    use BoardMove::*;
+    let mut board = OwnedBoard::new_solved(3, 3);
+    board
+        .apply_moves(&[Up, Left, Up, Right, Down, Left, Left, Up])
+        .unwrap();
+    board
+}
+
+fn solvers() -> Vec<Box<dyn Solver>> {
+    let mut solvers = counting_solvers(&board());
+    solvers.push(Box::new(DFSSolver::new(board(), MoveGenerator::default())));
+    solvers.push(Box::new(FrontierBFSSolver::new(board())));
+    solvers.push(Box::new(BreadthFirstHeuristicSolver::new(
+        board(),
+        Box::new(ManhattanDistance),
+    )));
+    solvers.push(Box::new(FocalSolver::new(
+        board(),
+        Box::new(ManhattanDistance),
+        0.5,
+    )));
+    solvers.push(Box::new(PerimeterSearchSolver::new(
+        board(),
+        Box::new(ManhattanDistance),
+        2,
+    )));
+    solvers
+}
+
+/// Solvers which do not count their nodes, see [`solver::solving::algorithm::SolveReport`]
+fn solvers_without_counters(board: &OwnedBoard) -> Vec<Box<dyn Solver>> {
+    vec![
+        Box::new(HashDistributedAStarSolver::new(
+            board.clone(),
+            || Box::new(ManhattanDistance),
+            2,
+        )),
+        Box::new(RetrogradeSolver::new(board.clone())),
+        Box::new(MacroOperatorSolver::new(board.clone())),
+        Box::new(PhasedSolver::new(
+            board.clone(),
+            vec![Phase::new(Box::new(SolvedState))],
+        )),
+        Box::new(LastTwoRowsSolver::new(board.clone())),
+        Box::new(
+            PortfolioSolver::new(board.clone()).with_member("A*", |board| {
+                Box::new(solver::solving::algorithm::solvers::AStarSolver::new(
+                    board,
+                    Box::new(ManhattanDistance),
+                ))
+            }),
+        ),
+    ]
+}
+
+#[test]
+fn every_solver_fills_report() {
+    for solver in solvers() {
+        let report = solver.solve_with_stats(&SolverConfig::default()).unwrap();
+
+        let mut board = board();
+        board.apply_moves(&report.solution).unwrap();
+        assert!(board.is_solved());
+        assert!(
+            report.nodes_expanded.is_some_and(|count| count > 0),
+            "{report:?}"
+        );
+        assert!(
+            report.nodes_generated.is_some_and(|count| count > 0),
+            "{report:?}"
+        );
+        assert!(
+            report.max_frontier.is_some_and(|count| count > 0),
+            "{report:?}"
+        );
+        assert!(
+            report.peak_memory_estimate.is_some_and(|bytes| bytes > 0),
+            "{report:?}"
+        );
+    }
+}
+
+#[test]
+fn solvers_without_counters_report_only_solution() {
+    // the top row is solved, as the last two rows solver requires
+    let board: OwnedBoard = "3 3\n1 2 3\n4 5 6\n0 7 8".parse().unwrap();
+    for solver in solvers_without_counters(&board) {
+        let report = solver.solve_with_stats(&SolverConfig::default()).unwrap();
+
+        assert_eq!(2, report.solution.len());
+        assert_eq!(None, report.nodes_expanded);
+        assert_eq!(None, report.nodes_generated);
+        assert_eq!(None, report.max_frontier);
+        assert_eq!(None, report.peak_memory_estimate);
+    }
+}
+
+#[test]
+fn reported_expansions_fit_expansion_limit() {
+    let expanded: Vec<_> = counting_solvers(&board())
+        .into_iter()
+        .map(|solver| {
+            solver
+                .solve_with_stats(&SolverConfig::default())
+                .unwrap()
+                .nodes_expanded
+                .unwrap()
+        })
+        .collect();
+
+    for (solver, expanded) in counting_solvers(&board()).into_iter().zip(&expanded) {
+        let config = SolverConfig::default().with_max_expansions(*expanded);
+        assert!(solver.solve_with_stats(&config).is_ok());
+    }
+    for (solver, expanded) in counting_solvers(&board()).into_iter().zip(&expanded) {
+        let config = SolverConfig::default().with_max_expansions(expanded - 1);
+        let result = solver.solve_with_stats(&config);
+        assert!(
+            matches!(result, Err(SolvingError::ExpansionLimitReached { .. })),
+            "{expanded} expansions: {result:?}"
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use solver::board::{parse_moves, Board, OwnedBoard};
+use solver::solving::algorithm::heuristic::heuristics::{
+    EnhancedLinearConflict, Heuristic, ManhattanDistance, WalkingDistance,
+};
+use solver::solving::algorithm::retrograde::RetrogradeTable;
+use solver::solving::algorithm::solvers::{BFSSolver, RetrogradeSolver};
+use solver::solving::algorithm::{Solver, SolvingError};
+use solver::solving::movegen::MoveGenerator;
+
+#[test]
+fn produces_shortest_solution() {
+    for (rows, columns, scramble) in [
+        (2, 3, "LULDRRUL"),
+        (2, 4, "LLLURRRDLLUR"),
+        (3, 3, "ULULDRDRULLDRUUL"),
+    ] {
+        let scramble = parse_moves(scramble).unwrap();
+        let board = OwnedBoard::from_scramble(rows, columns, &scramble).unwrap();
+        let shortest = Box::new(BFSSolver::new(board.clone(), MoveGenerator::default()))
+            .solve()
+            .unwrap();
+
+        let solution = Box::new(RetrogradeSolver::new(board.clone()))
+            .solve()
+            .unwrap();
+
+        let mut solved = board;
+        solved.apply_moves(&solution).unwrap();
+        assert!(solved.is_solved());
+        assert_eq!(shortest.len(), solution.len());
+    }
+}
+
+#[test]
+fn enumerates_whole_state_space() {
+    // half of the permutations are reachable, with the known diameters of the boards
+    for (rows, columns, states, max_distance) in
+        [(2, 3, 360, 21), (2, 4, 20_160, 36), (3, 3, 181_440, 31)]
+    {
+        let table = RetrogradeTable::standard(rows, columns).unwrap();
+
+        assert_eq!(states, table.distance_counts().iter().sum::<usize>());
+        assert_eq!(max_distance, table.max_distance());
+    }
+}
+
+#[test]
+fn heuristics_never_overestimate_distance() {
+    let heuristics: [Box<dyn Heuristic>; 3] = [
+        Box::new(ManhattanDistance),
+        Box::<WalkingDistance>::default(),
+        Box::<EnhancedLinearConflict>::default(),
+    ];
+    let table = RetrogradeTable::standard(2, 4).unwrap();
+
+    for (board, distance) in table.positions() {
+        for heuristic in &heuristics {
+            assert!(heuristic.evaluate(&board) <= distance, "{board:?}");
+        }
+    }
+}
+
+#[test]
+fn enhanced_linear_conflict_never_overestimates_eight_puzzle() {
+    let heuristic = EnhancedLinearConflict::default();
+    let table = RetrogradeTable::standard(3, 3).unwrap();
+
+    for (board, distance) in table.positions() {
+        let value = heuristic.evaluate(&board);
+        assert!(value <= distance, "{board:?}");
+        assert!(value >= ManhattanDistance.evaluate(&board), "{board:?}");
+    }
+}
+
+#[test]
+fn solves_board_with_custom_goal() {
+    let goal: OwnedBoard = "2 3\n0 1 2\n3 4 5".parse().unwrap();
+    let board: OwnedBoard = "2 3\n1 0 2\n3 4 5".parse().unwrap();
+    let board = board.with_goal(goal).unwrap();
+
+    let table = RetrogradeTable::generate(&board).unwrap();
+
+    assert_eq!(Some(1), table.distance(&board));
+    for (board, distance) in table.positions() {
+        let solution = table.solution(&board).unwrap();
+        let mut solved = board;
+        solved.apply_moves(&solution).unwrap();
+        assert!(solved.is_solved());
+        assert_eq!(distance, solution.len() as u64);
+    }
+}
+
+#[test]
+fn rejects_large_board() {
+    let board = OwnedBoard::new_solved(4, 4);
+
+    assert!(matches!(
+        Box::new(RetrogradeSolver::new(board)).solve(),
+        Err(SolvingError::AlgorithmError(_))
+    ));
+}
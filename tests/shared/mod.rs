@@ -1,12 +1,20 @@
-use solver::board::{Board, BoardMove, OwnedBoard};
-use solver::solving::algorithm::Solver;
+#![allow(dead_code)]
 
-fn is_valid_solution(mut board: OwnedBoard, solution: Vec<BoardMove>) -> bool {
-    for m in solution {
-        board.exec_move(m);
-    }
+use std::fmt::Debug;
 
-    board.is_solved()
+use solver::board::scramble::round_trips;
+use solver::board::{parse_moves, Board, BoardMove, OwnedBoard};
+use solver::solving::algorithm::heuristic::bestfs::BestFSSolver;
+use solver::solving::algorithm::heuristic::heuristics::ManhattanDistance;
+use solver::solving::algorithm::solvers::{
+    AStarSolver, BFSSolver, IncrementalDFSSolver, IterativeAStarSolver, MemoryBoundedAStarSolver,
+};
+use solver::solving::algorithm::{SolveReport, SolveStep, Solver, SolverConfig};
+use solver::solving::movegen::MoveGenerator;
+
+fn is_valid_solution(mut board: OwnedBoard, solution: Vec<BoardMove>) -> bool {
+    let original = board.clone();
+    board.apply_moves(&solution).is_ok() && board.is_solved() && round_trips(&original, &solution)
 }
 
 fn solution_to_string(solution: &[BoardMove]) -> String {
@@ -115,3 +123,114 @@ pub fn assert_produces_shortest_solution<S: Solver>(
         assert_eq!(solution.len(), shortest_solution);
     }
 }
+
+/// Checks that the solutions are valid and at most `factor` times longer than the shortest ones,
+/// as guaranteed by bounded-suboptimal solvers, e.g. weighted A* with the weight as the factor
+pub fn assert_produces_solution_within<S: Solver>(
+    factor: f64,
+    mut solver_builder: impl FnMut(OwnedBoard) -> S,
+) {
+    let test_data = generate_test_data();
+
+    for (board, shortest_solution) in test_data {
+        let solver = Box::new(solver_builder(board.clone()));
+        let solution = solver.solve().expect("board should be solvable");
+
+        eprintln!(
+            "Solution length {}, shortest {shortest_solution}",
+            solution.len()
+        );
+        eprintln!("{}", solution_to_string(&solution));
+
+        assert!(
+            solution.len() as f64 <= factor * shortest_solution as f64,
+            "solution of length {} is more than {factor} times longer than {shortest_solution}",
+            solution.len()
+        );
+        assert!(is_valid_solution(board, solution));
+    }
+}
+
+/// Board far too scrambled for any of the solvers to finish within a small limit
+pub fn hard_board() -> OwnedBoard {
+    r"4 4
+    13 14 15 7
+    11 12 9 5
+    6 0 2 1
+    4 8 10 3"
+        .parse()
+        .unwrap()
+}
+
+/// Board deep enough for the counters of the heuristic searches to differ clearly, still solved quickly
+pub fn deep_board() -> OwnedBoard {
+    let scramble = parse_moves("LUURULLLDRDDRRULLDLUUURRRDDDLUURDDLUUULD").unwrap();
+    OwnedBoard::from_scramble(4, 4, &scramble).unwrap()
+}
+
+/// Solves the board by resuming the suspended search until it finishes, letting every slice expire immediately.
+/// Returns the solution and the number of times the search was suspended
+pub fn solve_in_slices(mut solver: Box<dyn Solver>) -> (Vec<BoardMove>, usize) {
+    let mut slices = 0;
+    loop {
+        match solver.solve_for(std::time::Duration::ZERO) {
+            SolveStep::Done(result) => return (result.unwrap().solution, slices),
+            SolveStep::Pending(pending) => solver = pending,
+        }
+        slices += 1;
+        assert!(slices < 1_000_000, "Sliced search should finish");
+    }
+}
+
+/// Solvers which count their nodes and whose search does not depend on the remaining stack space
+pub fn counting_solvers(board: &OwnedBoard) -> Vec<Box<dyn Solver>> {
+    vec![
+        Box::new(BFSSolver::new(board.clone(), MoveGenerator::default())),
+        Box::new(IncrementalDFSSolver::new(
+            board.clone(),
+            MoveGenerator::default(),
+        )),
+        Box::new(AStarSolver::new(board.clone(), Box::new(ManhattanDistance))),
+        Box::new(BestFSSolver::new(
+            board.clone(),
+            Box::new(ManhattanDistance),
+        )),
+        Box::new(IterativeAStarSolver::new(
+            board.clone(),
+            Box::new(ManhattanDistance),
+        )),
+        Box::new(MemoryBoundedAStarSolver::new(
+            board.clone(),
+            Box::new(ManhattanDistance),
+            100_000,
+        )),
+    ]
+}
+
+/// Solves the board with both solvers, checking that the first one reports a smaller counter than the baseline
+/// and a valid solution at most `factor` times longer than the baseline's
+pub fn assert_counts_fewer<C: PartialOrd + Debug>(
+    board: &OwnedBoard,
+    counter: impl Fn(&SolveReport) -> Option<C>,
+    factor: f64,
+    solver: Box<dyn Solver>,
+    baseline: Box<dyn Solver>,
+) {
+    let solve = |solver: Box<dyn Solver>| {
+        solver
+            .solve_with_stats(&SolverConfig::default())
+            .expect("board should be solvable")
+    };
+    let (report, baseline) = (solve(solver), solve(baseline));
+
+    let count = counter(&report).expect("solver should count its nodes");
+    let baseline_count = counter(&baseline).expect("baseline should count its nodes");
+    assert!(count < baseline_count, "{count:?} >= {baseline_count:?}");
+    assert!(
+        report.solution.len() as f64 <= factor * baseline.solution.len() as f64,
+        "solution of length {} is more than {factor} times longer than {}",
+        report.solution.len(),
+        baseline.solution.len()
+    );
+    assert!(is_valid_solution(board.clone(), report.solution));
+}
@@ -0,0 +1,97 @@
+use solver::board::OwnedBoard;
+use solver::solving::algorithm::heuristic::heuristics::ManhattanDistance;
+use solver::solving::algorithm::heuristic::sma::MemoryBoundedError;
+use solver::solving::algorithm::solvers::{IterativeAStarSolver, MemoryBoundedAStarSolver};
+use solver::solving::algorithm::{Solver, SolvingError};
+
+use crate::shared::{assert_produces_shortest_solution, assert_produces_valid_solution};
+
+mod shared;
+
+#[test]
+fn produces_correct_solution() {
+    assert_produces_valid_solution(|board| {
+        MemoryBoundedAStarSolver::new(board, Box::new(ManhattanDistance), 1_000)
+    });
+}
+
+#[test]
+fn produces_shortest_solution_with_little_memory() {
+    // enough for the longest test solution, but far too few for the nodes generated by A*
+    assert_produces_shortest_solution(|board| {
+        MemoryBoundedAStarSolver::new(board, Box::new(ManhattanDistance), 20)
+    });
+}
+
+#[test]
+fn is_deterministic_and_optimal_on_larger_board() {
+    let board: OwnedBoard = r"4 4
+    5 1 3 4
+    9 2 7 8
+    0 6 10 12
+    13 14 11 15"
+        .parse()
+        .unwrap();
+    let shortest = Box::new(IterativeAStarSolver::new(
+        board.clone(),
+        Box::new(ManhattanDistance),
+    ))
+    .solve()
+    .unwrap();
+
+    let solve = || {
+        Box::new(MemoryBoundedAStarSolver::new(
+            board.clone(),
+            Box::new(ManhattanDistance),
+            64,
+        ))
+        .solve()
+        .unwrap()
+    };
+    let first = solve();
+
+    assert_eq!(shortest.len(), first.len());
+    assert_eq!(first, solve());
+}
+
+#[test]
+fn fails_when_solution_does_not_fit_in_memory() {
+    let board: OwnedBoard = r"3 3
+    0 4 2
+    1 7 3
+    5 8 6"
+        .parse()
+        .unwrap();
+
+    let result = Box::new(MemoryBoundedAStarSolver::new(
+        board,
+        Box::new(ManhattanDistance),
+        12,
+    ))
+    .solve();
+
+    let Err(SolvingError::AlgorithmError(error)) = result else {
+        unreachable!("12 moves long solution needs 13 nodes in memory");
+    };
+    assert_eq!(
+        Some(&MemoryBoundedError::NodeLimitExceeded { node_limit: 12 }),
+        error.downcast_ref()
+    );
+}
+
+#[test]
+fn reports_unsolvable_board() {
+    let board: OwnedBoard = "3 3\n2 1 3\n4 5 6\n7 8 0".parse().unwrap();
+
+    let result = Box::new(MemoryBoundedAStarSolver::new(
+        board,
+        Box::new(ManhattanDistance),
+        100,
+    ))
+    .solve();
+
+    assert!(matches!(
+        result,
+        Err(SolvingError::UnsolvableBoard(Some(_)))
+    ));
+}
@@ -0,0 +1,77 @@
+use solver::board::{Board, BoardMove, OwnedBoard};
+use solver::solving::algorithm::heuristic::heuristics::{Heuristic, ManhattanDistance};
+use solver::solving::algorithm::solvers::{AStarSolver, BFSSolver};
+use solver::solving::algorithm::stepper::{SearchStepper, StepEvent};
+use solver::solving::algorithm::{Solver, SolverConfig};
+use solver::solving::movegen::MoveGenerator;
+
+fn board() -> OwnedBoard {
+    use BoardMove::*;
+    OwnedBoard::from_scramble(3, 3, &[Up, Left, Up, Right, Down, Left]).unwrap()
+}
+
+/// Steps the search until it finishes, returning the solution and the number of expansions
+fn step_to_solution(stepper: &mut impl SearchStepper) -> (Vec<BoardMove>, u64) {
+    let mut expansions = 0;
+    loop {
+        match stepper.step() {
+            StepEvent::Expanded { .. } => expansions += 1,
+            StepEvent::Skipped(_) => {}
+            StepEvent::Solved(solution) => return (solution, expansions),
+            StepEvent::Failed(e) => unreachable!("Search should find the solution: {e}"),
+        }
+    }
+}
+
+#[test]
+fn first_step_expands_initial_board() {
+    let mut stepper = BFSSolver::new(board(), MoveGenerator::default());
+
+    let StepEvent::Expanded { node, children } = stepper.step() else {
+        unreachable!("Initial board is expanded first");
+    };
+
+    assert_eq!(board(), node.board);
+    assert!(node.path.is_empty());
+    assert!(!children.is_empty());
+    assert_eq!(children, stepper.frontier());
+    for child in children {
+        let mut expected = board();
+        expected.apply_moves(&child.path).unwrap();
+        assert_eq!(expected, child.board);
+    }
+}
+
+#[test]
+fn stepped_search_finds_same_solution() {
+    let solved = Box::new(BFSSolver::new(board(), MoveGenerator::default()))
+        .solve_with_stats(&SolverConfig::default())
+        .unwrap();
+
+    let mut stepper = BFSSolver::new(board(), MoveGenerator::default());
+    let (solution, expansions) = step_to_solution(&mut stepper);
+
+    assert_eq!(solved.solution, solution);
+    assert_eq!(solved.nodes_expanded, Some(expansions));
+}
+
+#[test]
+fn astar_frontier_is_ordered_by_cost() {
+    let mut stepper = AStarSolver::new(board(), Box::new(ManhattanDistance));
+    for _ in 0..3 {
+        assert!(matches!(stepper.step(), StepEvent::Expanded { .. }));
+    }
+
+    let costs: Vec<_> = stepper
+        .frontier()
+        .iter()
+        .map(|node| node.path.len() as u64 + ManhattanDistance.evaluate(&node.board))
+        .collect();
+
+    assert!(!costs.is_empty());
+    assert!(costs.is_sorted());
+    let (solution, _) = step_to_solution(&mut stepper);
+    let mut board = board();
+    board.apply_moves(&solution).unwrap();
+    assert!(board.is_solved());
+}
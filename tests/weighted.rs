@@ -0,0 +1,128 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use solver::board::{Board, BoardMove, OwnedBoard};
+use solver::solving::algorithm::heuristic::heuristics::WeightedManhattanDistance;
+use solver::solving::algorithm::solvers::*;
+use solver::solving::algorithm::Solver;
+use solver::solving::cost::{CostModel, TileCosts};
+
+fn create_costs() -> TileCosts {
+    TileCosts::new([1, 5, 2, 7, 3])
+}
+
+fn moved_tile(board: &OwnedBoard, board_move: BoardMove) -> u8 {
+    let mut after = board.clone();
+    after.exec_move(board_move);
+    let (row, column) = board.empty_cell_pos();
+    after.at(row, column)
+}
+
+fn solution_cost(mut board: OwnedBoard, solution: &[BoardMove], costs: &TileCosts) -> u64 {
+    let mut total = 0;
+    for &m in solution {
+        total += costs.tile_cost(moved_tile(&board, m));
+        board.exec_move(m);
+    }
+    assert!(board.is_solved());
+    total
+}
+
+/// Dijkstra over the whole state space, used as the reference optimal cost
+fn brute_force_optimal_cost(board: &OwnedBoard, costs: &TileCosts) -> u64 {
+    let mut best: HashMap<OwnedBoard, u64> = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    let mut counter = 0usize;
+    let mut boards = vec![board.clone()];
+    queue.push(Reverse((0u64, counter)));
+    best.insert(board.clone(), 0);
+
+    while let Some(Reverse((cost, index))) = queue.pop() {
+        let current = boards[index].clone();
+        if current.is_solved() {
+            return cost;
+        }
+        if best.get(&current).is_some_and(|&c| c < cost) {
+            continue;
+        }
+        for m in [
+            BoardMove::Up,
+            BoardMove::Down,
+            BoardMove::Left,
+            BoardMove::Right,
+        ] {
+            if !current.can_move(m) {
+                continue;
+            }
+            let new_cost = cost + costs.tile_cost(moved_tile(&current, m));
+            let mut next = current.clone();
+            next.exec_move(m);
+            if best.get(&next).is_some_and(|&c| c <= new_cost) {
+                continue;
+            }
+            best.insert(next.clone(), new_cost);
+            counter += 1;
+            boards.push(next);
+            queue.push(Reverse((new_cost, counter)));
+        }
+    }
+    unreachable!("Test boards are solvable")
+}
+
+fn create_boards() -> Vec<OwnedBoard> {
+    use BoardMove::*;
+    let scrambles: &[&[BoardMove]] = &[
+        &[Up, Left, Left],
+        &[Left, Up, Right, Down, Left, Left],
+        &[Up, Left, Down, Left, Up, Right, Right, Down],
+        &[Left, Left, Up, Right, Right, Down, Left, Up, Left, Down],
+    ];
+    scrambles
+        .iter()
+        .map(|scramble| {
+            let mut board: OwnedBoard = "2 3\n1 2 3\n4 5 0".parse().unwrap();
+            board.apply_moves(scramble).unwrap();
+            board
+        })
+        .collect()
+}
+
+#[test]
+fn astar_finds_cheapest_solution() {
+    for board in create_boards() {
+        let solution = Box::new(
+            AStarSolver::new(
+                board.clone(),
+                Box::new(WeightedManhattanDistance::new(Box::new(create_costs()))),
+            )
+            .with_cost_model(Box::new(create_costs())),
+        )
+        .solve()
+        .unwrap();
+
+        assert_eq!(
+            brute_force_optimal_cost(&board, &create_costs()),
+            solution_cost(board, &solution, &create_costs())
+        );
+    }
+}
+
+#[test]
+fn ida_finds_cheapest_solution() {
+    for board in create_boards() {
+        let solution = Box::new(
+            IterativeAStarSolver::new(
+                board.clone(),
+                Box::new(WeightedManhattanDistance::new(Box::new(create_costs()))),
+            )
+            .with_cost_model(Box::new(create_costs())),
+        )
+        .solve()
+        .unwrap();
+
+        assert_eq!(
+            brute_force_optimal_cost(&board, &create_costs()),
+            solution_cost(board, &solution, &create_costs())
+        );
+    }
+}